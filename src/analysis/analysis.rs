@@ -0,0 +1,42 @@
+use crate::{
+    ast::Statement, cfg::build_control_flow_graphs, diagnostics::Diagnostic,
+    resolver::resolve_program,
+};
+
+use super::{
+    analysis_definite_assignment::{check_definite_assignment, collect_captures},
+    analysis_field_assignments::{
+        check_classes_without_constructor, check_field_assignments, collect_required_fields,
+    },
+    analysis_returns::{check_missing_returns, check_unreachable_code},
+    analysis_super_calls::{check_super_calls, collect_derived_constructors},
+};
+
+///
+/// Run the data flow analyses over the graphs of the program and of its
+/// functions, the findings are sorted by their position in the source
+///
+pub fn analyze_program(program: &Statement) -> Vec<Diagnostic> {
+    let resolution = resolve_program(program);
+    let captures = collect_captures(program, &resolution);
+    let derived_constructors = collect_derived_constructors(program, &resolution);
+    let mut required_fields = vec![];
+    collect_required_fields(program, &mut required_fields);
+    let mut diagnostics = check_classes_without_constructor(&required_fields);
+
+    for graph in build_control_flow_graphs(program) {
+        diagnostics.extend(check_definite_assignment(&graph, &resolution, &captures));
+        diagnostics.extend(check_super_calls(&graph, &derived_constructors));
+        diagnostics.extend(check_field_assignments(&graph, &required_fields));
+        diagnostics.extend(check_missing_returns(&graph));
+        diagnostics.extend(check_unreachable_code(&graph));
+    }
+
+    diagnostics.sort_by_key(|diagnostic| {
+        diagnostic
+            .span
+            .map(|span| span.start.offset)
+            .unwrap_or_default()
+    });
+    diagnostics
+}
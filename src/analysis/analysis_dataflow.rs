@@ -0,0 +1,229 @@
+use crate::{
+    ast::{Expression, Statement},
+    cfg::{CfgElement, ControlFlowGraph},
+};
+
+///
+/// Forward data flow analysis whose facts hold at a point only when they
+/// hold on every path leading to it
+///
+/// The analysis supplies the facts and their transfer, the graph is solved
+/// by solve_must_analysis
+///
+pub(super) trait MustAnalysis {
+    type Facts: Clone + PartialEq;
+
+    ///
+    /// Facts at the entry of the graph, before anything ran
+    ///
+    fn initial(&self) -> Self::Facts;
+
+    ///
+    /// Facts of blocks no path reaches yet, every fact holds there
+    ///
+    fn unreached(&self) -> Self::Facts;
+
+    ///
+    /// Facts holding on both paths
+    ///
+    fn meet(&self, left: &Self::Facts, right: &Self::Facts) -> Self::Facts;
+
+    ///
+    /// Transfer the facts over an expression, diagnostics are only reported
+    /// once the facts are stable
+    ///
+    /// Returns false to leave the expression to walk_expression, which
+    /// evaluates its operands in the runtime order
+    ///
+    fn transfer(&mut self, expression: &Expression, facts: &mut Self::Facts, report: bool) -> bool;
+}
+
+///
+/// Solve the analysis over the graph and run it once more reporting its
+/// findings, returns the facts at the exit of the graph
+///
+pub(super) fn solve_must_analysis<A: MustAnalysis>(
+    graph: &ControlFlowGraph,
+    analysis: &mut A,
+) -> A::Facts {
+    // Facts at the end of each block, blocks start with every fact and only
+    // lose facts until nothing changes
+    let mut facts_out = vec![analysis.unreached(); graph.blocks.len()];
+    facts_out[ControlFlowGraph::ENTRY] = analysis.initial();
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for block in 0..graph.blocks.len() {
+            if block == ControlFlowGraph::ENTRY {
+                continue;
+            }
+
+            let facts = transfer_block(graph, analysis, &facts_out, block, false);
+            if facts != facts_out[block] {
+                facts_out[block] = facts;
+                changed = true;
+            }
+        }
+    }
+
+    for block in 0..graph.blocks.len() {
+        transfer_block(graph, analysis, &facts_out, block, true);
+    }
+
+    facts_in(graph, analysis, &facts_out, ControlFlowGraph::EXIT)
+}
+
+fn transfer_block<A: MustAnalysis>(
+    graph: &ControlFlowGraph,
+    analysis: &mut A,
+    facts_out: &[A::Facts],
+    block: usize,
+    report: bool,
+) -> A::Facts {
+    let mut facts = facts_in(graph, analysis, facts_out, block);
+    for element in &graph.blocks[block].elements {
+        walk_element(analysis, element, &mut facts, report);
+    }
+    facts
+}
+
+fn facts_in<A: MustAnalysis>(
+    graph: &ControlFlowGraph,
+    analysis: &A,
+    facts_out: &[A::Facts],
+    block: usize,
+) -> A::Facts {
+    let mut predecessors = graph.predecessors(block).map(|edge| &facts_out[edge.from]);
+
+    // Blocks that are never entered have nothing to report
+    let Some(first) = predecessors.next() else {
+        return analysis.unreached();
+    };
+
+    predecessors.fold(first.clone(), |facts, other| analysis.meet(&facts, other))
+}
+
+fn walk_element<A: MustAnalysis>(
+    analysis: &mut A,
+    element: &CfgElement,
+    facts: &mut A::Facts,
+    report: bool,
+) {
+    match element {
+        CfgElement::Statement(Statement::Expression { expression, .. }) => {
+            walk_expression(analysis, expression, facts, report)
+        }
+        CfgElement::Statement(Statement::VariableDeclaration { variables, .. }) => {
+            for variable in variables {
+                walk_expression(analysis, variable, facts, report);
+            }
+        }
+        CfgElement::Statement(Statement::Return {
+            argument: Some(argument),
+            ..
+        }) => walk_expression(analysis, argument, facts, report),
+        CfgElement::Condition(expression) | CfgElement::Expression(expression) => {
+            walk_expression(analysis, expression, facts, report)
+        }
+        // Bodies of nested declarations belong to their own graphs
+        CfgElement::Statement(_) => {}
+    }
+}
+
+///
+/// Evaluate the expression in the runtime order, the analysis transfers the
+/// expressions it knows and the operands of the others are walked
+///
+pub(super) fn walk_expression<A: MustAnalysis>(
+    analysis: &mut A,
+    expression: &Expression,
+    facts: &mut A::Facts,
+    report: bool,
+) {
+    if analysis.transfer(expression, facts, report) {
+        return;
+    }
+
+    match expression {
+        Expression::Variable { initializer, .. } => {
+            if let Some(initializer) = initializer {
+                walk_expression(analysis, initializer, facts, report);
+            }
+        }
+        Expression::Assignment { left, right, .. } | Expression::Binary { left, right, .. } => {
+            walk_expression(analysis, left, facts, report);
+            walk_expression(analysis, right, facts, report);
+        }
+        Expression::Logical { left, right, .. } => {
+            walk_expression(analysis, left, facts, report);
+
+            // The right operand might not be evaluated, what it adds is dropped
+            let mut right_facts = facts.clone();
+            walk_expression(analysis, right, &mut right_facts, report);
+        }
+        Expression::Unary { right, .. }
+        | Expression::TypeTest {
+            expression: right, ..
+        } => walk_expression(analysis, right, facts, report),
+        Expression::Member {
+            computed,
+            object,
+            property,
+            ..
+        } => {
+            walk_expression(analysis, object, facts, report);
+            if *computed {
+                walk_expression(analysis, property, facts, report);
+            }
+        }
+        Expression::Call {
+            callee, arguments, ..
+        }
+        | Expression::New {
+            callee, arguments, ..
+        } => {
+            walk_expression(analysis, callee, facts, report);
+            for argument in arguments {
+                walk_expression(analysis, argument, facts, report);
+            }
+        }
+        Expression::EnumVariant { arguments, .. }
+        | Expression::ArrayLiteral {
+            elements: arguments,
+            ..
+        } => {
+            for argument in arguments {
+                walk_expression(analysis, argument, facts, report);
+            }
+        }
+        Expression::Match { subject, arms, .. } => {
+            walk_expression(analysis, subject, facts, report);
+
+            // Exactly one arm is taken, only what holds after every arm is kept
+            let mut taken: Option<A::Facts> = None;
+            for arm in arms {
+                let mut arm_facts = facts.clone();
+                if let Some(guard) = &arm.guard {
+                    walk_expression(analysis, guard, &mut arm_facts, report);
+                }
+                walk_expression(analysis, &arm.value, &mut arm_facts, report);
+                taken = Some(match taken {
+                    Some(taken) => analysis.meet(&taken, &arm_facts),
+                    None => arm_facts,
+                });
+            }
+            if let Some(taken) = taken {
+                *facts = taken;
+            }
+        }
+        Expression::BooleanLiteral { .. }
+        | Expression::NilLiteral { .. }
+        | Expression::StringLiteral { .. }
+        | Expression::NumericLiteral { .. }
+        | Expression::Identifier { .. }
+        | Expression::This { .. }
+        | Expression::Super { .. }
+        | Expression::Destructuring { .. } => {}
+    }
+}
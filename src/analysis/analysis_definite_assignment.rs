@@ -0,0 +1,339 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::{
+    ast::{AssignmentOperator, ClassMember, Expression, Statement},
+    cfg::{CfgElement, ControlFlowGraph},
+    diagnostics::Diagnostic,
+    lexer::Span,
+    resolver::{Resolution, SymbolKind},
+};
+
+use super::analysis_dataflow::{solve_must_analysis, walk_expression, MustAnalysis};
+
+///
+/// Variables of enclosing scopes each function reads, directly or through
+/// the functions it uses, by the symbol of the function
+///
+pub(super) type Captures = HashMap<usize, BTreeSet<usize>>;
+
+///
+/// Report reads of variables declared without an initializer that are not
+/// assigned on every path leading to the read
+///
+/// A function reads the variables it captures whenever it is used, so every
+/// use of its name needs them assigned, the body of the function takes them
+/// as assigned
+///
+pub(super) fn check_definite_assignment(
+    graph: &ControlFlowGraph,
+    resolution: &Resolution,
+    captures: &Captures,
+) -> Vec<Diagnostic> {
+    let mut analysis = DefiniteAssignment::new(resolution, captures);
+    for block in &graph.blocks {
+        for element in &block.elements {
+            analysis.collect_uninitialized(element);
+        }
+    }
+    if analysis.tracked.is_empty() {
+        return vec![];
+    }
+
+    solve_must_analysis(graph, &mut analysis);
+    analysis.diagnostics
+}
+
+///
+/// Variables each function of the program captures, functions used by a
+/// function pass their captures on to it
+///
+pub(super) fn collect_captures(program: &Statement, resolution: &Resolution) -> Captures {
+    let mut functions = vec![];
+    collect_functions(program, &mut functions);
+
+    let symbols: HashMap<usize, usize> = resolution
+        .symbols
+        .iter()
+        .enumerate()
+        .map(|(index, symbol)| (symbol.span.start.offset, index))
+        .collect();
+    let functions: Vec<(usize, Span)> = functions
+        .into_iter()
+        .filter_map(|(name, span)| Some((*symbols.get(&name.span().start.offset)?, span)))
+        .collect();
+
+    // Symbols declared outside the function and used inside it
+    let mut captures = Captures::new();
+    let mut callees: HashMap<usize, BTreeSet<usize>> = HashMap::new();
+    for (function, span) in &functions {
+        for reference in &resolution.references {
+            let symbol = &resolution.symbols[reference.symbol];
+            if !span.contains(reference.span.start) || span.contains(symbol.span.start) {
+                continue;
+            }
+            match symbol.kind {
+                SymbolKind::Variable => {
+                    captures
+                        .entry(*function)
+                        .or_default()
+                        .insert(reference.symbol);
+                }
+                SymbolKind::Function => {
+                    callees
+                        .entry(*function)
+                        .or_default()
+                        .insert(reference.symbol);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (function, span) in &functions {
+            let inherited: Vec<usize> = callees
+                .get(function)
+                .into_iter()
+                .flatten()
+                .flat_map(|callee| captures.get(callee).into_iter().flatten())
+                .copied()
+                .filter(|symbol| !span.contains(resolution.symbols[*symbol].span.start))
+                .collect();
+            let function_captures = captures.entry(*function).or_default();
+            for symbol in inherited {
+                changed |= function_captures.insert(symbol);
+            }
+        }
+    }
+
+    captures
+}
+
+///
+/// Names and ranges of the function declarations, nested ones and the ones
+/// in methods included
+///
+fn collect_functions<'a>(statement: &'a Statement, functions: &mut Vec<(&'a Expression, Span)>) {
+    match statement {
+        Statement::Program { body, .. } | Statement::Block { body, .. } => {
+            for statement in body {
+                collect_functions(statement, functions);
+            }
+        }
+        Statement::If {
+            consequent,
+            alternative,
+            ..
+        } => {
+            collect_functions(consequent, functions);
+            if let Some(alternative) = alternative {
+                collect_functions(alternative, functions);
+            }
+        }
+        Statement::While { body, .. }
+        | Statement::DoWhile { body, .. }
+        | Statement::For { body, .. }
+        | Statement::Labeled { body, .. } => collect_functions(body, functions),
+        Statement::FunctionDeclaration {
+            name, body, span, ..
+        } => {
+            functions.push((name, *span));
+            collect_functions(body, functions);
+        }
+        Statement::ClassDeclaration { members, .. } => {
+            for member in members {
+                if let ClassMember::Method { declaration }
+                | ClassMember::Constructor { declaration } = member
+                {
+                    if let Statement::FunctionDeclaration { body, .. } = declaration.as_ref() {
+                        collect_functions(body, functions);
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+struct DefiniteAssignment<'a> {
+    resolution: &'a Resolution,
+    captures: &'a Captures,
+
+    ///
+    /// Symbol of each declaration and use, by its start offset
+    ///
+    symbols: HashMap<usize, usize>,
+
+    ///
+    /// Variables of the graph declared without an initializer
+    ///
+    tracked: HashSet<usize>,
+
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> DefiniteAssignment<'a> {
+    fn new(resolution: &'a Resolution, captures: &'a Captures) -> Self {
+        let mut symbols = HashMap::new();
+        for (index, symbol) in resolution.symbols.iter().enumerate() {
+            symbols.insert(symbol.span.start.offset, index);
+        }
+        for reference in &resolution.references {
+            symbols.insert(reference.span.start.offset, reference.symbol);
+        }
+
+        DefiniteAssignment {
+            resolution,
+            captures,
+            symbols,
+            tracked: HashSet::new(),
+            diagnostics: vec![],
+        }
+    }
+
+    ///
+    /// Variable symbol of an identifier
+    ///
+    fn variable(&self, identifier: &Expression) -> Option<usize> {
+        let Expression::Identifier { span, .. } = identifier else {
+            return None;
+        };
+        let symbol = *self.symbols.get(&span.start.offset)?;
+
+        (self.resolution.symbols[symbol].kind == SymbolKind::Variable).then_some(symbol)
+    }
+
+    ///
+    /// Report the captured variables of a used function that are not
+    /// assigned yet
+    ///
+    fn check_captures(&mut self, name: &str, span: Span, assigned: &HashSet<usize>) {
+        let Some(captured) = self
+            .symbols
+            .get(&span.start.offset)
+            .and_then(|function| self.captures.get(function))
+        else {
+            return;
+        };
+
+        for symbol in captured {
+            if self.tracked.contains(symbol) && !assigned.contains(symbol) {
+                self.diagnostics.push(Diagnostic::error(
+                    format!(
+                        "Flow error: Function '{}' reads '{}' before it is assigned on every path",
+                        name, self.resolution.symbols[*symbol].name
+                    ),
+                    Some(span),
+                ));
+            }
+        }
+    }
+
+    fn collect_uninitialized(&mut self, element: &CfgElement) {
+        let CfgElement::Statement(Statement::VariableDeclaration { variables, .. }) = element
+        else {
+            return;
+        };
+
+        for variable in variables {
+            if let Expression::Variable {
+                identifier,
+                initializer: None,
+                ..
+            } = variable
+            {
+                self.tracked.extend(self.variable(identifier));
+            }
+        }
+    }
+}
+
+impl MustAnalysis for DefiniteAssignment<'_> {
+    ///
+    /// Variables assigned on every path
+    ///
+    type Facts = HashSet<usize>;
+
+    fn initial(&self) -> Self::Facts {
+        HashSet::new()
+    }
+
+    fn unreached(&self) -> Self::Facts {
+        self.tracked.clone()
+    }
+
+    fn meet(&self, left: &Self::Facts, right: &Self::Facts) -> Self::Facts {
+        left.intersection(right).copied().collect()
+    }
+
+    ///
+    /// Reads are checked against the assigned variables and assignments add
+    /// to them
+    ///
+    fn transfer(
+        &mut self,
+        expression: &Expression,
+        assigned: &mut Self::Facts,
+        report: bool,
+    ) -> bool {
+        match expression {
+            Expression::Variable {
+                identifier,
+                initializer,
+                ..
+            } => {
+                // Destructured variables always have an initializer and are not tracked
+                let symbol = self.variable(identifier);
+                match initializer {
+                    Some(initializer) => {
+                        walk_expression(self, initializer, assigned, report);
+                        assigned.extend(symbol);
+                    }
+                    // Loop bodies declare the variable again on every iteration
+                    None => {
+                        if let Some(symbol) = symbol {
+                            assigned.remove(&symbol);
+                        }
+                    }
+                }
+            }
+            Expression::Identifier { name, span } => {
+                if report {
+                    self.check_captures(name, *span, assigned);
+                }
+                let Some(symbol) = self.variable(expression) else {
+                    return true;
+                };
+                if report && self.tracked.contains(&symbol) && !assigned.contains(&symbol) {
+                    self.diagnostics.push(Diagnostic::error(
+                        format!(
+                            "Flow error: Variable '{}' is read before it is assigned on every path",
+                            name
+                        ),
+                        Some(*span),
+                    ));
+                }
+            }
+            Expression::Assignment {
+                operator,
+                left,
+                right,
+                ..
+            } => {
+                let Some(symbol) = self.variable(left) else {
+                    return false;
+                };
+                if *operator != AssignmentOperator::Assign {
+                    walk_expression(self, left, assigned, report);
+                }
+                walk_expression(self, right, assigned, report);
+                assigned.insert(symbol);
+            }
+            _ => return false,
+        }
+
+        true
+    }
+}
@@ -0,0 +1,218 @@
+use std::{collections::HashSet, ptr};
+
+use crate::{
+    ast::{AssignmentOperator, ClassMember, Expression, Statement, Type},
+    cfg::ControlFlowGraph,
+    diagnostics::Diagnostic,
+};
+
+use super::analysis_dataflow::{solve_must_analysis, walk_expression, MustAnalysis};
+
+///
+/// Fields of a class that need a value from its constructor, they have no
+/// initializer and their type does not accept nil. Inherited fields are
+/// left to the constructors of the superclasses, subclass constructors have
+/// to call them on every path
+///
+pub(super) struct RequiredFields<'a> {
+    class_name: String,
+    constructor: Option<&'a Statement>,
+    fields: Vec<(&'a String, &'a Expression)>,
+}
+
+///
+/// Classes with required fields, classes declared in function bodies
+/// included
+///
+pub(super) fn collect_required_fields<'a>(
+    statement: &'a Statement,
+    classes: &mut Vec<RequiredFields<'a>>,
+) {
+    match statement {
+        Statement::Program { body, .. } | Statement::Block { body, .. } => {
+            for statement in body {
+                collect_required_fields(statement, classes);
+            }
+        }
+        Statement::If {
+            consequent,
+            alternative,
+            ..
+        } => {
+            collect_required_fields(consequent, classes);
+            if let Some(alternative) = alternative {
+                collect_required_fields(alternative, classes);
+            }
+        }
+        Statement::While { body, .. }
+        | Statement::DoWhile { body, .. }
+        | Statement::For { body, .. }
+        | Statement::Labeled { body, .. }
+        | Statement::FunctionDeclaration { body, .. } => collect_required_fields(body, classes),
+        Statement::ClassDeclaration { name, members, .. } => {
+            let mut constructor = None;
+            let mut fields = vec![];
+            for member in members {
+                match member {
+                    ClassMember::Field {
+                        name,
+                        type_annotation,
+                        initializer: None,
+                        ..
+                    } if !matches!(type_annotation, Type::Nullable(_) | Type::Nil) => {
+                        if let Expression::Identifier { name: field, .. } = name.as_ref() {
+                            fields.push((field, name.as_ref()));
+                        }
+                    }
+                    ClassMember::Field { .. } => {}
+                    ClassMember::Constructor { declaration } => {
+                        constructor = Some(declaration.as_ref());
+                        collect_required_fields(declaration, classes);
+                    }
+                    ClassMember::Method { declaration } => {
+                        collect_required_fields(declaration, classes)
+                    }
+                }
+            }
+
+            if !fields.is_empty() {
+                let class_name = match name.as_ref() {
+                    Expression::Identifier { name, .. } => name.clone(),
+                    _ => String::new(),
+                };
+                classes.push(RequiredFields {
+                    class_name,
+                    constructor,
+                    fields,
+                });
+            }
+        }
+        _ => {}
+    }
+}
+
+///
+/// Report required fields of classes without a constructor, nothing ever
+/// assigns them
+///
+pub(super) fn check_classes_without_constructor(classes: &[RequiredFields]) -> Vec<Diagnostic> {
+    classes
+        .iter()
+        .filter(|class| class.constructor.is_none())
+        .flat_map(|class| {
+            class.fields.iter().map(|(field, name)| {
+                Diagnostic::error(
+                    format!(
+                        "Flow error: Field '{}' of class '{}' needs an initializer or a constructor assigning it",
+                        field, class.class_name
+                    ),
+                    Some(name.span()),
+                )
+            })
+        })
+        .collect()
+}
+
+///
+/// Report required fields that the constructor does not assign on every
+/// path to its end
+///
+pub(super) fn check_field_assignments(
+    graph: &ControlFlowGraph,
+    classes: &[RequiredFields],
+) -> Vec<Diagnostic> {
+    let Some(class) = classes.iter().find(|class| {
+        class
+            .constructor
+            .zip(graph.declaration)
+            .is_some_and(|(constructor, declaration)| ptr::eq(constructor, declaration))
+    }) else {
+        return vec![];
+    };
+    let mut analysis = FieldAssignments {
+        required: class
+            .fields
+            .iter()
+            .map(|(field, _)| field.to_string())
+            .collect(),
+    };
+    let assigned = solve_must_analysis(graph, &mut analysis);
+
+    class
+        .fields
+        .iter()
+        .filter(|(field, _)| !assigned.contains(*field))
+        .map(|(field, name)| {
+            Diagnostic::error(
+                format!(
+                    "Flow error: Field '{}' is not assigned on every path of the constructor of '{}'",
+                    field, class.class_name
+                ),
+                Some(name.span()),
+            )
+        })
+        .collect()
+}
+
+///
+/// Fields a constructor assigns through 'this'
+///
+struct FieldAssignments {
+    required: HashSet<String>,
+}
+
+impl MustAnalysis for FieldAssignments {
+    ///
+    /// Fields assigned on every path
+    ///
+    type Facts = HashSet<String>;
+
+    fn initial(&self) -> Self::Facts {
+        HashSet::new()
+    }
+
+    fn unreached(&self) -> Self::Facts {
+        self.required.clone()
+    }
+
+    fn meet(&self, left: &Self::Facts, right: &Self::Facts) -> Self::Facts {
+        left.intersection(right).cloned().collect()
+    }
+
+    fn transfer(
+        &mut self,
+        expression: &Expression,
+        assigned: &mut Self::Facts,
+        report: bool,
+    ) -> bool {
+        let Expression::Assignment {
+            operator,
+            left,
+            right,
+            ..
+        } = expression
+        else {
+            return false;
+        };
+        let Expression::Member {
+            object,
+            property,
+            computed: false,
+            ..
+        } = left.as_ref()
+        else {
+            return false;
+        };
+        let (Expression::This { .. }, Expression::Identifier { name, .. }) =
+            (object.as_ref(), property.as_ref())
+        else {
+            return false;
+        };
+
+        walk_expression(self, right, assigned, report);
+        if *operator == AssignmentOperator::Assign {
+            assigned.insert(name.clone());
+        }
+        true
+    }
+}
@@ -0,0 +1,109 @@
+use crate::{
+    ast::{Expression, Statement, Type},
+    cfg::{CfgElement, ControlFlowGraph},
+    diagnostics::Diagnostic,
+    lexer::Span,
+};
+
+///
+/// Report functions with a return type that can reach the end of their body
+/// or return without a value
+///
+pub(super) fn check_missing_returns(graph: &ControlFlowGraph) -> Vec<Diagnostic> {
+    let Some(Statement::FunctionDeclaration {
+        name, return_type, ..
+    }) = graph.declaration
+    else {
+        return vec![];
+    };
+    if *return_type == Type::Void {
+        return vec![];
+    }
+    let function_name = match name.as_ref() {
+        Expression::Identifier { name, .. } => name.as_str(),
+        _ => "",
+    };
+
+    let reachable = graph.reachable_blocks();
+    let mut diagnostics = vec![];
+
+    for block in (0..graph.blocks.len()).filter(|block| reachable[*block]) {
+        for element in &graph.blocks[block].elements {
+            if let CfgElement::Statement(Statement::Return {
+                argument: None,
+                span,
+            }) = element
+            {
+                diagnostics.push(Diagnostic::error(
+                    format!(
+                        "Flow error: Function '{}' must return a value of type '{}'",
+                        function_name, return_type
+                    ),
+                    Some(*span),
+                ));
+            }
+        }
+    }
+
+    // Every block leading to the exit ends with a return unless it falls off the end
+    let falls_off_end = graph
+        .predecessors(ControlFlowGraph::EXIT)
+        .filter(|edge| reachable[edge.from])
+        .any(|edge| {
+            !matches!(
+                graph.blocks[edge.from].elements.last(),
+                Some(CfgElement::Statement(Statement::Return { .. }))
+            )
+        });
+    if falls_off_end {
+        diagnostics.push(Diagnostic::error(
+            format!(
+                "Flow error: Function '{}' can reach the end of its body without returning a value",
+                function_name
+            ),
+            Some(name.span()),
+        ));
+    }
+
+    diagnostics
+}
+
+///
+/// Warn about code that follows a return, a break or a continue on every
+/// path, once per unreachable region
+///
+pub(super) fn check_unreachable_code(graph: &ControlFlowGraph) -> Vec<Diagnostic> {
+    let reachable = graph.reachable_blocks();
+    let mut covered = vec![false; graph.blocks.len()];
+    let mut diagnostics = vec![];
+
+    // Blocks are created in source order, so a region starts at its lowest block
+    for start in 0..graph.blocks.len() {
+        if reachable[start] || covered[start] {
+            continue;
+        }
+
+        let mut first: Option<Span> = None;
+        let mut pending = vec![start];
+        while let Some(block) = pending.pop() {
+            if reachable[block] || covered[block] {
+                continue;
+            }
+            covered[block] = true;
+
+            for element in &graph.blocks[block].elements {
+                let span = element.span();
+                if first.is_none_or(|first| span.start.offset < first.start.offset) {
+                    first = Some(span);
+                }
+            }
+            pending.extend(graph.successors(block).map(|edge| edge.to));
+        }
+
+        if let Some(span) = first {
+            diagnostics.push(Diagnostic::warning("Unreachable code", Some(span)));
+        }
+    }
+
+    diagnostics
+}
@@ -0,0 +1,229 @@
+use std::{collections::HashSet, ptr};
+
+use crate::{
+    ast::{ClassMember, Expression, Statement},
+    cfg::ControlFlowGraph,
+    diagnostics::Diagnostic,
+    resolver::Resolution,
+};
+
+use super::analysis_dataflow::{solve_must_analysis, walk_expression, MustAnalysis};
+
+///
+/// Constructor of a subclass, the 'super' call is required when one of the
+/// superclasses declares a constructor
+///
+pub(super) struct DerivedConstructor<'a> {
+    class_name: String,
+    declaration: &'a Statement,
+    requires_super_call: bool,
+}
+
+///
+/// Report uses of 'this' and 'super' members in constructors of subclasses
+/// that are not preceded by a 'super' call on every path, and constructors
+/// that can end without a required 'super' call
+///
+pub(super) fn check_super_calls(
+    graph: &ControlFlowGraph,
+    derived_constructors: &[DerivedConstructor],
+) -> Vec<Diagnostic> {
+    let Some(constructor) = derived_constructors.iter().find(|constructor| {
+        graph
+            .declaration
+            .is_some_and(|declaration| ptr::eq(constructor.declaration, declaration))
+    }) else {
+        return vec![];
+    };
+
+    let mut analysis = SuperCalls::default();
+    let called = solve_must_analysis(graph, &mut analysis);
+
+    if constructor.requires_super_call && !called {
+        if let Statement::FunctionDeclaration { name, .. } = constructor.declaration {
+            analysis.diagnostics.push(Diagnostic::error(
+                format!(
+                    "Flow error: Constructor of '{}' does not call 'super' on every path",
+                    constructor.class_name
+                ),
+                Some(name.span()),
+            ));
+        }
+    }
+
+    analysis.diagnostics
+}
+
+///
+/// Constructors of the classes with a superclass, classes declared in
+/// function bodies included
+///
+pub(super) fn collect_derived_constructors<'a>(
+    program: &'a Statement,
+    resolution: &Resolution,
+) -> Vec<DerivedConstructor<'a>> {
+    let mut classes = vec![];
+    collect_classes(program, &mut classes);
+
+    // Superclasses are found through the symbol their name refers to
+    let super_class = |class: &ClassDeclaration| {
+        let symbol = resolution.symbol_of(class.super_class?.span())?;
+        classes
+            .iter()
+            .find(|other| other.name.span() == symbol.span)
+    };
+
+    classes
+        .iter()
+        .filter(|class| class.super_class.is_some())
+        .filter_map(|class| {
+            let declaration = class.constructor?;
+            let mut requires_super_call = false;
+            let mut visited = HashSet::new();
+            let mut current = super_class(class);
+            while let Some(ancestor) = current {
+                if !visited.insert(ancestor.name.span().start.offset) {
+                    break;
+                }
+                if ancestor.constructor.is_some() {
+                    requires_super_call = true;
+                    break;
+                }
+                current = super_class(ancestor);
+            }
+
+            let class_name = match class.name {
+                Expression::Identifier { name, .. } => name.clone(),
+                _ => String::new(),
+            };
+            Some(DerivedConstructor {
+                class_name,
+                declaration,
+                requires_super_call,
+            })
+        })
+        .collect()
+}
+
+struct ClassDeclaration<'a> {
+    name: &'a Expression,
+    super_class: Option<&'a Expression>,
+    constructor: Option<&'a Statement>,
+}
+
+fn collect_classes<'a>(statement: &'a Statement, classes: &mut Vec<ClassDeclaration<'a>>) {
+    match statement {
+        Statement::Program { body, .. } | Statement::Block { body, .. } => {
+            for statement in body {
+                collect_classes(statement, classes);
+            }
+        }
+        Statement::If {
+            consequent,
+            alternative,
+            ..
+        } => {
+            collect_classes(consequent, classes);
+            if let Some(alternative) = alternative {
+                collect_classes(alternative, classes);
+            }
+        }
+        Statement::While { body, .. }
+        | Statement::DoWhile { body, .. }
+        | Statement::For { body, .. }
+        | Statement::Labeled { body, .. }
+        | Statement::FunctionDeclaration { body, .. } => collect_classes(body, classes),
+        Statement::ClassDeclaration {
+            name,
+            super_class,
+            members,
+            ..
+        } => {
+            let mut constructor = None;
+            for member in members {
+                match member {
+                    ClassMember::Constructor { declaration } => {
+                        constructor = Some(declaration.as_ref());
+                        collect_classes(declaration, classes);
+                    }
+                    ClassMember::Method { declaration } => collect_classes(declaration, classes),
+                    ClassMember::Field { .. } => {}
+                }
+            }
+            classes.push(ClassDeclaration {
+                name,
+                super_class: super_class.as_deref(),
+                constructor,
+            });
+        }
+        _ => {}
+    }
+}
+
+///
+/// Whether 'super' was called, uses of 'this' and 'super' are reported
+/// until it was
+///
+#[derive(Default)]
+struct SuperCalls {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl MustAnalysis for SuperCalls {
+    ///
+    /// Whether 'super' was called on every path
+    ///
+    type Facts = bool;
+
+    fn initial(&self) -> Self::Facts {
+        false
+    }
+
+    fn unreached(&self) -> Self::Facts {
+        true
+    }
+
+    fn meet(&self, left: &Self::Facts, right: &Self::Facts) -> Self::Facts {
+        *left && *right
+    }
+
+    ///
+    /// The call of the parent constructor happens after its arguments are
+    /// evaluated
+    ///
+    fn transfer(
+        &mut self,
+        expression: &Expression,
+        called: &mut Self::Facts,
+        report: bool,
+    ) -> bool {
+        match expression {
+            Expression::This { span } | Expression::Super { span } => {
+                if report && !*called {
+                    let keyword = match expression {
+                        Expression::This { .. } => "this",
+                        _ => "super",
+                    };
+                    self.diagnostics.push(Diagnostic::error(
+                        format!(
+                            "Flow error: '{}' is used before 'super' is called on every path",
+                            keyword
+                        ),
+                        Some(*span),
+                    ));
+                }
+            }
+            Expression::Call {
+                callee, arguments, ..
+            } if matches!(callee.as_ref(), Expression::Super { .. }) => {
+                for argument in arguments {
+                    walk_expression(self, argument, called, report);
+                }
+                *called = true;
+            }
+            _ => return false,
+        }
+
+        true
+    }
+}
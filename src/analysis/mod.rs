@@ -0,0 +1,9 @@
+mod analysis;
+mod analysis_dataflow;
+mod analysis_definite_assignment;
+mod analysis_field_assignments;
+mod analysis_returns;
+mod analysis_super_calls;
+mod tests;
+
+pub(crate) use self::analysis::*;
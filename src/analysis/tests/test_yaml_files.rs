@@ -0,0 +1,21 @@
+use super::internal_util::execute_yaml_test_cases;
+
+#[test]
+fn test_analysis_definite_assignment() {
+    execute_yaml_test_cases("tests/analysis/test_analysis_definite_assignment.yaml");
+}
+
+#[test]
+fn test_analysis_field_assignments() {
+    execute_yaml_test_cases("tests/analysis/test_analysis_field_assignments.yaml");
+}
+
+#[test]
+fn test_analysis_returns() {
+    execute_yaml_test_cases("tests/analysis/test_analysis_returns.yaml");
+}
+
+#[test]
+fn test_analysis_super_calls() {
+    execute_yaml_test_cases("tests/analysis/test_analysis_super_calls.yaml");
+}
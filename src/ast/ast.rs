@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::lexer::Span;
+
 use super::{
     ast_operators::{AssignmentOperator, BinaryOperator, LogicalOperator, UnaryOperator},
     ast_types::Type,
@@ -10,42 +12,71 @@ pub type ExpressionRef = Box<Expression>;
 pub type StatementList = Vec<Statement>;
 pub type ExpressionList = Vec<Expression>;
 pub type ParameterList = Vec<(Expression, Type)>;
+pub type ClassMemberList = Vec<ClassMember>;
+pub type InterfaceMemberList = Vec<InterfaceMember>;
+pub type EnumVariantList = Vec<EnumVariant>;
+pub type TypeParameterList = Vec<TypeParameter>;
+pub type MatchArmList = Vec<MatchArm>;
+pub type PatternList = Vec<Pattern>;
+pub type PatternPropertyList = Vec<PatternProperty>;
+
+///
+/// Name of the method used as a class constructor
+///
+pub const CONSTRUCTOR_NAME: &str = "constructor";
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "PascalCase")]
 pub enum Statement {
     Program {
         body: StatementList,
+        #[serde(skip)]
+        span: Span,
     },
 
     Block {
         body: StatementList,
+        #[serde(skip)]
+        span: Span,
     },
 
-    Empty,
+    Empty {
+        #[serde(skip)]
+        span: Span,
+    },
 
     Expression {
         expression: ExpressionRef,
+        #[serde(skip)]
+        span: Span,
     },
 
     VariableDeclaration {
         variables: ExpressionList,
+        #[serde(skip)]
+        span: Span,
     },
 
     If {
         condition: ExpressionRef,
         consequent: StatementRef,
         alternative: Option<StatementRef>,
+        #[serde(skip)]
+        span: Span,
     },
 
     While {
         condition: ExpressionRef,
         body: StatementRef,
+        #[serde(skip)]
+        span: Span,
     },
 
     DoWhile {
         body: StatementRef,
         condition: ExpressionRef,
+        #[serde(skip)]
+        span: Span,
     },
 
     For {
@@ -53,24 +84,236 @@ pub enum Statement {
         condition: Option<ExpressionRef>,
         increment: Option<ExpressionRef>,
         body: StatementRef,
+        #[serde(skip)]
+        span: Span,
     },
 
     FunctionDeclaration {
         name: ExpressionRef,
+        type_parameters: TypeParameterList,
         parameters: ParameterList,
         return_type: Type,
         body: StatementRef,
+        #[serde(skip)]
+        span: Span,
     },
 
     Return {
         argument: Option<ExpressionRef>,
+        #[serde(skip)]
+        span: Span,
+    },
+
+    ///
+    /// Leave the innermost loop, or the enclosing loop with the label
+    ///
+    Break {
+        label: Option<ExpressionRef>,
+        #[serde(skip)]
+        span: Span,
+    },
+
+    ///
+    /// Skip to the next iteration of the innermost loop, or of the enclosing
+    /// loop with the label
+    ///
+    Continue {
+        label: Option<ExpressionRef>,
+        #[serde(skip)]
+        span: Span,
+    },
+
+    ///
+    /// Loop named by a label, 'outer: while (...) ...'
+    ///
+    Labeled {
+        label: ExpressionRef,
+        body: StatementRef,
+        #[serde(skip)]
+        span: Span,
     },
 
     ClassDeclaration {
         name: ExpressionRef,
+        type_parameters: TypeParameterList,
         super_class: Option<ExpressionRef>,
-        body: StatementRef,
+        implements: ExpressionList,
+        members: ClassMemberList,
+        #[serde(skip)]
+        span: Span,
+    },
+
+    ///
+    /// Fields and methods a class promises to have by implementing the interface
+    ///
+    InterfaceDeclaration {
+        name: ExpressionRef,
+        members: InterfaceMemberList,
+        #[serde(skip)]
+        span: Span,
+    },
+
+    ///
+    /// Type whose values are built from one of its variants
+    ///
+    EnumDeclaration {
+        name: ExpressionRef,
+        variants: EnumVariantList,
+        #[serde(skip)]
+        span: Span,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "PascalCase")]
+pub enum ClassMember {
+    Field {
+        name: ExpressionRef,
+        type_annotation: Type,
+        initializer: Option<ExpressionRef>,
+        #[serde(skip)]
+        span: Span,
+    },
+
+    ///
+    /// Function declaration called on an instance, 'this' refers to the instance
+    ///
+    Method { declaration: StatementRef },
+
+    ///
+    /// Function declaration named 'constructor', called by 'new' with its arguments
+    ///
+    Constructor { declaration: StatementRef },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "PascalCase")]
+pub enum InterfaceMember {
+    Field {
+        name: ExpressionRef,
+        type_annotation: Type,
+        #[serde(skip)]
+        span: Span,
+    },
+
+    ///
+    /// Signature of a method, without a body
+    ///
+    Method {
+        name: ExpressionRef,
+        parameters: ParameterList,
+        return_type: Type,
+        #[serde(skip)]
+        span: Span,
+    },
+}
+
+///
+/// Variant of an enum with the types of the values it carries
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnumVariant {
+    pub name: ExpressionRef,
+    pub payload: Vec<Type>,
+    #[serde(skip)]
+    pub span: Span,
+}
+
+///
+/// Arm of a match, its value is the result when the pattern matches and
+/// the guard holds
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub guard: Option<ExpressionRef>,
+    pub value: ExpressionRef,
+    #[serde(skip)]
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "PascalCase")]
+pub enum Pattern {
+    ///
+    /// Any value, '_', or any value of a type, '_: Type'
+    ///
+    Wildcard {
+        type_annotation: Option<Type>,
+        #[serde(skip)]
+        span: Span,
+    },
+
+    ///
+    /// Any value bound to a name, 'name', or any value of a type, 'name: Type'
+    ///
+    Binding {
+        name: ExpressionRef,
+        type_annotation: Option<Type>,
+        #[serde(skip)]
+        span: Span,
+    },
+
+    ///
+    /// Value equal to a boolean, nil, numeric or string literal
+    ///
+    Literal {
+        value: ExpressionRef,
+        #[serde(skip)]
+        span: Span,
     },
+
+    ///
+    /// Value built from a variant, 'Enum::Variant(patterns)', with its values
+    /// matching the nested patterns
+    ///
+    EnumVariant {
+        enum_name: ExpressionRef,
+        variant: ExpressionRef,
+        arguments: PatternList,
+        #[serde(skip)]
+        span: Span,
+    },
+
+    ///
+    /// Array with as many elements as nested patterns, '[patterns]'
+    ///
+    Array {
+        elements: PatternList,
+        #[serde(skip)]
+        span: Span,
+    },
+
+    ///
+    /// Instance whose fields match the nested patterns, '{ field: pattern }'
+    ///
+    Object {
+        properties: PatternPropertyList,
+        #[serde(skip)]
+        span: Span,
+    },
+}
+
+///
+/// Field of an object pattern with the pattern its value has to match,
+/// '{ name }' is short for '{ name: name }'
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PatternProperty {
+    pub key: ExpressionRef,
+    pub value: Pattern,
+    #[serde(skip)]
+    pub span: Span,
+}
+
+///
+/// Type parameter of a generic function or class, its type arguments have
+/// to be assignable to the bound
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypeParameter {
+    pub name: ExpressionRef,
+    pub bound: Option<Type>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -78,68 +321,632 @@ pub enum Statement {
 pub enum Expression {
     Variable {
         identifier: ExpressionRef,
-        type_annotation: Type,
+        type_annotation: Option<Type>,
         initializer: Option<ExpressionRef>,
+        #[serde(skip)]
+        span: Span,
     },
 
     Assignment {
         operator: AssignmentOperator,
         left: ExpressionRef,
         right: ExpressionRef,
+        #[serde(skip)]
+        span: Span,
     },
 
     Binary {
         operator: BinaryOperator,
         left: ExpressionRef,
         right: ExpressionRef,
+        #[serde(skip)]
+        span: Span,
     },
 
     Unary {
         operator: UnaryOperator,
         right: ExpressionRef,
+        #[serde(skip)]
+        span: Span,
     },
 
     Logical {
         operator: LogicalOperator,
         left: ExpressionRef,
         right: ExpressionRef,
+        #[serde(skip)]
+        span: Span,
     },
 
     BooleanLiteral {
         value: bool,
+        #[serde(skip)]
+        span: Span,
     },
 
-    NilLiteral,
+    NilLiteral {
+        #[serde(skip)]
+        span: Span,
+    },
 
     StringLiteral {
         value: String,
+        #[serde(skip)]
+        span: Span,
     },
 
     NumericLiteral {
         value: i32,
+        #[serde(skip)]
+        span: Span,
     },
 
     Identifier {
         name: String,
+        #[serde(skip)]
+        span: Span,
+    },
+
+    ///
+    /// Array holding the values of its elements in order, '[elements]'
+    ///
+    ArrayLiteral {
+        elements: ExpressionList,
+        #[serde(skip)]
+        span: Span,
     },
 
     Member {
         computed: bool,
+        ///
+        /// Access with '?.', a nil object gives nil instead of failing
+        ///
+        optional: bool,
         object: ExpressionRef,
         property: ExpressionRef,
+        #[serde(skip)]
+        span: Span,
     },
 
     Call {
         callee: ExpressionRef,
         arguments: ExpressionList,
+        #[serde(skip)]
+        span: Span,
     },
 
-    This {},
+    This {
+        #[serde(skip)]
+        span: Span,
+    },
 
-    Super {},
+    Super {
+        #[serde(skip)]
+        span: Span,
+    },
 
     New {
         callee: ExpressionRef,
         arguments: ExpressionList,
+        #[serde(skip)]
+        span: Span,
+    },
+
+    ///
+    /// Runtime test of the type of a value, 'value is Type'
+    ///
+    TypeTest {
+        expression: ExpressionRef,
+        type_annotation: Type,
+        #[serde(skip)]
+        span: Span,
     },
+
+    ///
+    /// Value of an enum built from one of its variants, 'Enum::Variant(arguments)'
+    ///
+    EnumVariant {
+        enum_name: ExpressionRef,
+        variant: ExpressionRef,
+        arguments: ExpressionList,
+        #[serde(skip)]
+        span: Span,
+    },
+
+    ///
+    /// Names a declaration binds to the parts of its value, it takes the place
+    /// of the identifier of a variable or a parameter, '[a, b]' or '{ x, y }'
+    ///
+    Destructuring {
+        pattern: Pattern,
+        #[serde(skip)]
+        span: Span,
+    },
+
+    ///
+    /// Value of the first arm whose pattern matches the subject,
+    /// 'match (subject) { pattern [if guard] => value, ... }'
+    ///
+    Match {
+        subject: ExpressionRef,
+        arms: MatchArmList,
+        #[serde(skip)]
+        span: Span,
+    },
+}
+
+impl Statement {
+    ///
+    /// Source range covered by the statement
+    ///
+    pub fn span(&self) -> Span {
+        match self {
+            Statement::Program { span, .. }
+            | Statement::Block { span, .. }
+            | Statement::Empty { span }
+            | Statement::Expression { span, .. }
+            | Statement::VariableDeclaration { span, .. }
+            | Statement::If { span, .. }
+            | Statement::While { span, .. }
+            | Statement::DoWhile { span, .. }
+            | Statement::For { span, .. }
+            | Statement::FunctionDeclaration { span, .. }
+            | Statement::Return { span, .. }
+            | Statement::Break { span, .. }
+            | Statement::Continue { span, .. }
+            | Statement::Labeled { span, .. }
+            | Statement::ClassDeclaration { span, .. }
+            | Statement::InterfaceDeclaration { span, .. }
+            | Statement::EnumDeclaration { span, .. } => *span,
+        }
+    }
+}
+
+impl ClassMember {
+    ///
+    /// Source range covered by the member
+    ///
+    pub fn span(&self) -> Span {
+        match self {
+            ClassMember::Field { span, .. } => *span,
+            ClassMember::Method { declaration } | ClassMember::Constructor { declaration } => {
+                declaration.span()
+            }
+        }
+    }
+
+    ///
+    /// Apply the callback to the span of the member and of every node inside it
+    ///
+    pub fn for_each_span_mut(&mut self, callback: &mut dyn FnMut(&mut Span)) {
+        match self {
+            ClassMember::Field {
+                name,
+                initializer,
+                span,
+                ..
+            } => {
+                callback(span);
+                name.for_each_span_mut(callback);
+                if let Some(initializer) = initializer {
+                    initializer.for_each_span_mut(callback);
+                }
+            }
+            ClassMember::Method { declaration } | ClassMember::Constructor { declaration } => {
+                declaration.for_each_span_mut(callback);
+            }
+        }
+    }
+}
+
+impl InterfaceMember {
+    ///
+    /// Source range covered by the member
+    ///
+    pub fn span(&self) -> Span {
+        match self {
+            InterfaceMember::Field { span, .. } | InterfaceMember::Method { span, .. } => *span,
+        }
+    }
+
+    ///
+    /// Apply the callback to the span of the member and of every node inside it
+    ///
+    pub fn for_each_span_mut(&mut self, callback: &mut dyn FnMut(&mut Span)) {
+        match self {
+            InterfaceMember::Field { name, span, .. } => {
+                callback(span);
+                name.for_each_span_mut(callback);
+            }
+            InterfaceMember::Method {
+                name,
+                parameters,
+                span,
+                ..
+            } => {
+                callback(span);
+                name.for_each_span_mut(callback);
+                for (parameter, _) in parameters {
+                    parameter.for_each_span_mut(callback);
+                }
+            }
+        }
+    }
+}
+
+impl Expression {
+    ///
+    /// Source range covered by the expression
+    ///
+    pub fn span(&self) -> Span {
+        match self {
+            Expression::Variable { span, .. }
+            | Expression::Assignment { span, .. }
+            | Expression::Binary { span, .. }
+            | Expression::Unary { span, .. }
+            | Expression::Logical { span, .. }
+            | Expression::BooleanLiteral { span, .. }
+            | Expression::NilLiteral { span }
+            | Expression::StringLiteral { span, .. }
+            | Expression::NumericLiteral { span, .. }
+            | Expression::Identifier { span, .. }
+            | Expression::ArrayLiteral { span, .. }
+            | Expression::Member { span, .. }
+            | Expression::Call { span, .. }
+            | Expression::This { span }
+            | Expression::Super { span }
+            | Expression::New { span, .. }
+            | Expression::TypeTest { span, .. }
+            | Expression::EnumVariant { span, .. }
+            | Expression::Destructuring { span, .. }
+            | Expression::Match { span, .. } => *span,
+        }
+    }
+}
+
+impl Statement {
+    ///
+    /// Apply the callback to the span of the statement and of every node inside it
+    ///
+    pub fn for_each_span_mut(&mut self, callback: &mut dyn FnMut(&mut Span)) {
+        match self {
+            Statement::Program { body, span } | Statement::Block { body, span } => {
+                callback(span);
+                for statement in body {
+                    statement.for_each_span_mut(callback);
+                }
+            }
+            Statement::Empty { span } => callback(span),
+            Statement::Expression { expression, span } => {
+                callback(span);
+                expression.for_each_span_mut(callback);
+            }
+            Statement::VariableDeclaration { variables, span } => {
+                callback(span);
+                for variable in variables {
+                    variable.for_each_span_mut(callback);
+                }
+            }
+            Statement::If {
+                condition,
+                consequent,
+                alternative,
+                span,
+            } => {
+                callback(span);
+                condition.for_each_span_mut(callback);
+                consequent.for_each_span_mut(callback);
+                if let Some(alternative) = alternative {
+                    alternative.for_each_span_mut(callback);
+                }
+            }
+            Statement::While {
+                condition,
+                body,
+                span,
+            }
+            | Statement::DoWhile {
+                body,
+                condition,
+                span,
+            } => {
+                callback(span);
+                condition.for_each_span_mut(callback);
+                body.for_each_span_mut(callback);
+            }
+            Statement::For {
+                initializer,
+                condition,
+                increment,
+                body,
+                span,
+            } => {
+                callback(span);
+                if let Some(initializer) = initializer {
+                    initializer.for_each_span_mut(callback);
+                }
+                if let Some(condition) = condition {
+                    condition.for_each_span_mut(callback);
+                }
+                if let Some(increment) = increment {
+                    increment.for_each_span_mut(callback);
+                }
+                body.for_each_span_mut(callback);
+            }
+            Statement::FunctionDeclaration {
+                name,
+                type_parameters,
+                parameters,
+                body,
+                span,
+                ..
+            } => {
+                callback(span);
+                name.for_each_span_mut(callback);
+                for type_parameter in type_parameters {
+                    type_parameter.name.for_each_span_mut(callback);
+                }
+                for (parameter, _) in parameters {
+                    parameter.for_each_span_mut(callback);
+                }
+                body.for_each_span_mut(callback);
+            }
+            Statement::Return { argument, span } => {
+                callback(span);
+                if let Some(argument) = argument {
+                    argument.for_each_span_mut(callback);
+                }
+            }
+            Statement::Break { label, span } | Statement::Continue { label, span } => {
+                callback(span);
+                if let Some(label) = label {
+                    label.for_each_span_mut(callback);
+                }
+            }
+            Statement::Labeled { label, body, span } => {
+                callback(span);
+                label.for_each_span_mut(callback);
+                body.for_each_span_mut(callback);
+            }
+            Statement::ClassDeclaration {
+                name,
+                type_parameters,
+                super_class,
+                implements,
+                members,
+                span,
+            } => {
+                callback(span);
+                name.for_each_span_mut(callback);
+                for type_parameter in type_parameters {
+                    type_parameter.name.for_each_span_mut(callback);
+                }
+                if let Some(super_class) = super_class {
+                    super_class.for_each_span_mut(callback);
+                }
+                for interface in implements {
+                    interface.for_each_span_mut(callback);
+                }
+                for member in members {
+                    member.for_each_span_mut(callback);
+                }
+            }
+            Statement::InterfaceDeclaration {
+                name,
+                members,
+                span,
+            } => {
+                callback(span);
+                name.for_each_span_mut(callback);
+                for member in members {
+                    member.for_each_span_mut(callback);
+                }
+            }
+            Statement::EnumDeclaration {
+                name,
+                variants,
+                span,
+            } => {
+                callback(span);
+                name.for_each_span_mut(callback);
+                for variant in variants {
+                    callback(&mut variant.span);
+                    variant.name.for_each_span_mut(callback);
+                }
+            }
+        }
+    }
+}
+
+impl Expression {
+    ///
+    /// Apply the callback to the span of the expression and of every node inside it
+    ///
+    pub fn for_each_span_mut(&mut self, callback: &mut dyn FnMut(&mut Span)) {
+        match self {
+            Expression::Variable {
+                identifier,
+                initializer,
+                span,
+                ..
+            } => {
+                callback(span);
+                identifier.for_each_span_mut(callback);
+                if let Some(initializer) = initializer {
+                    initializer.for_each_span_mut(callback);
+                }
+            }
+            Expression::Assignment {
+                left, right, span, ..
+            }
+            | Expression::Binary {
+                left, right, span, ..
+            }
+            | Expression::Logical {
+                left, right, span, ..
+            } => {
+                callback(span);
+                left.for_each_span_mut(callback);
+                right.for_each_span_mut(callback);
+            }
+            Expression::Unary { right, span, .. }
+            | Expression::TypeTest {
+                expression: right,
+                span,
+                ..
+            } => {
+                callback(span);
+                right.for_each_span_mut(callback);
+            }
+            Expression::Member {
+                object,
+                property,
+                span,
+                ..
+            } => {
+                callback(span);
+                object.for_each_span_mut(callback);
+                property.for_each_span_mut(callback);
+            }
+            Expression::Call {
+                callee,
+                arguments,
+                span,
+            }
+            | Expression::New {
+                callee,
+                arguments,
+                span,
+            } => {
+                callback(span);
+                callee.for_each_span_mut(callback);
+                for argument in arguments {
+                    argument.for_each_span_mut(callback);
+                }
+            }
+            Expression::ArrayLiteral { elements, span } => {
+                callback(span);
+                for element in elements {
+                    element.for_each_span_mut(callback);
+                }
+            }
+            Expression::EnumVariant {
+                enum_name,
+                variant,
+                arguments,
+                span,
+            } => {
+                callback(span);
+                enum_name.for_each_span_mut(callback);
+                variant.for_each_span_mut(callback);
+                for argument in arguments {
+                    argument.for_each_span_mut(callback);
+                }
+            }
+            Expression::Match {
+                subject,
+                arms,
+                span,
+            } => {
+                callback(span);
+                subject.for_each_span_mut(callback);
+                for arm in arms {
+                    callback(&mut arm.span);
+                    arm.pattern.for_each_span_mut(callback);
+                    if let Some(guard) = &mut arm.guard {
+                        guard.for_each_span_mut(callback);
+                    }
+                    arm.value.for_each_span_mut(callback);
+                }
+            }
+            Expression::Destructuring { pattern, span } => {
+                callback(span);
+                pattern.for_each_span_mut(callback);
+            }
+            Expression::BooleanLiteral { span, .. }
+            | Expression::NilLiteral { span }
+            | Expression::StringLiteral { span, .. }
+            | Expression::NumericLiteral { span, .. }
+            | Expression::Identifier { span, .. }
+            | Expression::This { span }
+            | Expression::Super { span } => callback(span),
+        }
+    }
+}
+
+impl Pattern {
+    ///
+    /// Source range covered by the pattern
+    ///
+    pub fn span(&self) -> Span {
+        match self {
+            Pattern::Wildcard { span, .. }
+            | Pattern::Binding { span, .. }
+            | Pattern::Literal { span, .. }
+            | Pattern::EnumVariant { span, .. }
+            | Pattern::Array { span, .. }
+            | Pattern::Object { span, .. } => *span,
+        }
+    }
+
+    ///
+    /// Names bound by the pattern and its nested patterns, in source order
+    ///
+    pub fn bindings(&self) -> Vec<&Expression> {
+        match self {
+            Pattern::Binding { name, .. } => vec![name],
+            Pattern::EnumVariant {
+                arguments: patterns,
+                ..
+            }
+            | Pattern::Array {
+                elements: patterns, ..
+            } => patterns.iter().flat_map(Pattern::bindings).collect(),
+            Pattern::Object { properties, .. } => properties
+                .iter()
+                .flat_map(|property| property.value.bindings())
+                .collect(),
+            Pattern::Wildcard { .. } | Pattern::Literal { .. } => vec![],
+        }
+    }
+
+    ///
+    /// Apply the callback to the span of the pattern and of every node inside it
+    ///
+    pub fn for_each_span_mut(&mut self, callback: &mut dyn FnMut(&mut Span)) {
+        match self {
+            Pattern::Wildcard { span, .. } => callback(span),
+            Pattern::Binding { name, span, .. } | Pattern::Literal { value: name, span } => {
+                callback(span);
+                name.for_each_span_mut(callback);
+            }
+            Pattern::EnumVariant {
+                enum_name,
+                variant,
+                arguments,
+                span,
+            } => {
+                callback(span);
+                enum_name.for_each_span_mut(callback);
+                variant.for_each_span_mut(callback);
+                for argument in arguments {
+                    argument.for_each_span_mut(callback);
+                }
+            }
+            Pattern::Array { elements, span } => {
+                callback(span);
+                for element in elements {
+                    element.for_each_span_mut(callback);
+                }
+            }
+            Pattern::Object { properties, span } => {
+                callback(span);
+                for property in properties {
+                    callback(&mut property.span);
+                    property.key.for_each_span_mut(callback);
+                    property.value.for_each_span_mut(callback);
+                }
+            }
+        }
+    }
 }
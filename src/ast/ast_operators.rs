@@ -79,6 +79,7 @@ impl fmt::Display for UnaryOperator {
 pub enum LogicalOperator {
     And,
     Or,
+    NilCoalescing,
 }
 
 impl fmt::Display for LogicalOperator {
@@ -86,6 +87,7 @@ impl fmt::Display for LogicalOperator {
         match self {
             LogicalOperator::And => write!(f, "&&"),
             LogicalOperator::Or => write!(f, "||"),
+            LogicalOperator::NilCoalescing => write!(f, "??"),
         }
     }
 }
@@ -25,7 +25,154 @@ pub enum Type {
         type_args: Vec<Type>,
     },
 
+    ///
+    /// Type parameter of the enclosing generic function or class
+    ///
+    Parameter(String),
+
+    ///
+    /// Type that also accepts nil, plain types do not
+    ///
+    Nullable(Box<Type>),
+
+    ///
+    /// Type accepting a value of any of its members, nil is kept out of
+    /// the members by making the union nullable
+    ///
+    Union(Vec<Type>),
+
+    ///
+    /// Enum declared in the program, annotations name it like a class and
+    /// the checker tells them apart
+    ///
+    Enum(String),
+
     Void,
+
+    Nil,
+}
+
+impl Type {
+    pub fn number() -> Self {
+        Type::Primitive(PrimitiveType::Number)
+    }
+
+    pub fn boolean() -> Self {
+        Type::Primitive(PrimitiveType::Boolean)
+    }
+
+    pub fn string() -> Self {
+        Type::Primitive(PrimitiveType::String)
+    }
+
+    ///
+    /// Type accepting nil as well, nullable types are not wrapped twice
+    ///
+    pub fn nullable(value_type: Type) -> Self {
+        match value_type {
+            Type::Nullable(_) | Type::Nil => value_type,
+            _ => Type::Nullable(Box::new(value_type)),
+        }
+    }
+
+    ///
+    /// Type without nil
+    ///
+    pub fn non_nullable(&self) -> &Type {
+        match self {
+            Type::Nullable(value_type) => value_type,
+            _ => self,
+        }
+    }
+
+    ///
+    /// Type accepting any of the given types, nested unions are flattened,
+    /// duplicates are dropped and a nil member makes the union nullable
+    ///
+    pub fn union(types: Vec<Type>) -> Self {
+        let mut members: Vec<Type> = vec![];
+        let mut is_nullable = false;
+
+        for member in types.iter().flat_map(Type::members) {
+            if member == Type::Nil {
+                is_nullable = true;
+            } else if !members.contains(&member) {
+                members.push(member);
+            }
+        }
+
+        let value_type = match members.len() {
+            0 => return Type::Nil,
+            1 => members.remove(0),
+            _ => Type::Union(members),
+        };
+        match is_nullable {
+            true => Type::nullable(value_type),
+            false => value_type,
+        }
+    }
+
+    ///
+    /// Types a value of the type can have, nil included for nullable types
+    ///
+    pub fn members(&self) -> Vec<Type> {
+        match self {
+            Type::Union(members) => members.clone(),
+            Type::Nullable(value_type) => {
+                let mut members = value_type.members();
+                members.push(Type::Nil);
+                members
+            }
+            _ => vec![self.clone()],
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Primitive(PrimitiveType::Number) => write!(f, "number"),
+            Type::Primitive(PrimitiveType::Boolean) => write!(f, "boolean"),
+            Type::Primitive(PrimitiveType::String) => write!(f, "string"),
+            Type::Array(element_type) => write!(f, "[{}]", element_type),
+            Type::Function {
+                params,
+                return_type,
+            } => {
+                let params = params
+                    .iter()
+                    .map(|param| param.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(f, "def({}): {}", params, return_type)
+            }
+            Type::Class { name, .. } => write!(f, "{}", name),
+            Type::Generic { base, type_args } => {
+                let type_args = type_args
+                    .iter()
+                    .map(|type_arg| type_arg.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(f, "{}[{}]", base, type_args)
+            }
+            Type::Parameter(name) => write!(f, "{}", name),
+            Type::Nullable(value_type) if matches!(value_type.as_ref(), Type::Union(_)) => {
+                write!(f, "{} | nil", value_type)
+            }
+            Type::Nullable(value_type) => write!(f, "{}?", value_type),
+            Type::Union(members) => {
+                let members = members
+                    .iter()
+                    .map(|member| member.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" | ");
+                write!(f, "{}", members)
+            }
+            Type::Enum(name) => write!(f, "{}", name),
+            Type::Void => write!(f, "void"),
+            Type::Nil => write!(f, "nil"),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -6,3 +6,4 @@ mod visitor_ast;
 pub(crate) use self::ast::*;
 pub(crate) use self::ast_operators::*;
 pub(crate) use self::ast_types::*;
+pub(crate) use self::visitor_ast::*;
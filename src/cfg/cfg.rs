@@ -0,0 +1,143 @@
+use core::fmt;
+
+use crate::{
+    ast::{Expression, Statement},
+    lexer::Span,
+};
+
+pub type BlockId = usize;
+
+///
+/// Node of a basic block, evaluated in order
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CfgElement<'a> {
+    ///
+    /// Statement without control flow of its own, a return statement ends its block
+    ///
+    Statement(&'a Statement),
+
+    ///
+    /// Condition of a branch or a loop, always the last element of its block
+    ///
+    Condition(&'a Expression),
+
+    ///
+    /// Increment of a for loop
+    ///
+    Expression(&'a Expression),
+}
+
+impl CfgElement<'_> {
+    pub fn span(&self) -> Span {
+        match self {
+            CfgElement::Statement(statement) => statement.span(),
+            CfgElement::Condition(expression) | CfgElement::Expression(expression) => {
+                expression.span()
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeKind {
+    Unconditional,
+    True,
+    False,
+}
+
+impl fmt::Display for EdgeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EdgeKind::Unconditional => write!(f, "unconditional"),
+            EdgeKind::True => write!(f, "true"),
+            EdgeKind::False => write!(f, "false"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Edge {
+    pub from: BlockId,
+    pub to: BlockId,
+    pub kind: EdgeKind,
+}
+
+///
+/// Straight line sequence of elements, control only enters at the start
+/// and leaves at the end
+///
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BasicBlock<'a> {
+    pub elements: Vec<CfgElement<'a>>,
+}
+
+///
+/// Control flow graph of the top level program or of a function body
+///
+/// The entry and the exit blocks never hold elements, every return statement
+/// and the end of the body lead to the exit block. Statements following a
+/// return are placed in blocks without predecessors
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct ControlFlowGraph<'a> {
+    pub name: String,
+
+    ///
+    /// Function declaration the graph belongs to, none for the program
+    ///
+    pub declaration: Option<&'a Statement>,
+
+    pub blocks: Vec<BasicBlock<'a>>,
+    pub edges: Vec<Edge>,
+}
+
+#[allow(dead_code)]
+impl<'a> ControlFlowGraph<'a> {
+    pub const ENTRY: BlockId = 0;
+    pub const EXIT: BlockId = 1;
+
+    pub fn new(name: String, declaration: Option<&'a Statement>) -> Self {
+        ControlFlowGraph {
+            name,
+            declaration,
+            blocks: vec![BasicBlock::default(), BasicBlock::default()],
+            edges: vec![],
+        }
+    }
+
+    pub fn add_block(&mut self) -> BlockId {
+        self.blocks.push(BasicBlock::default());
+        self.blocks.len() - 1
+    }
+
+    pub fn add_edge(&mut self, from: BlockId, to: BlockId, kind: EdgeKind) {
+        self.edges.push(Edge { from, to, kind });
+    }
+
+    pub fn successors(&self, block: BlockId) -> impl Iterator<Item = &Edge> {
+        self.edges.iter().filter(move |edge| edge.from == block)
+    }
+
+    pub fn predecessors(&self, block: BlockId) -> impl Iterator<Item = &Edge> {
+        self.edges.iter().filter(move |edge| edge.to == block)
+    }
+
+    ///
+    /// Whether each block can be reached from the entry block
+    ///
+    pub fn reachable_blocks(&self) -> Vec<bool> {
+        let mut reachable = vec![false; self.blocks.len()];
+        let mut pending = vec![Self::ENTRY];
+
+        while let Some(block) = pending.pop() {
+            if reachable[block] {
+                continue;
+            }
+            reachable[block] = true;
+            pending.extend(self.successors(block).map(|edge| edge.to));
+        }
+
+        reachable
+    }
+}
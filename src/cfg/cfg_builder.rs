@@ -0,0 +1,409 @@
+use crate::ast::{ClassMember, Expression, ExpressionRef, Statement};
+
+use super::{BlockId, CfgElement, ControlFlowGraph, EdgeKind};
+
+///
+/// Name of the graph of the top level program
+///
+pub const PROGRAM_GRAPH_NAME: &str = "program";
+
+///
+/// Graphs of the program and of every function declaration in it, methods
+/// are named after their class
+///
+pub fn build_control_flow_graphs(program: &Statement) -> Vec<ControlFlowGraph<'_>> {
+    let mut graphs = vec![build_control_flow_graph(
+        String::from(PROGRAM_GRAPH_NAME),
+        None,
+        program,
+    )];
+    collect_function_graphs(program, None, &mut graphs);
+    graphs
+}
+
+///
+/// Graph of a single body, the declaration is the function the body belongs to
+///
+pub fn build_control_flow_graph<'a>(
+    name: String,
+    declaration: Option<&'a Statement>,
+    body: &'a Statement,
+) -> ControlFlowGraph<'a> {
+    let mut builder = CfgBuilder {
+        graph: ControlFlowGraph::new(name, declaration),
+        current: None,
+        loops: vec![],
+    };
+
+    let start = builder.graph.add_block();
+    builder
+        .graph
+        .add_edge(ControlFlowGraph::ENTRY, start, EdgeKind::Unconditional);
+    builder.current = Some(start);
+
+    builder.build_statement(body);
+    if let Some(end) = builder.current {
+        builder
+            .graph
+            .add_edge(end, ControlFlowGraph::EXIT, EdgeKind::Unconditional);
+    }
+
+    builder.graph
+}
+
+fn collect_function_graphs<'a>(
+    statement: &'a Statement,
+    class_name: Option<&str>,
+    graphs: &mut Vec<ControlFlowGraph<'a>>,
+) {
+    match statement {
+        Statement::Program { body, .. } | Statement::Block { body, .. } => {
+            for statement in body {
+                collect_function_graphs(statement, class_name, graphs);
+            }
+        }
+        Statement::If {
+            consequent,
+            alternative,
+            ..
+        } => {
+            collect_function_graphs(consequent, None, graphs);
+            if let Some(alternative) = alternative {
+                collect_function_graphs(alternative, None, graphs);
+            }
+        }
+        Statement::While { body, .. }
+        | Statement::DoWhile { body, .. }
+        | Statement::For { body, .. }
+        | Statement::Labeled { body, .. } => collect_function_graphs(body, None, graphs),
+        Statement::FunctionDeclaration { name, body, .. } => {
+            let name = identifier_name(name);
+            let name = match class_name {
+                Some(class_name) => format!("{}.{}", class_name, name),
+                None => name,
+            };
+            graphs.push(build_control_flow_graph(name, Some(statement), body));
+            collect_function_graphs(body, None, graphs);
+        }
+        Statement::ClassDeclaration { name, members, .. } => {
+            let class_name = identifier_name(name);
+            for member in members {
+                if let ClassMember::Method { declaration }
+                | ClassMember::Constructor { declaration } = member
+                {
+                    collect_function_graphs(declaration, Some(&class_name), graphs);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+struct CfgBuilder<'a> {
+    graph: ControlFlowGraph<'a>,
+
+    ///
+    /// Block the next element goes into, none after a return or a jump
+    ///
+    current: Option<BlockId>,
+
+    ///
+    /// Loops enclosing the statement being built, innermost last
+    ///
+    loops: Vec<LoopTarget>,
+}
+
+///
+/// Loop whose body is being built, with the blocks of the 'break' and
+/// 'continue' statements jumping out of the body
+///
+struct LoopTarget {
+    label: Option<String>,
+    breaks: Vec<BlockId>,
+    continues: Vec<BlockId>,
+}
+
+impl<'a> CfgBuilder<'a> {
+    ///
+    /// Block the next element goes into, code after a return or a jump starts
+    /// a block without predecessors
+    ///
+    fn current_block(&mut self) -> BlockId {
+        match self.current {
+            Some(block) => block,
+            None => {
+                let block = self.graph.add_block();
+                self.current = Some(block);
+                block
+            }
+        }
+    }
+
+    fn push(&mut self, element: CfgElement<'a>) -> BlockId {
+        let block = self.current_block();
+        self.graph.blocks[block].elements.push(element);
+        block
+    }
+
+    ///
+    /// Block where the given edges meet, none when no edge gets there
+    ///
+    fn join(&mut self, predecessors: &[(BlockId, EdgeKind)]) -> Option<BlockId> {
+        if predecessors.is_empty() {
+            return None;
+        }
+
+        let block = self.graph.add_block();
+        for (predecessor, kind) in predecessors {
+            self.graph.add_edge(*predecessor, block, *kind);
+        }
+        Some(block)
+    }
+
+    ///
+    /// Start a new block entered from the current one
+    ///
+    fn start_block(&mut self) -> BlockId {
+        let block = self.graph.add_block();
+        if let Some(current) = self.current {
+            self.graph.add_edge(current, block, EdgeKind::Unconditional);
+        }
+        self.current = Some(block);
+        block
+    }
+
+    ///
+    /// Build the body of a branch or a loop entered through the given edge,
+    /// returns the block the body ends in
+    ///
+    fn build_branch(
+        &mut self,
+        from: BlockId,
+        kind: EdgeKind,
+        body: &'a Statement,
+    ) -> Option<BlockId> {
+        let start = self.graph.add_block();
+        self.graph.add_edge(from, start, kind);
+        self.current = Some(start);
+        self.build_statement(body);
+        self.current
+    }
+
+    fn build_statement(&mut self, statement: &'a Statement) {
+        match statement {
+            Statement::Program { body, .. } | Statement::Block { body, .. } => {
+                for statement in body {
+                    self.build_statement(statement);
+                }
+            }
+            Statement::Empty { .. } => {}
+            Statement::Expression { .. }
+            | Statement::VariableDeclaration { .. }
+            | Statement::FunctionDeclaration { .. }
+            | Statement::ClassDeclaration { .. }
+            | Statement::InterfaceDeclaration { .. }
+            | Statement::EnumDeclaration { .. } => {
+                self.push(CfgElement::Statement(statement));
+            }
+            Statement::Return { .. } => {
+                let block = self.push(CfgElement::Statement(statement));
+                self.graph
+                    .add_edge(block, ControlFlowGraph::EXIT, EdgeKind::Unconditional);
+                self.current = None;
+            }
+            Statement::If {
+                condition,
+                consequent,
+                alternative,
+                ..
+            } => {
+                let condition_block = self.push(CfgElement::Condition(condition));
+
+                let mut ends = vec![];
+                let consequent_end = self.build_branch(condition_block, EdgeKind::True, consequent);
+                ends.extend(consequent_end.map(|end| (end, EdgeKind::Unconditional)));
+
+                match alternative {
+                    Some(alternative) => {
+                        let alternative_end =
+                            self.build_branch(condition_block, EdgeKind::False, alternative);
+                        ends.extend(alternative_end.map(|end| (end, EdgeKind::Unconditional)));
+                    }
+                    None => ends.push((condition_block, EdgeKind::False)),
+                }
+
+                self.current = self.join(&ends);
+            }
+            Statement::Break { label, .. } | Statement::Continue { label, .. } => {
+                let block = self.push(CfgElement::Statement(statement));
+                if let Some(target) = self.jump_target(label) {
+                    match statement {
+                        Statement::Break { .. } => target.breaks.push(block),
+                        _ => target.continues.push(block),
+                    }
+                }
+                self.current = None;
+            }
+            Statement::Labeled { label, body, .. } => {
+                self.build_loop(body, Some(identifier_name(label)))
+            }
+            Statement::While { .. } | Statement::DoWhile { .. } | Statement::For { .. } => {
+                self.build_loop(statement, None)
+            }
+        }
+    }
+
+    ///
+    /// Loop a jump leaves or continues, the innermost one without a label
+    ///
+    fn jump_target(&mut self, label: &Option<ExpressionRef>) -> Option<&mut LoopTarget> {
+        match label {
+            Some(label) => {
+                let name = identifier_name(label);
+                self.loops
+                    .iter_mut()
+                    .rev()
+                    .find(|target| target.label.as_deref() == Some(name.as_str()))
+            }
+            None => self.loops.last_mut(),
+        }
+    }
+
+    fn enter_loop(&mut self, label: Option<String>) {
+        self.loops.push(LoopTarget {
+            label,
+            breaks: vec![],
+            continues: vec![],
+        });
+    }
+
+    ///
+    /// Leave the innermost loop, returns the blocks of the jumps out of its body
+    ///
+    fn exit_loop(&mut self) -> LoopTarget {
+        self.loops.pop().expect("a loop was entered")
+    }
+
+    fn build_loop(&mut self, statement: &'a Statement, label: Option<String>) {
+        match statement {
+            Statement::While {
+                condition, body, ..
+            } => {
+                let header = self.start_block();
+                self.push(CfgElement::Condition(condition));
+
+                self.enter_loop(label);
+                let body_end = self.build_branch(header, EdgeKind::True, body);
+                let target = self.exit_loop();
+                for end in body_end.into_iter().chain(target.continues) {
+                    self.graph.add_edge(end, header, EdgeKind::Unconditional);
+                }
+
+                // A condition that is always true is only left by a return or a break
+                let mut exits = vec![];
+                if !is_always_true(condition) {
+                    exits.push((header, EdgeKind::False));
+                }
+                exits.extend(unconditional(&target.breaks));
+                self.current = self.join(&exits);
+            }
+            Statement::DoWhile {
+                body, condition, ..
+            } => {
+                let body_start = self.start_block();
+                self.enter_loop(label);
+                self.build_statement(body);
+                let target = self.exit_loop();
+
+                // A body that always returns or breaks never evaluates the condition
+                let mut ends: Vec<BlockId> = self.current.into_iter().collect();
+                ends.extend(&target.continues);
+                let mut exits = vec![];
+                if let Some(condition_block) = self.join(&unconditional(&ends)) {
+                    self.current = Some(condition_block);
+                    self.push(CfgElement::Condition(condition));
+                    self.graph
+                        .add_edge(condition_block, body_start, EdgeKind::True);
+                    if !is_always_true(condition) {
+                        exits.push((condition_block, EdgeKind::False));
+                    }
+                }
+
+                exits.extend(unconditional(&target.breaks));
+                self.current = self.join(&exits);
+            }
+            Statement::For {
+                initializer,
+                condition,
+                increment,
+                body,
+                ..
+            } => {
+                if let Some(initializer) = initializer {
+                    self.build_statement(initializer);
+                }
+
+                let header = self.start_block();
+                let body_edge = match condition {
+                    Some(condition) => {
+                        self.push(CfgElement::Condition(condition));
+                        EdgeKind::True
+                    }
+                    None => EdgeKind::Unconditional,
+                };
+
+                self.enter_loop(label);
+                let body_end = self.build_branch(header, body_edge, body);
+                let target = self.exit_loop();
+
+                // A body that always returns or breaks never gets to the increment
+                let mut ends: Vec<BlockId> = body_end.into_iter().collect();
+                ends.extend(&target.continues);
+                if let Some(increment) = increment {
+                    if let Some(increment_block) = self.join(&unconditional(&ends)) {
+                        self.current = Some(increment_block);
+                        self.push(CfgElement::Expression(increment));
+                        ends = vec![increment_block];
+                    }
+                }
+                for end in ends {
+                    self.graph.add_edge(end, header, EdgeKind::Unconditional);
+                }
+
+                // Without a condition, or with one that is always true, the loop
+                // is only left by a return or a break
+                let mut exits = vec![];
+                if condition
+                    .as_ref()
+                    .is_some_and(|condition| !is_always_true(condition))
+                {
+                    exits.push((header, EdgeKind::False));
+                }
+                exits.extend(unconditional(&target.breaks));
+                self.current = self.join(&exits);
+            }
+            _ => self.build_statement(statement),
+        }
+    }
+}
+
+///
+/// Whether the loop condition is the literal 'true'
+///
+fn is_always_true(condition: &Expression) -> bool {
+    matches!(condition, Expression::BooleanLiteral { value: true, .. })
+}
+
+fn unconditional(blocks: &[BlockId]) -> Vec<(BlockId, EdgeKind)> {
+    blocks
+        .iter()
+        .map(|block| (*block, EdgeKind::Unconditional))
+        .collect()
+}
+
+fn identifier_name(expression: &Expression) -> String {
+    match expression {
+        Expression::Identifier { name, .. } => name.clone(),
+        _ => String::new(),
+    }
+}
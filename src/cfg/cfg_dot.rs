@@ -0,0 +1,67 @@
+use std::fmt::Write;
+
+use super::{CfgElement, ControlFlowGraph, EdgeKind};
+
+impl ControlFlowGraph<'_> {
+    ///
+    /// Graphviz description of the graph, elements are labeled with the first
+    /// line of their source text
+    ///
+    pub fn to_dot(&self, source: &str) -> String {
+        let mut dot = String::new();
+
+        let _ = writeln!(dot, "digraph \"{}\" {{", escape(&self.name));
+        let _ = writeln!(dot, "  node [shape=box, fontname=\"monospace\"];");
+
+        for (id, block) in self.blocks.iter().enumerate() {
+            let mut label = match id {
+                Self::ENTRY => String::from("entry"),
+                Self::EXIT => String::from("exit"),
+                _ => format!("B{}", id),
+            };
+            label.push_str("\\l");
+
+            for element in &block.elements {
+                label.push_str(&escape(&element_text(element, source)));
+                label.push_str("\\l");
+            }
+
+            let _ = writeln!(dot, "  B{} [label=\"{}\"];", id, label);
+        }
+
+        for edge in &self.edges {
+            match edge.kind {
+                EdgeKind::Unconditional => {
+                    let _ = writeln!(dot, "  B{} -> B{};", edge.from, edge.to);
+                }
+                kind => {
+                    let _ = writeln!(
+                        dot,
+                        "  B{} -> B{} [label=\"{}\"];",
+                        edge.from, edge.to, kind
+                    );
+                }
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn element_text(element: &CfgElement, source: &str) -> String {
+    let span = element.span();
+    let text = source
+        .get(span.start.offset..span.end.offset)
+        .unwrap_or_default();
+    let first_line = text.lines().next().unwrap_or_default().trim_end();
+
+    match element {
+        CfgElement::Condition(_) => format!("[{}]", first_line),
+        _ => String::from(first_line),
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -0,0 +1,7 @@
+mod cfg;
+mod cfg_builder;
+mod cfg_dot;
+mod tests;
+
+pub(crate) use self::cfg::*;
+pub(crate) use self::cfg_builder::*;
@@ -0,0 +1,6 @@
+use super::internal_util::execute_yaml_test_cases;
+
+#[test]
+fn test_cfg_graphs() {
+    execute_yaml_test_cases("tests/cfg/test_cfg_graphs.yaml");
+}
@@ -0,0 +1,3064 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+
+use crate::{
+    ast::{
+        AssignmentOperator, AstVisitor, BinaryOperator, ClassMember, ClassMemberList,
+        EnumVariantList, Expression, ExpressionList, ExpressionRef, InterfaceMember,
+        InterfaceMemberList, LogicalOperator, MatchArm, MatchArmList, ParameterList, Pattern,
+        Statement, StatementList, StatementRef, Type, TypeParameter, TypeParameterList,
+        UnaryOperator, CONSTRUCTOR_NAME,
+    },
+    lexer::Span,
+};
+
+use super::{
+    checker_narrowing::{
+        always_exits, assigned_names, breaks_out_of, captured_assigned_names, condition_narrowings,
+        Narrowings,
+    },
+    checker_patterns::{is_useful, pattern_space, Space},
+    checker_scope::{ClassInfo, EnumInfo, InterfaceInfo, ScopeStack, TypeParameterInfo},
+    CheckerError, CheckerResult,
+};
+
+///
+/// Functions provided by the runtime, they accept any number of arguments of any type
+///
+pub const BUILTIN_FUNCTIONS: &[&str] = &["print"];
+
+///
+/// Static type checker
+///
+/// Declarations are kept between calls, so the same checker can be fed
+/// a program piece by piece
+///
+#[derive(Debug, Clone)]
+pub struct TypeChecker {
+    scopes: ScopeStack,
+    classes: HashMap<String, ClassInfo>,
+    interfaces: HashMap<String, InterfaceInfo>,
+    enums: HashMap<String, EnumInfo>,
+    type_parameters: Vec<TypeParameterInfo>,
+    current_return_type: Option<Type>,
+
+    ///
+    /// Labels of the loops enclosing the statement being checked, innermost last
+    ///
+    loop_labels: Vec<Option<String>>,
+
+    ///
+    /// Variables assigned by function bodies they are not declared in, they
+    /// are never narrowed since any call can assign them
+    ///
+    captured_assignments: HashSet<String>,
+    current_class: Option<String>,
+    in_method: bool,
+    in_constructor: bool,
+
+    ///
+    /// Types of the declared names by the span of their identifier
+    ///
+    declared_types: HashMap<Span, Type>,
+}
+
+#[allow(dead_code)]
+impl TypeChecker {
+    pub fn new() -> Self {
+        TypeChecker {
+            scopes: ScopeStack::new(),
+            classes: HashMap::new(),
+            interfaces: HashMap::new(),
+            enums: HashMap::new(),
+            type_parameters: vec![],
+            current_return_type: None,
+            loop_labels: vec![],
+            captured_assignments: HashSet::new(),
+            current_class: None,
+            in_method: false,
+            in_constructor: false,
+            declared_types: HashMap::new(),
+        }
+    }
+
+    ///
+    /// Check a program, its top level declarations stay visible to later calls
+    ///
+    pub fn check_program(&mut self, program: &Statement) -> CheckerResult<()> {
+        self.captured_assignments
+            .extend(captured_assigned_names(program));
+        self.visit_statement(program)
+            .map(|_| ())
+            .map_err(into_checker_error)
+    }
+
+    ///
+    /// Infer the type of a standalone expression
+    ///
+    pub fn infer_expression(&mut self, expression: &Expression) -> CheckerResult<Type> {
+        self.visit_expression(expression)
+            .map_err(into_checker_error)
+    }
+
+    ///
+    /// Type of a declared variable or function
+    ///
+    pub fn lookup_variable(&self, name: &str) -> Option<&Type> {
+        self.scopes.lookup_declared(name)
+    }
+
+    ///
+    /// Type of the name declared by the identifier at the span, inferred
+    /// types included
+    ///
+    pub fn declared_type_at(&self, span: Span) -> Option<&Type> {
+        self.declared_types.get(&span)
+    }
+
+    ///
+    /// Type of a variable at the current point, narrowings included
+    ///
+    pub(super) fn narrowed_variable(&self, name: &str) -> Option<&Type> {
+        self.scopes.lookup(name)
+    }
+
+    ///
+    /// Variants of a declared enum
+    ///
+    pub(super) fn enum_info(&self, name: &str) -> Option<&EnumInfo> {
+        self.enums.get(name)
+    }
+
+    fn check_statement_list(&mut self, statements: &StatementList) -> Result<()> {
+        self.hoist_declarations(statements)?;
+
+        for statement in statements {
+            self.visit_statement(statement)?;
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Function signatures, class and interface members and enum variants are
+    /// visible to the whole statement list
+    ///
+    fn hoist_declarations(&mut self, statements: &StatementList) -> Result<()> {
+        for statement in statements {
+            match statement {
+                Statement::InterfaceDeclaration { name, .. } => {
+                    let (interface_name, span) = identifier_name(name)?;
+                    if self.is_type_declared(&interface_name) {
+                        return Err(semantic_error(
+                            format!("Interface '{}' is already declared", interface_name),
+                            span,
+                        ));
+                    }
+
+                    self.interfaces
+                        .insert(interface_name, InterfaceInfo::default());
+                }
+                Statement::EnumDeclaration { name, .. } => {
+                    let (enum_name, span) = identifier_name(name)?;
+                    if self.is_type_declared(&enum_name) {
+                        return Err(semantic_error(
+                            format!("Enum '{}' is already declared", enum_name),
+                            span,
+                        ));
+                    }
+
+                    self.enums.insert(enum_name, EnumInfo::default());
+                }
+                _ => {}
+            }
+        }
+
+        for statement in statements {
+            if let Statement::ClassDeclaration {
+                name,
+                type_parameters,
+                super_class,
+                ..
+            } = statement
+            {
+                let (class_name, span) = identifier_name(name)?;
+                if self.is_type_declared(&class_name) {
+                    return Err(semantic_error(
+                        format!("Class '{}' is already declared", class_name),
+                        span,
+                    ));
+                }
+
+                let super_class = match super_class {
+                    Some(super_class) => Some(identifier_name(super_class)?.0),
+                    None => None,
+                };
+                // Bounds are resolved once every class of the list is known
+                let type_parameters = type_parameters
+                    .iter()
+                    .map(|type_parameter| {
+                        Ok(TypeParameterInfo {
+                            name: identifier_name(&type_parameter.name)?.0,
+                            bound: None,
+                        })
+                    })
+                    .collect::<Result<_>>()?;
+                self.classes
+                    .insert(class_name, ClassInfo::new(super_class, type_parameters));
+            }
+        }
+
+        for statement in statements {
+            if let Statement::ClassDeclaration {
+                name,
+                type_parameters,
+                super_class,
+                implements,
+                ..
+            } = statement
+            {
+                let (class_name, _) = identifier_name(name)?;
+                let type_parameters = self.type_parameter_infos(type_parameters)?;
+                let interfaces = self.implemented_interfaces(implements)?;
+                if let Some(class_info) = self.classes.get_mut(&class_name) {
+                    class_info.type_parameters = type_parameters;
+                    class_info.interfaces = interfaces;
+                }
+
+                if let Some(super_class) = super_class {
+                    self.check_super_class(name, super_class)?;
+                }
+            }
+        }
+
+        for statement in statements {
+            match statement {
+                Statement::FunctionDeclaration {
+                    name,
+                    type_parameters,
+                    parameters,
+                    return_type,
+                    ..
+                } => {
+                    let (function_name, span) = identifier_name(name)?;
+                    self.declare_function(
+                        &function_name,
+                        type_parameters,
+                        parameters,
+                        return_type,
+                        span,
+                    )?;
+                }
+                Statement::ClassDeclaration { name, members, .. } => {
+                    self.hoist_class_members(name, members)?;
+                }
+                Statement::InterfaceDeclaration { name, members, .. } => {
+                    self.hoist_interface_members(name, members)?;
+                }
+                Statement::EnumDeclaration { name, variants, .. } => {
+                    self.hoist_enum_variants(name, variants)?;
+                }
+                _ => {}
+            }
+        }
+
+        // Overridden methods might belong to classes declared further down
+        for statement in statements {
+            if let Statement::ClassDeclaration { name, members, .. } = statement {
+                self.check_overrides(name, members)?;
+                self.check_implements(name)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// The superclass has to be a declared class that does not inherit from the class
+    ///
+    fn check_super_class(&self, name: &ExpressionRef, super_class: &ExpressionRef) -> Result<()> {
+        let (class_name, _) = identifier_name(name)?;
+        let (super_class_name, span) = identifier_name(super_class)?;
+
+        if self.interfaces.contains_key(&super_class_name) {
+            return Err(semantic_error(
+                format!(
+                    "Interface '{}' can not be extended, it has to be implemented",
+                    super_class_name
+                ),
+                span,
+            ));
+        }
+        if !self.classes.contains_key(&super_class_name) {
+            return Err(semantic_error(
+                format!("Undefined class '{}'", super_class_name),
+                span,
+            ));
+        }
+        if self.is_subclass(&super_class_name, &class_name) {
+            return Err(semantic_error(
+                format!("Class '{}' inherits from itself", class_name),
+                span,
+            ));
+        }
+        if self
+            .classes
+            .get(&super_class_name)
+            .is_some_and(|class_info| !class_info.type_parameters.is_empty())
+        {
+            return Err(semantic_error(
+                format!("Generic class '{}' can not be extended", super_class_name),
+                span,
+            ));
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Overriding methods take the parameters of the overridden method, their
+    /// return type has to be assignable to the overridden return type.
+    /// Redeclared fields keep the inherited type, fields and methods can not
+    /// take the name of an inherited member of the other kind
+    ///
+    fn check_overrides(&self, name: &ExpressionRef, members: &ClassMemberList) -> Result<()> {
+        let (class_name, _) = identifier_name(name)?;
+        let Some(class_info) = self.classes.get(&class_name) else {
+            return Ok(());
+        };
+        let Some(super_class) = &class_info.super_class else {
+            return Ok(());
+        };
+
+        for member in members {
+            let declaration = match member {
+                ClassMember::Field { name, .. } => {
+                    let (field_name, span) = identifier_name(name)?;
+                    if self.find_method(super_class, &field_name).is_some() {
+                        return Err(semantic_error(
+                            format!(
+                                "Field '{}' of class '{}' has the name of an inherited method",
+                                field_name, class_name
+                            ),
+                            span,
+                        ));
+                    }
+                    let inherited = self
+                        .class_chain(super_class)
+                        .into_iter()
+                        .find_map(|class_info| class_info.fields.get(&field_name));
+                    let field = class_info.fields.get(&field_name);
+                    if let (Some(inherited), Some(field)) = (inherited, field) {
+                        if inherited != field {
+                            return Err(type_error(
+                                format!(
+                                    "Field '{}' of class '{}' must keep the inherited type: expected '{}', found '{}'",
+                                    field_name, class_name, inherited, field
+                                ),
+                                span,
+                            ));
+                        }
+                    }
+                    continue;
+                }
+                ClassMember::Method { declaration } => declaration,
+                ClassMember::Constructor { .. } => continue,
+            };
+            let Statement::FunctionDeclaration { name, .. } = declaration.as_ref() else {
+                continue;
+            };
+            let (method_name, span) = identifier_name(name)?;
+            if self
+                .class_chain(super_class)
+                .iter()
+                .any(|class_info| class_info.fields.contains_key(&method_name))
+            {
+                return Err(semantic_error(
+                    format!(
+                        "Method '{}' of class '{}' has the name of an inherited field",
+                        method_name, class_name
+                    ),
+                    span,
+                ));
+            }
+            let (Some(method), Some(overridden)) = (
+                class_info.methods.get(&method_name),
+                self.find_method(super_class, &method_name),
+            ) else {
+                continue;
+            };
+
+            if !self.is_compatible_override(&overridden, method) {
+                return Err(type_error(
+                    format!(
+                        "Method '{}' of class '{}' is not compatible with the overridden method: expected '{}', found '{}'",
+                        method_name, class_name, overridden, method
+                    ),
+                    span,
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Names of the interfaces listed in an implements clause, each has to be
+    /// a declared interface listed once
+    ///
+    fn implemented_interfaces(&self, implements: &ExpressionList) -> Result<Vec<String>> {
+        let mut interfaces: Vec<String> = vec![];
+
+        for interface in implements {
+            let (interface_name, span) = identifier_name(interface)?;
+            if !self.interfaces.contains_key(&interface_name) {
+                return Err(semantic_error(
+                    format!("Undefined interface '{}'", interface_name),
+                    span,
+                ));
+            }
+            if interfaces.contains(&interface_name) {
+                return Err(semantic_error(
+                    format!("Interface '{}' is already implemented", interface_name),
+                    span,
+                ));
+            }
+            interfaces.push(interface_name);
+        }
+
+        Ok(interfaces)
+    }
+
+    ///
+    /// A class has every member of the interfaces it implements, inherited
+    /// members included
+    ///
+    /// Fields keep the type of the interface field, methods are checked like
+    /// overrides of the interface method
+    ///
+    fn check_implements(&self, name: &ExpressionRef) -> Result<()> {
+        let (class_name, span) = identifier_name(name)?;
+        let Some(class_info) = self.classes.get(&class_name) else {
+            return Ok(());
+        };
+
+        for interface_name in &class_info.interfaces {
+            let Some(interface_info) = self.interfaces.get(interface_name) else {
+                continue;
+            };
+
+            let mut members = vec![];
+            for (field_name, field_type) in &interface_info.fields {
+                let found = self
+                    .class_chain(&class_name)
+                    .into_iter()
+                    .find_map(|class_info| class_info.fields.get(field_name).cloned());
+                let matches = found.as_ref() == Some(field_type);
+                members.push((field_name, field_type, found, matches));
+            }
+            for (method_name, method_type) in &interface_info.methods {
+                let found = self.find_method(&class_name, method_name);
+                let matches = found
+                    .as_ref()
+                    .is_some_and(|found| self.is_compatible_override(method_type, found));
+                members.push((method_name, method_type, found, matches));
+            }
+            members.sort_by_key(|(member_name, ..)| *member_name);
+
+            for (member_name, expected, found, matches) in members {
+                match found {
+                    _ if matches => {}
+                    Some(found) => {
+                        return Err(type_error(
+                            format!(
+                                "Member '{}' of class '{}' does not match interface '{}': expected '{}', found '{}'",
+                                member_name, class_name, interface_name, expected, found
+                            ),
+                            span,
+                        ))
+                    }
+                    None => {
+                        return Err(type_error(
+                            format!(
+                                "Class '{}' does not implement '{}' of interface '{}'",
+                                class_name, member_name, interface_name
+                            ),
+                            span,
+                        ))
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn is_compatible_override(&self, overridden: &Type, method: &Type) -> bool {
+        match (overridden, method) {
+            (
+                Type::Function {
+                    params: overridden_params,
+                    return_type: overridden_return_type,
+                },
+                Type::Function {
+                    params,
+                    return_type,
+                },
+            ) => {
+                overridden_params == params
+                    && (**overridden_return_type == Type::Void) == (**return_type == Type::Void)
+                    && self.is_assignable(overridden_return_type, return_type)
+            }
+            _ => false,
+        }
+    }
+
+    fn hoist_class_members(
+        &mut self,
+        name: &ExpressionRef,
+        members: &ClassMemberList,
+    ) -> Result<()> {
+        let (class_name, _) = identifier_name(name)?;
+        let class_type_parameters = self.class_type_parameters(&class_name);
+
+        let (fields, methods, method_type_parameters) = self
+            .with_type_parameters(&class_type_parameters, |checker| {
+                checker.collect_class_members(&class_name, members)
+            })?;
+
+        if let Some(class_info) = self.classes.get_mut(&class_name) {
+            class_info.fields = fields;
+            class_info.methods = methods;
+            class_info.method_type_parameters = method_type_parameters;
+        }
+
+        Ok(())
+    }
+
+    fn hoist_interface_members(
+        &mut self,
+        name: &ExpressionRef,
+        members: &InterfaceMemberList,
+    ) -> Result<()> {
+        let (interface_name, _) = identifier_name(name)?;
+        let mut interface_info = InterfaceInfo::default();
+
+        for member in members {
+            let (member_name, member_type, span) = match member {
+                InterfaceMember::Field {
+                    name,
+                    type_annotation,
+                    ..
+                } => {
+                    let (field_name, span) = identifier_name(name)?;
+                    self.validate_type(type_annotation, span)?;
+                    (field_name, self.resolve_type(type_annotation), span)
+                }
+                InterfaceMember::Method {
+                    name,
+                    parameters,
+                    return_type,
+                    ..
+                } => {
+                    let (method_name, span) = identifier_name(name)?;
+                    let method_type = self.function_type(parameters, return_type, span)?;
+                    (method_name, method_type, span)
+                }
+            };
+
+            if interface_info.fields.contains_key(&member_name)
+                || interface_info.methods.contains_key(&member_name)
+            {
+                return Err(semantic_error(
+                    format!(
+                        "Member '{}' is already declared in interface '{}'",
+                        member_name, interface_name
+                    ),
+                    span,
+                ));
+            }
+            match member {
+                InterfaceMember::Field { .. } => {
+                    interface_info.fields.insert(member_name, member_type);
+                }
+                InterfaceMember::Method { .. } => {
+                    interface_info.methods.insert(member_name, member_type);
+                }
+            }
+        }
+
+        self.interfaces.insert(interface_name, interface_info);
+        Ok(())
+    }
+
+    fn hoist_enum_variants(
+        &mut self,
+        name: &ExpressionRef,
+        variants: &EnumVariantList,
+    ) -> Result<()> {
+        let (enum_name, _) = identifier_name(name)?;
+        let mut enum_info = EnumInfo::default();
+
+        for variant in variants {
+            let (variant_name, span) = identifier_name(&variant.name)?;
+            if enum_info.variant(&variant_name).is_some() {
+                return Err(semantic_error(
+                    format!(
+                        "Variant '{}' is already declared in enum '{}'",
+                        variant_name, enum_name
+                    ),
+                    span,
+                ));
+            }
+
+            let mut payload = vec![];
+            for value_type in &variant.payload {
+                self.validate_type(value_type, variant.span)?;
+                payload.push(self.resolve_type(value_type));
+            }
+            enum_info.variants.push((variant_name, payload));
+        }
+
+        self.enums.insert(enum_name, enum_info);
+        Ok(())
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn collect_class_members(
+        &mut self,
+        class_name: &str,
+        members: &ClassMemberList,
+    ) -> Result<(
+        HashMap<String, Type>,
+        HashMap<String, Type>,
+        HashMap<String, Vec<TypeParameterInfo>>,
+    )> {
+        let mut fields = HashMap::new();
+        let mut methods = HashMap::new();
+        let mut method_type_parameters = HashMap::new();
+
+        for member in members {
+            match member {
+                ClassMember::Field {
+                    name,
+                    type_annotation,
+                    ..
+                } => {
+                    let (field_name, span) = identifier_name(name)?;
+                    self.validate_type(type_annotation, span)?;
+                    if methods.contains_key(&field_name) {
+                        return Err(member_name_conflict(&field_name, class_name, span));
+                    }
+                    if fields
+                        .insert(field_name.clone(), self.resolve_type(type_annotation))
+                        .is_some()
+                    {
+                        return Err(semantic_error(
+                            format!(
+                                "Field '{}' is already declared in class '{}'",
+                                field_name, class_name
+                            ),
+                            span,
+                        ));
+                    }
+                }
+                ClassMember::Method { declaration } | ClassMember::Constructor { declaration } => {
+                    let Statement::FunctionDeclaration {
+                        name,
+                        type_parameters,
+                        parameters,
+                        return_type,
+                        ..
+                    } = declaration.as_ref()
+                    else {
+                        continue;
+                    };
+
+                    let (method_name, span) = identifier_name(name)?;
+                    if matches!(member, ClassMember::Constructor { .. })
+                        && !type_parameters.is_empty()
+                    {
+                        return Err(semantic_error(
+                            String::from("A constructor can not declare type parameters"),
+                            span,
+                        ));
+                    }
+
+                    if fields.contains_key(&method_name) {
+                        return Err(member_name_conflict(&method_name, class_name, span));
+                    }
+
+                    let type_parameters = self.type_parameter_infos(type_parameters)?;
+                    let method_type = self.with_type_parameters(&type_parameters, |checker| {
+                        checker.function_type(parameters, return_type, span)
+                    })?;
+                    if methods.insert(method_name.clone(), method_type).is_some() {
+                        return Err(semantic_error(
+                            format!(
+                                "Method '{}' is already declared in class '{}'",
+                                method_name, class_name
+                            ),
+                            span,
+                        ));
+                    }
+                    if !type_parameters.is_empty() {
+                        method_type_parameters.insert(method_name, type_parameters);
+                    }
+                }
+            }
+        }
+
+        Ok((fields, methods, method_type_parameters))
+    }
+
+    fn declare_function(
+        &mut self,
+        name: &str,
+        type_parameters: &TypeParameterList,
+        parameters: &ParameterList,
+        return_type: &Type,
+        span: Span,
+    ) -> Result<()> {
+        let type_parameters = self.type_parameter_infos(type_parameters)?;
+        let function_type = self.with_type_parameters(&type_parameters, |checker| {
+            checker.function_type(parameters, return_type, span)
+        })?;
+        self.declare(name, function_type, span)?;
+        if !type_parameters.is_empty() {
+            self.scopes.declare_type_parameters(name, type_parameters);
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Type parameters with their bounds resolved, a bound can not refer to
+    /// the type parameters it belongs to
+    ///
+    fn type_parameter_infos(
+        &self,
+        type_parameters: &TypeParameterList,
+    ) -> Result<Vec<TypeParameterInfo>> {
+        let mut infos: Vec<TypeParameterInfo> = vec![];
+
+        for TypeParameter { name, bound } in type_parameters {
+            let (name, span) = identifier_name(name)?;
+            if infos.iter().any(|info| info.name == name) {
+                return Err(semantic_error(
+                    format!("Type parameter '{}' is already declared", name),
+                    span,
+                ));
+            }
+
+            let bound = match bound {
+                Some(bound) => {
+                    self.validate_type(bound, span)?;
+                    Some(self.resolve_type(bound))
+                }
+                None => None,
+            };
+            infos.push(TypeParameterInfo { name, bound });
+        }
+
+        Ok(infos)
+    }
+
+    ///
+    /// Run a check with the type parameters in scope
+    ///
+    fn with_type_parameters<T>(
+        &mut self,
+        type_parameters: &[TypeParameterInfo],
+        check: impl FnOnce(&mut Self) -> Result<T>,
+    ) -> Result<T> {
+        let depth = self.type_parameters.len();
+        self.type_parameters.extend_from_slice(type_parameters);
+        let result = check(self);
+        self.type_parameters.truncate(depth);
+        result
+    }
+
+    fn class_type_parameters(&self, class_name: &str) -> Vec<TypeParameterInfo> {
+        self.classes
+            .get(class_name)
+            .map(|class_info| class_info.type_parameters.clone())
+            .unwrap_or_default()
+    }
+
+    fn lookup_type_parameter(&self, name: &str) -> Option<&TypeParameterInfo> {
+        self.type_parameters
+            .iter()
+            .rev()
+            .find(|type_parameter| type_parameter.name == name)
+    }
+
+    fn check_function_declaration(
+        &mut self,
+        name: &ExpressionRef,
+        type_parameters: &TypeParameterList,
+        parameters: &ParameterList,
+        return_type: &Type,
+        body: &StatementRef,
+    ) -> Result<()> {
+        let (function_name, span) = identifier_name(name)?;
+
+        // Declarations outside of a statement list were not hoisted
+        if !self.scopes.is_declared_in_current_scope(&function_name) {
+            self.declare_function(
+                &function_name,
+                type_parameters,
+                parameters,
+                return_type,
+                span,
+            )?;
+        }
+
+        let type_parameters = self
+            .scopes
+            .lookup_type_parameters(&function_name)
+            .cloned()
+            .unwrap_or_default();
+        self.with_type_parameters(&type_parameters, |checker| {
+            checker.check_function_body(parameters, return_type, body)
+        })
+    }
+
+    fn check_function_body(
+        &mut self,
+        parameters: &ParameterList,
+        return_type: &Type,
+        body: &StatementRef,
+    ) -> Result<()> {
+        let enclosing_return_type = self
+            .current_return_type
+            .replace(self.resolve_type(return_type));
+        // The function can be called after its enclosing variables changed
+        let enclosing_narrowings = self.scopes.take_narrowings();
+        // Jumps cannot leave the function to an enclosing loop
+        let enclosing_loop_labels = std::mem::take(&mut self.loop_labels);
+
+        self.scopes.push();
+        for (parameter, parameter_type) in parameters {
+            let parameter_type = self.resolve_type(parameter_type);
+            if let Expression::Destructuring { pattern, .. } = parameter {
+                self.declare_pattern(pattern, &parameter_type)?;
+                continue;
+            }
+            let (parameter_name, span) = identifier_name(parameter)?;
+            self.declare(&parameter_name, parameter_type, span)?;
+        }
+        self.visit_statement(body)?;
+        self.scopes.pop();
+
+        self.scopes.restore_narrowings(enclosing_narrowings);
+        self.loop_labels = enclosing_loop_labels;
+        self.current_return_type = enclosing_return_type;
+        Ok(())
+    }
+
+    fn check_return(&mut self, argument: &Option<ExpressionRef>, span: Span) -> Result<()> {
+        let Some(return_type) = self.current_return_type.clone() else {
+            return Err(semantic_error(
+                String::from("Return statement outside of a function"),
+                span,
+            ));
+        };
+
+        if let Some(argument) = argument {
+            if return_type == Type::Void {
+                self.visit_expression(argument)?;
+                return Err(type_error(
+                    String::from("Cannot return a value from a 'void' function"),
+                    argument.span(),
+                ));
+            }
+            self.check_initializer(&return_type, argument)?;
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Check a 'break' or a 'continue', the label has to name an enclosing loop
+    ///
+    fn check_jump(&self, keyword: &str, label: &Option<ExpressionRef>, span: Span) -> Result<()> {
+        if self.loop_labels.is_empty() {
+            return Err(semantic_error(
+                format!("{} statement outside of a loop", keyword),
+                span,
+            ));
+        }
+
+        if let Some(label) = label {
+            let (label_name, label_span) = identifier_name(label)?;
+            if !self.loop_labels.contains(&Some(label_name.clone())) {
+                return Err(semantic_error(
+                    format!("Undefined label '{}'", label_name),
+                    label_span,
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_labeled(&mut self, label: &Expression, body: &Statement) -> Result<()> {
+        let (label_name, span) = identifier_name(label)?;
+        if self.loop_labels.contains(&Some(label_name.clone())) {
+            return Err(semantic_error(
+                format!(
+                    "Label '{}' is already used by an enclosing loop",
+                    label_name
+                ),
+                span,
+            ));
+        }
+
+        self.check_loop(body, Some(label_name))
+    }
+
+    fn check_loop(&mut self, statement: &Statement, label: Option<String>) -> Result<()> {
+        self.loop_labels.push(label.clone());
+        let mut exit_narrowings = Narrowings::new();
+        match statement {
+            Statement::While {
+                condition, body, ..
+            } => {
+                self.forget_loop_narrowings(statement);
+                let (when_true, when_false) = self.check_condition(condition)?;
+                self.with_narrowings(when_true, |checker| checker.visit_statement(body))?;
+                if !breaks_out_of(body, label.as_deref()) {
+                    exit_narrowings = when_false;
+                }
+            }
+            Statement::DoWhile {
+                condition, body, ..
+            } => {
+                self.forget_loop_narrowings(statement);
+                self.visit_statement(body)?;
+                let (_, when_false) = self.check_condition(condition)?;
+                if !breaks_out_of(body, label.as_deref()) {
+                    exit_narrowings = when_false;
+                }
+            }
+            Statement::For {
+                initializer,
+                condition,
+                increment,
+                body,
+                ..
+            } => {
+                self.scopes.push();
+                if let Some(initializer) = initializer {
+                    self.visit_statement(initializer)?;
+                }
+                self.forget_loop_narrowings(statement);
+                let when_true = match condition {
+                    Some(condition) => self.check_condition(condition)?.0,
+                    None => Narrowings::new(),
+                };
+                self.with_narrowings(when_true, |checker| {
+                    checker.visit_statement(body)?;
+                    if let Some(increment) = increment {
+                        checker.visit_expression(increment)?;
+                    }
+                    Ok(())
+                })?;
+                self.scopes.pop();
+            }
+            _ => {
+                self.visit_statement(statement)?;
+            }
+        }
+        self.loop_labels.pop();
+
+        // Without a 'break' the loop is only left once its condition is false
+        for (name, narrowed_type) in exit_narrowings {
+            self.narrow(&name, narrowed_type);
+        }
+
+        Ok(())
+    }
+
+    fn check_class_declaration(
+        &mut self,
+        name: &ExpressionRef,
+        members: &ClassMemberList,
+    ) -> Result<()> {
+        let (class_name, _) = identifier_name(name)?;
+        let class_type_parameters = self.class_type_parameters(&class_name);
+        let enclosing_class = self.current_class.replace(class_name.clone());
+        let enclosing_in_method = std::mem::replace(&mut self.in_method, false);
+        let enclosing_narrowings = self.scopes.take_narrowings();
+
+        let result = self.with_type_parameters(&class_type_parameters, |checker| {
+            checker.check_class_members(&class_name, members)
+        });
+
+        self.scopes.restore_narrowings(enclosing_narrowings);
+        self.current_class = enclosing_class;
+        self.in_method = enclosing_in_method;
+        result
+    }
+
+    fn check_class_members(&mut self, class_name: &str, members: &ClassMemberList) -> Result<()> {
+        for member in members {
+            let (declaration, is_constructor) = match member {
+                ClassMember::Field {
+                    type_annotation,
+                    initializer: Some(initializer),
+                    ..
+                } => {
+                    self.check_initializer(&self.resolve_type(type_annotation), initializer)?;
+                    continue;
+                }
+                ClassMember::Field { .. } => continue,
+                ClassMember::Method { declaration } => (declaration, false),
+                ClassMember::Constructor { declaration } => (declaration, true),
+            };
+            let Statement::FunctionDeclaration {
+                name,
+                parameters,
+                return_type,
+                body,
+                ..
+            } = declaration.as_ref()
+            else {
+                continue;
+            };
+
+            let (method_name, _) = identifier_name(name)?;
+            let type_parameters = self
+                .classes
+                .get(class_name)
+                .and_then(|class_info| class_info.method_type_parameters.get(&method_name))
+                .cloned()
+                .unwrap_or_default();
+
+            self.in_method = true;
+            self.in_constructor = is_constructor;
+            let result = self.with_type_parameters(&type_parameters, |checker| {
+                checker.check_function_body(parameters, return_type, body)
+            });
+            self.in_method = false;
+            self.in_constructor = false;
+            result?;
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Check a condition, returns the narrowings that hold when it is true
+    /// and when it is false
+    ///
+    fn check_condition(&mut self, condition: &ExpressionRef) -> Result<(Narrowings, Narrowings)> {
+        let condition_type = self.visit_expression(condition)?;
+        self.ensure_assignable(&Type::boolean(), &condition_type, condition.span())?;
+        Ok(condition_narrowings(self, condition))
+    }
+
+    ///
+    /// Narrow a variable in the current scope, unless a function body assigns it
+    ///
+    fn narrow(&mut self, name: &str, narrowed_type: Type) {
+        if !self.captured_assignments.contains(name) {
+            self.scopes.narrow(name, narrowed_type);
+        }
+    }
+
+    ///
+    /// Run a check in a new scope with the variables narrowed
+    ///
+    fn with_narrowings<T>(
+        &mut self,
+        narrowings: Narrowings,
+        check: impl FnOnce(&mut Self) -> Result<T>,
+    ) -> Result<T> {
+        self.scopes.push();
+        for (name, narrowed_type) in narrowings {
+            self.narrow(&name, narrowed_type);
+        }
+        let result = check(self);
+        self.scopes.pop();
+        result
+    }
+
+    fn check_if(
+        &mut self,
+        condition: &ExpressionRef,
+        consequent: &StatementRef,
+        alternative: &Option<StatementRef>,
+    ) -> Result<()> {
+        let (when_true, when_false) = self.check_condition(condition)?;
+        self.with_narrowings(when_true.clone(), |checker| {
+            checker.visit_statement(consequent)
+        })?;
+        if let Some(alternative) = alternative {
+            self.with_narrowings(when_false.clone(), |checker| {
+                checker.visit_statement(alternative)
+            })?;
+        }
+
+        // When one branch returns, the rest of the scope is only reached
+        // through the other one
+        let (narrowings, branch) = match alternative {
+            _ if always_exits(consequent) => (when_false, alternative.as_deref()),
+            Some(alternative) if always_exits(alternative) => {
+                (when_true, Some(consequent.as_ref()))
+            }
+            _ => return Ok(()),
+        };
+        let assigned = branch.map(assigned_names).unwrap_or_default();
+        for (name, narrowed_type) in narrowings {
+            if !assigned.contains(&name) {
+                self.narrow(&name, narrowed_type);
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Variables assigned in a loop lose their narrowings, the loop can run
+    /// again after the assignment
+    ///
+    fn forget_loop_narrowings(&mut self, statement: &Statement) {
+        for name in assigned_names(statement) {
+            self.scopes.forget_narrowing(&name);
+        }
+    }
+
+    ///
+    /// Narrowed type of a nullable or union variable that was given a value
+    /// that is not nil, only the members accepting the value are kept
+    ///
+    fn assigned_narrowing(&self, declared_type: &Type, value_type: &Type) -> Option<Type> {
+        if *value_type == Type::Nil {
+            return None;
+        }
+
+        let value_members = value_type.members();
+        let narrowed_type = Type::union(
+            declared_type
+                .members()
+                .into_iter()
+                .filter(|member| {
+                    value_members
+                        .iter()
+                        .any(|value_member| self.is_assignable(member, value_member))
+                })
+                .collect(),
+        );
+        match narrowed_type == *declared_type || narrowed_type == Type::Nil {
+            true => None,
+            false => Some(narrowed_type),
+        }
+    }
+
+    fn check_assignment(
+        &mut self,
+        operator: &AssignmentOperator,
+        left: &ExpressionRef,
+        right: &ExpressionRef,
+    ) -> Result<Type> {
+        let right_type = self.visit_expression(right)?;
+
+        // Variables are assigned as declared, compound assignments read the
+        // narrowed value
+        let (declared_type, left_type) = match left.as_ref() {
+            Expression::Identifier { name, span } => {
+                match (self.scopes.lookup_declared(name), self.scopes.lookup(name)) {
+                    (Some(Type::Function { .. }), _) => {
+                        return Err(semantic_error(
+                            format!("Cannot assign to function '{}'", name),
+                            *span,
+                        ))
+                    }
+                    (Some(declared_type), Some(variable_type)) => {
+                        (declared_type.clone(), variable_type.clone())
+                    }
+                    _ => {
+                        return Err(semantic_error(
+                            format!("Undefined variable '{}'", name),
+                            *span,
+                        ))
+                    }
+                }
+            }
+            _ => {
+                let left_type = self.visit_expression(left)?;
+                (left_type.clone(), left_type)
+            }
+        };
+
+        let value_type = match operator {
+            AssignmentOperator::Assign => right_type,
+            AssignmentOperator::AssignAdd => {
+                self.binary_type(BinaryOperator::Add, &left_type, &right_type, right.span())?
+            }
+            AssignmentOperator::AssignSubtract => self.binary_type(
+                BinaryOperator::Subtract,
+                &left_type,
+                &right_type,
+                right.span(),
+            )?,
+            AssignmentOperator::AssignMultiply => self.binary_type(
+                BinaryOperator::Multiply,
+                &left_type,
+                &right_type,
+                right.span(),
+            )?,
+            AssignmentOperator::AssignDivide => self.binary_type(
+                BinaryOperator::Divide,
+                &left_type,
+                &right_type,
+                right.span(),
+            )?,
+        };
+
+        self.ensure_assignable(&declared_type, &value_type, right.span())?;
+
+        if let Expression::Identifier { name, .. } = left.as_ref() {
+            self.scopes.forget_narrowing(name);
+            if let Some(narrowed_type) = self.assigned_narrowing(&declared_type, &value_type) {
+                self.narrow(name, narrowed_type);
+            }
+        }
+
+        Ok(declared_type)
+    }
+
+    ///
+    /// 'a ?? b' takes 'b' when 'a' is nil, a 'b' that is never nil makes the
+    /// result not nullable
+    ///
+    fn check_nil_coalescing(
+        &mut self,
+        left: &ExpressionRef,
+        right: &ExpressionRef,
+        span: Span,
+    ) -> Result<Type> {
+        let left_type = self.visit_expression(left)?;
+        let right_type = self.visit_expression(right)?;
+
+        let value_type = left_type.non_nullable();
+        if left_type == Type::Nil {
+            return Ok(right_type);
+        }
+        if self.is_assignable(value_type, &right_type) {
+            return Ok(value_type.clone());
+        }
+        let nullable_type = Type::nullable(value_type.clone());
+        if self.is_assignable(&nullable_type, &right_type) {
+            return Ok(nullable_type);
+        }
+
+        Err(type_error(
+            format!(
+                "Operator '{}' cannot be applied to '{}' and '{}'",
+                LogicalOperator::NilCoalescing,
+                left_type,
+                right_type
+            ),
+            span,
+        ))
+    }
+
+    fn binary_type(
+        &self,
+        operator: BinaryOperator,
+        left: &Type,
+        right: &Type,
+        span: Span,
+    ) -> Result<Type> {
+        let number = Type::number();
+        let string = Type::string();
+
+        // Enum values are separate objects, equality would only compare
+        // their references
+        if matches!(operator, BinaryOperator::Equal | BinaryOperator::NotEqual)
+            && *left != Type::Nil
+            && *right != Type::Nil
+            && (holds_enum(left) || holds_enum(right))
+        {
+            return Err(type_error(
+                format!(
+                    "Operator '{}' cannot compare enum values, use a match instead",
+                    operator
+                ),
+                span,
+            ));
+        }
+
+        let result = match (left, right) {
+            // Equality compares the whole values, other operators have to
+            // apply to every member of a union
+            _ if matches!(operator, BinaryOperator::Equal | BinaryOperator::NotEqual) => None,
+            (Type::Union(members), _) => Some(
+                members
+                    .iter()
+                    .map(|member| self.binary_type(operator, member, right, span).ok())
+                    .collect::<Option<Vec<_>>>()
+                    .map(Type::union),
+            ),
+            (_, Type::Union(members)) => Some(
+                members
+                    .iter()
+                    .map(|member| self.binary_type(operator, left, member, span).ok())
+                    .collect::<Option<Vec<_>>>()
+                    .map(Type::union),
+            ),
+            _ => None,
+        };
+
+        let result = result.unwrap_or_else(|| match operator {
+            BinaryOperator::Add if *left == number && *right == number => Some(number),
+            BinaryOperator::Add if *left == string && *right == string => Some(string),
+            BinaryOperator::Subtract | BinaryOperator::Multiply | BinaryOperator::Divide
+                if *left == number && *right == number =>
+            {
+                Some(number)
+            }
+            // Anything can be compared to nil, even if it never is
+            BinaryOperator::Equal | BinaryOperator::NotEqual
+                if *left == Type::Nil
+                    || *right == Type::Nil
+                    || self.is_assignable(left, right)
+                    || self.is_assignable(right, left) =>
+            {
+                Some(Type::boolean())
+            }
+            BinaryOperator::GreaterThan
+            | BinaryOperator::GreaterThanOrEqualTo
+            | BinaryOperator::LessThan
+            | BinaryOperator::LessThanOrEqualTo
+                if (*left == number && *right == number)
+                    || (*left == string && *right == string) =>
+            {
+                Some(Type::boolean())
+            }
+            _ => None,
+        });
+
+        result.ok_or_else(|| {
+            type_error(
+                format!(
+                    "Operator '{}' cannot be applied to '{}' and '{}'",
+                    operator, left, right
+                ),
+                span,
+            )
+        })
+    }
+
+    fn check_member(
+        &mut self,
+        computed: bool,
+        optional: bool,
+        object: &ExpressionRef,
+        property: &ExpressionRef,
+        span: Span,
+    ) -> Result<Type> {
+        let (member_type, _) =
+            self.check_member_access(computed, optional, object, property, span)?;
+        Ok(member_type)
+    }
+
+    ///
+    /// Type of a member with the type parameters of a generic method
+    ///
+    /// Optional access on a nullable object gives a nullable member type
+    ///
+    fn check_member_access(
+        &mut self,
+        computed: bool,
+        optional: bool,
+        object: &ExpressionRef,
+        property: &ExpressionRef,
+        span: Span,
+    ) -> Result<(Type, Vec<TypeParameterInfo>)> {
+        let object_type = self.visit_expression(object)?;
+
+        let Type::Nullable(value_type) = &object_type else {
+            return self.check_present_member_access(computed, object_type, property, span);
+        };
+        if !optional {
+            let message = match property.as_ref() {
+                Expression::Identifier { name, .. } if !computed => format!(
+                    "Cannot access '{}' on a possibly nil value of type '{}'",
+                    name, object_type
+                ),
+                _ => format!(
+                    "Cannot index a possibly nil value of type '{}'",
+                    object_type
+                ),
+            };
+            return Err(type_error(message, span));
+        }
+
+        let value_type = value_type.as_ref().clone();
+        let (member_type, type_parameters) =
+            self.check_present_member_access(computed, value_type, property, span)?;
+        Ok((Type::nullable(member_type), type_parameters))
+    }
+
+    fn check_present_member_access(
+        &mut self,
+        computed: bool,
+        object_type: Type,
+        property: &ExpressionRef,
+        span: Span,
+    ) -> Result<(Type, Vec<TypeParameterInfo>)> {
+        if computed {
+            let index_type = self.visit_expression(property)?;
+            self.ensure_assignable(&Type::number(), &index_type, property.span())?;
+
+            return match self.element_type(&object_type) {
+                Some(element_type) => Ok((element_type, vec![])),
+                None => Err(type_error(
+                    format!("Type '{}' cannot be indexed", object_type),
+                    span,
+                )),
+            };
+        }
+
+        let (property_name, property_span) = identifier_name(property)?;
+        self.property_type(&object_type, &property_name)
+            .ok_or_else(|| {
+                type_error(
+                    format!(
+                        "Property '{}' does not exist on type '{}'",
+                        property_name, object_type
+                    ),
+                    property_span,
+                )
+            })
+    }
+
+    ///
+    /// Type of the elements of an indexed value, every member of a union
+    /// has to be indexable
+    ///
+    fn element_type(&self, object_type: &Type) -> Option<Type> {
+        match object_type {
+            Type::Array(element_type) => Some(element_type.as_ref().clone()),
+            Type::Primitive(_) if *object_type == Type::string() => Some(Type::string()),
+            Type::Union(members) => members
+                .iter()
+                .map(|member| self.element_type(member))
+                .collect::<Option<Vec<_>>>()
+                .map(Type::union),
+            _ => None,
+        }
+    }
+
+    ///
+    /// Type of a property, every member of a union has to have it
+    ///
+    fn property_type(
+        &self,
+        object_type: &Type,
+        property_name: &str,
+    ) -> Option<(Type, Vec<TypeParameterInfo>)> {
+        match object_type {
+            Type::Array(_) if property_name == "length" => Some((Type::number(), vec![])),
+            Type::Primitive(_) if *object_type == Type::string() && property_name == "length" => {
+                Some((Type::number(), vec![]))
+            }
+            Type::Union(members) => {
+                let mut member_types = members
+                    .iter()
+                    .map(|member| self.property_type(member, property_name))
+                    .collect::<Option<Vec<_>>>()?;
+
+                // Members sharing the same property keep its type parameters
+                let (first_type, _) = &member_types[0];
+                if member_types
+                    .iter()
+                    .all(|(member_type, _)| member_type == first_type)
+                {
+                    return Some(member_types.remove(0));
+                }
+                let member_types = member_types.into_iter().map(|(member_type, _)| member_type);
+                Some((Type::union(member_types.collect()), vec![]))
+            }
+            _ => self.member_type(object_type, property_name),
+        }
+    }
+
+    ///
+    /// Member of a class instance, the type arguments of a generic class are
+    /// substituted and type parameters are accessed through their bound
+    ///
+    fn member_type(
+        &self,
+        object_type: &Type,
+        member_name: &str,
+    ) -> Option<(Type, Vec<TypeParameterInfo>)> {
+        match object_type {
+            Type::Class { name, .. } if self.interfaces.contains_key(name) => {
+                let interface_info = &self.interfaces[name];
+                interface_info
+                    .fields
+                    .get(member_name)
+                    .or_else(|| interface_info.methods.get(member_name))
+                    .map(|member_type| (member_type.clone(), vec![]))
+            }
+            Type::Class { name, .. } => self.find_member(name, member_name),
+            Type::Generic { base, type_args } => {
+                let (member_type, type_parameters) = self.find_member(base, member_name)?;
+                let type_arguments =
+                    bind_type_arguments(&self.class_type_parameters(base), type_args);
+                Some((substitute(&member_type, &type_arguments), type_parameters))
+            }
+            Type::Parameter(name) => {
+                let bound = self.lookup_type_parameter(name)?.bound.as_ref()?;
+                self.member_type(bound, member_name)
+            }
+            _ => None,
+        }
+    }
+
+    ///
+    /// Field of a class instance, methods are left out
+    ///
+    fn field_type(&self, object_type: &Type, field_name: &str) -> Option<Type> {
+        match object_type {
+            Type::Class { name, .. } if self.interfaces.contains_key(name) => {
+                self.interfaces[name].fields.get(field_name).cloned()
+            }
+            Type::Class { name, .. } => self
+                .class_chain(name)
+                .into_iter()
+                .find_map(|class_info| class_info.fields.get(field_name).cloned()),
+            Type::Generic { base, type_args } => {
+                let field_type = self.field_type(
+                    &Type::Class {
+                        name: base.clone(),
+                        super_class: None,
+                    },
+                    field_name,
+                )?;
+                let type_arguments =
+                    bind_type_arguments(&self.class_type_parameters(base), type_args);
+                Some(substitute(&field_type, &type_arguments))
+            }
+            Type::Parameter(name) => {
+                let bound = self.lookup_type_parameter(name)?.bound.as_ref()?;
+                self.field_type(bound, field_name)
+            }
+            _ => None,
+        }
+    }
+
+    fn check_call(
+        &mut self,
+        callee: &ExpressionRef,
+        arguments: &ExpressionList,
+        span: Span,
+    ) -> Result<Type> {
+        match callee.as_ref() {
+            Expression::Identifier { name, .. }
+                if self.scopes.lookup(name).is_none()
+                    && BUILTIN_FUNCTIONS.contains(&name.as_str()) =>
+            {
+                for argument in arguments {
+                    self.visit_expression(argument)?;
+                }
+                return Ok(Type::Void);
+            }
+            Expression::Super { span: super_span } => {
+                if !self.in_constructor {
+                    return Err(semantic_error(
+                        String::from("'super' can only be called from a constructor"),
+                        *super_span,
+                    ));
+                }
+
+                let super_class = self.current_super_class(*super_span)?;
+                let parameters = self.constructor_parameters(&super_class);
+                self.check_arguments(&parameters, arguments, span)?;
+                return Ok(Type::Void);
+            }
+            _ => {}
+        }
+
+        // Generic functions and methods infer their type arguments from the call
+        let (callee_type, type_parameters) = match callee.as_ref() {
+            Expression::Identifier { name, .. } => {
+                let callee_type = self.visit_expression(callee)?;
+                let type_parameters = self
+                    .scopes
+                    .lookup_type_parameters(name)
+                    .cloned()
+                    .unwrap_or_default();
+                (callee_type, type_parameters)
+            }
+            Expression::Member {
+                computed,
+                optional,
+                object,
+                property,
+                span,
+            } => self.check_member_access(*computed, *optional, object, property, *span)?,
+            _ => (self.visit_expression(callee)?, vec![]),
+        };
+
+        // 'a?.m()' is not called when 'a' is nil and gives nil instead
+        let (callee_type, optional) = match callee_type {
+            Type::Nullable(callee_type)
+                if matches!(callee.as_ref(), Expression::Member { optional: true, .. }) =>
+            {
+                (*callee_type, true)
+            }
+            _ => (callee_type, false),
+        };
+
+        let return_type = match callee_type {
+            Type::Function {
+                params,
+                return_type,
+            } if type_parameters.is_empty() => {
+                self.check_arguments(&params, arguments, span)?;
+                *return_type
+            }
+            Type::Function {
+                params,
+                return_type,
+            } => {
+                let type_arguments = self.infer_type_arguments(
+                    &type_parameters,
+                    &params,
+                    arguments,
+                    HashMap::new(),
+                    span,
+                )?;
+                substitute(&return_type, &type_arguments)
+            }
+            callee_type => {
+                return Err(type_error(
+                    format!("Type '{}' is not callable", callee_type),
+                    callee.span(),
+                ))
+            }
+        };
+
+        match return_type {
+            Type::Void => Ok(Type::Void),
+            _ if optional => Ok(Type::nullable(return_type)),
+            _ => Ok(return_type),
+        }
+    }
+
+    fn check_new(
+        &mut self,
+        callee: &ExpressionRef,
+        arguments: &ExpressionList,
+        target_type: Option<&Type>,
+        span: Span,
+    ) -> Result<Type> {
+        let (class_name, class_span) = identifier_name(callee)?;
+        if self.interfaces.contains_key(&class_name) {
+            return Err(semantic_error(
+                format!("Interface '{}' can not be instantiated", class_name),
+                class_span,
+            ));
+        }
+        if self.enums.contains_key(&class_name) {
+            return Err(semantic_error(
+                format!(
+                    "Enum '{}' can not be instantiated, build one of its variants instead",
+                    class_name
+                ),
+                class_span,
+            ));
+        }
+        if !self.classes.contains_key(&class_name) {
+            return Err(semantic_error(
+                format!("Undefined class '{}'", class_name),
+                class_span,
+            ));
+        }
+
+        let parameters = self.constructor_parameters(&class_name);
+        let type_parameters = self.class_type_parameters(&class_name);
+        if type_parameters.is_empty() {
+            self.check_arguments(&parameters, arguments, span)?;
+            return Ok(self.class_type(&class_name));
+        }
+
+        // The type arguments come from the type the instance is stored as,
+        // the rest is inferred from the constructor arguments
+        let type_arguments = match target_type {
+            Some(Type::Generic { base, type_args }) if *base == class_name => {
+                bind_type_arguments(&type_parameters, type_args)
+            }
+            _ => HashMap::new(),
+        };
+        let type_arguments = self.infer_type_arguments(
+            &type_parameters,
+            &parameters,
+            arguments,
+            type_arguments,
+            span,
+        )?;
+
+        Ok(Type::Generic {
+            base: class_name,
+            type_args: type_parameters
+                .iter()
+                .map(|type_parameter| type_arguments[&type_parameter.name].clone())
+                .collect(),
+        })
+    }
+
+    ///
+    /// Value of an enum variant, the arguments have to match its payload
+    ///
+    fn check_enum_variant(
+        &mut self,
+        enum_name: &ExpressionRef,
+        variant: &ExpressionRef,
+        arguments: &ExpressionList,
+        span: Span,
+    ) -> Result<Type> {
+        let (enum_name, enum_span) = identifier_name(enum_name)?;
+        let (variant_name, variant_span) = identifier_name(variant)?;
+        let Some(enum_info) = self.enums.get(&enum_name) else {
+            return Err(semantic_error(
+                format!("Undefined enum '{}'", enum_name),
+                enum_span,
+            ));
+        };
+        let Some(payload) = enum_info.variant(&variant_name).cloned() else {
+            return Err(type_error(
+                format!(
+                    "Variant '{}' does not exist on enum '{}'",
+                    variant_name, enum_name
+                ),
+                variant_span,
+            ));
+        };
+
+        self.check_arguments(&payload, arguments, span)?;
+        Ok(Type::Enum(enum_name))
+    }
+
+    ///
+    /// Match of a value against arms tried in order, every value has to be
+    /// matched by an arm and every arm has to match some value
+    ///
+    fn check_match(
+        &mut self,
+        subject: &ExpressionRef,
+        arms: &MatchArmList,
+        span: Span,
+    ) -> Result<Type> {
+        let subject_type = self.visit_expression(subject)?;
+
+        // Values matched by the arms before, arms with a guard may match none
+        let mut matched = vec![];
+        let mut arm_types = vec![];
+        for arm in arms {
+            self.scopes.push();
+            let arm_type = self.check_match_arm(arm, &subject_type, &mut matched);
+            self.scopes.pop();
+            arm_types.push(arm_type?);
+        }
+
+        if is_useful(self, &matched, &Space::Any, &subject_type) {
+            return Err(type_error(
+                format!(
+                    "Match on a value of type '{}' is not exhaustive, add the missing arms or a '_' arm",
+                    subject_type
+                ),
+                span,
+            ));
+        }
+
+        match arm_types.is_empty() || arm_types.contains(&Type::Void) {
+            true => Ok(Type::Void),
+            false => Ok(Type::union(arm_types)),
+        }
+    }
+
+    fn check_match_arm(
+        &mut self,
+        arm: &MatchArm,
+        subject_type: &Type,
+        matched: &mut Vec<Space>,
+    ) -> Result<Type> {
+        self.check_pattern(&arm.pattern, subject_type)?;
+
+        let space = pattern_space(self, &arm.pattern);
+        if !is_useful(self, matched, &space, subject_type) {
+            return Err(type_error(
+                String::from(
+                    "Match arm is unreachable, the arms before it match all of its values",
+                ),
+                arm.pattern.span(),
+            ));
+        }
+
+        // A name bound to the whole value only gets the values the arms
+        // before did not match
+        if let Pattern::Binding {
+            name,
+            type_annotation: None,
+            ..
+        } = &arm.pattern
+        {
+            let (binding_name, _) = identifier_name(name)?;
+            let members: Vec<Type> = subject_type
+                .members()
+                .into_iter()
+                .filter(|member| is_useful(self, matched, &Space::Any, member))
+                .collect();
+            let remaining_type = Type::union(members);
+            if remaining_type != *subject_type {
+                self.narrow(&binding_name, remaining_type);
+            }
+        }
+
+        let narrowings = match &arm.guard {
+            Some(guard) => self.check_condition(guard)?.0,
+            None => {
+                matched.push(space);
+                Narrowings::new()
+            }
+        };
+        self.with_narrowings(narrowings, |checker| checker.visit_expression(&arm.value))
+    }
+
+    ///
+    /// Check a pattern against the type of the values it is matched with and
+    /// declare its bindings
+    ///
+    fn check_pattern(&mut self, pattern: &Pattern, expected_type: &Type) -> Result<()> {
+        match pattern {
+            Pattern::Wildcard {
+                type_annotation,
+                span,
+            } => {
+                if let Some(type_annotation) = type_annotation {
+                    self.pattern_type(type_annotation, expected_type, *span)?;
+                }
+                Ok(())
+            }
+            Pattern::Binding {
+                name,
+                type_annotation,
+                span,
+            } => {
+                let (binding_name, name_span) = identifier_name(name)?;
+                let binding_type = match type_annotation {
+                    Some(type_annotation) => {
+                        self.pattern_type(type_annotation, expected_type, *span)?
+                    }
+                    None => expected_type.clone(),
+                };
+                self.declare(&binding_name, binding_type, name_span)
+            }
+            Pattern::Literal { value, span } => {
+                let literal_type = self.visit_expression(value)?;
+                let matches = expected_type
+                    .members()
+                    .iter()
+                    .any(|member| self.is_assignable(member, &literal_type));
+                match matches {
+                    true => Ok(()),
+                    false => Err(never_matches(&literal_type, expected_type, *span)),
+                }
+            }
+            Pattern::EnumVariant {
+                enum_name,
+                variant,
+                arguments,
+                span,
+            } => {
+                let (enum_name, enum_span) = identifier_name(enum_name)?;
+                let (variant_name, variant_span) = identifier_name(variant)?;
+                let Some(enum_info) = self.enums.get(&enum_name) else {
+                    return Err(semantic_error(
+                        format!("Undefined enum '{}'", enum_name),
+                        enum_span,
+                    ));
+                };
+                let Some(payload) = enum_info.variant(&variant_name).cloned() else {
+                    return Err(type_error(
+                        format!(
+                            "Variant '{}' does not exist on enum '{}'",
+                            variant_name, enum_name
+                        ),
+                        variant_span,
+                    ));
+                };
+
+                let enum_type = Type::Enum(enum_name);
+                if !expected_type.members().contains(&enum_type) {
+                    return Err(never_matches(&enum_type, expected_type, *span));
+                }
+                if payload.len() != arguments.len() {
+                    return Err(type_error(
+                        format!(
+                            "Variant '{}' carries {} values, found {} patterns",
+                            variant_name,
+                            payload.len(),
+                            arguments.len()
+                        ),
+                        *span,
+                    ));
+                }
+
+                for (argument, value_type) in arguments.iter().zip(&payload) {
+                    self.check_pattern(argument, value_type)?;
+                }
+                Ok(())
+            }
+            Pattern::Array { elements, span } => {
+                let element_type =
+                    expected_type
+                        .members()
+                        .into_iter()
+                        .find_map(|member| match member {
+                            Type::Array(element_type) => Some(*element_type),
+                            _ => None,
+                        });
+                let Some(element_type) = element_type else {
+                    return Err(type_error(
+                        format!(
+                            "Array pattern can never match a value of type '{}'",
+                            expected_type
+                        ),
+                        *span,
+                    ));
+                };
+
+                for element in elements {
+                    self.check_pattern(element, &element_type)?;
+                }
+                Ok(())
+            }
+            Pattern::Object { span, .. } => Err(type_error(
+                String::from("Object patterns are only allowed in declarations"),
+                *span,
+            )),
+        }
+    }
+
+    ///
+    /// Check a destructured variable declaration, the bindings take the
+    /// types of the parts of the initializer they are bound to
+    ///
+    fn check_destructuring(
+        &mut self,
+        pattern: &Pattern,
+        type_annotation: &Option<Type>,
+        initializer: &Option<ExpressionRef>,
+        span: Span,
+    ) -> Result<()> {
+        let Some(initializer) = initializer else {
+            return Err(type_error(
+                String::from("Destructured variables need an initializer"),
+                span,
+            ));
+        };
+
+        let value_type = match type_annotation {
+            Some(type_annotation) => {
+                self.validate_type(type_annotation, span)?;
+                let value_type = self.resolve_type(type_annotation);
+                self.check_initializer(&value_type, initializer)?;
+                value_type
+            }
+            None => self.visit_expression(initializer)?,
+        };
+        self.declare_pattern(pattern, &value_type)
+    }
+
+    ///
+    /// Declare the bindings of a destructuring pattern, the pattern has to
+    /// match every value of the type
+    ///
+    fn declare_pattern(&mut self, pattern: &Pattern, value_type: &Type) -> Result<()> {
+        match pattern {
+            Pattern::Wildcard { .. } => Ok(()),
+            Pattern::Binding { name, .. } => {
+                let (binding_name, span) = identifier_name(name)?;
+                self.declare(&binding_name, value_type.clone(), span)
+            }
+            Pattern::Array { elements, span } => {
+                let Type::Array(element_type) = value_type else {
+                    return Err(cannot_destructure(value_type, "an array", *span));
+                };
+                for element in elements {
+                    self.declare_pattern(element, element_type)?;
+                }
+                Ok(())
+            }
+            Pattern::Object { properties, span } => {
+                if !matches!(
+                    value_type,
+                    Type::Class { .. } | Type::Generic { .. } | Type::Parameter(_)
+                ) {
+                    return Err(cannot_destructure(value_type, "an object", *span));
+                }
+                for property in properties {
+                    let (field_name, field_span) = identifier_name(&property.key)?;
+                    let Some(field_type) = self.field_type(value_type, &field_name) else {
+                        return Err(type_error(
+                            format!(
+                                "Field '{}' does not exist on type '{}'",
+                                field_name, value_type
+                            ),
+                            field_span,
+                        ));
+                    };
+                    self.declare_pattern(&property.value, &field_type)?;
+                }
+                Ok(())
+            }
+            Pattern::Literal { span, .. } | Pattern::EnumVariant { span, .. } => Err(type_error(
+                String::from("Only names and '_' can be destructured into"),
+                *span,
+            )),
+        }
+    }
+
+    ///
+    /// Type of the values matched by a type annotation in a pattern, the
+    /// members of the expected type it narrows down to
+    ///
+    fn pattern_type(
+        &mut self,
+        type_annotation: &Type,
+        expected_type: &Type,
+        span: Span,
+    ) -> Result<Type> {
+        let tested_type = self.tested_type(type_annotation, span)?;
+
+        let mut members = vec![];
+        for member in expected_type.members() {
+            if self.is_assignable(&tested_type, &member) {
+                members.push(member);
+            } else if self.is_assignable(&member, &tested_type) {
+                members.push(tested_type.clone());
+            }
+        }
+
+        match members.is_empty() {
+            true => Err(never_matches(&tested_type, expected_type, span)),
+            false => Ok(Type::union(members)),
+        }
+    }
+
+    ///
+    /// Type tested by a type test or a pattern, only the kinds of values are
+    /// known at runtime
+    ///
+    fn tested_type(&self, type_annotation: &Type, span: Span) -> Result<Type> {
+        self.validate_type(type_annotation, span)?;
+
+        let tested_type = self.resolve_type(type_annotation);
+        match tested_type {
+            Type::Class { ref name, .. } if self.interfaces.contains_key(name) => Err(type_error(
+                format!("Type '{}' cannot be tested at runtime", tested_type),
+                span,
+            )),
+            Type::Primitive(_) | Type::Class { .. } | Type::Enum(_) | Type::Nil => Ok(tested_type),
+            _ => Err(type_error(
+                format!("Type '{}' cannot be tested at runtime", tested_type),
+                span,
+            )),
+        }
+    }
+
+    ///
+    /// Bind the type parameters of a generic call to the types of the
+    /// arguments, every type parameter has to be inferred
+    ///
+    fn infer_type_arguments(
+        &mut self,
+        type_parameters: &[TypeParameterInfo],
+        parameters: &[Type],
+        arguments: &ExpressionList,
+        mut type_arguments: HashMap<String, Type>,
+        span: Span,
+    ) -> Result<HashMap<String, Type>> {
+        if parameters.len() != arguments.len() {
+            return Err(type_error(
+                format!(
+                    "Expected {} arguments, found {}",
+                    parameters.len(),
+                    arguments.len()
+                ),
+                span,
+            ));
+        }
+
+        let mut argument_types = vec![];
+        for argument in arguments {
+            argument_types.push(self.visit_expression(argument)?);
+        }
+
+        for (parameter_type, argument_type) in parameters.iter().zip(&argument_types) {
+            unify(parameter_type, argument_type, &mut type_arguments);
+        }
+
+        let mut type_args = vec![];
+        for type_parameter in type_parameters {
+            match type_arguments.get(&type_parameter.name) {
+                Some(type_arg) => type_args.push(type_arg.clone()),
+                None => {
+                    return Err(type_error(
+                        format!("Cannot infer the type argument '{}'", type_parameter.name),
+                        span,
+                    ))
+                }
+            }
+        }
+        self.check_bounds(type_parameters, &type_args, span)?;
+
+        for ((parameter_type, argument), argument_type) in
+            parameters.iter().zip(arguments).zip(&argument_types)
+        {
+            let parameter_type = substitute(parameter_type, &type_arguments);
+            self.ensure_assignable(&parameter_type, argument_type, argument.span())?;
+        }
+
+        Ok(type_arguments)
+    }
+
+    ///
+    /// Check a value against the type it is stored as, a generic class
+    /// instantiated by the value takes its type arguments from that type
+    ///
+    fn check_initializer(&mut self, target_type: &Type, initializer: &Expression) -> Result<Type> {
+        let initializer_type = match initializer {
+            Expression::New {
+                callee,
+                arguments,
+                span,
+            } => self.check_new(callee, arguments, Some(target_type), *span)?,
+            Expression::ArrayLiteral { elements, span } => {
+                self.check_array_literal(elements, Some(target_type), *span)?
+            }
+            _ => self.visit_expression(initializer)?,
+        };
+        self.ensure_assignable(target_type, &initializer_type, initializer.span())?;
+        Ok(initializer_type)
+    }
+
+    ///
+    /// Check the elements of an array literal, they take the element type of
+    /// the array it is stored as or the array takes the types of its elements
+    ///
+    fn check_array_literal(
+        &mut self,
+        elements: &ExpressionList,
+        target_type: Option<&Type>,
+        span: Span,
+    ) -> Result<Type> {
+        let target_element_type = target_type.and_then(|target_type| {
+            target_type
+                .members()
+                .into_iter()
+                .find_map(|member| match member {
+                    Type::Array(element_type) => Some(*element_type),
+                    _ => None,
+                })
+        });
+
+        if let Some(element_type) = target_element_type {
+            for element in elements {
+                self.check_initializer(&element_type, element)?;
+            }
+            return Ok(Type::Array(Box::new(element_type)));
+        }
+
+        let mut element_types = vec![];
+        for element in elements {
+            let element_type = self.visit_expression(element)?;
+            if element_type == Type::Void {
+                return Err(type_error(
+                    String::from("Array elements cannot be of type 'void'"),
+                    element.span(),
+                ));
+            }
+            element_types.push(element_type);
+        }
+
+        match Type::union(element_types) {
+            // Neither tells what the array can hold later on
+            Type::Nil => Err(type_error(
+                String::from("Cannot infer the element type of the array, add a type annotation"),
+                span,
+            )),
+            element_type => Ok(Type::Array(Box::new(element_type))),
+        }
+    }
+
+    ///
+    /// Type of a variable declared without an annotation, taken from its
+    /// initializer
+    ///
+    fn infer_variable_type(&mut self, name: &str, initializer: &ExpressionRef) -> Result<Type> {
+        let initializer_type = self.visit_expression(initializer)?;
+        match initializer_type {
+            // Neither tells what the variable can hold later on
+            Type::Nil | Type::Void => Err(type_error(
+                format!(
+                    "Cannot infer the type of '{}' from '{}', add a type annotation",
+                    name, initializer_type
+                ),
+                initializer.span(),
+            )),
+            _ => Ok(initializer_type),
+        }
+    }
+
+    fn check_arguments(
+        &mut self,
+        parameters: &[Type],
+        arguments: &ExpressionList,
+        span: Span,
+    ) -> Result<()> {
+        if parameters.len() != arguments.len() {
+            return Err(type_error(
+                format!(
+                    "Expected {} arguments, found {}",
+                    parameters.len(),
+                    arguments.len()
+                ),
+                span,
+            ));
+        }
+
+        for (parameter_type, argument) in parameters.iter().zip(arguments) {
+            self.check_initializer(parameter_type, argument)?;
+        }
+
+        Ok(())
+    }
+
+    fn constructor_parameters(&self, class_name: &str) -> Vec<Type> {
+        match self.find_method(class_name, CONSTRUCTOR_NAME) {
+            Some(Type::Function { params, .. }) => params,
+            _ => vec![],
+        }
+    }
+
+    ///
+    /// Superclass of the class whose method is being checked
+    ///
+    fn current_super_class(&self, span: Span) -> Result<String> {
+        self.current_class
+            .as_ref()
+            .filter(|_| self.in_method)
+            .and_then(|class_name| self.classes.get(class_name))
+            .and_then(|class_info| class_info.super_class.clone())
+            .ok_or_else(|| {
+                semantic_error(
+                    String::from("'super' can only be used in a subclass method"),
+                    span,
+                )
+            })
+    }
+
+    ///
+    /// Class type with its superclass
+    ///
+    fn class_type(&self, class_name: &str) -> Type {
+        Type::Class {
+            name: String::from(class_name),
+            super_class: self
+                .classes
+                .get(class_name)
+                .and_then(|class_info| class_info.super_class.clone()),
+        }
+    }
+
+    ///
+    /// Type of 'this' in a class, generic classes are instantiated with their
+    /// own type parameters
+    ///
+    fn instance_type(&self, class_name: &str) -> Type {
+        let type_parameters = self.class_type_parameters(class_name);
+        if type_parameters.is_empty() {
+            return self.class_type(class_name);
+        }
+
+        Type::Generic {
+            base: String::from(class_name),
+            type_args: type_parameters
+                .into_iter()
+                .map(|type_parameter| Type::Parameter(type_parameter.name))
+                .collect(),
+        }
+    }
+
+    ///
+    /// Annotation with the superclasses of its class types filled in
+    ///
+    pub(super) fn resolve_type(&self, annotation: &Type) -> Type {
+        match annotation {
+            Type::Array(element_type) => Type::Array(Box::new(self.resolve_type(element_type))),
+            Type::Function {
+                params,
+                return_type,
+            } => Type::Function {
+                params: params
+                    .iter()
+                    .map(|param| self.resolve_type(param))
+                    .collect(),
+                return_type: Box::new(self.resolve_type(return_type)),
+            },
+            Type::Class { name, .. } if self.lookup_type_parameter(name).is_some() => {
+                Type::Parameter(name.clone())
+            }
+            Type::Class { name, .. } if self.enums.contains_key(name) => Type::Enum(name.clone()),
+            Type::Class { name, .. } => self.class_type(name),
+            Type::Generic { base, type_args } => Type::Generic {
+                base: base.clone(),
+                type_args: type_args
+                    .iter()
+                    .map(|type_arg| self.resolve_type(type_arg))
+                    .collect(),
+            },
+            Type::Nullable(value_type) => Type::nullable(self.resolve_type(value_type)),
+            Type::Union(members) => Type::union(
+                members
+                    .iter()
+                    .map(|member| self.resolve_type(member))
+                    .collect(),
+            ),
+            Type::Primitive(_) | Type::Parameter(_) | Type::Enum(_) | Type::Void | Type::Nil => {
+                annotation.clone()
+            }
+        }
+    }
+
+    ///
+    /// Walk the class and its ancestors, stops on inheritance cycles
+    ///
+    fn class_chain(&self, class_name: &str) -> Vec<&ClassInfo> {
+        let mut chain = vec![];
+        let mut visited = HashSet::new();
+        let mut current = Some(class_name.to_string());
+
+        while let Some(name) = current {
+            if !visited.insert(name.clone()) {
+                break;
+            }
+            let Some(class_info) = self.classes.get(&name) else {
+                break;
+            };
+            chain.push(class_info);
+            current = class_info.super_class.clone();
+        }
+
+        chain
+    }
+
+    fn find_method(&self, class_name: &str, method_name: &str) -> Option<Type> {
+        self.class_chain(class_name)
+            .into_iter()
+            .find_map(|class_info| class_info.methods.get(method_name).cloned())
+    }
+
+    fn find_member(
+        &self,
+        class_name: &str,
+        member_name: &str,
+    ) -> Option<(Type, Vec<TypeParameterInfo>)> {
+        self.class_chain(class_name)
+            .into_iter()
+            .find_map(|class_info| {
+                if let Some(field_type) = class_info.fields.get(member_name) {
+                    return Some((field_type.clone(), vec![]));
+                }
+
+                let method_type = class_info.methods.get(member_name)?;
+                let type_parameters = class_info
+                    .method_type_parameters
+                    .get(member_name)
+                    .cloned()
+                    .unwrap_or_default();
+                Some((method_type.clone(), type_parameters))
+            })
+    }
+
+    fn is_subclass(&self, class_name: &str, ancestor_name: &str) -> bool {
+        let mut visited = HashSet::new();
+        let mut current = Some(class_name.to_string());
+
+        while let Some(name) = current {
+            if name == ancestor_name {
+                return true;
+            }
+            if !visited.insert(name.clone()) {
+                return false;
+            }
+            current = self
+                .classes
+                .get(&name)
+                .and_then(|class_info| class_info.super_class.clone());
+        }
+
+        false
+    }
+
+    ///
+    /// Whether the class or one of its ancestors implements the interface
+    ///
+    fn implements(&self, class_name: &str, interface_name: &str) -> bool {
+        self.class_chain(class_name).into_iter().any(|class_info| {
+            class_info
+                .interfaces
+                .iter()
+                .any(|interface| interface == interface_name)
+        })
+    }
+
+    ///
+    /// Whether a name refers to a declared class, interface or enum
+    ///
+    fn is_type_declared(&self, name: &str) -> bool {
+        self.classes.contains_key(name)
+            || self.interfaces.contains_key(name)
+            || self.enums.contains_key(name)
+    }
+
+    pub(super) fn is_assignable(&self, target: &Type, value: &Type) -> bool {
+        match (target, value) {
+            (_, Type::Union(values)) => {
+                values.iter().all(|value| self.is_assignable(target, value))
+            }
+            (Type::Nullable(_) | Type::Nil, Type::Nil) => true,
+            (_, Type::Nil) => false,
+            (Type::Nullable(target), Type::Nullable(value)) => self.is_assignable(target, value),
+            (Type::Nullable(target), _) => self.is_assignable(target, value),
+            (Type::Union(targets), _) => targets
+                .iter()
+                .any(|target| self.is_assignable(target, value)),
+            (
+                Type::Class { name: target, .. },
+                Type::Class { name: value, .. } | Type::Generic { base: value, .. },
+            ) => self.is_subclass(value, target) || self.implements(value, target),
+            // A type parameter is only known to be its bound
+            (_, Type::Parameter(name)) if target != value => self
+                .lookup_type_parameter(name)
+                .and_then(|type_parameter| type_parameter.bound.as_ref())
+                .is_some_and(|bound| self.is_assignable(target, bound)),
+            _ => target == value,
+        }
+    }
+
+    fn ensure_assignable(&self, target: &Type, value: &Type, span: Span) -> Result<()> {
+        if self.is_assignable(target, value) {
+            return Ok(());
+        }
+
+        Err(type_error(
+            format!("Type mismatch: expected '{}', found '{}'", target, value),
+            span,
+        ))
+    }
+
+    fn function_type(
+        &self,
+        parameters: &ParameterList,
+        return_type: &Type,
+        span: Span,
+    ) -> Result<Type> {
+        let mut params = vec![];
+        for (parameter, parameter_type) in parameters {
+            self.validate_type(parameter_type, parameter.span())?;
+            params.push(self.resolve_type(parameter_type));
+        }
+        if *return_type != Type::Void {
+            self.validate_type(return_type, span)?;
+        }
+
+        Ok(Type::Function {
+            params,
+            return_type: Box::new(self.resolve_type(return_type)),
+        })
+    }
+
+    ///
+    /// Check that an annotation only refers to known types
+    ///
+    fn validate_type(&self, annotation: &Type, span: Span) -> Result<()> {
+        match annotation {
+            Type::Primitive(_)
+            | Type::Function { .. }
+            | Type::Parameter(_)
+            | Type::Enum(_)
+            | Type::Nil => Ok(()),
+            Type::Array(element_type) | Type::Nullable(element_type) => {
+                self.validate_type(element_type, span)
+            }
+            Type::Union(members) => {
+                for member in members {
+                    self.validate_type(member, span)?;
+                }
+                Ok(())
+            }
+            Type::Class { name, .. }
+                if self.lookup_type_parameter(name).is_some()
+                    || self.interfaces.contains_key(name)
+                    || self.enums.contains_key(name) =>
+            {
+                Ok(())
+            }
+            Type::Class { name, .. } => match self.classes.get(name) {
+                Some(class_info) => {
+                    self.check_type_arguments(name, &class_info.type_parameters, &[], span)
+                }
+                None => Err(type_error(format!("Unknown type '{}'", name), span)),
+            },
+            Type::Generic { base, type_args } => {
+                let Some(class_info) = self.classes.get(base) else {
+                    return Err(type_error(format!("Unknown generic type '{}'", base), span));
+                };
+
+                let mut resolved_type_args = vec![];
+                for type_arg in type_args {
+                    self.validate_type(type_arg, span)?;
+                    resolved_type_args.push(self.resolve_type(type_arg));
+                }
+                self.check_type_arguments(
+                    base,
+                    &class_info.type_parameters,
+                    &resolved_type_args,
+                    span,
+                )
+            }
+            Type::Void => Err(type_error(
+                String::from("'void' is only allowed as a return type"),
+                span,
+            )),
+        }
+    }
+
+    ///
+    /// Type arguments of a generic class have to match its type parameters
+    ///
+    fn check_type_arguments(
+        &self,
+        name: &str,
+        type_parameters: &[TypeParameterInfo],
+        type_args: &[Type],
+        span: Span,
+    ) -> Result<()> {
+        if type_parameters.len() != type_args.len() {
+            return Err(type_error(
+                format!(
+                    "Expected {} type arguments for '{}', found {}",
+                    type_parameters.len(),
+                    name,
+                    type_args.len()
+                ),
+                span,
+            ));
+        }
+
+        self.check_bounds(type_parameters, type_args, span)
+    }
+
+    fn check_bounds(
+        &self,
+        type_parameters: &[TypeParameterInfo],
+        type_args: &[Type],
+        span: Span,
+    ) -> Result<()> {
+        for (type_parameter, type_arg) in type_parameters.iter().zip(type_args) {
+            let Some(bound) = &type_parameter.bound else {
+                continue;
+            };
+            if !self.is_assignable(bound, type_arg) {
+                return Err(type_error(
+                    format!(
+                        "Type '{}' does not satisfy the bound '{}' of type parameter '{}'",
+                        type_arg, bound, type_parameter.name
+                    ),
+                    span,
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn declare(&mut self, name: &str, declared_type: Type, span: Span) -> Result<()> {
+        if self.scopes.declare(name, declared_type.clone()) {
+            self.declared_types.insert(span, declared_type);
+            return Ok(());
+        }
+
+        Err(semantic_error(
+            format!("'{}' is already declared in this scope", name),
+            span,
+        ))
+    }
+}
+
+impl AstVisitor for TypeChecker {
+    type Output = Type;
+
+    fn visit_statement(&mut self, statement: &Statement) -> Result<Self::Output> {
+        match statement {
+            Statement::Program { body, .. } => {
+                self.check_statement_list(body)?;
+            }
+            Statement::Block { body, .. } => {
+                self.scopes.push();
+                self.check_statement_list(body)?;
+                self.scopes.pop();
+            }
+            // Interface members and enum variants are hoisted with the declarations
+            Statement::Empty { .. }
+            | Statement::InterfaceDeclaration { .. }
+            | Statement::EnumDeclaration { .. } => {}
+            Statement::Expression { expression, .. } => {
+                self.visit_expression(expression)?;
+            }
+            Statement::VariableDeclaration { variables, .. } => {
+                for variable in variables {
+                    self.visit_expression(variable)?;
+                }
+            }
+            Statement::If {
+                condition,
+                consequent,
+                alternative,
+                ..
+            } => {
+                self.check_if(condition, consequent, alternative)?;
+            }
+            Statement::While { .. } | Statement::DoWhile { .. } | Statement::For { .. } => {
+                self.check_loop(statement, None)?;
+            }
+            Statement::Labeled { label, body, .. } => {
+                self.check_labeled(label, body)?;
+            }
+            Statement::FunctionDeclaration {
+                name,
+                type_parameters,
+                parameters,
+                return_type,
+                body,
+                ..
+            } => {
+                self.check_function_declaration(
+                    name,
+                    type_parameters,
+                    parameters,
+                    return_type,
+                    body,
+                )?;
+            }
+            Statement::Return { argument, span } => {
+                self.check_return(argument, *span)?;
+            }
+            Statement::Break { label, span } => {
+                self.check_jump("Break", label, *span)?;
+            }
+            Statement::Continue { label, span } => {
+                self.check_jump("Continue", label, *span)?;
+            }
+            Statement::ClassDeclaration { name, members, .. } => {
+                self.check_class_declaration(name, members)?;
+            }
+        }
+
+        Ok(Type::Void)
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) -> Result<Self::Output> {
+        match expression {
+            Expression::Variable {
+                identifier,
+                type_annotation,
+                initializer,
+                ..
+            } => {
+                if let Expression::Destructuring { pattern, span } = identifier.as_ref() {
+                    self.check_destructuring(pattern, type_annotation, initializer, *span)?;
+                    return Ok(Type::Void);
+                }
+
+                let (variable_name, span) = identifier_name(identifier)?;
+                let mut narrowed_type = None;
+                let variable_type = match (type_annotation, initializer) {
+                    (Some(type_annotation), _) => {
+                        self.validate_type(type_annotation, span)?;
+                        let variable_type = self.resolve_type(type_annotation);
+                        if let Some(initializer) = initializer {
+                            let initializer_type =
+                                self.check_initializer(&variable_type, initializer)?;
+                            narrowed_type =
+                                self.assigned_narrowing(&variable_type, &initializer_type);
+                        }
+                        variable_type
+                    }
+                    (None, Some(initializer)) => {
+                        self.infer_variable_type(&variable_name, initializer)?
+                    }
+                    (None, None) => {
+                        return Err(type_error(
+                            format!(
+                                "Variable '{}' needs a type annotation or an initializer",
+                                variable_name
+                            ),
+                            span,
+                        ))
+                    }
+                };
+
+                self.declare(&variable_name, variable_type, span)?;
+                if let Some(narrowed_type) = narrowed_type {
+                    self.narrow(&variable_name, narrowed_type);
+                }
+                Ok(Type::Void)
+            }
+            Expression::Assignment {
+                operator,
+                left,
+                right,
+                ..
+            } => self.check_assignment(operator, left, right),
+            Expression::Binary {
+                operator,
+                left,
+                right,
+                span,
+            } => {
+                let left_type = self.visit_expression(left)?;
+                let right_type = self.visit_expression(right)?;
+                self.binary_type(*operator, &left_type, &right_type, *span)
+            }
+            Expression::Unary {
+                operator,
+                right,
+                span,
+            } => {
+                let right_type = self.visit_expression(right)?;
+                let expected_type = match operator {
+                    UnaryOperator::Plus | UnaryOperator::Minus => Type::number(),
+                    UnaryOperator::Not => Type::boolean(),
+                };
+
+                if right_type != expected_type {
+                    return Err(type_error(
+                        format!(
+                            "Operator '{}' cannot be applied to '{}'",
+                            operator, right_type
+                        ),
+                        *span,
+                    ));
+                }
+
+                Ok(expected_type)
+            }
+            Expression::TypeTest {
+                expression,
+                type_annotation,
+                span,
+            } => {
+                self.visit_expression(expression)?;
+                self.validate_type(type_annotation, *span)?;
+
+                self.tested_type(type_annotation, *span)?;
+                Ok(Type::boolean())
+            }
+            Expression::Logical {
+                operator,
+                left,
+                right,
+                span,
+            } if *operator == LogicalOperator::NilCoalescing => {
+                self.check_nil_coalescing(left, right, *span)
+            }
+            Expression::Logical {
+                operator,
+                left,
+                right,
+                span,
+            } => {
+                let left_type = self.visit_expression(left)?;
+                // The right operand is only evaluated when the left one is
+                // true for '&&' and false for '||'
+                let (when_true, when_false) = condition_narrowings(self, left);
+                let narrowings = match operator {
+                    LogicalOperator::Or => when_false,
+                    _ => when_true,
+                };
+                let right_type =
+                    self.with_narrowings(narrowings, |checker| checker.visit_expression(right))?;
+
+                if left_type != Type::boolean() || right_type != Type::boolean() {
+                    return Err(type_error(
+                        format!(
+                            "Operator '{}' cannot be applied to '{}' and '{}'",
+                            operator, left_type, right_type
+                        ),
+                        *span,
+                    ));
+                }
+
+                // Keep the operator in the match to catch new logical operators
+                match operator {
+                    LogicalOperator::And | LogicalOperator::Or => Ok(Type::boolean()),
+                    LogicalOperator::NilCoalescing => unreachable!("Checked by the previous arm"),
+                }
+            }
+            Expression::BooleanLiteral { .. } => Ok(Type::boolean()),
+            Expression::NilLiteral { .. } => Ok(Type::Nil),
+            Expression::StringLiteral { .. } => Ok(Type::string()),
+            Expression::NumericLiteral { .. } => Ok(Type::number()),
+            Expression::Identifier { name, span } => match self.scopes.lookup(name) {
+                Some(variable_type) => Ok(variable_type.clone()),
+                None if BUILTIN_FUNCTIONS.contains(&name.as_str()) => Err(semantic_error(
+                    format!("Builtin function '{}' can only be called", name),
+                    *span,
+                )),
+                None => Err(semantic_error(
+                    format!("Undefined variable '{}'", name),
+                    *span,
+                )),
+            },
+            Expression::ArrayLiteral { elements, span } => {
+                self.check_array_literal(elements, None, *span)
+            }
+            Expression::Member {
+                computed,
+                optional,
+                object,
+                property,
+                span,
+            } => self.check_member(*computed, *optional, object, property, *span),
+            Expression::Call {
+                callee,
+                arguments,
+                span,
+            } => self.check_call(callee, arguments, *span),
+            Expression::This { span } => match &self.current_class {
+                Some(class_name) if self.in_method => Ok(self.instance_type(class_name)),
+                // Field initializers run before the other fields have values
+                Some(_) => Err(semantic_error(
+                    String::from("'this' can not be used in field initializers"),
+                    *span,
+                )),
+                None => Err(semantic_error(
+                    String::from("'this' used outside of a class"),
+                    *span,
+                )),
+            },
+            Expression::Super { span } => {
+                let super_class = self.current_super_class(*span)?;
+                Ok(self.class_type(&super_class))
+            }
+            Expression::New {
+                callee,
+                arguments,
+                span,
+            } => self.check_new(callee, arguments, None, *span),
+            Expression::EnumVariant {
+                enum_name,
+                variant,
+                arguments,
+                span,
+            } => self.check_enum_variant(enum_name, variant, arguments, *span),
+            Expression::Match {
+                subject,
+                arms,
+                span,
+            } => self.check_match(subject, arms, *span),
+            // Declarations check their patterns against their initializer
+            Expression::Destructuring { span, .. } => Err(semantic_error(
+                String::from("Destructuring is only allowed in declarations"),
+                *span,
+            )),
+        }
+    }
+}
+
+fn bind_type_arguments(
+    type_parameters: &[TypeParameterInfo],
+    type_args: &[Type],
+) -> HashMap<String, Type> {
+    type_parameters
+        .iter()
+        .zip(type_args)
+        .map(|(type_parameter, type_arg)| (type_parameter.name.clone(), type_arg.clone()))
+        .collect()
+}
+
+///
+/// Replace the type parameters bound to type arguments
+///
+fn substitute(value_type: &Type, type_arguments: &HashMap<String, Type>) -> Type {
+    match value_type {
+        Type::Parameter(name) => type_arguments
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| value_type.clone()),
+        Type::Array(element_type) => {
+            Type::Array(Box::new(substitute(element_type, type_arguments)))
+        }
+        Type::Function {
+            params,
+            return_type,
+        } => Type::Function {
+            params: params
+                .iter()
+                .map(|param| substitute(param, type_arguments))
+                .collect(),
+            return_type: Box::new(substitute(return_type, type_arguments)),
+        },
+        Type::Generic { base, type_args } => Type::Generic {
+            base: base.clone(),
+            type_args: type_args
+                .iter()
+                .map(|type_arg| substitute(type_arg, type_arguments))
+                .collect(),
+        },
+        Type::Nullable(value_type) => Type::nullable(substitute(value_type, type_arguments)),
+        Type::Union(members) => Type::union(
+            members
+                .iter()
+                .map(|member| substitute(member, type_arguments))
+                .collect(),
+        ),
+        Type::Primitive(_) | Type::Class { .. } | Type::Enum(_) | Type::Void | Type::Nil => {
+            value_type.clone()
+        }
+    }
+}
+
+///
+/// Whether values of the type might be enum values
+///
+fn holds_enum(value_type: &Type) -> bool {
+    match value_type {
+        Type::Enum(_) => true,
+        Type::Nullable(value_type) => holds_enum(value_type),
+        Type::Union(members) => members.iter().any(holds_enum),
+        _ => false,
+    }
+}
+
+///
+/// Bind the type parameters of a parameter type to the matching parts of an
+/// argument type, the first binding of a type parameter wins
+///
+fn unify(parameter_type: &Type, argument_type: &Type, type_arguments: &mut HashMap<String, Type>) {
+    match (parameter_type, argument_type) {
+        // A nil argument tells nothing about the type parameter
+        (_, Type::Nil) => {}
+        (Type::Nullable(parameter_type), Type::Nullable(argument_type)) => {
+            unify(parameter_type, argument_type, type_arguments)
+        }
+        (Type::Nullable(parameter_type), _) => unify(parameter_type, argument_type, type_arguments),
+        (Type::Parameter(name), _) => {
+            type_arguments
+                .entry(name.clone())
+                .or_insert_with(|| argument_type.clone());
+        }
+        (Type::Array(parameter_element), Type::Array(argument_element)) => {
+            unify(parameter_element, argument_element, type_arguments)
+        }
+        (
+            Type::Function {
+                params: parameter_params,
+                return_type: parameter_return,
+            },
+            Type::Function {
+                params: argument_params,
+                return_type: argument_return,
+            },
+        ) => {
+            for (parameter, argument) in parameter_params.iter().zip(argument_params) {
+                unify(parameter, argument, type_arguments);
+            }
+            unify(parameter_return, argument_return, type_arguments);
+        }
+        (
+            Type::Generic {
+                base: parameter_base,
+                type_args: parameter_args,
+            },
+            Type::Generic {
+                base: argument_base,
+                type_args: argument_args,
+            },
+        ) if parameter_base == argument_base => {
+            for (parameter, argument) in parameter_args.iter().zip(argument_args) {
+                unify(parameter, argument, type_arguments);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn identifier_name(expression: &Expression) -> Result<(String, Span)> {
+    match expression {
+        Expression::Identifier { name, span } => Ok((name.clone(), *span)),
+        _ => Err(semantic_error(
+            String::from("Expected an identifier"),
+            expression.span(),
+        )),
+    }
+}
+
+fn never_matches(pattern_type: &Type, expected_type: &Type, span: Span) -> anyhow::Error {
+    type_error(
+        format!(
+            "Pattern of type '{}' can never match a value of type '{}'",
+            pattern_type, expected_type
+        ),
+        span,
+    )
+}
+
+fn member_name_conflict(name: &str, class_name: &str, span: Span) -> anyhow::Error {
+    semantic_error(
+        format!(
+            "'{}' is declared as both a field and a method in class '{}'",
+            name, class_name
+        ),
+        span,
+    )
+}
+
+fn cannot_destructure(value_type: &Type, pattern_kind: &str, span: Span) -> anyhow::Error {
+    type_error(
+        format!(
+            "Cannot destructure a value of type '{}' with {} pattern",
+            value_type, pattern_kind
+        ),
+        span,
+    )
+}
+
+fn type_error(message: String, span: Span) -> anyhow::Error {
+    CheckerError::TypeError { message, span }.into()
+}
+
+fn semantic_error(message: String, span: Span) -> anyhow::Error {
+    CheckerError::SemanticError { message, span }.into()
+}
+
+fn into_checker_error(error: anyhow::Error) -> CheckerError {
+    match error.downcast::<CheckerError>() {
+        Ok(error) => error,
+        Err(error) => CheckerError::SemanticError {
+            message: error.to_string(),
+            span: Span::default(),
+        },
+    }
+}
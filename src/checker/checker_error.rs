@@ -0,0 +1,25 @@
+use thiserror::Error;
+
+use crate::lexer::Span;
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Error, Debug)]
+pub enum CheckerError {
+    #[error("Type error: {message}")]
+    TypeError { message: String, span: Span },
+
+    #[error("Semantic error: {message}")]
+    SemanticError { message: String, span: Span },
+}
+
+impl CheckerError {
+    pub fn span(&self) -> Span {
+        match self {
+            CheckerError::TypeError { span, .. } | CheckerError::SemanticError { span, .. } => {
+                *span
+            }
+        }
+    }
+}
+
+pub type CheckerResult<T> = Result<T, CheckerError>;
@@ -0,0 +1,503 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::ast::{
+    BinaryOperator, ClassMember, Expression, LogicalOperator, Statement, Type, UnaryOperator,
+};
+
+use super::TypeChecker;
+
+///
+/// Narrowed types of variables, by name
+///
+pub(super) type Narrowings = HashMap<String, Type>;
+
+///
+/// Narrowings that hold when a condition is true and when it is false
+///
+/// Type tests and comparisons with literals narrow variables, '&&', '||'
+/// and '!' combine the narrowings of their operands
+///
+pub(super) fn condition_narrowings(
+    checker: &TypeChecker,
+    condition: &Expression,
+) -> (Narrowings, Narrowings) {
+    match condition {
+        Expression::TypeTest {
+            expression,
+            type_annotation,
+            ..
+        } => {
+            let Some((name, variable_type)) = variable(checker, expression) else {
+                return (Narrowings::new(), Narrowings::new());
+            };
+            let tested_type = checker.resolve_type(type_annotation);
+
+            // A member wider than the tested type narrows down to it
+            let mut matching = vec![];
+            let mut others = vec![];
+            for member in variable_type.members() {
+                if checker.is_assignable(&tested_type, &member) {
+                    matching.push(member);
+                } else if checker.is_assignable(&member, &tested_type) {
+                    matching.push(tested_type.clone());
+                    others.push(member);
+                } else {
+                    others.push(member);
+                }
+            }
+
+            (
+                narrowing(&name, &variable_type, matching),
+                narrowing(&name, &variable_type, others),
+            )
+        }
+        Expression::Binary {
+            operator: operator @ (BinaryOperator::Equal | BinaryOperator::NotEqual),
+            left,
+            right,
+            ..
+        } => {
+            let (equal, not_equal) = literal_narrowings(checker, left, right)
+                .or_else(|| literal_narrowings(checker, right, left))
+                .unwrap_or_default();
+
+            match operator {
+                BinaryOperator::NotEqual => (not_equal, equal),
+                _ => (equal, not_equal),
+            }
+        }
+        Expression::Logical {
+            operator: LogicalOperator::And,
+            left,
+            right,
+            ..
+        } => {
+            let (left_true, left_false) = condition_narrowings(checker, left);
+            let (right_true, right_false) = condition_narrowings(checker, right);
+
+            // Either the left operand is false or it is true and the right one is false
+            let when_false = either(&left_false, &both(checker, &left_true, &right_false));
+            (both(checker, &left_true, &right_true), when_false)
+        }
+        Expression::Logical {
+            operator: LogicalOperator::Or,
+            left,
+            right,
+            ..
+        } => {
+            let (left_true, left_false) = condition_narrowings(checker, left);
+            let (right_true, right_false) = condition_narrowings(checker, right);
+
+            // Either the left operand is true or it is false and the right one is true
+            let when_true = either(&left_true, &both(checker, &left_false, &right_true));
+            (when_true, both(checker, &left_false, &right_false))
+        }
+        Expression::Unary {
+            operator: UnaryOperator::Not,
+            right,
+            ..
+        } => {
+            let (when_true, when_false) = condition_narrowings(checker, right);
+            (when_false, when_true)
+        }
+        _ => (Narrowings::new(), Narrowings::new()),
+    }
+}
+
+///
+/// Narrowings of a variable compared to a literal, when they are equal and
+/// when they are not
+///
+/// Only nil is a whole type, other literals narrow only when equal
+///
+fn literal_narrowings(
+    checker: &TypeChecker,
+    variable_expression: &Expression,
+    literal: &Expression,
+) -> Option<(Narrowings, Narrowings)> {
+    let literal_type = match literal {
+        Expression::NilLiteral { .. } => Type::Nil,
+        Expression::BooleanLiteral { .. } => Type::boolean(),
+        Expression::StringLiteral { .. } => Type::string(),
+        Expression::NumericLiteral { .. } => Type::number(),
+        _ => return None,
+    };
+    let (name, variable_type) = variable(checker, variable_expression)?;
+
+    let (matching, others): (Vec<_>, Vec<_>) = variable_type
+        .members()
+        .into_iter()
+        .partition(|member| checker.is_assignable(member, &literal_type));
+    let others = match literal_type {
+        Type::Nil => others,
+        _ => variable_type.members(),
+    };
+
+    Some((
+        narrowing(&name, &variable_type, matching),
+        narrowing(&name, &variable_type, others),
+    ))
+}
+
+fn variable(checker: &TypeChecker, expression: &Expression) -> Option<(String, Type)> {
+    let Expression::Identifier { name, .. } = expression else {
+        return None;
+    };
+    let variable_type = checker.narrowed_variable(name)?;
+    Some((name.clone(), variable_type.clone()))
+}
+
+///
+/// Variable narrowed to the given members, no narrowing when none is left
+/// or the type stays the same
+///
+fn narrowing(name: &str, variable_type: &Type, members: Vec<Type>) -> Narrowings {
+    if members.is_empty() {
+        return Narrowings::new();
+    }
+
+    let narrowed_type = Type::union(members);
+    match narrowed_type == *variable_type {
+        true => Narrowings::new(),
+        false => Narrowings::from([(String::from(name), narrowed_type)]),
+    }
+}
+
+///
+/// Narrowings that hold together, a variable narrowed by both keeps the
+/// members that fit either narrowing
+///
+fn both(checker: &TypeChecker, left: &Narrowings, right: &Narrowings) -> Narrowings {
+    let mut narrowings = left.clone();
+    for (name, right_type) in right {
+        let narrowed_type = match left.get(name) {
+            Some(left_type) => {
+                let members: Vec<_> = left_type
+                    .members()
+                    .into_iter()
+                    .filter(|member| checker.is_assignable(right_type, member))
+                    .collect();
+                match members.is_empty() {
+                    true => left_type.clone(),
+                    false => Type::union(members),
+                }
+            }
+            None => right_type.clone(),
+        };
+        narrowings.insert(name.clone(), narrowed_type);
+    }
+    narrowings
+}
+
+///
+/// Narrowings of which one holds, only variables narrowed by both are kept
+/// with the members of both narrowings
+///
+fn either(left: &Narrowings, right: &Narrowings) -> Narrowings {
+    left.iter()
+        .filter_map(|(name, left_type)| {
+            let right_type = right.get(name)?;
+            let narrowed_type = Type::union(vec![left_type.clone(), right_type.clone()]);
+            Some((name.clone(), narrowed_type))
+        })
+        .collect()
+}
+
+///
+/// Whether a statement returns or jumps out of its loop on every path, the
+/// code after it is never reached
+///
+pub(super) fn always_exits(statement: &Statement) -> bool {
+    match statement {
+        Statement::Return { .. } | Statement::Break { .. } | Statement::Continue { .. } => true,
+        Statement::Block { body, .. } => body.iter().any(always_exits),
+        Statement::If {
+            consequent,
+            alternative: Some(alternative),
+            ..
+        } => always_exits(consequent) && always_exits(alternative),
+        _ => false,
+    }
+}
+
+///
+/// Whether a loop body has a 'break' leaving the loop with the given label,
+/// unlabeled ones only leave the innermost loop
+///
+pub(super) fn breaks_out_of(body: &Statement, label: Option<&str>) -> bool {
+    breaks_out_at_depth(body, label, 0)
+}
+
+fn breaks_out_at_depth(statement: &Statement, label: Option<&str>, depth: usize) -> bool {
+    match statement {
+        Statement::Break {
+            label: Some(target),
+            ..
+        } => {
+            matches!(target.as_ref(), Expression::Identifier { name, .. } if Some(name.as_str()) == label)
+        }
+        Statement::Break { label: None, .. } => depth == 0,
+        Statement::Block { body, .. } => body
+            .iter()
+            .any(|statement| breaks_out_at_depth(statement, label, depth)),
+        Statement::If {
+            consequent,
+            alternative,
+            ..
+        } => {
+            breaks_out_at_depth(consequent, label, depth)
+                || alternative
+                    .as_ref()
+                    .is_some_and(|alternative| breaks_out_at_depth(alternative, label, depth))
+        }
+        Statement::Labeled { body, .. } => breaks_out_at_depth(body, label, depth),
+        Statement::While { body, .. }
+        | Statement::DoWhile { body, .. }
+        | Statement::For { body, .. } => breaks_out_at_depth(body, label, depth + 1),
+        _ => false,
+    }
+}
+
+///
+/// Names of the variables a statement assigns to, nested functions included
+///
+pub(super) fn assigned_names(statement: &Statement) -> HashSet<String> {
+    let mut names = HashSet::new();
+    collect_statement(statement, &mut names);
+    names
+}
+
+///
+/// Names of the variables of enclosing scopes that function bodies assign
+/// to, a call can change them after a check narrowed them
+///
+/// Parameters and the variables declared at the top of a body belong to the
+/// function, assignments to them are not counted
+///
+pub(super) fn captured_assigned_names(statement: &Statement) -> HashSet<String> {
+    let mut names = HashSet::new();
+    collect_captured(statement, &mut names);
+    names
+}
+
+fn collect_captured(statement: &Statement, names: &mut HashSet<String>) {
+    match statement {
+        Statement::Program { body, .. } | Statement::Block { body, .. } => {
+            for statement in body {
+                collect_captured(statement, names);
+            }
+        }
+        Statement::If {
+            consequent,
+            alternative,
+            ..
+        } => {
+            collect_captured(consequent, names);
+            if let Some(alternative) = alternative {
+                collect_captured(alternative, names);
+            }
+        }
+        Statement::While { body, .. }
+        | Statement::DoWhile { body, .. }
+        | Statement::For { body, .. }
+        | Statement::Labeled { body, .. } => collect_captured(body, names),
+        Statement::FunctionDeclaration {
+            parameters, body, ..
+        } => {
+            let mut own = HashSet::new();
+            for (parameter, _) in parameters {
+                declared_names(parameter, &mut own);
+            }
+            if let Statement::Block { body, .. } = body.as_ref() {
+                for statement in body {
+                    if let Statement::VariableDeclaration { variables, .. } = statement {
+                        for variable in variables {
+                            if let Expression::Variable { identifier, .. } = variable {
+                                declared_names(identifier, &mut own);
+                            }
+                        }
+                    }
+                }
+            }
+
+            let assigned = assigned_names(body);
+            names.extend(assigned.into_iter().filter(|name| !own.contains(name)));
+            collect_captured(body, names);
+        }
+        Statement::ClassDeclaration { members, .. } => {
+            for member in members {
+                if let ClassMember::Method { declaration }
+                | ClassMember::Constructor { declaration } = member
+                {
+                    collect_captured(declaration, names);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn declared_names(identifier: &Expression, names: &mut HashSet<String>) {
+    match identifier {
+        Expression::Identifier { name, .. } => {
+            names.insert(name.clone());
+        }
+        Expression::Destructuring { pattern, .. } => {
+            for binding in pattern.bindings() {
+                declared_names(binding, names);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn collect_statement(statement: &Statement, names: &mut HashSet<String>) {
+    match statement {
+        Statement::Program { body, .. } | Statement::Block { body, .. } => {
+            for statement in body {
+                collect_statement(statement, names);
+            }
+        }
+        Statement::Empty { .. }
+        | Statement::Break { .. }
+        | Statement::Continue { .. }
+        | Statement::InterfaceDeclaration { .. }
+        | Statement::EnumDeclaration { .. } => {}
+        Statement::Expression { expression, .. } => collect_expression(expression, names),
+        Statement::VariableDeclaration { variables, .. } => {
+            for variable in variables {
+                collect_expression(variable, names);
+            }
+        }
+        Statement::If {
+            condition,
+            consequent,
+            alternative,
+            ..
+        } => {
+            collect_expression(condition, names);
+            collect_statement(consequent, names);
+            if let Some(alternative) = alternative {
+                collect_statement(alternative, names);
+            }
+        }
+        Statement::While {
+            condition, body, ..
+        }
+        | Statement::DoWhile {
+            condition, body, ..
+        } => {
+            collect_expression(condition, names);
+            collect_statement(body, names);
+        }
+        Statement::For {
+            initializer,
+            condition,
+            increment,
+            body,
+            ..
+        } => {
+            if let Some(initializer) = initializer {
+                collect_statement(initializer, names);
+            }
+            if let Some(condition) = condition {
+                collect_expression(condition, names);
+            }
+            if let Some(increment) = increment {
+                collect_expression(increment, names);
+            }
+            collect_statement(body, names);
+        }
+        Statement::Labeled { body, .. } | Statement::FunctionDeclaration { body, .. } => {
+            collect_statement(body, names)
+        }
+        Statement::Return { argument, .. } => {
+            if let Some(argument) = argument {
+                collect_expression(argument, names);
+            }
+        }
+        Statement::ClassDeclaration { members, .. } => {
+            for member in members {
+                match member {
+                    ClassMember::Field {
+                        initializer: Some(initializer),
+                        ..
+                    } => collect_expression(initializer, names),
+                    ClassMember::Field { .. } => {}
+                    ClassMember::Method { declaration }
+                    | ClassMember::Constructor { declaration } => {
+                        collect_statement(declaration, names)
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn collect_expression(expression: &Expression, names: &mut HashSet<String>) {
+    match expression {
+        Expression::Variable { initializer, .. } => {
+            if let Some(initializer) = initializer {
+                collect_expression(initializer, names);
+            }
+        }
+        Expression::Assignment { left, right, .. } => {
+            if let Expression::Identifier { name, .. } = left.as_ref() {
+                names.insert(name.clone());
+            }
+            collect_expression(left, names);
+            collect_expression(right, names);
+        }
+        Expression::Binary { left, right, .. } | Expression::Logical { left, right, .. } => {
+            collect_expression(left, names);
+            collect_expression(right, names);
+        }
+        Expression::Unary { right, .. }
+        | Expression::TypeTest {
+            expression: right, ..
+        } => collect_expression(right, names),
+        Expression::Member {
+            object, property, ..
+        } => {
+            collect_expression(object, names);
+            collect_expression(property, names);
+        }
+        Expression::Call {
+            callee, arguments, ..
+        }
+        | Expression::New {
+            callee, arguments, ..
+        } => {
+            collect_expression(callee, names);
+            for argument in arguments {
+                collect_expression(argument, names);
+            }
+        }
+        Expression::EnumVariant { arguments, .. }
+        | Expression::ArrayLiteral {
+            elements: arguments,
+            ..
+        } => {
+            for argument in arguments {
+                collect_expression(argument, names);
+            }
+        }
+        Expression::Match { subject, arms, .. } => {
+            collect_expression(subject, names);
+            for arm in arms {
+                if let Some(guard) = &arm.guard {
+                    collect_expression(guard, names);
+                }
+                collect_expression(&arm.value, names);
+            }
+        }
+        Expression::BooleanLiteral { .. }
+        | Expression::NilLiteral { .. }
+        | Expression::StringLiteral { .. }
+        | Expression::NumericLiteral { .. }
+        | Expression::Identifier { .. }
+        | Expression::This { .. }
+        | Expression::Super { .. }
+        | Expression::Destructuring { .. } => {}
+    }
+}
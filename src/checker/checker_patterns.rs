@@ -0,0 +1,355 @@
+use crate::ast::{Expression, Pattern, PrimitiveType, Type};
+
+use super::TypeChecker;
+
+///
+/// Values a pattern matches, patterns binding a name match like wildcards
+///
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Space {
+    Any,
+
+    ///
+    /// Values of a type narrower than the type of the matched value
+    ///
+    Type(Type),
+
+    Literal(Literal),
+
+    Variant {
+        enum_name: String,
+        variant: String,
+        arguments: Vec<Space>,
+    },
+
+    ///
+    /// Arrays with exactly one element per nested space
+    ///
+    Array(Vec<Space>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Literal {
+    Boolean(bool),
+    Number(i32),
+    String(String),
+    Nil,
+}
+
+impl Literal {
+    fn value_type(&self) -> Type {
+        match self {
+            Literal::Boolean(_) => Type::boolean(),
+            Literal::Number(_) => Type::number(),
+            Literal::String(_) => Type::string(),
+            Literal::Nil => Type::Nil,
+        }
+    }
+}
+
+///
+/// Values matched by a checked pattern
+///
+pub(super) fn pattern_space(checker: &TypeChecker, pattern: &Pattern) -> Space {
+    match pattern {
+        Pattern::Wildcard {
+            type_annotation, ..
+        }
+        | Pattern::Binding {
+            type_annotation, ..
+        } => match type_annotation {
+            Some(type_annotation) => Space::Type(checker.resolve_type(type_annotation)),
+            None => Space::Any,
+        },
+        // Only declarations destructure objects, they match every value
+        Pattern::Object { .. } => Space::Any,
+        Pattern::Literal { value, .. } => Space::Literal(match value.as_ref() {
+            Expression::BooleanLiteral { value, .. } => Literal::Boolean(*value),
+            Expression::NumericLiteral { value, .. } => Literal::Number(*value),
+            Expression::StringLiteral { value, .. } => Literal::String(value.clone()),
+            _ => Literal::Nil,
+        }),
+        Pattern::EnumVariant {
+            enum_name,
+            variant,
+            arguments,
+            ..
+        } => Space::Variant {
+            enum_name: identifier(enum_name),
+            variant: identifier(variant),
+            arguments: arguments
+                .iter()
+                .map(|argument| pattern_space(checker, argument))
+                .collect(),
+        },
+        Pattern::Array { elements, .. } => Space::Array(
+            elements
+                .iter()
+                .map(|element| pattern_space(checker, element))
+                .collect(),
+        ),
+    }
+}
+
+///
+/// Whether a value of the type can match a space that none of the rows
+/// match, the rows are the earlier arms without a guard
+///
+/// An arm that is not useful is unreachable, a match is exhaustive when a
+/// wildcard after its last arm would not be useful
+///
+pub(super) fn is_useful(
+    checker: &TypeChecker,
+    rows: &[Space],
+    space: &Space,
+    value_type: &Type,
+) -> bool {
+    let rows: Vec<Vec<Space>> = rows.iter().map(|row| vec![row.clone()]).collect();
+    useful(
+        checker,
+        &rows,
+        std::slice::from_ref(space),
+        std::slice::from_ref(value_type),
+    )
+}
+
+///
+/// Usefulness over rows of spaces, one column per value, the vector is
+/// useful when a value it matches is matched by none of the rows
+///
+fn useful(checker: &TypeChecker, rows: &[Vec<Space>], vector: &[Space], types: &[Type]) -> bool {
+    let Some((head, tail)) = vector.split_first() else {
+        return rows.is_empty();
+    };
+    let column_type = &types[0];
+
+    // A value of a union or a nullable type is a value of one of its members
+    let cases = column_type.members();
+    if cases.len() > 1 {
+        return cases.iter().any(|case| {
+            if !admits(checker, head, case) {
+                return false;
+            }
+            let case_types = [std::slice::from_ref(case), &types[1..]].concat();
+            let vector = [vec![narrow(checker, head, case)], tail.to_vec()].concat();
+            useful(
+                checker,
+                &narrow_rows(checker, rows, case),
+                &vector,
+                &case_types,
+            )
+        });
+    }
+
+    let rows = narrow_rows(checker, rows, column_type);
+    match narrow(checker, head, column_type) {
+        Space::Any => match signature(checker, &rows, column_type) {
+            // Every kind of value has a row, one of them has to be useful
+            Some(constructors) => constructors.iter().any(|constructor| {
+                let arguments = wildcards(checker, constructor, column_type);
+                let vector = [arguments, tail.to_vec()].concat();
+                let types = [
+                    argument_types(checker, constructor, column_type),
+                    types[1..].to_vec(),
+                ]
+                .concat();
+                useful(
+                    checker,
+                    &specialize(checker, &rows, constructor, column_type),
+                    &vector,
+                    &types,
+                )
+            }),
+            // Some kind of value has no row, only the wildcard rows can match it
+            None => {
+                let rows: Vec<Vec<Space>> = rows
+                    .iter()
+                    .filter(|row| row[0] == Space::Any)
+                    .map(|row| row[1..].to_vec())
+                    .collect();
+                useful(checker, &rows, tail, &types[1..])
+            }
+        },
+        constructor => {
+            let vector = [arguments(&constructor), tail.to_vec()].concat();
+            let types = [
+                argument_types(checker, &constructor, column_type),
+                types[1..].to_vec(),
+            ]
+            .concat();
+            useful(
+                checker,
+                &specialize(checker, &rows, &constructor, column_type),
+                &vector,
+                &types,
+            )
+        }
+    }
+}
+
+///
+/// Whether the space can match a value of the type
+///
+fn admits(checker: &TypeChecker, space: &Space, value_type: &Type) -> bool {
+    match space {
+        Space::Any => true,
+        Space::Type(space_type) => {
+            checker.is_assignable(space_type, value_type)
+                || checker.is_assignable(value_type, space_type)
+        }
+        Space::Literal(literal) => checker.is_assignable(value_type, &literal.value_type()),
+        Space::Variant { enum_name, .. } => *value_type == Type::Enum(enum_name.clone()),
+        Space::Array(_) => matches!(value_type, Type::Array(_)),
+    }
+}
+
+///
+/// Space restricted to the values of the type, a type matching all of them
+/// becomes a wildcard
+///
+fn narrow(checker: &TypeChecker, space: &Space, value_type: &Type) -> Space {
+    match space {
+        Space::Type(space_type) if checker.is_assignable(space_type, value_type) => Space::Any,
+        _ => space.clone(),
+    }
+}
+
+fn narrow_rows(checker: &TypeChecker, rows: &[Vec<Space>], value_type: &Type) -> Vec<Vec<Space>> {
+    rows.iter()
+        .filter(|row| admits(checker, &row[0], value_type))
+        .map(|row| {
+            let mut row = row.clone();
+            row[0] = narrow(checker, &row[0], value_type);
+            row
+        })
+        .collect()
+}
+
+///
+/// Kinds of values of the type when the rows have one for each of them,
+/// types with unbounded kinds of values never do
+///
+fn signature(checker: &TypeChecker, rows: &[Vec<Space>], value_type: &Type) -> Option<Vec<Space>> {
+    let constructors: Vec<Space> = match value_type {
+        Type::Primitive(PrimitiveType::Boolean) => vec![
+            Space::Literal(Literal::Boolean(true)),
+            Space::Literal(Literal::Boolean(false)),
+        ],
+        Type::Nil => vec![Space::Literal(Literal::Nil)],
+        Type::Enum(enum_name) => checker
+            .enum_info(enum_name)?
+            .variants
+            .iter()
+            .map(|(variant, payload)| Space::Variant {
+                enum_name: enum_name.clone(),
+                variant: variant.clone(),
+                arguments: vec![Space::Any; payload.len()],
+            })
+            .collect(),
+        _ => return None,
+    };
+
+    let is_complete = constructors.iter().all(|constructor| {
+        rows.iter()
+            .any(|row| same_constructor(&row[0], constructor))
+    });
+    is_complete.then_some(constructors)
+}
+
+fn same_constructor(left: &Space, right: &Space) -> bool {
+    match (left, right) {
+        (Space::Literal(left), Space::Literal(right)) => left == right,
+        (
+            Space::Variant {
+                enum_name: left_enum,
+                variant: left_variant,
+                ..
+            },
+            Space::Variant {
+                enum_name: right_enum,
+                variant: right_variant,
+                ..
+            },
+        ) => left_enum == right_enum && left_variant == right_variant,
+        (Space::Array(left), Space::Array(right)) => left.len() == right.len(),
+        (Space::Type(left), Space::Type(right)) => left == right,
+        _ => false,
+    }
+}
+
+///
+/// Rows matching the values of the constructor, with the head replaced by
+/// the spaces of its arguments
+///
+fn specialize(
+    checker: &TypeChecker,
+    rows: &[Vec<Space>],
+    constructor: &Space,
+    value_type: &Type,
+) -> Vec<Vec<Space>> {
+    rows.iter()
+        .filter_map(|row| {
+            let head = match &row[0] {
+                Space::Any => wildcards(checker, constructor, value_type),
+                Space::Type(row_type) if covers(checker, row_type, constructor) => {
+                    wildcards(checker, constructor, value_type)
+                }
+                head if same_constructor(head, constructor) => arguments(head),
+                _ => return None,
+            };
+            Some([head, row[1..].to_vec()].concat())
+        })
+        .collect()
+}
+
+///
+/// Whether every value of the constructor belongs to the type
+///
+fn covers(checker: &TypeChecker, covering_type: &Type, constructor: &Space) -> bool {
+    match constructor {
+        Space::Type(constructor_type) => checker.is_assignable(covering_type, constructor_type),
+        Space::Literal(literal) => checker.is_assignable(covering_type, &literal.value_type()),
+        Space::Variant { enum_name, .. } => {
+            checker.is_assignable(covering_type, &Type::Enum(enum_name.clone()))
+        }
+        Space::Array(_) | Space::Any => false,
+    }
+}
+
+fn arguments(constructor: &Space) -> Vec<Space> {
+    match constructor {
+        Space::Variant { arguments, .. } => arguments.clone(),
+        Space::Array(elements) => elements.clone(),
+        _ => vec![],
+    }
+}
+
+fn wildcards(checker: &TypeChecker, constructor: &Space, value_type: &Type) -> Vec<Space> {
+    vec![Space::Any; argument_types(checker, constructor, value_type).len()]
+}
+
+fn argument_types(checker: &TypeChecker, constructor: &Space, value_type: &Type) -> Vec<Type> {
+    match (constructor, value_type) {
+        (
+            Space::Variant {
+                enum_name, variant, ..
+            },
+            _,
+        ) => checker
+            .enum_info(enum_name)
+            .and_then(|enum_info| enum_info.variant(variant))
+            .cloned()
+            .unwrap_or_default(),
+        (Space::Array(elements), Type::Array(element_type)) => {
+            vec![element_type.as_ref().clone(); elements.len()]
+        }
+        _ => vec![],
+    }
+}
+
+fn identifier(expression: &Expression) -> String {
+    match expression {
+        Expression::Identifier { name, .. } => name.clone(),
+        _ => String::new(),
+    }
+}
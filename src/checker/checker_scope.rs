@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+
+use crate::ast::Type;
+
+///
+/// Lexical scopes mapping names to their declared types
+///
+/// Generic functions keep their type parameters next to their type, in the
+/// scope of the function name. Variables narrowed by a condition keep their
+/// narrowed type in the scope the narrowing applies to
+///
+#[derive(Debug, Clone)]
+pub(super) struct ScopeStack {
+    scopes: Vec<HashMap<String, Type>>,
+    type_parameters: Vec<HashMap<String, Vec<TypeParameterInfo>>>,
+    narrowings: Vec<HashMap<String, Type>>,
+}
+
+impl ScopeStack {
+    pub(super) fn new() -> Self {
+        ScopeStack {
+            scopes: vec![HashMap::new()],
+            type_parameters: vec![HashMap::new()],
+            narrowings: vec![HashMap::new()],
+        }
+    }
+
+    pub(super) fn push(&mut self) {
+        self.scopes.push(HashMap::new());
+        self.type_parameters.push(HashMap::new());
+        self.narrowings.push(HashMap::new());
+    }
+
+    pub(super) fn pop(&mut self) {
+        self.scopes.pop();
+        self.type_parameters.pop();
+        self.narrowings.pop();
+    }
+
+    ///
+    /// Declare a name in the innermost scope, fails if it is already declared there
+    ///
+    pub(super) fn declare(&mut self, name: &str, declared_type: Type) -> bool {
+        let scope = self
+            .scopes
+            .last_mut()
+            .expect("Scope stack should never be empty");
+        if scope.contains_key(name) {
+            return false;
+        }
+
+        scope.insert(String::from(name), declared_type);
+        // A narrowing of a shadowed variable does not apply to the new one
+        self.narrowings
+            .last_mut()
+            .expect("Scope stack should never be empty")
+            .remove(name);
+        true
+    }
+
+    pub(super) fn is_declared_in_current_scope(&self, name: &str) -> bool {
+        self.scopes
+            .last()
+            .is_some_and(|scope| scope.contains_key(name))
+    }
+
+    ///
+    /// Type of a name, narrowed if a condition narrowed it
+    ///
+    pub(super) fn lookup(&self, name: &str) -> Option<&Type> {
+        self.scopes
+            .iter()
+            .zip(&self.narrowings)
+            .rev()
+            .find_map(|(scope, narrowings)| narrowings.get(name).or_else(|| scope.get(name)))
+    }
+
+    ///
+    /// Type a name was declared with, ignoring narrowings
+    ///
+    pub(super) fn lookup_declared(&self, name: &str) -> Option<&Type> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    ///
+    /// Narrow the type of a name for the rest of the innermost scope
+    ///
+    pub(super) fn narrow(&mut self, name: &str, narrowed_type: Type) {
+        self.narrowings
+            .last_mut()
+            .expect("Scope stack should never be empty")
+            .insert(String::from(name), narrowed_type);
+    }
+
+    ///
+    /// Drop every narrowing of a name, used when the name is assigned
+    ///
+    pub(super) fn forget_narrowing(&mut self, name: &str) {
+        for narrowings in &mut self.narrowings {
+            narrowings.remove(name);
+        }
+    }
+
+    ///
+    /// Remove all narrowings, nested functions can run after the checks
+    /// that narrowed their enclosing variables
+    ///
+    pub(super) fn take_narrowings(&mut self) -> Vec<HashMap<String, Type>> {
+        let cleared = vec![HashMap::new(); self.narrowings.len()];
+        std::mem::replace(&mut self.narrowings, cleared)
+    }
+
+    pub(super) fn restore_narrowings(&mut self, narrowings: Vec<HashMap<String, Type>>) {
+        self.narrowings = narrowings;
+    }
+
+    ///
+    /// Attach type parameters to a name declared in the innermost scope
+    ///
+    pub(super) fn declare_type_parameters(
+        &mut self,
+        name: &str,
+        type_parameters: Vec<TypeParameterInfo>,
+    ) {
+        self.type_parameters
+            .last_mut()
+            .expect("Scope stack should never be empty")
+            .insert(String::from(name), type_parameters);
+    }
+
+    ///
+    /// Type parameters of the generic function a name refers to
+    ///
+    pub(super) fn lookup_type_parameters(&self, name: &str) -> Option<&Vec<TypeParameterInfo>> {
+        self.scopes
+            .iter()
+            .zip(&self.type_parameters)
+            .rev()
+            .find(|(scope, _)| scope.contains_key(name))
+            .and_then(|(_, type_parameters)| type_parameters.get(name))
+    }
+}
+
+///
+/// Type parameter of a generic function or class with its resolved bound
+///
+#[derive(Debug, Clone)]
+pub(super) struct TypeParameterInfo {
+    pub(super) name: String,
+    pub(super) bound: Option<Type>,
+}
+
+///
+/// Members collected from a class declaration
+///
+#[derive(Debug, Clone)]
+pub(super) struct ClassInfo {
+    pub(super) super_class: Option<String>,
+    pub(super) type_parameters: Vec<TypeParameterInfo>,
+    pub(super) fields: HashMap<String, Type>,
+    pub(super) methods: HashMap<String, Type>,
+    pub(super) method_type_parameters: HashMap<String, Vec<TypeParameterInfo>>,
+    pub(super) interfaces: Vec<String>,
+}
+
+impl ClassInfo {
+    pub(super) fn new(
+        super_class: Option<String>,
+        type_parameters: Vec<TypeParameterInfo>,
+    ) -> Self {
+        ClassInfo {
+            super_class,
+            type_parameters,
+            fields: HashMap::new(),
+            methods: HashMap::new(),
+            method_type_parameters: HashMap::new(),
+            interfaces: vec![],
+        }
+    }
+}
+
+///
+/// Member signatures collected from an interface declaration
+///
+#[derive(Debug, Clone, Default)]
+pub(super) struct InterfaceInfo {
+    pub(super) fields: HashMap<String, Type>,
+    pub(super) methods: HashMap<String, Type>,
+}
+
+///
+/// Variants of an enum declaration with the types of their payloads, in
+/// declaration order
+///
+#[derive(Debug, Clone, Default)]
+pub(super) struct EnumInfo {
+    pub(super) variants: Vec<(String, Vec<Type>)>,
+}
+
+impl EnumInfo {
+    pub(super) fn variant(&self, name: &str) -> Option<&Vec<Type>> {
+        self.variants
+            .iter()
+            .find(|(variant_name, _)| variant_name == name)
+            .map(|(_, payload)| payload)
+    }
+}
@@ -0,0 +1,9 @@
+mod checker;
+mod checker_error;
+mod checker_narrowing;
+mod checker_patterns;
+mod checker_scope;
+mod tests;
+
+pub(crate) use self::checker::{TypeChecker, BUILTIN_FUNCTIONS};
+pub(crate) use self::checker_error::*;
@@ -0,0 +1,21 @@
+use super::internal_util::execute_yaml_test_cases;
+
+#[test]
+fn test_checker_classes() {
+    execute_yaml_test_cases("tests/checker/test_checker_classes.yaml");
+}
+
+#[test]
+fn test_checker_expressions() {
+    execute_yaml_test_cases("tests/checker/test_checker_expressions.yaml");
+}
+
+#[test]
+fn test_checker_functions() {
+    execute_yaml_test_cases("tests/checker/test_checker_functions.yaml");
+}
+
+#[test]
+fn test_checker_statements() {
+    execute_yaml_test_cases("tests/checker/test_checker_statements.yaml");
+}
@@ -0,0 +1,212 @@
+use std::{
+    fs,
+    io::{BufReader, Read, Write},
+};
+
+use crate::{
+    cfg::build_control_flow_graphs,
+    diagnostics::{render_diagnostic, Diagnostic},
+    driver::{
+        check_source, format_token, generate_c_source, generate_js_source, generate_wat_source,
+        lower_source, optimize_source, parse_source, parse_source_with_cst, tokenize_source,
+    },
+    interpreter::Interpreter,
+    linter::lint_program,
+    lsp::LspServer,
+    repl::Repl,
+};
+
+use super::{
+    parse_arguments, CliArguments, CliError, CliResult, Command, OutputFormat, Target, USAGE,
+};
+
+pub const EXIT_SUCCESS: u8 = 0;
+pub const EXIT_FAILURE: u8 = 1;
+pub const EXIT_USAGE: u8 = 2;
+
+///
+/// Name shown in diagnostics for programs read from stdin
+///
+const STDIN_PATH: &str = "<stdin>";
+
+///
+/// Command line entry point, returns the process exit code
+///
+pub fn run_cli(
+    args: &[String],
+    stdin: &mut dyn Read,
+    stdout: &mut dyn Write,
+    stderr: &mut dyn Write,
+) -> u8 {
+    let arguments = match parse_arguments(args) {
+        Ok(arguments) => arguments,
+        Err(error) => {
+            let _ = writeln!(stderr, "error: {}\n\n{}", error, USAGE);
+            return EXIT_USAGE;
+        }
+    };
+
+    if arguments.command == Command::Help {
+        let _ = writeln!(stdout, "{}", USAGE);
+        return EXIT_SUCCESS;
+    }
+
+    if arguments.command == Command::Repl {
+        return match Repl::new(stdout).run(&mut BufReader::new(stdin), stderr) {
+            Ok(()) => EXIT_SUCCESS,
+            Err(error) => {
+                let _ = writeln!(stderr, "error: {}", error);
+                EXIT_FAILURE
+            }
+        };
+    }
+
+    if arguments.command == Command::Lsp {
+        return match LspServer::new(stdout).run(&mut BufReader::new(stdin)) {
+            // The protocol asks for a failure code when the client exits without a shutdown
+            Ok(true) => EXIT_SUCCESS,
+            Ok(false) => EXIT_FAILURE,
+            Err(error) => {
+                let _ = writeln!(stderr, "error: {}", error);
+                EXIT_FAILURE
+            }
+        };
+    }
+
+    let (source, path) = match read_source(&arguments, stdin) {
+        Ok(source) => source,
+        Err(error) => {
+            let _ = writeln!(stderr, "error: {}", error);
+            return EXIT_USAGE;
+        }
+    };
+
+    let mut findings = vec![];
+    let result = run_command(&arguments, &source, stdout, &mut findings);
+
+    for finding in &findings {
+        let _ = write!(stderr, "{}", render_diagnostic(finding, &source, &path));
+    }
+
+    match result {
+        Ok(()) if findings.iter().any(Diagnostic::is_error) => EXIT_FAILURE,
+        Ok(()) => EXIT_SUCCESS,
+        Err(diagnostic) => {
+            let _ = write!(stderr, "{}", render_diagnostic(&diagnostic, &source, &path));
+            EXIT_FAILURE
+        }
+    }
+}
+
+fn read_source(arguments: &CliArguments, stdin: &mut dyn Read) -> CliResult<(String, String)> {
+    match &arguments.path {
+        Some(path) => fs::read_to_string(path)
+            .map(|source| (source, path.clone()))
+            .map_err(|error| CliError::InputError {
+                path: path.clone(),
+                message: error.to_string(),
+            }),
+        None => {
+            let mut source = String::new();
+            stdin
+                .read_to_string(&mut source)
+                .map_err(|error| CliError::InputError {
+                    path: String::from(STDIN_PATH),
+                    message: error.to_string(),
+                })?;
+            Ok((source, String::from(STDIN_PATH)))
+        }
+    }
+}
+
+fn run_command(
+    arguments: &CliArguments,
+    source: &str,
+    stdout: &mut dyn Write,
+    findings: &mut Vec<Diagnostic>,
+) -> Result<(), Diagnostic> {
+    match arguments.command {
+        Command::Lex => {
+            for token in tokenize_source(source)? {
+                writeln!(stdout, "{}", format_token(&token, source)).map_err(output_error)?;
+            }
+            Ok(())
+        }
+        Command::Parse | Command::Optimize => {
+            let program = match (arguments.command, arguments.format) {
+                (Command::Parse, OutputFormat::Cst) => {
+                    let (cst, error) = parse_source_with_cst(source)?;
+                    write!(stdout, "{}", cst.dump()).map_err(output_error)?;
+                    return error.map_or(Ok(()), Err);
+                }
+                (Command::Parse, _) => parse_source(source)?,
+                _ => {
+                    let (program, analysis_warnings) = optimize_source(source)?;
+                    findings.extend(analysis_warnings);
+                    program
+                }
+            };
+            let output = match arguments.format {
+                OutputFormat::Json => {
+                    serde_json::to_string_pretty(&program).map_err(output_error)? + "\n"
+                }
+                _ => serde_yaml::to_string(&program).map_err(output_error)?,
+            };
+            write!(stdout, "{}", output).map_err(output_error)
+        }
+        Command::Cfg => {
+            let program = parse_source(source)?;
+            for graph in build_control_flow_graphs(&program) {
+                write!(stdout, "{}", graph.to_dot(source)).map_err(output_error)?;
+            }
+            Ok(())
+        }
+        Command::Ir => {
+            let (module, analysis_warnings) = lower_source(source)?;
+            findings.extend(analysis_warnings);
+            write!(stdout, "{}", module).map_err(output_error)
+        }
+        Command::Compile => {
+            let (output, analysis_warnings) = match arguments.target {
+                Target::C => generate_c_source(source)?,
+                Target::Js => generate_js_source(source, arguments.jsdoc)?,
+                Target::Wat => generate_wat_source(source)?,
+            };
+            findings.extend(analysis_warnings);
+            write!(stdout, "{}", output).map_err(output_error)
+        }
+        Command::Lint => {
+            let (program, analysis_warnings) = check_source(source)?;
+            findings.extend(analysis_warnings);
+            findings.extend(lint_program(&program, &arguments.lint_config));
+            findings.sort_by_key(|finding| {
+                finding
+                    .span
+                    .map(|span| span.start.offset)
+                    .unwrap_or_default()
+            });
+            Ok(())
+        }
+        Command::Check => {
+            let (_, analysis_warnings) = optimize_source(source)?;
+            findings.extend(analysis_warnings);
+            Ok(())
+        }
+        Command::Run => {
+            let (program, analysis_warnings) = optimize_source(source)?;
+            findings.extend(analysis_warnings);
+            Interpreter::new(stdout)
+                .execute(&program)
+                .map(|_| ())
+                .map_err(|error| Diagnostic::from_interpreter_error(&error))
+        }
+        Command::Repl | Command::Lsp | Command::Help => Ok(()),
+    }
+}
+
+fn output_error(error: impl ToString) -> Diagnostic {
+    Diagnostic::error(
+        format!("Could not write the output: {}", error.to_string()),
+        None,
+    )
+}
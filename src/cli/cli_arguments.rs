@@ -0,0 +1,191 @@
+use crate::linter::LintConfig;
+
+use super::{CliError, CliResult};
+
+pub const USAGE: &str = "\
+Usage: senbonzakura <command> [options] [file]
+
+Reads the program from the file, or from stdin when the file is missing or '-'.
+The repl command reads its input line by line from stdin, the lsp command
+serves the Language Server Protocol over stdin and stdout.
+
+Commands:
+  lex       Print the tokens of the program
+  parse     Print the syntax tree of the program
+  check     Type check the program and fold its constants
+  optimize  Print the syntax tree with its constants folded
+  cfg       Print the control flow graphs of the program and its functions
+            in Graphviz DOT format
+  ir        Print the SSA intermediate representation of the program
+  compile   Print the program translated to the target language
+  lint      Check the program and report the findings of the lint rules
+  run       Type check, optimize and run the program
+  repl      Start an interactive session
+  lsp       Start the language server
+
+Options:
+  --format <yaml|json|cst>  Syntax tree format of the parse and optimize commands,
+                            cst prints the lossless tree with whitespace and
+                            comments, parse only [default: yaml]
+  --target <c|js|wat>       Target language of the compile command [default: c]
+  --jsdoc                   Keep the type annotations as JSDoc comments in the
+                            js target
+  --rule <rule>=<level>     Level of a lint rule, one of off, warning and error,
+                            may be repeated [default: warning]
+  -h, --help                Print this help
+
+Lint rules:
+  unused-variable, shadowed-binding, empty-block, assignment-in-condition,
+  constant-condition, nil-comparison";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Lex,
+    Parse,
+    Check,
+    Optimize,
+    Cfg,
+    Ir,
+    Compile,
+    Lint,
+    Run,
+    Repl,
+    Lsp,
+    Help,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Yaml,
+    Json,
+    Cst,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    C,
+    Js,
+    Wat,
+}
+
+///
+/// Parsed command line
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CliArguments {
+    pub command: Command,
+    pub format: OutputFormat,
+    pub target: Target,
+    pub jsdoc: bool,
+    pub path: Option<String>,
+    pub lint_config: LintConfig,
+}
+
+pub fn parse_arguments(args: &[String]) -> CliResult<CliArguments> {
+    let mut arguments = args.iter();
+
+    let command = match arguments.next().map(String::as_str) {
+        Some("lex") => Command::Lex,
+        Some("parse") => Command::Parse,
+        Some("check") => Command::Check,
+        Some("optimize") => Command::Optimize,
+        Some("cfg") => Command::Cfg,
+        Some("ir") => Command::Ir,
+        Some("compile") => Command::Compile,
+        Some("lint") => Command::Lint,
+        Some("run") => Command::Run,
+        Some("repl") => Command::Repl,
+        Some("lsp") => Command::Lsp,
+        Some("-h" | "--help" | "help") => Command::Help,
+        Some(command) => return Err(usage_error(format!("Unknown command '{}'", command))),
+        None => return Err(usage_error(String::from("Missing command"))),
+    };
+
+    let mut format = OutputFormat::Yaml;
+    let mut target = Target::C;
+    let mut jsdoc = false;
+    let mut path = None;
+    let mut lint_config = LintConfig::new();
+
+    while let Some(argument) = arguments.next() {
+        match argument.as_str() {
+            "-h" | "--help" => {
+                return Ok(CliArguments {
+                    command: Command::Help,
+                    format,
+                    target,
+                    jsdoc,
+                    path,
+                    lint_config,
+                })
+            }
+            "--format" => {
+                format = match arguments.next().map(String::as_str) {
+                    Some("yaml") => OutputFormat::Yaml,
+                    Some("json") => OutputFormat::Json,
+                    Some("cst") => OutputFormat::Cst,
+                    Some(format) => {
+                        return Err(usage_error(format!("Unknown format '{}'", format)))
+                    }
+                    None => return Err(usage_error(String::from("Missing value for --format"))),
+                };
+            }
+            "--target" => {
+                target = match arguments.next().map(String::as_str) {
+                    Some("c") => Target::C,
+                    Some("js") => Target::Js,
+                    Some("wat") => Target::Wat,
+                    Some(target) => {
+                        return Err(usage_error(format!("Unknown target '{}'", target)))
+                    }
+                    None => return Err(usage_error(String::from("Missing value for --target"))),
+                };
+            }
+            "--jsdoc" => jsdoc = true,
+            "--rule" => {
+                let Some(setting) = arguments.next() else {
+                    return Err(usage_error(String::from("Missing value for --rule")));
+                };
+                lint_config
+                    .apply_setting(setting)
+                    .map_err(|error| usage_error(error.to_string()))?;
+            }
+            "-" if path.is_none() => path = Some(String::from("-")),
+            option if option.starts_with('-') => {
+                return Err(usage_error(format!("Unknown option '{}'", option)))
+            }
+            file if path.is_none() => path = Some(String::from(file)),
+            file => return Err(usage_error(format!("Unexpected argument '{}'", file))),
+        }
+    }
+
+    // '-' is an explicit request for stdin
+    let path = path.filter(|path| path != "-");
+
+    // Interactive commands talk over stdin and stdout
+    if matches!(command, Command::Repl | Command::Lsp) {
+        if let Some(path) = path {
+            return Err(usage_error(format!("Unexpected argument '{}'", path)));
+        }
+    }
+
+    // The optimized tree has no source text to keep
+    if command == Command::Optimize && format == OutputFormat::Cst {
+        return Err(usage_error(String::from(
+            "The cst format is only available to the parse command",
+        )));
+    }
+
+    Ok(CliArguments {
+        command,
+        format,
+        target,
+        jsdoc,
+        path,
+        lint_config,
+    })
+}
+
+fn usage_error(message: String) -> CliError {
+    CliError::UsageError { message }
+}
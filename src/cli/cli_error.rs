@@ -0,0 +1,12 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum CliError {
+    #[error("{message}")]
+    UsageError { message: String },
+
+    #[error("Could not read '{path}': {message}")]
+    InputError { path: String, message: String },
+}
+
+pub type CliResult<T> = Result<T, CliError>;
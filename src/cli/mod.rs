@@ -0,0 +1,8 @@
+mod cli;
+mod cli_arguments;
+mod cli_error;
+mod tests;
+
+pub(crate) use self::cli::run_cli;
+pub(crate) use self::cli_arguments::*;
+pub(crate) use self::cli_error::*;
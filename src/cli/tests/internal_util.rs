@@ -0,0 +1,78 @@
+use crate::cli::run_cli;
+use crate::interpreter::with_interpreter_stack;
+use serde::Deserialize;
+use std::fs;
+
+#[derive(Debug, Deserialize)]
+pub(super) struct YamlTestCase {
+    description: String,
+    args: Vec<String>,
+    stdin: String,
+    expected_stdout: String,
+    expected_stderr: String,
+    expected_exit_code: u8,
+}
+
+pub(super) fn execute_yaml_test(test_case: &YamlTestCase) {
+    let (exit_code, stdout, stderr) = with_interpreter_stack(|| {
+        let mut stdin = test_case.stdin.as_bytes();
+        let mut stdout: Vec<u8> = vec![];
+        let mut stderr: Vec<u8> = vec![];
+        let exit_code = run_cli(&test_case.args, &mut stdin, &mut stdout, &mut stderr);
+        (exit_code, stdout, stderr)
+    });
+
+    assert_eq!(
+        String::from_utf8(stdout).unwrap(),
+        test_case.expected_stdout
+    );
+    assert_eq!(
+        String::from_utf8(stderr).unwrap(),
+        test_case.expected_stderr
+    );
+    assert_eq!(exit_code, test_case.expected_exit_code);
+}
+
+pub(super) fn load_yaml_test_cases(path: &str) -> Vec<YamlTestCase> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(error) => {
+            eprintln!("Failed to read the test yaml file: {}", error);
+            eprintln!("Path: {}", path);
+            panic!("Could not read test file: {}", error);
+        }
+    };
+
+    // Parse multiple documents
+    let mut test_cases = Vec::new();
+
+    for document in serde_yaml::Deserializer::from_str(&content) {
+        match YamlTestCase::deserialize(document) {
+            Ok(test_case) => test_cases.push(test_case),
+            Err(e) => {
+                eprintln!("Failed to parse a YAML document: {}", e);
+                eprintln!("Path: {}", path);
+                panic!("Could not parse YAML content: {}", e);
+            }
+        }
+    }
+
+    // Verify that we found some test cases
+    if test_cases.is_empty() {
+        eprintln!("No test cases found in file: {}", path);
+        panic!("File contained no valid test cases");
+    }
+
+    test_cases
+}
+
+pub(super) fn execute_yaml_test_cases(path: &str) {
+    let test_cases = load_yaml_test_cases(path);
+
+    for (index, test_case) in test_cases.iter().enumerate() {
+        println!("Testing case #{}:\n{}\n", index + 1, test_case.description);
+        execute_yaml_test(test_case);
+    }
+
+    println!("All {} test cases passed!", test_cases.len());
+}
@@ -0,0 +1,16 @@
+use super::internal_util::execute_yaml_test_cases;
+
+#[test]
+fn test_cli_commands() {
+    execute_yaml_test_cases("tests/cli/test_cli_commands.yaml");
+}
+
+#[test]
+fn test_cli_diagnostics() {
+    execute_yaml_test_cases("tests/cli/test_cli_diagnostics.yaml");
+}
+
+#[test]
+fn test_cli_repl() {
+    execute_yaml_test_cases("tests/cli/test_cli_repl.yaml");
+}
@@ -0,0 +1,909 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+};
+
+use crate::{
+    ast::{BinaryOperator, PrimitiveType, Type, UnaryOperator},
+    ir::{
+        BlockId, Constant, InstructionKind, IrClass, IrFunction, IrModule, Terminator, ValueId,
+        PROGRAM_FUNCTION_NAME,
+    },
+};
+
+use super::{
+    classes_in_order, method_name, method_tables, parameter_types, CodegenError, CodegenResult,
+    MethodSlot,
+};
+
+///
+/// Strings, arrays, objects and checked arithmetic used by the generated code
+///
+const RUNTIME: &str = include_str!("codegen_c_runtime.c");
+
+///
+/// Emit a standalone C99 program for the module
+///
+/// Classes become structs embedding their superclass, with a table of
+/// method pointers shared by their instances. Objects and strings are
+/// never freed
+///
+pub fn generate_c(module: &IrModule) -> CodegenResult<String> {
+    CGenerator::new(module).generate()
+}
+
+struct CGenerator<'a> {
+    module: &'a IrModule,
+    function_names: HashMap<String, String>,
+    method_tables: HashMap<String, Vec<MethodSlot>>,
+    strings: Vec<String>,
+    function_values: Vec<String>,
+}
+
+impl<'a> CGenerator<'a> {
+    fn new(module: &'a IrModule) -> Self {
+        Self {
+            module,
+            function_names: HashMap::new(),
+            method_tables: HashMap::new(),
+            strings: vec![],
+            function_values: vec![],
+        }
+    }
+
+    fn generate(mut self) -> CodegenResult<String> {
+        let mut used_names = HashSet::new();
+        for function in &self.module.functions {
+            let mut name = format!("fn_{}", mangle(&function.name));
+            let base_name = name.clone();
+            let mut suffix = 1;
+            while !used_names.insert(name.clone()) {
+                suffix += 1;
+                name = format!("{}_{}", base_name, suffix);
+            }
+            self.function_names.insert(function.name.clone(), name);
+        }
+
+        let classes = classes_in_order(self.module);
+        self.method_tables = method_tables(self.module, &classes);
+
+        // Bodies go first since they collect the literals and function values
+        let mut bodies = String::new();
+        for function in &self.module.functions {
+            bodies.push('\n');
+            bodies += &self.function_body(function)?;
+        }
+
+        let mut output = String::from(RUNTIME);
+
+        if !classes.is_empty() {
+            output.push('\n');
+            for class in &classes {
+                writeln!(output, "struct c_{};", class.name).unwrap();
+            }
+            for class in &classes {
+                output.push('\n');
+                output += &self.class_struct(class)?;
+            }
+        }
+
+        output.push('\n');
+        for function in &self.module.functions {
+            writeln!(output, "static {};", self.function_signature(function)?).unwrap();
+        }
+
+        if !self.module.globals.is_empty() {
+            output.push('\n');
+            for (name, global_type) in &self.module.globals {
+                writeln!(
+                    output,
+                    "static {};",
+                    declaration(&c_type(global_type)?, &format!("g_{}", name))
+                )
+                .unwrap();
+            }
+        }
+
+        if !self.strings.is_empty() {
+            output.push('\n');
+            for (index, string) in self.strings.iter().enumerate() {
+                writeln!(
+                    output,
+                    "static sbz_string str_{} = {{{}, {}}};",
+                    index,
+                    string.len(),
+                    string_literal(string)
+                )
+                .unwrap();
+            }
+        }
+
+        if !self.function_values.is_empty() {
+            output.push('\n');
+            for name in &self.function_values {
+                let function_name = &self.function_names[name];
+                writeln!(
+                    output,
+                    "static const sbz_function fv_{} = {{{}, (void (*)(void)){}}};",
+                    &function_name[3..],
+                    string_literal(display_name(name)),
+                    function_name
+                )
+                .unwrap();
+            }
+        }
+
+        for class in &classes {
+            output.push('\n');
+            output += &self.class_info(class)?;
+        }
+
+        output += &bodies;
+
+        writeln!(
+            output,
+            "\nint main(void) {{\n    {}();\n    return 0;\n}}",
+            self.function_names[PROGRAM_FUNCTION_NAME]
+        )
+        .unwrap();
+
+        Ok(output)
+    }
+
+    fn class_struct(&self, class: &IrClass) -> CodegenResult<String> {
+        let mut output = format!("struct c_{} {{\n", class.name);
+        match &class.super_class {
+            Some(super_class) => writeln!(output, "    struct c_{} base;", super_class).unwrap(),
+            None => writeln!(output, "    sbz_object header;").unwrap(),
+        }
+        for (field, field_type) in &class.fields {
+            writeln!(
+                output,
+                "    {};",
+                declaration(&c_type(field_type)?, &format!("f_{}", field))
+            )
+            .unwrap();
+        }
+        output += "};\n";
+
+        let slots = &self.method_tables[&class.name];
+        if !slots.is_empty() {
+            writeln!(output, "\nstruct m_{} {{", class.name).unwrap();
+            for slot in slots {
+                writeln!(
+                    output,
+                    "    {};",
+                    function_pointer(
+                        &slot.return_type,
+                        &slot.parameter_types,
+                        &format!("m_{}", slot.method),
+                        true
+                    )?
+                )
+                .unwrap();
+            }
+            output += "};\n";
+        }
+
+        Ok(output)
+    }
+
+    fn class_info(&self, class: &IrClass) -> CodegenResult<String> {
+        let slots = &self.method_tables[&class.name];
+        let mut output = String::new();
+
+        if !slots.is_empty() {
+            writeln!(
+                output,
+                "static const struct m_{} methods_{} = {{",
+                class.name, class.name
+            )
+            .unwrap();
+            let mut entries = vec![];
+            for slot in slots {
+                let function = self.function(&slot.function)?;
+                let slot_type =
+                    function_pointer(&slot.return_type, &slot.parameter_types, "", true)?;
+                let function_type = function_pointer(
+                    &function.return_type,
+                    &parameter_types(function)[1..],
+                    "",
+                    true,
+                )?;
+
+                // Overriding methods may narrow the types of the slot
+                let function_name = &self.function_names[&slot.function];
+                entries.push(match slot_type == function_type {
+                    true => format!("    {}", function_name),
+                    false => format!("    ({}){}", slot_type, function_name),
+                });
+            }
+            output += &entries.join(",\n");
+            output += "\n};\n";
+        }
+
+        let methods = match slots.is_empty() {
+            true => String::from("NULL"),
+            false => format!("&methods_{}", class.name),
+        };
+        let variants = match class.variants.is_empty() {
+            true => String::from("NULL"),
+            false => {
+                let names = class
+                    .variants
+                    .iter()
+                    .map(|variant| string_literal(variant))
+                    .collect::<Vec<_>>();
+                writeln!(
+                    output,
+                    "static const char *const variants_{}[] = {{{}}};",
+                    class.name,
+                    names.join(", ")
+                )
+                .unwrap();
+                format!("variants_{}", class.name)
+            }
+        };
+        writeln!(
+            output,
+            "static const sbz_class_info class_{} = {{{}, {}, {}}};",
+            class.name,
+            string_literal(&class.name),
+            methods,
+            variants
+        )
+        .unwrap();
+
+        Ok(output)
+    }
+
+    fn function(&self, name: &str) -> CodegenResult<&'a IrFunction> {
+        self.module
+            .function(name)
+            .ok_or_else(|| generation_error(format!("Undefined function '{}'", name)))
+    }
+
+    fn function_signature(&self, function: &IrFunction) -> CodegenResult<String> {
+        let is_method = method_name(self.module, function).is_some();
+
+        let mut parameters = vec![];
+        for (index, parameter) in function.parameters.iter().enumerate() {
+            // Methods take the instance untyped so subclasses can share their tables
+            parameters.push(match is_method && index == 0 {
+                true => String::from("void *self"),
+                false => declaration(
+                    &c_type(&function.value_types[*parameter])?,
+                    &format!("v{}", parameter),
+                ),
+            });
+        }
+        if parameters.is_empty() {
+            parameters.push(String::from("void"));
+        }
+
+        Ok(declaration(
+            &c_type(&function.return_type)?,
+            &format!(
+                "{}({})",
+                self.function_names[&function.name],
+                parameters.join(", ")
+            ),
+        ))
+    }
+
+    fn function_body(&mut self, function: &IrFunction) -> CodegenResult<String> {
+        let mut output = format!("static {} {{\n", self.function_signature(function)?);
+
+        let is_method = method_name(self.module, function).is_some();
+        let parameters: HashSet<ValueId> = match is_method {
+            true => function.parameters[1..].iter().copied().collect(),
+            false => function.parameters.iter().copied().collect(),
+        };
+
+        let mut phis = vec![];
+        let mut defined = vec![];
+        for block in &function.blocks {
+            for instruction in &block.instructions {
+                if let Some(result) = instruction.result {
+                    defined.push(result);
+                    if matches!(instruction.kind, InstructionKind::Phi { .. }) {
+                        phis.push(result);
+                    }
+                }
+            }
+        }
+
+        if is_method {
+            defined.insert(0, function.parameters[0]);
+        }
+        for value in defined.iter().filter(|value| !parameters.contains(value)) {
+            let value_type = c_type(&function.value_types[*value])?;
+            writeln!(
+                output,
+                "    {};",
+                declaration(&value_type, &format!("v{}", value))
+            )
+            .unwrap();
+        }
+        for value in &phis {
+            let value_type = c_type(&function.value_types[*value])?;
+            writeln!(
+                output,
+                "    {};",
+                declaration(&value_type, &format!("p{}", value))
+            )
+            .unwrap();
+        }
+        if !defined.is_empty() || !phis.is_empty() {
+            output.push('\n');
+        }
+
+        if is_method {
+            writeln!(output, "    v{} = self;", function.parameters[0]).unwrap();
+        }
+
+        let targets: HashSet<BlockId> = function
+            .blocks
+            .iter()
+            .filter_map(|block| block.terminator.as_ref())
+            .flat_map(Terminator::successors)
+            .collect();
+
+        for (id, block) in function.blocks.iter().enumerate() {
+            if targets.contains(&id) {
+                writeln!(output, "b{}:", id).unwrap();
+            }
+
+            for instruction in &block.instructions {
+                let statement = match (&instruction.kind, instruction.result) {
+                    // Phi values are copied in by the predecessors
+                    (InstructionKind::Phi { .. }, Some(result)) => {
+                        format!("v{} = p{};", result, result)
+                    }
+                    (kind, result) => self.instruction(function, kind, result)?,
+                };
+                for line in statement.lines() {
+                    writeln!(output, "    {}", line).unwrap();
+                }
+            }
+
+            let statement = match &block.terminator {
+                Some(Terminator::Jump(target)) => self.jump(function, id, *target)?,
+                Some(Terminator::Branch {
+                    condition,
+                    consequent,
+                    alternative,
+                }) => format!(
+                    "if (v{}) {{\n{}}} else {{\n{}}}",
+                    condition,
+                    indent(&self.jump(function, id, *consequent)?),
+                    indent(&self.jump(function, id, *alternative)?)
+                ),
+                Some(Terminator::Return(value)) => {
+                    let value = match (value, &function.return_type) {
+                        (_, Type::Void) => None,
+                        (Some(value), return_type) => {
+                            Some(self.convert(function, *value, return_type)?)
+                        }
+                        (None, return_type) => Some(zero_value(return_type)?),
+                    };
+                    match value {
+                        Some(value) => format!("return {};", value),
+                        None => String::from("return;"),
+                    }
+                }
+                None => String::from("sbz_unreachable();"),
+            };
+            for line in statement.lines() {
+                writeln!(output, "    {}", line).unwrap();
+            }
+        }
+
+        output += "}\n";
+        Ok(output)
+    }
+
+    ///
+    /// Jump to the target block after copying the values of its phi nodes
+    ///
+    fn jump(&self, function: &IrFunction, from: BlockId, target: BlockId) -> CodegenResult<String> {
+        let mut output = String::new();
+        for instruction in &function.blocks[target].instructions {
+            let (InstructionKind::Phi { incoming }, Some(result)) =
+                (&instruction.kind, instruction.result)
+            else {
+                continue;
+            };
+            if let Some((_, value)) = incoming.iter().find(|(block, _)| *block == from) {
+                let value = self.convert(function, *value, &function.value_types[result])?;
+                writeln!(output, "p{} = {};", result, value).unwrap();
+            }
+        }
+        writeln!(output, "goto b{};", target).unwrap();
+        Ok(output)
+    }
+
+    fn instruction(
+        &mut self,
+        function: &IrFunction,
+        kind: &InstructionKind,
+        result: Option<ValueId>,
+    ) -> CodegenResult<String> {
+        let value_type = |value: ValueId| &function.value_types[value];
+
+        let expression = match kind {
+            InstructionKind::Constant(constant) => {
+                let result_type = result.map(value_type).unwrap_or(&Type::Nil);
+                self.constant(constant, result_type)?
+            }
+            InstructionKind::Undefined => zero_value(result.map(value_type).unwrap_or(&Type::Nil))?,
+            InstructionKind::Unary { operator, operand } => match operator {
+                UnaryOperator::Plus => format!("v{}", operand),
+                UnaryOperator::Minus => format!("sbz_neg(v{})", operand),
+                UnaryOperator::Not => format!("!v{}", operand),
+            },
+            InstructionKind::Binary {
+                operator,
+                left,
+                right,
+            } => binary_expression(
+                *operator,
+                (*left, value_type(*left)),
+                (*right, value_type(*right)),
+            )?,
+            InstructionKind::Phi { .. } => unreachable!("phi nodes are copied by the jumps"),
+            InstructionKind::LoadGlobal { name } => format!("g_{}", name),
+            InstructionKind::StoreGlobal { name, value } => {
+                let global_type = self.global_type(name)?;
+                return Ok(format!(
+                    "g_{} = {};",
+                    name,
+                    self.convert(function, *value, global_type)?
+                ));
+            }
+            InstructionKind::Function { name } => {
+                if !self.function_values.contains(name) {
+                    self.function_values.push(name.clone());
+                }
+                format!("&fv_{}", &self.function_names[name][3..])
+            }
+            InstructionKind::Call {
+                function: name,
+                arguments,
+            } if name == "print" && self.module.function(name).is_none() => {
+                let mut output = String::new();
+                for (index, argument) in arguments.iter().enumerate() {
+                    if index > 0 {
+                        output += "sbz_print_separator();\n";
+                    }
+                    writeln!(
+                        output,
+                        "{}(v{});",
+                        print_function(value_type(*argument)),
+                        argument
+                    )
+                    .unwrap();
+                }
+                return Ok(output + "sbz_print_end();");
+            }
+            InstructionKind::Call {
+                function: name,
+                arguments,
+            } => {
+                let callee = self.function(name)?;
+                let mut parameter_types = parameter_types(callee);
+                if method_name(self.module, callee).is_some() {
+                    parameter_types[0] = Type::Nil;
+                }
+                format!(
+                    "{}({})",
+                    self.function_names[name],
+                    self.arguments(function, arguments, &parameter_types)?
+                )
+            }
+            InstructionKind::CallIndirect { callee, arguments } => {
+                let Type::Function {
+                    params,
+                    return_type,
+                } = value_type(*callee)
+                else {
+                    return Err(generation_error(String::from("Expression is not callable")));
+                };
+                format!(
+                    "(({})sbz_check_function(v{})->code)({})",
+                    function_pointer(return_type, params, "", false)?,
+                    callee,
+                    self.arguments(function, arguments, params)?
+                )
+            }
+            InstructionKind::CallMethod {
+                object,
+                class_name,
+                method,
+                arguments,
+            } => {
+                let slot = self
+                    .method_tables
+                    .get(class_name)
+                    .and_then(|slots| slots.iter().find(|slot| &slot.method == method))
+                    .ok_or_else(|| {
+                        generation_error(format!(
+                            "Undefined method '{}' on class '{}'",
+                            method, class_name
+                        ))
+                    })?;
+                let mut parameter_types = vec![Type::Nil];
+                parameter_types.extend(slot.parameter_types.iter().cloned());
+                format!(
+                    "((const struct m_{} *)sbz_methods(v{}, {}))->m_{}({})",
+                    class_name,
+                    object,
+                    string_literal(method),
+                    method,
+                    self.arguments(
+                        function,
+                        &[vec![*object], arguments.clone()].concat(),
+                        &parameter_types
+                    )?
+                )
+            }
+            InstructionKind::NewArray { length } => format!("sbz_array_new(v{})", length),
+            InstructionKind::New { class_name } => format!(
+                "sbz_new(sizeof(struct c_{}), &class_{})",
+                class_name, class_name
+            ),
+            InstructionKind::LoadField {
+                object,
+                class_name,
+                field,
+            } => field_access(*object, class_name, field),
+            InstructionKind::StoreField {
+                object,
+                class_name,
+                field,
+                value,
+            } => {
+                let field_type = self.field_type(class_name, field)?;
+                return Ok(format!(
+                    "{} = {};",
+                    field_access(*object, class_name, field),
+                    self.convert(function, *value, field_type)?
+                ));
+            }
+            InstructionKind::LoadIndex { object, index } => match value_type(*object) {
+                Type::Primitive(PrimitiveType::String) => {
+                    format!("sbz_string_index(v{}, v{})", object, index)
+                }
+                Type::Array(element_type) => {
+                    let slot = format!("sbz_array_slot(v{}, v{})", object, index);
+                    match element_type.as_ref() {
+                        Type::Primitive(PrimitiveType::Number) => format!("{}->number", slot),
+                        Type::Primitive(PrimitiveType::Boolean) => format!("{}->boolean", slot),
+                        element_type => {
+                            format!("({}){}->pointer", c_type(element_type)?, slot)
+                        }
+                    }
+                }
+                object_type => {
+                    return Err(generation_error(format!(
+                        "Values of type {} can not be indexed",
+                        object_type
+                    )))
+                }
+            },
+            InstructionKind::StoreIndex {
+                object,
+                index,
+                value,
+            } => {
+                let Type::Array(element_type) = value_type(*object) else {
+                    return Err(generation_error(format!(
+                        "Values of type {} can not be modified",
+                        value_type(*object)
+                    )));
+                };
+                let member = match element_type.as_ref() {
+                    Type::Primitive(PrimitiveType::Number) => "number",
+                    Type::Primitive(PrimitiveType::Boolean) => "boolean",
+                    _ => "pointer",
+                };
+                return Ok(format!(
+                    "sbz_array_slot(v{}, v{})->{} = v{};",
+                    object, index, member, value
+                ));
+            }
+            InstructionKind::Length { object } => match value_type(*object) {
+                Type::Array(_) => format!("sbz_array_length(v{})", object),
+                _ => format!("sbz_string_length(v{})", object),
+            },
+        };
+
+        Ok(match result {
+            Some(result) => format!("v{} = {};", result, expression),
+            None => format!("{};", expression),
+        })
+    }
+
+    fn constant(&mut self, constant: &Constant, constant_type: &Type) -> CodegenResult<String> {
+        Ok(match constant {
+            Constant::Number(i32::MIN) => String::from("INT32_MIN"),
+            Constant::Number(value) => value.to_string(),
+            Constant::Boolean(value) => value.to_string(),
+            Constant::String(value) => {
+                let index = match self.strings.iter().position(|string| string == value) {
+                    Some(index) => index,
+                    None => {
+                        self.strings.push(value.clone());
+                        self.strings.len() - 1
+                    }
+                };
+                format!("&str_{}", index)
+            }
+            Constant::Nil => match constant_type {
+                Type::Primitive(PrimitiveType::Number | PrimitiveType::Boolean) => {
+                    return Err(generation_error(format!(
+                        "Nil values of type {} can not be represented in C",
+                        constant_type
+                    )))
+                }
+                _ => String::from("NULL"),
+            },
+        })
+    }
+
+    fn arguments(
+        &self,
+        function: &IrFunction,
+        arguments: &[ValueId],
+        parameter_types: &[Type],
+    ) -> CodegenResult<String> {
+        let mut values = vec![];
+        for (argument, parameter_type) in arguments.iter().zip(parameter_types) {
+            values.push(self.convert(function, *argument, parameter_type)?);
+        }
+        Ok(values.join(", "))
+    }
+
+    ///
+    /// Value passed where the target type is expected, instances of a
+    /// subclass are cast to the struct of their superclass
+    ///
+    fn convert(
+        &self,
+        function: &IrFunction,
+        value: ValueId,
+        target_type: &Type,
+    ) -> CodegenResult<String> {
+        let value_type = &function.value_types[value];
+        if value_type == target_type
+            || matches!(target_type, Type::Nil)
+            || matches!(value_type, Type::Primitive(_) | Type::Nil)
+        {
+            return Ok(format!("v{}", value));
+        }
+
+        let target = c_type(target_type)?;
+        match c_type(value_type)? == target {
+            true => Ok(format!("v{}", value)),
+            false => Ok(format!("({})v{}", target, value)),
+        }
+    }
+
+    fn global_type(&self, name: &str) -> CodegenResult<&'a Type> {
+        self.module
+            .globals
+            .iter()
+            .find(|(global, _)| global == name)
+            .map(|(_, global_type)| global_type)
+            .ok_or_else(|| generation_error(format!("Undefined global '{}'", name)))
+    }
+
+    fn field_type(&self, class_name: &str, field: &str) -> CodegenResult<&'a Type> {
+        self.module
+            .class(class_name)
+            .and_then(|class| class.fields.iter().find(|(name, _)| name == field))
+            .map(|(_, field_type)| field_type)
+            .ok_or_else(|| {
+                generation_error(format!(
+                    "Property '{}' does not exist on type '{}'",
+                    field, class_name
+                ))
+            })
+    }
+}
+
+fn binary_expression(
+    operator: BinaryOperator,
+    (left, left_type): (ValueId, &Type),
+    (right, right_type): (ValueId, &Type),
+) -> CodegenResult<String> {
+    let is_string = matches!(left_type, Type::Primitive(PrimitiveType::String));
+
+    Ok(match operator {
+        BinaryOperator::Add if is_string => format!("sbz_string_concat(v{}, v{})", left, right),
+        BinaryOperator::Add => format!("sbz_add(v{}, v{})", left, right),
+        BinaryOperator::Subtract => format!("sbz_sub(v{}, v{})", left, right),
+        BinaryOperator::Multiply => format!("sbz_mul(v{}, v{})", left, right),
+        BinaryOperator::Divide => format!("sbz_div(v{}, v{})", left, right),
+        BinaryOperator::Equal => equality((left, left_type), (right, right_type)),
+        BinaryOperator::NotEqual => {
+            format!("!({})", equality((left, left_type), (right, right_type)))
+        }
+        BinaryOperator::GreaterThan
+        | BinaryOperator::GreaterThanOrEqualTo
+        | BinaryOperator::LessThan
+        | BinaryOperator::LessThanOrEqualTo => {
+            let comparison = match operator {
+                BinaryOperator::GreaterThan => ">",
+                BinaryOperator::GreaterThanOrEqualTo => ">=",
+                BinaryOperator::LessThan => "<",
+                _ => "<=",
+            };
+            match is_string {
+                true => format!("sbz_string_compare(v{}, v{}) {} 0", left, right, comparison),
+                false => format!("v{} {} v{}", left, comparison, right),
+            }
+        }
+    })
+}
+
+///
+/// Primitives compare by value, everything else by identity
+///
+fn equality((left, left_type): (ValueId, &Type), (right, right_type): (ValueId, &Type)) -> String {
+    let is_scalar = |value_type: &Type| {
+        matches!(
+            value_type,
+            Type::Primitive(PrimitiveType::Number | PrimitiveType::Boolean)
+        )
+    };
+    let string = Type::string();
+
+    match (left_type, right_type) {
+        _ if is_scalar(left_type) || is_scalar(right_type) => match left_type == right_type {
+            true => format!("v{} == v{}", left, right),
+            false => String::from("false"),
+        },
+        (left_type, right_type) if left_type == &string && right_type == &string => {
+            format!("sbz_string_equals(v{}, v{})", left, right)
+        }
+        _ => format!("(const void *)v{} == (const void *)v{}", left, right),
+    }
+}
+
+fn field_access(object: ValueId, class_name: &str, field: &str) -> String {
+    format!(
+        "((struct c_{} *)sbz_check_object(v{}, {}))->f_{}",
+        class_name,
+        object,
+        string_literal(field),
+        field
+    )
+}
+
+fn print_function(value_type: &Type) -> &'static str {
+    match value_type {
+        Type::Primitive(PrimitiveType::Number) => "sbz_print_number",
+        Type::Primitive(PrimitiveType::Boolean) => "sbz_print_boolean",
+        Type::Primitive(PrimitiveType::String) => "sbz_print_string",
+        Type::Function { .. } => "sbz_print_function",
+        Type::Class { .. } => "sbz_print_object",
+        Type::Array(_) => "sbz_print_array",
+        _ => "sbz_print_nil",
+    }
+}
+
+fn c_type(value_type: &Type) -> CodegenResult<String> {
+    Ok(match value_type {
+        Type::Primitive(PrimitiveType::Number) => String::from("int32_t"),
+        Type::Primitive(PrimitiveType::Boolean) => String::from("bool"),
+        Type::Primitive(PrimitiveType::String) => String::from("sbz_string *"),
+        Type::Array(_) => String::from("sbz_array *"),
+        Type::Function { .. } => String::from("const sbz_function *"),
+        Type::Class { name, .. } => format!("struct c_{} *", name),
+        Type::Nullable(value_type) => return c_type(value_type),
+        Type::Nil => String::from("void *"),
+        Type::Void => String::from("void"),
+        Type::Generic { .. } | Type::Parameter(_) | Type::Union(_) | Type::Enum(_) => {
+            return Err(generation_error(format!(
+                "Values of type {} are not supported by the C backend",
+                value_type
+            )))
+        }
+    })
+}
+
+fn zero_value(value_type: &Type) -> CodegenResult<String> {
+    Ok(match value_type {
+        Type::Primitive(PrimitiveType::Number) => String::from("0"),
+        Type::Primitive(PrimitiveType::Boolean) => String::from("false"),
+        value_type => {
+            c_type(value_type)?;
+            String::from("NULL")
+        }
+    })
+}
+
+///
+/// Pointer to a function with the given signature, methods take the
+/// instance as an untyped first parameter
+///
+fn function_pointer(
+    return_type: &Type,
+    parameter_types: &[Type],
+    name: &str,
+    is_method: bool,
+) -> CodegenResult<String> {
+    let mut parameters = vec![];
+    if is_method {
+        parameters.push(String::from("void *"));
+    }
+    for parameter_type in parameter_types {
+        parameters.push(c_type(parameter_type)?);
+    }
+    if parameters.is_empty() {
+        parameters.push(String::from("void"));
+    }
+
+    Ok(declaration(
+        &c_type(return_type)?,
+        &format!("(*{})({})", name, parameters.join(", ")),
+    ))
+}
+
+fn declaration(c_type: &str, name: &str) -> String {
+    match c_type.ends_with('*') {
+        true => format!("{}{}", c_type, name),
+        false => format!("{} {}", c_type, name),
+    }
+}
+
+///
+/// Function names are made of identifiers joined by dots, duplicates get
+/// a '#' suffix
+///
+fn mangle(name: &str) -> String {
+    name.chars()
+        .filter_map(|character| match character {
+            '.' | '#' => Some('_'),
+            '<' | '>' => None,
+            character => Some(character),
+        })
+        .collect()
+}
+
+///
+/// Name the interpreter shows for the function, without the enclosing
+/// functions and the duplicate suffix
+///
+fn display_name(name: &str) -> &str {
+    let name = name.rsplit('.').next().unwrap_or(name);
+    name.split('#').next().unwrap_or(name)
+}
+
+///
+/// Bytes outside of printable ASCII are written as octal escapes
+///
+fn string_literal(value: &str) -> String {
+    let mut literal = String::from("\"");
+    for byte in value.bytes() {
+        match byte {
+            b'"' | b'\\' | b'?' => {
+                literal.push('\\');
+                literal.push(byte as char);
+            }
+            0x20..=0x7e => literal.push(byte as char),
+            byte => write!(literal, "\\{:03o}", byte).unwrap(),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+fn indent(text: &str) -> String {
+    text.lines().map(|line| format!("    {}\n", line)).collect()
+}
+
+fn generation_error(message: String) -> CodegenError {
+    CodegenError::GenerationError { message }
+}
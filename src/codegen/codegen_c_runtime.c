@@ -0,0 +1,317 @@
+/* Runtime of the senbonzakura C backend */
+
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+/* Immutable UTF-8 string, nil is the null pointer */
+typedef struct sbz_string {
+    int32_t length;
+    const char *data;
+} sbz_string;
+
+/* Array element, the member in use depends on the element type */
+typedef union sbz_slot {
+    int32_t number;
+    bool boolean;
+    void *pointer;
+} sbz_slot;
+
+typedef struct sbz_array {
+    int32_t length;
+    sbz_slot *items;
+} sbz_array;
+
+/* Function used as a value */
+typedef struct sbz_function {
+    const char *name;
+    void (*code)(void);
+} sbz_function;
+
+/* Class name, the table of its methods and the names of enum variants */
+typedef struct sbz_class_info {
+    const char *name;
+    const void *methods;
+    const char *const *variants;
+} sbz_class_info;
+
+/* First member of every instance */
+typedef struct sbz_object {
+    const sbz_class_info *class_info;
+} sbz_object;
+
+/* Enum values start with the tag of their variant */
+typedef struct sbz_enum {
+    sbz_object header;
+    int32_t tag;
+} sbz_enum;
+
+static void sbz_runtime_error(const char *message) {
+    fflush(stdout);
+    fprintf(stderr, "error: Runtime error: %s\n", message);
+    exit(1);
+}
+
+static void sbz_unreachable(void) {
+    sbz_runtime_error("Unreachable code");
+}
+
+static void *sbz_allocate(size_t size) {
+    void *memory = calloc(1, size);
+    if (memory == NULL) {
+        sbz_runtime_error("Out of memory");
+    }
+    return memory;
+}
+
+/* Checked arithmetic */
+
+static int32_t sbz_checked(int64_t value) {
+    if (value < INT32_MIN || value > INT32_MAX) {
+        sbz_runtime_error("Numeric overflow");
+    }
+    return (int32_t)value;
+}
+
+static int32_t sbz_add(int32_t left, int32_t right) {
+    return sbz_checked((int64_t)left + (int64_t)right);
+}
+
+static int32_t sbz_sub(int32_t left, int32_t right) {
+    return sbz_checked((int64_t)left - (int64_t)right);
+}
+
+static int32_t sbz_mul(int32_t left, int32_t right) {
+    return sbz_checked((int64_t)left * (int64_t)right);
+}
+
+static int32_t sbz_div(int32_t left, int32_t right) {
+    if (right == 0) {
+        sbz_runtime_error("Division by zero");
+    }
+    return sbz_checked((int64_t)left / (int64_t)right);
+}
+
+static int32_t sbz_neg(int32_t value) {
+    return sbz_checked(-(int64_t)value);
+}
+
+/* Strings */
+
+static sbz_string *sbz_string_new(const char *data, int32_t length) {
+    sbz_string *string = sbz_allocate(sizeof(sbz_string));
+    char *copy = sbz_allocate((size_t)length + 1);
+    memcpy(copy, data, (size_t)length);
+    string->length = length;
+    string->data = copy;
+    return string;
+}
+
+static sbz_string *sbz_string_concat(sbz_string *left, sbz_string *right) {
+    sbz_string *string;
+    char *data;
+
+    if (left == NULL || right == NULL) {
+        sbz_runtime_error(left == NULL ? "Operator '+' cannot be applied to nil and string"
+                                       : "Operator '+' cannot be applied to string and nil");
+    }
+    if ((int64_t)left->length + right->length > INT32_MAX) {
+        sbz_runtime_error("Numeric overflow");
+    }
+
+    string = sbz_allocate(sizeof(sbz_string));
+    data = sbz_allocate((size_t)left->length + (size_t)right->length + 1);
+    memcpy(data, left->data, (size_t)left->length);
+    memcpy(data + left->length, right->data, (size_t)right->length);
+    string->length = left->length + right->length;
+    string->data = data;
+    return string;
+}
+
+static bool sbz_string_equals(sbz_string *left, sbz_string *right) {
+    if (left == NULL || right == NULL) {
+        return left == right;
+    }
+    return left->length == right->length &&
+           memcmp(left->data, right->data, (size_t)left->length) == 0;
+}
+
+/* Byte order of UTF-8 matches the order of the characters */
+static int sbz_string_compare(sbz_string *left, sbz_string *right) {
+    int32_t length;
+    int result;
+
+    if (left == NULL || right == NULL) {
+        sbz_runtime_error("Operator cannot be applied to nil");
+    }
+
+    length = left->length < right->length ? left->length : right->length;
+    result = memcmp(left->data, right->data, (size_t)length);
+    if (result != 0) {
+        return result;
+    }
+    return (left->length > right->length) - (left->length < right->length);
+}
+
+static bool sbz_is_continuation(char byte) {
+    return ((unsigned char)byte & 0xC0) == 0x80;
+}
+
+/* Strings are measured and indexed in characters */
+static int32_t sbz_string_length(sbz_string *string) {
+    int32_t count = 0;
+    int32_t offset;
+
+    if (string == NULL) {
+        sbz_runtime_error("Property 'length' does not exist on nil");
+    }
+    for (offset = 0; offset < string->length; offset++) {
+        if (!sbz_is_continuation(string->data[offset])) {
+            count++;
+        }
+    }
+    return count;
+}
+
+static sbz_string *sbz_string_index(sbz_string *string, int32_t index) {
+    char message[64];
+    int32_t offset = 0;
+    int32_t current = 0;
+    int32_t end;
+
+    if (string == NULL) {
+        sbz_runtime_error("Cannot index nil with number");
+    }
+
+    while (index >= 0 && offset < string->length) {
+        end = offset + 1;
+        while (end < string->length && sbz_is_continuation(string->data[end])) {
+            end++;
+        }
+        if (current == index) {
+            return sbz_string_new(string->data + offset, end - offset);
+        }
+        current++;
+        offset = end;
+    }
+
+    sprintf(message, "Index %ld out of bounds", (long)index);
+    sbz_runtime_error(message);
+    return NULL;
+}
+
+/* Arrays */
+
+static sbz_array *sbz_array_new(int32_t length) {
+    sbz_array *array = sbz_allocate(sizeof(sbz_array));
+    array->length = length;
+    array->items = sbz_allocate(sizeof(sbz_slot) * (size_t)(length > 0 ? length : 1));
+    return array;
+}
+
+static sbz_slot *sbz_array_slot(sbz_array *array, int32_t index) {
+    char message[64];
+
+    if (array == NULL) {
+        sbz_runtime_error("Cannot index nil with number");
+    }
+    if (index < 0 || index >= array->length) {
+        sprintf(message, "Index %ld out of bounds", (long)index);
+        sbz_runtime_error(message);
+    }
+    return &array->items[index];
+}
+
+static int32_t sbz_array_length(sbz_array *array) {
+    if (array == NULL) {
+        sbz_runtime_error("Property 'length' does not exist on nil");
+    }
+    return array->length;
+}
+
+/* Functions and objects */
+
+static const sbz_function *sbz_check_function(const sbz_function *function) {
+    if (function == NULL) {
+        sbz_runtime_error("Value of type nil is not callable");
+    }
+    return function;
+}
+
+static void *sbz_new(size_t size, const sbz_class_info *class_info) {
+    sbz_object *object = sbz_allocate(size);
+    object->class_info = class_info;
+    return object;
+}
+
+static void *sbz_check_object(void *object, const char *property) {
+    char message[256];
+
+    if (object == NULL) {
+        sprintf(message, "Property '%.200s' does not exist on nil", property);
+        sbz_runtime_error(message);
+    }
+    return object;
+}
+
+static const void *sbz_methods(void *object, const char *method) {
+    return ((sbz_object *)sbz_check_object(object, method))->class_info->methods;
+}
+
+/* Output of the print builtin */
+
+static void sbz_print_separator(void) {
+    fputs(" ", stdout);
+}
+
+static void sbz_print_end(void) {
+    fputs("\n", stdout);
+}
+
+static void sbz_print_number(int32_t value) {
+    printf("%ld", (long)value);
+}
+
+static void sbz_print_boolean(bool value) {
+    fputs(value ? "true" : "false", stdout);
+}
+
+static void sbz_print_nil(const void *value) {
+    (void)value;
+    fputs("nil", stdout);
+}
+
+static void sbz_print_string(sbz_string *value) {
+    if (value == NULL) {
+        fputs("nil", stdout);
+        return;
+    }
+    fwrite(value->data, 1, (size_t)value->length, stdout);
+}
+
+static void sbz_print_function(const sbz_function *value) {
+    if (value == NULL) {
+        fputs("nil", stdout);
+        return;
+    }
+    printf("<def %s>", value->name);
+}
+
+static void sbz_print_object(void *value) {
+    if (value == NULL) {
+        fputs("nil", stdout);
+        return;
+    }
+    const sbz_class_info *class_info = ((sbz_object *)value)->class_info;
+    if (class_info->variants != NULL) {
+        fputs(class_info->variants[((sbz_enum *)value)->tag], stdout);
+        return;
+    }
+    printf("<%s instance>", class_info->name);
+}
+
+static void sbz_print_array(sbz_array *value) {
+    fputs(value == NULL ? "nil" : "<array>", stdout);
+}
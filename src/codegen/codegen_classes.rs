@@ -0,0 +1,119 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    ast::{Type, CONSTRUCTOR_NAME},
+    ir::{IrClass, IrFunction, IrModule, FIELD_INITIALIZER_NAME},
+};
+
+///
+/// Entry of a method table, inherited methods keep the position they have
+/// in the table of the superclass
+///
+#[derive(Debug, Clone)]
+pub(super) struct MethodSlot {
+    pub(super) method: String,
+    pub(super) function: String,
+    pub(super) return_type: Type,
+    pub(super) parameter_types: Vec<Type>,
+}
+
+///
+/// Superclasses come before their subclasses
+///
+pub(super) fn classes_in_order(module: &IrModule) -> Vec<&IrClass> {
+    let mut ordered: Vec<&IrClass> = vec![];
+    let mut visited = HashSet::new();
+
+    fn visit<'a>(
+        module: &'a IrModule,
+        class: &'a IrClass,
+        visited: &mut HashSet<String>,
+        ordered: &mut Vec<&'a IrClass>,
+    ) {
+        if !visited.insert(class.name.clone()) {
+            return;
+        }
+        if let Some(super_class) = class
+            .super_class
+            .as_ref()
+            .and_then(|name| module.class(name))
+        {
+            visit(module, super_class, visited, ordered);
+        }
+        ordered.push(class);
+    }
+
+    for class in &module.classes {
+        visit(module, class, &mut visited, &mut ordered);
+    }
+    ordered
+}
+
+///
+/// Method tables of the classes, the classes have to be ordered with
+/// [`classes_in_order`]
+///
+/// Every method except the constructor and the field initializer gets a
+/// slot, those two are always called directly
+///
+pub(super) fn method_tables(
+    module: &IrModule,
+    classes: &[&IrClass],
+) -> HashMap<String, Vec<MethodSlot>> {
+    let mut tables: HashMap<String, Vec<MethodSlot>> = HashMap::new();
+
+    for class in classes {
+        let mut slots = match &class.super_class {
+            Some(super_class) => tables.get(super_class).cloned().unwrap_or_default(),
+            None => vec![],
+        };
+
+        for function in &module.functions {
+            let Some(method) = method_name(module, function) else {
+                continue;
+            };
+            if !function.name.starts_with(&format!("{}.", class.name))
+                || method == CONSTRUCTOR_NAME
+                || method == FIELD_INITIALIZER_NAME
+            {
+                continue;
+            }
+
+            match slots.iter_mut().find(|slot| slot.method == method) {
+                Some(slot) => slot.function = function.name.clone(),
+                None => slots.push(MethodSlot {
+                    method: String::from(method),
+                    function: function.name.clone(),
+                    return_type: function.return_type.clone(),
+                    parameter_types: parameter_types(function)[1..].to_vec(),
+                }),
+            }
+        }
+
+        tables.insert(class.name.clone(), slots);
+    }
+
+    tables
+}
+
+///
+/// Name of the method implemented by the function, none for functions
+/// that are not declared in a class
+///
+pub(super) fn method_name<'a>(module: &IrModule, function: &'a IrFunction) -> Option<&'a str> {
+    let (class_name, method) = function.name.split_once('.')?;
+    let is_method = module.class(class_name).is_some()
+        && !method.contains(['.', '#'])
+        && function.parameters.first().is_some_and(|this| {
+            matches!(&function.value_types[*this], Type::Class { name, .. } if name == class_name)
+        });
+    is_method.then_some(method)
+}
+
+pub(super) fn parameter_types(function: &IrFunction) -> Vec<Type> {
+    function
+        .parameters
+        .iter()
+        .map(|parameter| function.value_types[*parameter].clone())
+        .collect()
+}
@@ -0,0 +1,9 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum CodegenError {
+    #[error("Code generation error: {message}")]
+    GenerationError { message: String },
+}
+
+pub type CodegenResult<T> = Result<T, CodegenError>;
@@ -0,0 +1,1284 @@
+use anyhow::Result;
+
+use crate::ast::{
+    AssignmentOperator, AstVisitor, BinaryOperator, ClassMember, ClassMemberList, Expression,
+    ExpressionList, ExpressionRef, InterfaceMember, InterfaceMemberList, LogicalOperator,
+    MatchArmList, ParameterList, Pattern, PrimitiveType, Statement, StatementList, Type,
+    TypeParameter, TypeParameterList, UnaryOperator, CONSTRUCTOR_NAME,
+};
+
+use super::{CodegenError, CodegenResult};
+
+///
+/// Output of the print builtin in the format of the interpreter
+///
+const PRINT_FUNCTION: &str = r#"function print(...values) {
+  const format = (value) => {
+    if (value === null || value === undefined) return "nil";
+    if (typeof value === "function") return `<def ${value.name}>`;
+    if (Array.isArray(value)) return "<array>";
+    if (typeof value === "object" && value.constructor.isEnum) {
+      return `${value.constructor.name}::${value.variant}`;
+    }
+    if (typeof value === "object") return `<${value.constructor.name} instance>`;
+    return String(value);
+  };
+  console.log(values.map(format).join(" "));
+}
+"#;
+
+///
+/// Words that can not name a variable in strict mode JavaScript
+///
+const RESERVED_WORDS: &[&str] = &[
+    "arguments",
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "eval",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "undefined",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
+const INDENT: &str = "  ";
+
+///
+/// Variable holding the value a match is made on, source names cannot
+/// contain '$'
+///
+const MATCH_SUBJECT: &str = "$subject";
+
+///
+/// Operator precedence of JavaScript, operands binding looser than their
+/// parent are put in parentheses
+///
+const PRECEDENCE_ASSIGNMENT: u8 = 2;
+const PRECEDENCE_OR: u8 = 3;
+const PRECEDENCE_AND: u8 = 4;
+const PRECEDENCE_EQUALITY: u8 = 8;
+const PRECEDENCE_RELATIONAL: u8 = 9;
+const PRECEDENCE_ADDITIVE: u8 = 11;
+const PRECEDENCE_MULTIPLICATIVE: u8 = 12;
+const PRECEDENCE_UNARY: u8 = 14;
+const PRECEDENCE_CALL: u8 = 17;
+const PRECEDENCE_PRIMARY: u8 = 20;
+
+///
+/// Translate a checked program into ES2020 JavaScript, with the type
+/// annotations kept as JSDoc comments when asked for
+///
+/// Numbers follow the interpreter except for overflows, which are not
+/// detected. Field initializers run in the constructor after the parent
+/// constructor, derived classes call it without arguments when their
+/// constructor does not
+///
+pub fn generate_js(program: &Statement, jsdoc: bool) -> CodegenResult<String> {
+    let mut generator = JsGenerator {
+        jsdoc,
+        uses_print: false,
+    };
+    let output = generator
+        .visit_statement(program)
+        .map_err(into_codegen_error)?;
+
+    Ok(match generator.uses_print {
+        true => format!("{}\n{}", PRINT_FUNCTION, output),
+        false => output,
+    })
+}
+
+///
+/// Classes, interfaces and enums are visible to the whole statement list,
+/// unlike JavaScript classes they come first with superclasses before their
+/// subclasses and the other statements follow in source order
+///
+fn hoisted_order(statements: &[Statement]) -> Vec<&Statement> {
+    let class_name = |statement: &Statement| match statement {
+        Statement::ClassDeclaration { name, .. }
+        | Statement::InterfaceDeclaration { name, .. }
+        | Statement::EnumDeclaration { name, .. } => match name.as_ref() {
+            Expression::Identifier { name, .. } => Some(name.clone()),
+            _ => None,
+        },
+        _ => None,
+    };
+    let super_class_name = |statement: &Statement| match statement {
+        Statement::ClassDeclaration {
+            super_class: Some(super_class),
+            ..
+        } => match super_class.as_ref() {
+            Expression::Identifier { name, .. } => Some(name.clone()),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    let (mut pending, others): (Vec<_>, Vec<_>) = statements
+        .iter()
+        .partition(|statement| class_name(statement).is_some());
+
+    let mut ordered = vec![];
+    while !pending.is_empty() {
+        // A class waits for its superclass when that is declared in the list,
+        // cycles are rejected by the checker
+        let position = pending
+            .iter()
+            .position(|statement| {
+                super_class_name(statement).is_none_or(|super_class| {
+                    !pending
+                        .iter()
+                        .any(|other| class_name(other).as_ref() == Some(&super_class))
+                })
+            })
+            .unwrap_or_default();
+        ordered.push(pending.remove(position));
+    }
+
+    ordered.extend(others);
+    ordered
+}
+
+struct JsGenerator {
+    jsdoc: bool,
+    uses_print: bool,
+}
+
+impl JsGenerator {
+    fn statement_list(&mut self, statements: &[Statement]) -> Result<String> {
+        let mut output = String::new();
+        let mut follows_declaration = None;
+        for statement in hoisted_order(statements) {
+            // Interfaces leave nothing behind without JSDoc
+            let statement_output = self.visit_statement(statement)?;
+            if statement_output.is_empty() {
+                continue;
+            }
+
+            // Declarations are set apart from their neighbours
+            let is_declaration = matches!(
+                statement,
+                Statement::FunctionDeclaration { .. }
+                    | Statement::ClassDeclaration { .. }
+                    | Statement::InterfaceDeclaration { .. }
+                    | Statement::EnumDeclaration { .. }
+            );
+            if follows_declaration.is_some_and(|follows| follows || is_declaration) {
+                output.push('\n');
+            }
+            output += &statement_output;
+            follows_declaration = Some(is_declaration);
+        }
+        Ok(output)
+    }
+
+    fn block(&mut self, statements: &[Statement]) -> Result<String> {
+        if statements.is_empty() {
+            return Ok(String::from("{}\n"));
+        }
+        Ok(format!(
+            "{{\n{}}}\n",
+            indent(&self.statement_list(statements)?)
+        ))
+    }
+
+    ///
+    /// Body of a loop or a branch, blocks stay on the line of their parent
+    ///
+    fn nested_statement(&mut self, statement: &Statement) -> Result<String> {
+        let output = self.visit_statement(statement)?;
+        Ok(match statement {
+            Statement::Block { .. } => format!(" {}", output),
+            _ => format!("\n{}", indent(&output)),
+        })
+    }
+
+    fn variable_declaration(&mut self, variables: &ExpressionList) -> Result<String> {
+        let mut declarations = vec![];
+        for variable in variables {
+            let Expression::Variable {
+                identifier,
+                type_annotation,
+                initializer,
+                ..
+            } = variable
+            else {
+                return Err(generation_error(String::from("Expected a variable")));
+            };
+
+            let initializer = match initializer {
+                Some(initializer) => self.expression(initializer, PRECEDENCE_ASSIGNMENT)?,
+                // Variables start out as nil
+                None => String::from("null"),
+            };
+            declarations.push((
+                format!("{} = {}", self.expression(identifier, 0)?, initializer),
+                type_annotation,
+            ));
+        }
+
+        if !self.jsdoc {
+            let declarations: Vec<_> = declarations
+                .into_iter()
+                .map(|(declaration, _)| declaration)
+                .collect();
+            return Ok(format!("let {};\n", declarations.join(", ")));
+        }
+
+        // JSDoc types a single declaration, inferred types are left to the
+        // JavaScript tooling
+        Ok(declarations
+            .into_iter()
+            .map(|(declaration, type_annotation)| match type_annotation {
+                Some(type_annotation) => format!(
+                    "/** @type {{{}}} */\nlet {};\n",
+                    jsdoc_type(type_annotation),
+                    declaration
+                ),
+                None => format!("let {};\n", declaration),
+            })
+            .collect())
+    }
+
+    fn function(
+        &mut self,
+        name: &str,
+        type_parameters: &TypeParameterList,
+        parameters: &ParameterList,
+        return_type: &Type,
+        body: &Statement,
+    ) -> Result<String> {
+        let body = self.block(function_body(body)?)?;
+        self.function_with_body(name, type_parameters, parameters, return_type, &body)
+    }
+
+    fn function_with_body(
+        &mut self,
+        name: &str,
+        type_parameters: &TypeParameterList,
+        parameters: &ParameterList,
+        return_type: &Type,
+        body: &str,
+    ) -> Result<String> {
+        Ok(format!(
+            "{}{}{} {}",
+            self.function_jsdoc(type_parameters, parameters, return_type),
+            name,
+            self.parameters(parameters)?,
+            body
+        ))
+    }
+
+    ///
+    /// Constructor body with the field initializers, they run as soon as
+    /// the parent constructor returns
+    ///
+    fn constructor_body(
+        &mut self,
+        body: &Statement,
+        fields: &str,
+        is_derived: bool,
+    ) -> Result<String> {
+        let body = function_body(body)?;
+        let super_call = body.iter().position(is_super_call);
+
+        let output = match (super_call, is_derived) {
+            (Some(index), _) => {
+                self.statement_list(&body[..=index])?
+                    + fields
+                    + &self.statement_list(&body[index + 1..])?
+            }
+            // Derived classes have to call the parent constructor in JavaScript
+            (None, true) => format!("super();\n{}{}", fields, self.statement_list(body)?),
+            (None, false) => String::from(fields) + &self.statement_list(body)?,
+        };
+
+        Ok(match output.is_empty() {
+            true => String::from("{}\n"),
+            false => format!("{{\n{}}}\n", indent(&output)),
+        })
+    }
+
+    fn function_jsdoc(
+        &self,
+        type_parameters: &TypeParameterList,
+        parameters: &ParameterList,
+        return_type: &Type,
+    ) -> String {
+        if !self.jsdoc {
+            return String::new();
+        }
+
+        let mut tags = template_tags(type_parameters);
+        for (parameter, parameter_type) in parameters {
+            if let Expression::Identifier { name, .. } = parameter {
+                tags.push(format!(
+                    "@param {{{}}} {}",
+                    jsdoc_type(parameter_type),
+                    variable_name(name)
+                ));
+            }
+        }
+        if return_type != &Type::Void {
+            tags.push(format!("@returns {{{}}}", jsdoc_type(return_type)));
+        }
+
+        jsdoc_comment(&tags)
+    }
+
+    fn parameters(&mut self, parameters: &ParameterList) -> Result<String> {
+        let mut names = vec![];
+        for (parameter, _) in parameters {
+            names.push(self.expression(parameter, 0)?);
+        }
+        Ok(format!("({})", names.join(", ")))
+    }
+
+    fn class(
+        &mut self,
+        name: &str,
+        type_parameters: &TypeParameterList,
+        super_class: Option<String>,
+        implements: &[String],
+        members: &ClassMemberList,
+    ) -> Result<String> {
+        let mut fields = String::new();
+        let mut constructor = None;
+        let mut methods = vec![];
+        for member in members {
+            match member {
+                ClassMember::Field {
+                    name,
+                    type_annotation,
+                    initializer,
+                    ..
+                } => {
+                    fields += &self.field_initializer(name, type_annotation, initializer)?;
+                }
+                ClassMember::Method { declaration } | ClassMember::Constructor { declaration } => {
+                    let Statement::FunctionDeclaration {
+                        name,
+                        type_parameters,
+                        parameters,
+                        return_type,
+                        body,
+                        ..
+                    } = declaration.as_ref()
+                    else {
+                        return Err(generation_error(String::from("Expected a method")));
+                    };
+                    let Expression::Identifier { name, .. } = name.as_ref() else {
+                        return Err(generation_error(String::from("Expected a method name")));
+                    };
+                    match member {
+                        ClassMember::Constructor { .. } => {
+                            constructor = Some((parameters, return_type, body))
+                        }
+                        _ => methods.push(self.function(
+                            name,
+                            type_parameters,
+                            parameters,
+                            return_type,
+                            body,
+                        )?),
+                    }
+                }
+            }
+        }
+
+        let is_derived = super_class.is_some();
+        let constructor = match constructor {
+            Some((parameters, return_type, body)) => {
+                let body = self.constructor_body(body, &fields, is_derived)?;
+                Some(self.function_with_body(
+                    CONSTRUCTOR_NAME,
+                    &TypeParameterList::new(),
+                    parameters,
+                    return_type,
+                    &body,
+                )?)
+            }
+            None if fields.is_empty() => None,
+            None if is_derived => Some(format!(
+                "constructor(...args) {{\n{}}}\n",
+                indent(&format!("super(...args);\n{}", fields))
+            )),
+            None => Some(format!("constructor() {{\n{}}}\n", indent(&fields))),
+        };
+
+        let members: Vec<String> = constructor.into_iter().chain(methods).collect();
+        let extends = match &super_class {
+            Some(super_class) => format!(" extends {}", super_class),
+            None => String::new(),
+        };
+
+        let jsdoc = match self.jsdoc {
+            true => {
+                let mut tags = template_tags(type_parameters);
+                for interface in implements {
+                    tags.push(format!("@implements {{{}}}", interface));
+                }
+                jsdoc_comment(&tags)
+            }
+            false => String::new(),
+        };
+        Ok(match members.is_empty() {
+            true => format!("{}class {}{} {{}}\n", jsdoc, name, extends),
+            false => format!(
+                "{}class {}{} {{\n{}}}\n",
+                jsdoc,
+                name,
+                extends,
+                indent(&members.join("\n"))
+            ),
+        })
+    }
+
+    ///
+    /// Enums are classes holding the name of the variant and its values
+    ///
+    fn enum_declaration(&self, name: &str) -> String {
+        let jsdoc = match self.jsdoc {
+            true => jsdoc_comment(&[
+                String::from("@param {string} variant"),
+                String::from("@param {...*} values"),
+            ]),
+            false => String::new(),
+        };
+        let constructor = format!(
+            "{}constructor(variant, ...values) {{\n{}}}\n",
+            jsdoc,
+            indent("this.variant = variant;\nthis.values = values;\n")
+        );
+        format!(
+            "class {} {{\n{}}}\n",
+            name,
+            indent(&format!("static isEnum = true;\n{}", constructor))
+        )
+    }
+
+    ///
+    /// Interfaces have no JavaScript form, JSDoc describes them as object types
+    ///
+    fn interface(&self, name: &str, members: &InterfaceMemberList) -> String {
+        if !self.jsdoc {
+            return String::new();
+        }
+
+        let mut tags = vec![format!("@typedef {{Object}} {}", name)];
+        for member in members {
+            let (name, member_type) = match member {
+                InterfaceMember::Field {
+                    name,
+                    type_annotation,
+                    ..
+                } => (name, type_annotation.clone()),
+                InterfaceMember::Method {
+                    name,
+                    parameters,
+                    return_type,
+                    ..
+                } => (
+                    name,
+                    Type::Function {
+                        params: parameters
+                            .iter()
+                            .map(|(_, parameter_type)| parameter_type.clone())
+                            .collect(),
+                        return_type: Box::new(return_type.clone()),
+                    },
+                ),
+            };
+            if let Expression::Identifier { name, .. } = name.as_ref() {
+                tags.push(format!(
+                    "@property {{{}}} {}",
+                    jsdoc_type(&member_type),
+                    name
+                ));
+            }
+        }
+        jsdoc_comment(&tags)
+    }
+
+    fn field_initializer(
+        &mut self,
+        name: &Expression,
+        type_annotation: &Type,
+        initializer: &Option<ExpressionRef>,
+    ) -> Result<String> {
+        let Expression::Identifier { name, .. } = name else {
+            return Err(generation_error(String::from("Expected a field name")));
+        };
+
+        let initializer = match initializer {
+            Some(initializer) => self.expression(initializer, PRECEDENCE_ASSIGNMENT)?,
+            None => String::from("null"),
+        };
+        let mut output = String::new();
+        if self.jsdoc {
+            output += &format!("/** @type {{{}}} */\n", jsdoc_type(type_annotation));
+        }
+        output += &format!("this.{} = {};\n", name, initializer);
+        Ok(output)
+    }
+
+    ///
+    /// Expression text, in parentheses when it binds looser than the
+    /// given precedence
+    ///
+    fn expression(&mut self, expression: &Expression, precedence: u8) -> Result<String> {
+        let (output, own_precedence) = self.expression_with_precedence(expression)?;
+        Ok(match own_precedence < precedence {
+            true => format!("({})", output),
+            false => output,
+        })
+    }
+
+    fn expression_with_precedence(&mut self, expression: &Expression) -> Result<(String, u8)> {
+        Ok(match expression {
+            Expression::Variable { identifier, .. } => {
+                (self.expression(identifier, 0)?, PRECEDENCE_PRIMARY)
+            }
+            Expression::Destructuring { pattern, .. } => {
+                (self.destructuring_pattern(pattern)?, PRECEDENCE_PRIMARY)
+            }
+            Expression::Assignment {
+                operator,
+                left,
+                right,
+                ..
+            } => {
+                let target = self.expression(left, PRECEDENCE_CALL)?;
+                let value = match operator {
+                    // Numbers are integers, the quotient is truncated
+                    AssignmentOperator::AssignDivide => format!(
+                        "{} = Math.trunc({} / {})",
+                        target,
+                        target,
+                        self.expression(right, PRECEDENCE_MULTIPLICATIVE + 1)?
+                    ),
+                    operator => format!(
+                        "{} {} {}",
+                        target,
+                        operator,
+                        self.expression(right, PRECEDENCE_ASSIGNMENT)?
+                    ),
+                };
+                (value, PRECEDENCE_ASSIGNMENT)
+            }
+            Expression::Binary {
+                operator,
+                left,
+                right,
+                ..
+            } => {
+                let precedence = binary_precedence(*operator);
+                let left = self.expression(left, precedence)?;
+                let right = self.expression(right, precedence + 1)?;
+                match operator {
+                    BinaryOperator::Divide => {
+                        (format!("Math.trunc({} / {})", left, right), PRECEDENCE_CALL)
+                    }
+                    BinaryOperator::Equal => (format!("{} === {}", left, right), precedence),
+                    BinaryOperator::NotEqual => (format!("{} !== {}", left, right), precedence),
+                    operator => (format!("{} {} {}", left, operator, right), precedence),
+                }
+            }
+            Expression::Unary {
+                operator, right, ..
+            } => {
+                let operand = self.expression(right, PRECEDENCE_UNARY)?;
+                // '--' and '++' would be read as decrement and increment
+                let operand = match (operator, operand.chars().next()) {
+                    (UnaryOperator::Minus, Some('-')) | (UnaryOperator::Plus, Some('+')) => {
+                        format!("({})", operand)
+                    }
+                    _ => operand,
+                };
+                (format!("{}{}", operator, operand), PRECEDENCE_UNARY)
+            }
+            Expression::TypeTest {
+                expression,
+                type_annotation,
+                ..
+            } => type_test(
+                self.expression_with_precedence(expression)?,
+                type_annotation,
+            )?,
+            Expression::Logical {
+                operator,
+                left,
+                right,
+                ..
+            } => {
+                let (precedence, left_precedence, right_precedence) = match operator {
+                    LogicalOperator::And => (PRECEDENCE_AND, PRECEDENCE_AND, PRECEDENCE_AND + 1),
+                    LogicalOperator::Or => (PRECEDENCE_OR, PRECEDENCE_OR, PRECEDENCE_OR + 1),
+                    // JavaScript does not allow '??' next to '&&' or '||' without
+                    // parentheses, it binds looser than both to get them
+                    LogicalOperator::NilCoalescing => {
+                        let left_precedence = match left.as_ref() {
+                            Expression::Logical {
+                                operator: LogicalOperator::NilCoalescing,
+                                ..
+                            } => PRECEDENCE_ASSIGNMENT,
+                            _ => PRECEDENCE_AND + 1,
+                        };
+                        (PRECEDENCE_ASSIGNMENT, left_precedence, PRECEDENCE_AND + 1)
+                    }
+                };
+                (
+                    format!(
+                        "{} {} {}",
+                        self.expression(left, left_precedence)?,
+                        operator,
+                        self.expression(right, right_precedence)?
+                    ),
+                    precedence,
+                )
+            }
+            Expression::BooleanLiteral { value, .. } => (value.to_string(), PRECEDENCE_PRIMARY),
+            Expression::NilLiteral { .. } => (String::from("null"), PRECEDENCE_PRIMARY),
+            Expression::StringLiteral { value, .. } => (
+                serde_json::to_string(value)
+                    .map_err(|error| generation_error(error.to_string()))?,
+                PRECEDENCE_PRIMARY,
+            ),
+            Expression::NumericLiteral { value, .. } if *value < 0 => {
+                (value.to_string(), PRECEDENCE_UNARY)
+            }
+            Expression::NumericLiteral { value, .. } => (value.to_string(), PRECEDENCE_PRIMARY),
+            Expression::Identifier { name, .. } => {
+                self.uses_print |= name == "print";
+                (variable_name(name), PRECEDENCE_PRIMARY)
+            }
+            Expression::Member {
+                computed,
+                optional,
+                object,
+                property,
+                ..
+            } => {
+                let object = self.expression(object, PRECEDENCE_CALL)?;
+                let output = match (computed, optional, property.as_ref()) {
+                    (false, false, Expression::Identifier { name, .. }) => {
+                        format!("{}.{}", object, name)
+                    }
+                    (false, true, Expression::Identifier { name, .. }) => {
+                        format!("{}?.{}", object, name)
+                    }
+                    (false, _, _) => {
+                        return Err(generation_error(String::from("Expected a property name")))
+                    }
+                    (true, false, property) => {
+                        format!("{}[{}]", object, self.expression(property, 0)?)
+                    }
+                    (true, true, property) => {
+                        format!("{}?.[{}]", object, self.expression(property, 0)?)
+                    }
+                };
+                (output, PRECEDENCE_CALL)
+            }
+            Expression::Call {
+                callee, arguments, ..
+            } => (
+                format!(
+                    "{}({})",
+                    self.expression(callee, PRECEDENCE_CALL)?,
+                    self.arguments(arguments)?
+                ),
+                PRECEDENCE_CALL,
+            ),
+            Expression::ArrayLiteral { elements, .. } => (
+                format!("[{}]", self.arguments(elements)?),
+                PRECEDENCE_PRIMARY,
+            ),
+            Expression::This { .. } => (String::from("this"), PRECEDENCE_PRIMARY),
+            Expression::Super { .. } => (String::from("super"), PRECEDENCE_PRIMARY),
+            Expression::New {
+                callee, arguments, ..
+            } => (
+                format!(
+                    "new {}({})",
+                    self.expression(callee, PRECEDENCE_PRIMARY)?,
+                    self.arguments(arguments)?
+                ),
+                PRECEDENCE_CALL,
+            ),
+            Expression::EnumVariant {
+                enum_name,
+                variant,
+                arguments,
+                ..
+            } => {
+                let Expression::Identifier { name: variant, .. } = variant.as_ref() else {
+                    return Err(generation_error(String::from("Expected a variant name")));
+                };
+                let mut values = vec![serde_json::to_string(variant)
+                    .map_err(|error| generation_error(error.to_string()))?];
+                if !arguments.is_empty() {
+                    values.push(self.arguments(arguments)?);
+                }
+                (
+                    format!(
+                        "new {}({})",
+                        self.expression(enum_name, PRECEDENCE_PRIMARY)?,
+                        values.join(", ")
+                    ),
+                    PRECEDENCE_CALL,
+                )
+            }
+            Expression::Match { subject, arms, .. } => {
+                (self.match_expression(subject, arms)?, PRECEDENCE_CALL)
+            }
+        })
+    }
+
+    ///
+    /// Match as a function called in place, every arm returns its value
+    /// once its tests pass
+    ///
+    fn match_expression(&mut self, subject: &Expression, arms: &MatchArmList) -> Result<String> {
+        let mut body = format!(
+            "const {} = {};\n",
+            MATCH_SUBJECT,
+            self.expression(subject, PRECEDENCE_ASSIGNMENT)?
+        );
+
+        for arm in arms {
+            let mut conditions = vec![];
+            let mut bindings = vec![];
+            self.pattern(&arm.pattern, MATCH_SUBJECT, &mut conditions, &mut bindings)?;
+
+            let value = self.expression(&arm.value, 0)?;
+            let mut arm_body: String = bindings
+                .iter()
+                .map(|(name, value)| format!("const {} = {};\n", name, value))
+                .collect();
+            arm_body += &match &arm.guard {
+                Some(guard) => format!("if ({}) return {};\n", self.expression(guard, 0)?, value),
+                None => format!("return {};\n", value),
+            };
+
+            body += &match (conditions.is_empty(), bindings.is_empty()) {
+                (true, true) => arm_body,
+                (true, false) => format!("{{\n{}}}\n", indent(&arm_body)),
+                (false, _) => format!(
+                    "if ({}) {{\n{}}}\n",
+                    conditions.join(" && "),
+                    indent(&arm_body)
+                ),
+            };
+        }
+
+        Ok(format!("(() => {{\n{}}})()", indent(&body)))
+    }
+
+    ///
+    /// Tests of a pattern on the value at the given path and the names it
+    /// binds, with the paths of their values
+    ///
+    fn pattern(
+        &mut self,
+        pattern: &Pattern,
+        path: &str,
+        conditions: &mut Vec<String>,
+        bindings: &mut Vec<(String, String)>,
+    ) -> Result<()> {
+        match pattern {
+            Pattern::Wildcard {
+                type_annotation, ..
+            } => {
+                if let Some(type_annotation) = type_annotation {
+                    conditions.push(pattern_type_test(path, type_annotation)?);
+                }
+            }
+            Pattern::Binding {
+                name,
+                type_annotation,
+                ..
+            } => {
+                if let Some(type_annotation) = type_annotation {
+                    conditions.push(pattern_type_test(path, type_annotation)?);
+                }
+                bindings.push((self.expression(name, 0)?, String::from(path)));
+            }
+            Pattern::Literal { value, .. } => conditions.push(format!(
+                "{} === {}",
+                path,
+                self.expression(value, PRECEDENCE_EQUALITY + 1)?
+            )),
+            Pattern::EnumVariant {
+                enum_name,
+                variant,
+                arguments,
+                ..
+            } => {
+                let Expression::Identifier { name: variant, .. } = variant.as_ref() else {
+                    return Err(generation_error(String::from("Expected a variant name")));
+                };
+                conditions.push(format!(
+                    "{} instanceof {}",
+                    path,
+                    self.expression(enum_name, PRECEDENCE_PRIMARY)?
+                ));
+                conditions.push(format!(
+                    "{}.variant === {}",
+                    path,
+                    serde_json::to_string(variant)
+                        .map_err(|error| generation_error(error.to_string()))?
+                ));
+                for (index, argument) in arguments.iter().enumerate() {
+                    let path = format!("{}.values[{}]", path, index);
+                    self.pattern(argument, &path, conditions, bindings)?;
+                }
+            }
+            Pattern::Array { elements, .. } => {
+                conditions.push(format!("Array.isArray({})", path));
+                conditions.push(format!("{}.length === {}", path, elements.len()));
+                for (index, element) in elements.iter().enumerate() {
+                    let path = format!("{}[{}]", path, index);
+                    self.pattern(element, &path, conditions, bindings)?;
+                }
+            }
+            Pattern::Object { .. } => {
+                return Err(generation_error(String::from(
+                    "Object patterns are only allowed in declarations",
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// Destructuring pattern of a declaration, JavaScript shares its syntax
+    ///
+    fn destructuring_pattern(&mut self, pattern: &Pattern) -> Result<String> {
+        match pattern {
+            // An array hole skips the element
+            Pattern::Wildcard { .. } => Ok(String::new()),
+            Pattern::Binding { name, .. } => self.expression(name, 0),
+            Pattern::Array { elements, .. } => {
+                let mut elements_output = vec![];
+                for element in elements {
+                    elements_output.push(self.destructuring_pattern(element)?);
+                }
+                // Trailing holes would leave a dangling comma
+                while elements_output.last().is_some_and(String::is_empty) {
+                    elements_output.pop();
+                }
+                Ok(format!("[{}]", elements_output.join(", ")))
+            }
+            Pattern::Object { properties, .. } => {
+                let mut properties_output = vec![];
+                for property in properties {
+                    let Expression::Identifier { name: key, .. } = property.key.as_ref() else {
+                        return Err(generation_error(String::from("Expected a field name")));
+                    };
+                    let value = match &property.value {
+                        Pattern::Wildcard { .. } => continue,
+                        value => self.destructuring_pattern(value)?,
+                    };
+                    properties_output.push(match value == *key {
+                        true => value,
+                        false => format!("{}: {}", key, value),
+                    });
+                }
+                Ok(match properties_output.is_empty() {
+                    true => String::from("{}"),
+                    false => format!("{{ {} }}", properties_output.join(", ")),
+                })
+            }
+            Pattern::Literal { .. } | Pattern::EnumVariant { .. } => Err(generation_error(
+                String::from("Only names can be destructured into"),
+            )),
+        }
+    }
+
+    fn arguments(&mut self, arguments: &ExpressionList) -> Result<String> {
+        let mut values = vec![];
+        for argument in arguments {
+            values.push(self.expression(argument, PRECEDENCE_ASSIGNMENT)?);
+        }
+        Ok(values.join(", "))
+    }
+}
+
+impl AstVisitor for JsGenerator {
+    type Output = String;
+
+    fn visit_statement(&mut self, statement: &Statement) -> Result<Self::Output> {
+        match statement {
+            Statement::Program { body, .. } => self.statement_list(body),
+            Statement::Block { body, .. } => self.block(body),
+            Statement::Empty { .. } => Ok(String::from(";\n")),
+            Statement::Expression { expression, .. } => {
+                Ok(format!("{};\n", self.expression(expression, 0)?))
+            }
+            Statement::VariableDeclaration { variables, .. } => {
+                self.variable_declaration(variables)
+            }
+            Statement::If {
+                condition,
+                consequent,
+                alternative,
+                ..
+            } => {
+                let mut output = format!(
+                    "if ({}){}",
+                    self.expression(condition, 0)?,
+                    self.nested_statement(consequent)?
+                );
+                if let Some(alternative) = alternative {
+                    let alternative = match alternative.as_ref() {
+                        Statement::If { .. } => format!(" {}", self.visit_statement(alternative)?),
+                        alternative => self.nested_statement(alternative)?,
+                    };
+                    match consequent.as_ref() {
+                        Statement::Block { .. } => {
+                            output.pop();
+                            output += &format!(" else{}", alternative);
+                        }
+                        _ => output += &format!("else{}", alternative),
+                    }
+                }
+                Ok(output)
+            }
+            Statement::While {
+                condition, body, ..
+            } => Ok(format!(
+                "while ({}){}",
+                self.expression(condition, 0)?,
+                self.nested_statement(body)?
+            )),
+            Statement::DoWhile {
+                body, condition, ..
+            } => {
+                let mut body = self.nested_statement(body)?;
+                body.pop();
+                let separator = if body.ends_with('}') { " " } else { "\n" };
+                Ok(format!(
+                    "do{}{}while ({});\n",
+                    body,
+                    separator,
+                    self.expression(condition, 0)?
+                ))
+            }
+            Statement::For {
+                initializer,
+                condition,
+                increment,
+                body,
+                ..
+            } => {
+                let initializer = match initializer.as_deref() {
+                    Some(Statement::VariableDeclaration { variables, .. }) => {
+                        let jsdoc = std::mem::replace(&mut self.jsdoc, false);
+                        let declaration = self.variable_declaration(variables);
+                        self.jsdoc = jsdoc;
+                        declaration?.trim_end().trim_end_matches(';').to_string()
+                    }
+                    Some(Statement::Expression { expression, .. }) => {
+                        self.expression(expression, 0)?
+                    }
+                    Some(_) => {
+                        return Err(generation_error(String::from(
+                            "Unexpected for loop initializer",
+                        )))
+                    }
+                    None => String::new(),
+                };
+                let condition = match condition {
+                    Some(condition) => format!(" {}", self.expression(condition, 0)?),
+                    None => String::new(),
+                };
+                let increment = match increment {
+                    Some(increment) => format!(" {}", self.expression(increment, 0)?),
+                    None => String::new(),
+                };
+                Ok(format!(
+                    "for ({};{};{}){}",
+                    initializer,
+                    condition,
+                    increment,
+                    self.nested_statement(body)?
+                ))
+            }
+            Statement::FunctionDeclaration {
+                name,
+                type_parameters,
+                parameters,
+                return_type,
+                body,
+                ..
+            } => {
+                let name = self.expression(name, 0)?;
+                self.function(
+                    &format!("function {}", name),
+                    type_parameters,
+                    parameters,
+                    return_type,
+                    body,
+                )
+            }
+            Statement::Return { argument, .. } => match argument {
+                Some(argument) => Ok(format!("return {};\n", self.expression(argument, 0)?)),
+                None => Ok(String::from("return;\n")),
+            },
+            Statement::Break { label, .. } | Statement::Continue { label, .. } => {
+                let keyword = match statement {
+                    Statement::Break { .. } => "break",
+                    _ => "continue",
+                };
+                match label {
+                    Some(label) => Ok(format!("{} {};\n", keyword, self.expression(label, 0)?)),
+                    None => Ok(format!("{};\n", keyword)),
+                }
+            }
+            Statement::Labeled { label, body, .. } => Ok(format!(
+                "{}: {}",
+                self.expression(label, 0)?,
+                self.visit_statement(body)?
+            )),
+            Statement::ClassDeclaration {
+                name,
+                type_parameters,
+                super_class,
+                implements,
+                members,
+                ..
+            } => {
+                let name = self.expression(name, 0)?;
+                let super_class = match super_class {
+                    Some(super_class) => Some(self.expression(super_class, PRECEDENCE_CALL)?),
+                    None => None,
+                };
+                let implements = implements
+                    .iter()
+                    .map(|interface| self.expression(interface, 0))
+                    .collect::<Result<Vec<_>>>()?;
+                self.class(&name, type_parameters, super_class, &implements, members)
+            }
+            Statement::InterfaceDeclaration { name, members, .. } => {
+                let name = self.expression(name, 0)?;
+                Ok(self.interface(&name, members))
+            }
+            Statement::EnumDeclaration { name, .. } => {
+                let name = self.expression(name, 0)?;
+                Ok(self.enum_declaration(&name))
+            }
+        }
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) -> Result<Self::Output> {
+        self.expression(expression, 0)
+    }
+}
+
+fn function_body(body: &Statement) -> Result<&StatementList> {
+    match body {
+        Statement::Block { body, .. } => Ok(body),
+        _ => Err(generation_error(String::from(
+            "Function bodies must be blocks",
+        ))),
+    }
+}
+
+fn is_super_call(statement: &Statement) -> bool {
+    let Statement::Expression { expression, .. } = statement else {
+        return false;
+    };
+    matches!(
+        expression.as_ref(),
+        Expression::Call { callee, .. } if matches!(callee.as_ref(), Expression::Super { .. })
+    )
+}
+
+///
+/// Runtime test of the type of an operand given with its precedence
+///
+fn type_test((operand, precedence): (String, u8), tested_type: &Type) -> Result<(String, u8)> {
+    let operand = |parent: u8| match precedence < parent {
+        true => format!("({})", operand),
+        false => operand.clone(),
+    };
+
+    Ok(match tested_type {
+        Type::Primitive(_) => (
+            format!(
+                "typeof {} === \"{}\"",
+                operand(PRECEDENCE_UNARY),
+                tested_type
+            ),
+            PRECEDENCE_EQUALITY,
+        ),
+        Type::Nil => (
+            format!("{} === null", operand(PRECEDENCE_EQUALITY)),
+            PRECEDENCE_EQUALITY,
+        ),
+        Type::Class { name, .. } | Type::Enum(name) => (
+            format!(
+                "{} instanceof {}",
+                operand(PRECEDENCE_RELATIONAL),
+                variable_name(name)
+            ),
+            PRECEDENCE_RELATIONAL,
+        ),
+        _ => {
+            return Err(generation_error(format!(
+                "Type '{}' cannot be tested at runtime",
+                tested_type
+            )))
+        }
+    })
+}
+
+fn pattern_type_test(path: &str, tested_type: &Type) -> Result<String> {
+    let (test, _) = type_test((String::from(path), PRECEDENCE_CALL), tested_type)?;
+    Ok(test)
+}
+
+fn binary_precedence(operator: BinaryOperator) -> u8 {
+    match operator {
+        BinaryOperator::Add | BinaryOperator::Subtract => PRECEDENCE_ADDITIVE,
+        BinaryOperator::Multiply | BinaryOperator::Divide => PRECEDENCE_MULTIPLICATIVE,
+        BinaryOperator::Equal | BinaryOperator::NotEqual => PRECEDENCE_EQUALITY,
+        BinaryOperator::GreaterThan
+        | BinaryOperator::GreaterThanOrEqualTo
+        | BinaryOperator::LessThan
+        | BinaryOperator::LessThanOrEqualTo => PRECEDENCE_RELATIONAL,
+    }
+}
+
+///
+/// Template tags of the type parameters, bounds are written the way
+/// TypeScript reads them
+///
+fn template_tags(type_parameters: &TypeParameterList) -> Vec<String> {
+    let mut tags = vec![];
+    for TypeParameter { name, bound } in type_parameters {
+        let Expression::Identifier { name, .. } = name.as_ref() else {
+            continue;
+        };
+        tags.push(match bound {
+            Some(bound) => format!("@template {{{}}} {}", jsdoc_type(bound), name),
+            None => format!("@template {}", name),
+        });
+    }
+    tags
+}
+
+fn jsdoc_comment(tags: &[String]) -> String {
+    if tags.is_empty() {
+        return String::new();
+    }
+    let tags: String = tags.iter().map(|tag| format!(" * {}\n", tag)).collect();
+    format!("/**\n{} */\n", tags)
+}
+
+fn jsdoc_type(value_type: &Type) -> String {
+    match value_type {
+        Type::Primitive(PrimitiveType::Number) => String::from("number"),
+        Type::Primitive(PrimitiveType::Boolean) => String::from("boolean"),
+        Type::Primitive(PrimitiveType::String) => String::from("string"),
+        Type::Array(element_type) => format!("Array<{}>", jsdoc_type(element_type)),
+        Type::Function {
+            params,
+            return_type,
+        } => format!(
+            "function({}): {}",
+            params.iter().map(jsdoc_type).collect::<Vec<_>>().join(", "),
+            jsdoc_type(return_type)
+        ),
+        Type::Class { name, .. } | Type::Enum(name) | Type::Parameter(name) => name.clone(),
+        Type::Generic { base, type_args } => format!(
+            "{}<{}>",
+            base,
+            type_args
+                .iter()
+                .map(jsdoc_type)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Type::Nullable(value_type) => format!("?{}", jsdoc_type(value_type)),
+        Type::Union(members) => format!(
+            "({})",
+            members.iter().map(jsdoc_type).collect::<Vec<_>>().join("|")
+        ),
+        Type::Void => String::from("void"),
+        Type::Nil => String::from("null"),
+    }
+}
+
+///
+/// Names that are reserved in JavaScript get a trailing underscore
+///
+fn variable_name(name: &str) -> String {
+    match RESERVED_WORDS.contains(&name) {
+        true => format!("{}_", name),
+        false => String::from(name),
+    }
+}
+
+fn indent(text: &str) -> String {
+    text.lines()
+        .map(|line| match line.is_empty() {
+            true => String::from("\n"),
+            false => format!("{}{}\n", INDENT, line),
+        })
+        .collect()
+}
+
+fn generation_error(message: String) -> anyhow::Error {
+    CodegenError::GenerationError { message }.into()
+}
+
+fn into_codegen_error(error: anyhow::Error) -> CodegenError {
+    match error.downcast::<CodegenError>() {
+        Ok(error) => error,
+        Err(error) => CodegenError::GenerationError {
+            message: error.to_string(),
+        },
+    }
+}
@@ -0,0 +1,939 @@
+use std::{collections::HashMap, fmt::Write};
+
+use crate::{
+    ast::{BinaryOperator, PrimitiveType, Type, UnaryOperator},
+    ir::{
+        BlockId, Constant, InstructionKind, IrFunction, IrModule, Terminator, ValueId,
+        PROGRAM_FUNCTION_NAME,
+    },
+};
+
+use super::{
+    classes_in_order, method_name, method_tables, CodegenError, CodegenResult, MethodSlot,
+};
+
+///
+/// Allocator, checked arithmetic, strings and arrays used by the generated
+/// code
+///
+const RUNTIME: &str = include_str!("codegen_wat_runtime.wat");
+
+///
+/// Formatting of the print builtin, only included when the program prints
+///
+const PRINT_RUNTIME: &str = include_str!("codegen_wat_print.wat");
+
+///
+/// Numbers are formatted in the bytes before this address, the data
+/// starts right after them and the address 0 is left for nil
+///
+const DATA_START: u32 = 16;
+
+const PAGE_SIZE: u32 = 65536;
+
+///
+/// Texts the print runtime writes, by the name of their global
+///
+const PRINT_TEXTS: [(&str, &str); 8] = [
+    ("true", "true"),
+    ("false", "false"),
+    ("nil", "nil"),
+    ("space", " "),
+    ("newline", "\n"),
+    ("open", "<"),
+    ("instance", " instance>"),
+    ("array", "<array>"),
+];
+
+///
+/// Emit a WebAssembly text module for the module
+///
+/// Every value is an i32, strings, arrays and class instances are
+/// addresses in the exported linear memory. Methods are dispatched through
+/// a function table and printing goes through an imported
+/// `env.write(address, length)`. Top level functions are exported by
+/// their name and the program itself as `_start`
+///
+pub fn generate_wat(module: &IrModule) -> CodegenResult<String> {
+    WatGenerator::new(module).generate()
+}
+
+///
+/// Shape of the control flow graph of a function, blocks are numbered in
+/// reverse postorder
+///
+struct ControlFlow {
+    order: Vec<Option<usize>>,
+    children: Vec<Vec<BlockId>>,
+    loop_headers: Vec<bool>,
+    merge_nodes: Vec<bool>,
+}
+
+impl ControlFlow {
+    fn new(function: &IrFunction) -> Self {
+        let count = function.blocks.len();
+        let successors: Vec<Vec<BlockId>> = function
+            .blocks
+            .iter()
+            .map(|block| {
+                block
+                    .terminator
+                    .as_ref()
+                    .map(Terminator::successors)
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        // Reverse postorder of the blocks reachable from the entry
+        let mut postorder = vec![];
+        let mut visited = vec![false; count];
+        let mut stack = vec![(0, 0)];
+        visited[0] = true;
+        while let Some((block, next)) = stack.pop() {
+            match successors[block].get(next) {
+                Some(&successor) => {
+                    stack.push((block, next + 1));
+                    if !visited[successor] {
+                        visited[successor] = true;
+                        stack.push((successor, 0));
+                    }
+                }
+                None => postorder.push(block),
+            }
+        }
+        let reverse_postorder: Vec<BlockId> = postorder.into_iter().rev().collect();
+        let mut order = vec![None; count];
+        for (index, block) in reverse_postorder.iter().enumerate() {
+            order[*block] = Some(index);
+        }
+
+        let mut predecessors = vec![vec![]; count];
+        for &block in &reverse_postorder {
+            for &successor in &successors[block] {
+                predecessors[successor].push(block);
+            }
+        }
+
+        // Dominators as described by Cooper, Harvey and Kennedy
+        let mut dominators: Vec<Option<BlockId>> = vec![None; count];
+        dominators[0] = Some(0);
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &block in &reverse_postorder[1..] {
+                let mut dominator: Option<BlockId> = None;
+                for &predecessor in &predecessors[block] {
+                    if dominators[predecessor].is_none() {
+                        continue;
+                    }
+                    dominator = Some(match dominator {
+                        None => predecessor,
+                        Some(mut other) => {
+                            let mut current = predecessor;
+                            while current != other {
+                                while order[current] > order[other] {
+                                    current = dominators[current].unwrap();
+                                }
+                                while order[other] > order[current] {
+                                    other = dominators[other].unwrap();
+                                }
+                            }
+                            current
+                        }
+                    });
+                }
+                if dominators[block] != dominator {
+                    dominators[block] = dominator;
+                    changed = true;
+                }
+            }
+        }
+
+        let mut children = vec![vec![]; count];
+        for &block in &reverse_postorder[1..] {
+            if let Some(dominator) = dominators[block] {
+                children[dominator].push(block);
+            }
+        }
+
+        let mut loop_headers = vec![false; count];
+        let mut merge_nodes = vec![false; count];
+        for block in 0..count {
+            let forward = predecessors[block]
+                .iter()
+                .filter(|predecessor| order[**predecessor] < order[block])
+                .count();
+            loop_headers[block] = forward < predecessors[block].len();
+            merge_nodes[block] = forward > 1;
+        }
+
+        Self {
+            order,
+            children,
+            loop_headers,
+            merge_nodes,
+        }
+    }
+
+    fn is_backward(&self, from: BlockId, target: BlockId) -> bool {
+        self.order[target] <= self.order[from]
+    }
+
+    ///
+    /// Dominated blocks that are reached from more than one block, the one
+    /// coming last in the code first
+    ///
+    fn merge_children(&self, block: BlockId) -> Vec<BlockId> {
+        let mut merges: Vec<BlockId> = self.children[block]
+            .iter()
+            .copied()
+            .filter(|child| self.merge_nodes[*child])
+            .collect();
+        merges.sort_by_key(|child| std::cmp::Reverse(self.order[*child]));
+        merges
+    }
+}
+
+struct WatGenerator<'a> {
+    module: &'a IrModule,
+    method_tables: HashMap<String, Vec<MethodSlot>>,
+    table: Vec<String>,
+    data: Vec<u8>,
+    strings: HashMap<String, u32>,
+    class_infos: HashMap<String, u32>,
+    uses_print: bool,
+    body: String,
+    depth: usize,
+}
+
+impl<'a> WatGenerator<'a> {
+    fn new(module: &'a IrModule) -> Self {
+        Self {
+            module,
+            method_tables: HashMap::new(),
+            table: vec![],
+            data: vec![],
+            strings: HashMap::new(),
+            class_infos: HashMap::new(),
+            uses_print: false,
+            body: String::new(),
+            depth: 0,
+        }
+    }
+
+    fn generate(mut self) -> CodegenResult<String> {
+        let classes = classes_in_order(self.module);
+        self.method_tables = method_tables(self.module, &classes);
+
+        for class in &classes {
+            let name = self.string(&class.name);
+            let variants = match class.variants.is_empty() {
+                true => 0,
+                false => {
+                    let names = class
+                        .variants
+                        .iter()
+                        .map(|variant| self.string(variant))
+                        .collect::<Vec<_>>();
+                    let address = self.allocate_data();
+                    for name in names {
+                        self.data.extend(name.to_le_bytes());
+                    }
+                    address
+                }
+            };
+            let mut entries = vec![name, variants];
+            for slot in &self.method_tables[&class.name] {
+                let index = match self.table.iter().position(|name| name == &slot.function) {
+                    Some(index) => index,
+                    None => {
+                        self.table.push(slot.function.clone());
+                        self.table.len() - 1
+                    }
+                };
+                entries.push(index as u32);
+            }
+
+            let address = self.allocate_data();
+            for entry in entries {
+                self.data.extend(entry.to_le_bytes());
+            }
+            self.class_infos.insert(class.name.clone(), address);
+        }
+
+        // Bodies go first since they collect the literals
+        let mut functions = String::new();
+        for function in &self.module.functions {
+            functions.push('\n');
+            functions += &self.function(function)?;
+        }
+
+        let mut texts = vec![];
+        if self.uses_print {
+            for (name, text) in PRINT_TEXTS {
+                texts.push((name, self.string(text)));
+            }
+        }
+
+        let heap_start = self.allocate_data();
+        let pages = heap_start.div_ceil(PAGE_SIZE).max(1);
+
+        let mut output = String::from("(module\n");
+        if self.uses_print {
+            output += "  (import \"env\" \"write\" (func $sbz_write (param i32 i32)))\n";
+        }
+        writeln!(output, "  (memory (export \"memory\") {})", pages).unwrap();
+        if !self.table.is_empty() {
+            writeln!(output, "  (table {} funcref)", self.table.len()).unwrap();
+            let functions: Vec<String> = self.table.iter().map(|name| function_id(name)).collect();
+            writeln!(
+                output,
+                "  (elem (i32.const 0) func {})",
+                functions.join(" ")
+            )
+            .unwrap();
+        }
+
+        writeln!(
+            output,
+            "  (global $sbz_heap (mut i32) (i32.const {}))",
+            heap_start
+        )
+        .unwrap();
+        if self.uses_print {
+            writeln!(
+                output,
+                "  (global $sbz_scratch_end i32 (i32.const {}))",
+                DATA_START
+            )
+            .unwrap();
+            for (name, address) in texts {
+                writeln!(
+                    output,
+                    "  (global $sbz_text_{} i32 (i32.const {}))",
+                    name, address
+                )
+                .unwrap();
+            }
+        }
+        for (name, global_type) in &self.module.globals {
+            check_type(global_type)?;
+            writeln!(output, "  (global $g:{} (mut i32) (i32.const 0))", name).unwrap();
+        }
+
+        if !self.data.is_empty() {
+            writeln!(
+                output,
+                "  (data (i32.const {}) {})",
+                DATA_START,
+                data_literal(&self.data)
+            )
+            .unwrap();
+        }
+
+        output.push('\n');
+        output += RUNTIME;
+        if self.uses_print {
+            output.push('\n');
+            output += PRINT_RUNTIME;
+        }
+        output += &functions;
+        output += ")\n";
+
+        Ok(output)
+    }
+
+    ///
+    /// Address of the next data, aligned for 32 bit values
+    ///
+    fn allocate_data(&mut self) -> u32 {
+        while !self.data.len().is_multiple_of(4) {
+            self.data.push(0);
+        }
+        DATA_START + self.data.len() as u32
+    }
+
+    fn string(&mut self, value: &str) -> u32 {
+        if let Some(address) = self.strings.get(value) {
+            return *address;
+        }
+
+        let address = self.allocate_data();
+        self.data.extend((value.len() as u32).to_le_bytes());
+        self.data.extend(value.bytes());
+        self.strings.insert(String::from(value), address);
+        address
+    }
+
+    fn function(&mut self, function: &IrFunction) -> CodegenResult<String> {
+        let mut output = format!("  (func {}", function_id(&function.name));
+        if let Some(export) = export_name(self.module, function) {
+            write!(output, " (export \"{}\")", export).unwrap();
+        }
+        for parameter in &function.parameters {
+            check_type(&function.value_types[*parameter])?;
+            write!(output, " (param $v{} i32)", parameter).unwrap();
+        }
+        if function.return_type != Type::Void {
+            check_type(&function.return_type)?;
+            output += " (result i32)";
+        }
+        output.push('\n');
+
+        let mut phis = vec![];
+        for block in &function.blocks {
+            for instruction in &block.instructions {
+                if let Some(result) = instruction.result {
+                    check_type(&function.value_types[result])?;
+                    writeln!(output, "    (local $v{} i32)", result).unwrap();
+                    if matches!(instruction.kind, InstructionKind::Phi { .. }) {
+                        phis.push(result);
+                    }
+                }
+            }
+        }
+        for phi in phis {
+            writeln!(output, "    (local $p{} i32)", phi).unwrap();
+        }
+
+        self.body = String::new();
+        self.depth = 2;
+        let control_flow = ControlFlow::new(function);
+        self.tree(function, &control_flow, 0)?;
+
+        // Control never leaves the structured code, the validator can not
+        // see that after a loop or a conditional
+        let last = self.body.lines().last().unwrap_or_default().trim();
+        let terminates = last == "return" || last == "unreachable" || last.starts_with("br ");
+        if function.return_type != Type::Void && !terminates {
+            self.line("unreachable");
+        }
+
+        output += &self.body;
+        output.pop();
+        output += ")\n";
+        Ok(output)
+    }
+
+    fn line(&mut self, text: &str) {
+        writeln!(self.body, "{}{}", "  ".repeat(self.depth), text).unwrap();
+    }
+
+    ///
+    /// Code of the block and the blocks it dominates, loop headers wrap
+    /// it in a loop so that back edges can continue it
+    ///
+    fn tree(
+        &mut self,
+        function: &IrFunction,
+        control_flow: &ControlFlow,
+        block: BlockId,
+    ) -> CodegenResult<()> {
+        let merges = control_flow.merge_children(block);
+        match control_flow.loop_headers[block] {
+            true => {
+                self.line(&format!("loop $l{}", block));
+                self.depth += 1;
+                self.within(function, control_flow, block, &merges)?;
+                self.depth -= 1;
+                self.line("end");
+            }
+            false => self.within(function, control_flow, block, &merges)?,
+        }
+        Ok(())
+    }
+
+    ///
+    /// Merge blocks follow a wasm block the code before them breaks out of
+    ///
+    fn within(
+        &mut self,
+        function: &IrFunction,
+        control_flow: &ControlFlow,
+        block: BlockId,
+        merges: &[BlockId],
+    ) -> CodegenResult<()> {
+        if let Some((merge, rest)) = merges.split_first() {
+            self.line(&format!("block $b{}", merge));
+            self.depth += 1;
+            self.within(function, control_flow, block, rest)?;
+            self.depth -= 1;
+            self.line("end");
+            return self.tree(function, control_flow, *merge);
+        }
+
+        for instruction in &function.blocks[block].instructions {
+            match (&instruction.kind, instruction.result) {
+                // Phi values are copied in by the predecessors
+                (InstructionKind::Phi { .. }, Some(result)) => {
+                    self.line(&format!("local.get $p{}", result));
+                    self.line(&format!("local.set $v{}", result));
+                }
+                (kind, result) => self.instruction(function, kind, result)?,
+            }
+        }
+
+        match &function.blocks[block].terminator {
+            Some(Terminator::Jump(target)) => {
+                self.branch(function, control_flow, block, *target)?
+            }
+            Some(Terminator::Branch {
+                condition,
+                consequent,
+                alternative,
+            }) => {
+                self.line(&format!("local.get $v{}", condition));
+                self.line("if");
+                self.depth += 1;
+                self.branch(function, control_flow, block, *consequent)?;
+                self.depth -= 1;
+                self.line("else");
+                self.depth += 1;
+                self.branch(function, control_flow, block, *alternative)?;
+                self.depth -= 1;
+                self.line("end");
+            }
+            Some(Terminator::Return(value)) => {
+                match (value, &function.return_type) {
+                    (_, Type::Void) => {}
+                    (Some(value), _) => self.line(&format!("local.get $v{}", value)),
+                    (None, _) => self.line("i32.const 0"),
+                }
+                self.line("return");
+            }
+            None => self.line("unreachable"),
+        }
+        Ok(())
+    }
+
+    ///
+    /// Transfer control to the target block after copying the values of
+    /// its phi nodes
+    ///
+    fn branch(
+        &mut self,
+        function: &IrFunction,
+        control_flow: &ControlFlow,
+        from: BlockId,
+        target: BlockId,
+    ) -> CodegenResult<()> {
+        for instruction in &function.blocks[target].instructions {
+            let (InstructionKind::Phi { incoming }, Some(result)) =
+                (&instruction.kind, instruction.result)
+            else {
+                continue;
+            };
+            if let Some((_, value)) = incoming.iter().find(|(block, _)| *block == from) {
+                self.line(&format!("local.get $v{}", value));
+                self.line(&format!("local.set $p{}", result));
+            }
+        }
+
+        if control_flow.is_backward(from, target) {
+            self.line(&format!("br $l{}", target));
+        } else if control_flow.merge_nodes[target] {
+            self.line(&format!("br $b{}", target));
+        } else {
+            self.tree(function, control_flow, target)?;
+        }
+        Ok(())
+    }
+
+    fn instruction(
+        &mut self,
+        function: &IrFunction,
+        kind: &InstructionKind,
+        result: Option<ValueId>,
+    ) -> CodegenResult<()> {
+        let value_type = |value: ValueId| &function.value_types[value];
+        let mut returns_value = true;
+
+        match kind {
+            InstructionKind::Constant(constant) => {
+                let result_type = result.map(value_type).unwrap_or(&Type::Nil);
+                let value = match constant {
+                    Constant::Number(value) => *value,
+                    Constant::Boolean(value) => *value as i32,
+                    Constant::String(value) => self.string(value) as i32,
+                    Constant::Nil => match result_type {
+                        Type::Primitive(PrimitiveType::Number | PrimitiveType::Boolean) => {
+                            return Err(generation_error(format!(
+                                "Nil values of type {} can not be represented in WebAssembly",
+                                result_type
+                            )))
+                        }
+                        _ => 0,
+                    },
+                };
+                self.line(&format!("i32.const {}", value));
+            }
+            InstructionKind::Undefined => self.line("i32.const 0"),
+            InstructionKind::Unary { operator, operand } => {
+                self.line(&format!("local.get $v{}", operand));
+                match operator {
+                    UnaryOperator::Plus => {}
+                    UnaryOperator::Minus => self.line("call $sbz_neg"),
+                    UnaryOperator::Not => self.line("i32.eqz"),
+                }
+            }
+            InstructionKind::Binary {
+                operator,
+                left,
+                right,
+            } => {
+                let instructions = binary_instructions(
+                    *operator,
+                    (*left, value_type(*left)),
+                    (*right, value_type(*right)),
+                );
+                for instruction in instructions {
+                    self.line(&instruction);
+                }
+            }
+            InstructionKind::Phi { .. } => unreachable!("phi nodes are copied by the branches"),
+            InstructionKind::LoadGlobal { name } => self.line(&format!("global.get $g:{}", name)),
+            InstructionKind::StoreGlobal { name, value } => {
+                self.line(&format!("local.get $v{}", value));
+                self.line(&format!("global.set $g:{}", name));
+                returns_value = false;
+            }
+            InstructionKind::Function { name } => {
+                return Err(generation_error(format!(
+                    "Function '{}' can not be used as a value in WebAssembly",
+                    name
+                )))
+            }
+            InstructionKind::Call {
+                function: name,
+                arguments,
+            } if name == "print" && self.module.function(name).is_none() => {
+                self.uses_print = true;
+                for (index, argument) in arguments.iter().enumerate() {
+                    if index > 0 {
+                        self.line("call $sbz_print_separator");
+                    }
+                    self.line(&format!("local.get $v{}", argument));
+                    self.line(&format!("call {}", print_function(value_type(*argument))));
+                }
+                self.line("call $sbz_print_end");
+                returns_value = false;
+            }
+            InstructionKind::Call {
+                function: name,
+                arguments,
+            } => {
+                let callee = self
+                    .module
+                    .function(name)
+                    .ok_or_else(|| generation_error(format!("Undefined function '{}'", name)))?;
+                for argument in arguments {
+                    self.line(&format!("local.get $v{}", argument));
+                }
+                self.line(&format!("call {}", function_id(name)));
+                returns_value = callee.return_type != Type::Void;
+            }
+            InstructionKind::CallIndirect { .. } => {
+                return Err(generation_error(String::from(
+                    "Function values are not supported by the WebAssembly backend",
+                )))
+            }
+            InstructionKind::CallMethod {
+                object,
+                class_name,
+                method,
+                arguments,
+            } => {
+                let slot_index = self
+                    .method_tables
+                    .get(class_name)
+                    .and_then(|slots| slots.iter().position(|slot| &slot.method == method))
+                    .ok_or_else(|| {
+                        generation_error(format!(
+                            "Undefined method '{}' on class '{}'",
+                            method, class_name
+                        ))
+                    })?;
+                let slot = &self.method_tables[class_name][slot_index];
+                let mut signature = String::from("(param");
+                for _ in 0..=slot.parameter_types.len() {
+                    signature += " i32";
+                }
+                signature.push(')');
+                returns_value = slot.return_type != Type::Void;
+                if returns_value {
+                    signature += " (result i32)";
+                }
+
+                for argument in [vec![*object], arguments.clone()].concat() {
+                    self.line(&format!("local.get $v{}", argument));
+                }
+                self.line(&format!("local.get $v{}", object));
+                self.line("call $sbz_check");
+                self.line("i32.load");
+                self.line(&format!("i32.load offset={}", 8 + 4 * slot_index));
+                self.line(&format!("call_indirect {}", signature));
+            }
+            InstructionKind::New { class_name } => {
+                let Some(result) = result else {
+                    return Ok(());
+                };
+                let size = 4 + 4 * self.fields(class_name).len();
+                self.line(&format!("i32.const {}", size));
+                self.line("call $sbz_allocate");
+                self.line(&format!("local.tee $v{}", result));
+                self.line(&format!("i32.const {}", self.class_infos[class_name]));
+                self.line("i32.store");
+                return Ok(());
+            }
+            InstructionKind::NewArray { length } => {
+                self.line(&format!("local.get $v{}", length));
+                self.line("call $sbz_array_new");
+            }
+            InstructionKind::LoadField {
+                object,
+                class_name,
+                field,
+            } => {
+                let offset = self.field_offset(class_name, field)?;
+                self.line(&format!("local.get $v{}", object));
+                self.line("call $sbz_check");
+                self.line(&format!("i32.load offset={}", offset));
+            }
+            InstructionKind::StoreField {
+                object,
+                class_name,
+                field,
+                value,
+            } => {
+                let offset = self.field_offset(class_name, field)?;
+                self.line(&format!("local.get $v{}", object));
+                self.line("call $sbz_check");
+                self.line(&format!("local.get $v{}", value));
+                self.line(&format!("i32.store offset={}", offset));
+                returns_value = false;
+            }
+            InstructionKind::LoadIndex { object, index } => {
+                self.line(&format!("local.get $v{}", object));
+                self.line(&format!("local.get $v{}", index));
+                match value_type(*object) {
+                    Type::Primitive(PrimitiveType::String) => self.line("call $sbz_string_index"),
+                    _ => {
+                        self.line("call $sbz_array_slot");
+                        self.line("i32.load");
+                    }
+                }
+            }
+            InstructionKind::StoreIndex {
+                object,
+                index,
+                value,
+            } => {
+                self.line(&format!("local.get $v{}", object));
+                self.line(&format!("local.get $v{}", index));
+                self.line("call $sbz_array_slot");
+                self.line(&format!("local.get $v{}", value));
+                self.line("i32.store");
+                returns_value = false;
+            }
+            InstructionKind::Length { object } => {
+                self.line(&format!("local.get $v{}", object));
+                match value_type(*object) {
+                    Type::Primitive(PrimitiveType::String) => self.line("call $sbz_string_length"),
+                    _ => {
+                        self.line("call $sbz_check");
+                        self.line("i32.load");
+                    }
+                }
+            }
+        }
+
+        match (result, returns_value) {
+            (Some(result), true) => self.line(&format!("local.set $v{}", result)),
+            (None, true) => self.line("drop"),
+            (_, false) => {}
+        }
+        Ok(())
+    }
+
+    ///
+    /// Fields of the instances of the class, inherited fields first
+    ///
+    fn fields(&self, class_name: &str) -> Vec<&'a str> {
+        let Some(class) = self.module.class(class_name) else {
+            return vec![];
+        };
+        let mut fields = match &class.super_class {
+            Some(super_class) => self.fields(super_class),
+            None => vec![],
+        };
+        fields.extend(class.fields.iter().map(|(name, _)| name.as_str()));
+        fields
+    }
+
+    ///
+    /// Fields follow the address of the class info, the class is the one
+    /// declaring the field
+    ///
+    fn field_offset(&self, class_name: &str, field: &str) -> CodegenResult<usize> {
+        self.fields(class_name)
+            .iter()
+            .rposition(|name| *name == field)
+            .map(|index| 4 + 4 * index)
+            .ok_or_else(|| {
+                generation_error(format!(
+                    "Property '{}' does not exist on type '{}'",
+                    field, class_name
+                ))
+            })
+    }
+}
+
+///
+/// Top level functions are exported by their name
+///
+fn export_name<'a>(module: &IrModule, function: &'a IrFunction) -> Option<&'a str> {
+    if function.name == PROGRAM_FUNCTION_NAME {
+        return Some("_start");
+    }
+    let is_top_level = !function.name.contains(['.', '#']);
+    (is_top_level && method_name(module, function).is_none()).then_some(&function.name)
+}
+
+fn binary_instructions(
+    operator: BinaryOperator,
+    (left, left_type): (ValueId, &Type),
+    (right, right_type): (ValueId, &Type),
+) -> Vec<String> {
+    let is_string = matches!(left_type, Type::Primitive(PrimitiveType::String));
+    let operands = vec![
+        format!("local.get $v{}", left),
+        format!("local.get $v{}", right),
+    ];
+    let with = |instructions: &[&str]| {
+        let mut all = operands.clone();
+        all.extend(
+            instructions
+                .iter()
+                .map(|instruction| String::from(*instruction)),
+        );
+        all
+    };
+
+    match operator {
+        BinaryOperator::Add if is_string => with(&["call $sbz_string_concat"]),
+        BinaryOperator::Add => with(&["call $sbz_add"]),
+        BinaryOperator::Subtract => with(&["call $sbz_sub"]),
+        BinaryOperator::Multiply => with(&["call $sbz_mul"]),
+        BinaryOperator::Divide => with(&["i32.div_s"]),
+        BinaryOperator::Equal | BinaryOperator::NotEqual => {
+            let mut instructions = equality((left, left_type), (right, right_type));
+            if operator == BinaryOperator::NotEqual {
+                instructions.push(String::from("i32.eqz"));
+            }
+            instructions
+        }
+        BinaryOperator::GreaterThan
+        | BinaryOperator::GreaterThanOrEqualTo
+        | BinaryOperator::LessThan
+        | BinaryOperator::LessThanOrEqualTo => {
+            let comparison = match operator {
+                BinaryOperator::GreaterThan => "i32.gt_s",
+                BinaryOperator::GreaterThanOrEqualTo => "i32.ge_s",
+                BinaryOperator::LessThan => "i32.lt_s",
+                _ => "i32.le_s",
+            };
+            match is_string {
+                true => with(&["call $sbz_string_compare", "i32.const 0", comparison]),
+                false => with(&[comparison]),
+            }
+        }
+    }
+}
+
+///
+/// Primitives compare by value, everything else by identity
+///
+fn equality(
+    (left, left_type): (ValueId, &Type),
+    (right, right_type): (ValueId, &Type),
+) -> Vec<String> {
+    let is_scalar = |value_type: &Type| {
+        matches!(
+            value_type,
+            Type::Primitive(PrimitiveType::Number | PrimitiveType::Boolean)
+        )
+    };
+    let string = Type::string();
+
+    let comparison = match (left_type, right_type) {
+        _ if is_scalar(left_type) || is_scalar(right_type) => match left_type == right_type {
+            true => "i32.eq",
+            false => return vec![String::from("i32.const 0")],
+        },
+        (left_type, right_type) if left_type == &string && right_type == &string => {
+            "call $sbz_string_equals"
+        }
+        _ => "i32.eq",
+    };
+    vec![
+        format!("local.get $v{}", left),
+        format!("local.get $v{}", right),
+        String::from(comparison),
+    ]
+}
+
+fn print_function(value_type: &Type) -> &'static str {
+    match value_type {
+        Type::Primitive(PrimitiveType::Number) => "$sbz_print_number",
+        Type::Primitive(PrimitiveType::Boolean) => "$sbz_print_boolean",
+        Type::Primitive(PrimitiveType::String) => "$sbz_print_string",
+        Type::Class { .. } => "$sbz_print_object",
+        Type::Array(_) => "$sbz_print_array",
+        _ => "$sbz_print_nil",
+    }
+}
+
+///
+/// Every value that can be represented is an i32
+///
+fn check_type(value_type: &Type) -> CodegenResult<()> {
+    match value_type {
+        Type::Function { .. } | Type::Generic { .. } | Type::Parameter(_) | Type::Void => {
+            Err(generation_error(format!(
+                "Values of type {} are not supported by the WebAssembly backend",
+                value_type
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+///
+/// Function names only contain characters allowed in identifiers
+///
+fn function_id(name: &str) -> String {
+    format!("$fn:{}", name)
+}
+
+///
+/// Bytes outside of printable ASCII are written as hexadecimal escapes
+///
+fn data_literal(bytes: &[u8]) -> String {
+    let mut literal = String::from("\"");
+    for byte in bytes {
+        match byte {
+            b'"' | b'\\' => {
+                literal.push('\\');
+                literal.push(*byte as char);
+            }
+            0x20..=0x7e => literal.push(*byte as char),
+            byte => write!(literal, "\\{:02x}", byte).unwrap(),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+fn generation_error(message: String) -> CodegenError {
+    CodegenError::GenerationError { message }
+}
@@ -0,0 +1,131 @@
+  ;; Output of the print builtin, written through the imported env.write
+
+  (func $sbz_print_text (param $string i32)
+    local.get $string
+    i32.const 4
+    i32.add
+    local.get $string
+    i32.load
+    call $sbz_write)
+
+  (func $sbz_print_separator
+    global.get $sbz_text_space
+    call $sbz_print_text)
+
+  (func $sbz_print_end
+    global.get $sbz_text_newline
+    call $sbz_print_text)
+
+  ;; Digits are written backwards into the scratch bytes before the data
+  (func $sbz_print_number (param $value i32)
+    (local $number i64)
+    (local $position i32)
+    local.get $value
+    i64.extend_i32_s
+    local.set $number
+    local.get $number
+    i64.const 0
+    i64.lt_s
+    if
+      i64.const 0
+      local.get $number
+      i64.sub
+      local.set $number
+    end
+    global.get $sbz_scratch_end
+    local.set $position
+    loop $digits
+      local.get $position
+      i32.const 1
+      i32.sub
+      local.tee $position
+      local.get $number
+      i64.const 10
+      i64.rem_u
+      i32.wrap_i64
+      i32.const 48
+      i32.add
+      i32.store8
+      local.get $number
+      i64.const 10
+      i64.div_u
+      local.tee $number
+      i64.const 0
+      i64.ne
+      br_if $digits
+    end
+    local.get $value
+    i32.const 0
+    i32.lt_s
+    if
+      local.get $position
+      i32.const 1
+      i32.sub
+      local.tee $position
+      i32.const 45
+      i32.store8
+    end
+    local.get $position
+    global.get $sbz_scratch_end
+    local.get $position
+    i32.sub
+    call $sbz_write)
+
+  (func $sbz_print_boolean (param $value i32)
+    global.get $sbz_text_true
+    global.get $sbz_text_false
+    local.get $value
+    select
+    call $sbz_print_text)
+
+  (func $sbz_print_nil (param $value i32)
+    global.get $sbz_text_nil
+    call $sbz_print_text)
+
+  (func $sbz_print_string (param $value i32)
+    global.get $sbz_text_nil
+    local.get $value
+    local.get $value
+    i32.eqz
+    select
+    call $sbz_print_text)
+
+  (func $sbz_print_object (param $value i32)
+    (local $variants i32)
+    local.get $value
+    i32.eqz
+    if
+      global.get $sbz_text_nil
+      call $sbz_print_text
+      return
+    end
+    local.get $value
+    i32.load
+    i32.load offset=4
+    local.tee $variants
+    if
+      local.get $variants
+      local.get $value
+      i32.load offset=4
+      i32.const 4
+      i32.mul
+      i32.add
+      i32.load
+      call $sbz_print_text
+      return
+    end
+    global.get $sbz_text_open
+    call $sbz_print_text
+    local.get $value
+    i32.load
+    i32.load
+    call $sbz_print_text
+    global.get $sbz_text_instance
+    call $sbz_print_text)
+
+  (func $sbz_print_array (param $value i32)
+    global.get $sbz_text_array
+    global.get $sbz_text_nil
+    local.get $value
+    select
+    call $sbz_print_text)
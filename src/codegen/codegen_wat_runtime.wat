@@ -0,0 +1,419 @@
+  ;; Runtime of the senbonzakura WebAssembly backend
+  ;;
+  ;; Strings are a 32 bit byte length followed by their UTF-8 bytes, arrays
+  ;; a 32 bit length followed by 32 bit items. Objects start with the
+  ;; address of their class info, a 32 bit address of the class name, the
+  ;; address of the variant names of enums or 0 for classes and the table
+  ;; indices of the methods. Enum values hold their tag after the class
+  ;; info. Nil is the address 0, runtime errors trap
+
+  (func $sbz_allocate (param $size i32) (result i32)
+    (local $address i32)
+    (local $end i32)
+    global.get $sbz_heap
+    local.set $address
+    local.get $address
+    local.get $size
+    i32.const 3
+    i32.add
+    i32.const -4
+    i32.and
+    i32.add
+    local.set $end
+    block $done
+      loop $grow
+        local.get $end
+        memory.size
+        i32.const 16
+        i32.shl
+        i32.le_u
+        br_if $done
+        i32.const 1
+        memory.grow
+        i32.const -1
+        i32.eq
+        if
+          unreachable
+        end
+        br $grow
+      end
+    end
+    ;; Memory is never reused so it is still zeroed
+    local.get $end
+    global.set $sbz_heap
+    local.get $address)
+
+  (func $sbz_check (param $address i32) (result i32)
+    local.get $address
+    i32.eqz
+    if
+      unreachable
+    end
+    local.get $address)
+
+  ;; Checked arithmetic
+
+  (func $sbz_checked (param $value i64) (result i32)
+    local.get $value
+    local.get $value
+    i32.wrap_i64
+    i64.extend_i32_s
+    i64.ne
+    if
+      unreachable
+    end
+    local.get $value
+    i32.wrap_i64)
+
+  (func $sbz_add (param $left i32) (param $right i32) (result i32)
+    local.get $left
+    i64.extend_i32_s
+    local.get $right
+    i64.extend_i32_s
+    i64.add
+    call $sbz_checked)
+
+  (func $sbz_sub (param $left i32) (param $right i32) (result i32)
+    local.get $left
+    i64.extend_i32_s
+    local.get $right
+    i64.extend_i32_s
+    i64.sub
+    call $sbz_checked)
+
+  (func $sbz_mul (param $left i32) (param $right i32) (result i32)
+    local.get $left
+    i64.extend_i32_s
+    local.get $right
+    i64.extend_i32_s
+    i64.mul
+    call $sbz_checked)
+
+  (func $sbz_neg (param $value i32) (result i32)
+    i64.const 0
+    local.get $value
+    i64.extend_i32_s
+    i64.sub
+    call $sbz_checked)
+
+  ;; Strings
+
+  (func $sbz_allocate_string (param $length i32) (result i32)
+    (local $string i32)
+    local.get $length
+    i32.const 4
+    i32.add
+    call $sbz_allocate
+    local.set $string
+    local.get $string
+    local.get $length
+    i32.store
+    local.get $string)
+
+  (func $sbz_string_new (param $data i32) (param $length i32) (result i32)
+    (local $string i32)
+    local.get $length
+    call $sbz_allocate_string
+    local.set $string
+    local.get $string
+    i32.const 4
+    i32.add
+    local.get $data
+    local.get $length
+    memory.copy
+    local.get $string)
+
+  (func $sbz_string_concat (param $left i32) (param $right i32) (result i32)
+    (local $string i32)
+    local.get $left
+    call $sbz_check
+    i32.load
+    i64.extend_i32_u
+    local.get $right
+    call $sbz_check
+    i32.load
+    i64.extend_i32_u
+    i64.add
+    call $sbz_checked
+    call $sbz_allocate_string
+    local.set $string
+    local.get $string
+    i32.const 4
+    i32.add
+    local.get $left
+    i32.const 4
+    i32.add
+    local.get $left
+    i32.load
+    memory.copy
+    local.get $string
+    i32.const 4
+    i32.add
+    local.get $left
+    i32.load
+    i32.add
+    local.get $right
+    i32.const 4
+    i32.add
+    local.get $right
+    i32.load
+    memory.copy
+    local.get $string)
+
+  (func $sbz_string_equals (param $left i32) (param $right i32) (result i32)
+    (local $offset i32)
+    local.get $left
+    local.get $right
+    i32.eq
+    if
+      i32.const 1
+      return
+    end
+    local.get $left
+    i32.eqz
+    local.get $right
+    i32.eqz
+    i32.or
+    if
+      i32.const 0
+      return
+    end
+    local.get $left
+    i32.load
+    local.get $right
+    i32.load
+    i32.ne
+    if
+      i32.const 0
+      return
+    end
+    block $done
+      loop $bytes
+        local.get $offset
+        local.get $left
+        i32.load
+        i32.ge_u
+        br_if $done
+        local.get $left
+        local.get $offset
+        i32.add
+        i32.load8_u offset=4
+        local.get $right
+        local.get $offset
+        i32.add
+        i32.load8_u offset=4
+        i32.ne
+        if
+          i32.const 0
+          return
+        end
+        local.get $offset
+        i32.const 1
+        i32.add
+        local.set $offset
+        br $bytes
+      end
+    end
+    i32.const 1)
+
+  ;; Byte order of UTF-8 matches the order of the characters
+  (func $sbz_string_compare (param $left i32) (param $right i32) (result i32)
+    (local $offset i32)
+    (local $length i32)
+    (local $difference i32)
+    local.get $left
+    call $sbz_check
+    i32.load
+    local.get $right
+    call $sbz_check
+    i32.load
+    local.get $left
+    i32.load
+    local.get $right
+    i32.load
+    i32.lt_u
+    select
+    local.set $length
+    block $done
+      loop $bytes
+        local.get $offset
+        local.get $length
+        i32.ge_u
+        br_if $done
+        local.get $left
+        local.get $offset
+        i32.add
+        i32.load8_u offset=4
+        local.get $right
+        local.get $offset
+        i32.add
+        i32.load8_u offset=4
+        i32.sub
+        local.tee $difference
+        if
+          local.get $difference
+          return
+        end
+        local.get $offset
+        i32.const 1
+        i32.add
+        local.set $offset
+        br $bytes
+      end
+    end
+    local.get $left
+    i32.load
+    local.get $right
+    i32.load
+    i32.gt_u
+    local.get $left
+    i32.load
+    local.get $right
+    i32.load
+    i32.lt_u
+    i32.sub)
+
+  (func $sbz_is_continuation (param $address i32) (result i32)
+    local.get $address
+    i32.load8_u
+    i32.const 192
+    i32.and
+    i32.const 128
+    i32.eq)
+
+  ;; Strings are measured and indexed in characters
+  (func $sbz_string_length (param $string i32) (result i32)
+    (local $offset i32)
+    (local $count i32)
+    local.get $string
+    call $sbz_check
+    drop
+    block $done
+      loop $bytes
+        local.get $offset
+        local.get $string
+        i32.load
+        i32.ge_u
+        br_if $done
+        local.get $string
+        local.get $offset
+        i32.add
+        i32.const 4
+        i32.add
+        call $sbz_is_continuation
+        i32.eqz
+        local.get $count
+        i32.add
+        local.set $count
+        local.get $offset
+        i32.const 1
+        i32.add
+        local.set $offset
+        br $bytes
+      end
+    end
+    local.get $count)
+
+  (func $sbz_string_index (param $string i32) (param $index i32) (result i32)
+    (local $offset i32)
+    (local $end i32)
+    (local $current i32)
+    local.get $string
+    call $sbz_check
+    drop
+    local.get $index
+    i32.const 0
+    i32.lt_s
+    if
+      unreachable
+    end
+    loop $characters
+      local.get $offset
+      local.get $string
+      i32.load
+      i32.ge_u
+      if
+        unreachable
+      end
+      local.get $offset
+      i32.const 1
+      i32.add
+      local.set $end
+      block $found
+        loop $bytes
+          local.get $end
+          local.get $string
+          i32.load
+          i32.ge_u
+          br_if $found
+          local.get $string
+          local.get $end
+          i32.add
+          i32.const 4
+          i32.add
+          call $sbz_is_continuation
+          i32.eqz
+          br_if $found
+          local.get $end
+          i32.const 1
+          i32.add
+          local.set $end
+          br $bytes
+        end
+      end
+      local.get $current
+      local.get $index
+      i32.eq
+      if
+        local.get $string
+        local.get $offset
+        i32.add
+        i32.const 4
+        i32.add
+        local.get $end
+        local.get $offset
+        i32.sub
+        call $sbz_string_new
+        return
+      end
+      local.get $current
+      i32.const 1
+      i32.add
+      local.set $current
+      local.get $end
+      local.set $offset
+      br $characters
+    end
+    unreachable)
+
+  ;; Arrays
+
+  (func $sbz_array_new (param $length i32) (result i32)
+    (local $array i32)
+    local.get $length
+    i32.const 4
+    i32.mul
+    i32.const 4
+    i32.add
+    call $sbz_allocate
+    local.tee $array
+    local.get $length
+    i32.store
+    local.get $array)
+
+  (func $sbz_array_slot (param $array i32) (param $index i32) (result i32)
+    local.get $index
+    local.get $array
+    call $sbz_check
+    i32.load
+    i32.ge_u
+    if
+      unreachable
+    end
+    local.get $array
+    local.get $index
+    i32.const 4
+    i32.mul
+    i32.add
+    i32.const 4
+    i32.add)
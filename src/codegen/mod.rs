@@ -0,0 +1,12 @@
+mod codegen_c;
+mod codegen_classes;
+mod codegen_error;
+mod codegen_js;
+mod codegen_wat;
+mod tests;
+
+pub(crate) use self::codegen_c::*;
+use self::codegen_classes::*;
+pub(crate) use self::codegen_error::*;
+pub(crate) use self::codegen_js::*;
+pub(crate) use self::codegen_wat::*;
@@ -0,0 +1,215 @@
+use crate::driver::{check_source, generate_c_source, generate_js_source, generate_wat_source};
+use crate::interpreter::{with_interpreter_stack, Interpreter};
+use serde::Deserialize;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static PROGRAM_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(super) enum TestTarget {
+    #[default]
+    C,
+    Js,
+    Wat,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct YamlTestCase {
+    description: String,
+    source: String,
+    #[serde(default)]
+    target: TestTarget,
+    #[serde(default)]
+    jsdoc: bool,
+    #[serde(default)]
+    expected_output: Option<String>,
+    #[serde(default)]
+    expected_error: Option<String>,
+    #[serde(default)]
+    expected_imports: Option<Vec<String>>,
+    #[serde(default)]
+    expected_exports: Option<Vec<String>>,
+}
+
+pub(super) fn execute_yaml_test(test_case: &YamlTestCase) {
+    let output = match test_case.target {
+        TestTarget::C => generate_c_source(&test_case.source),
+        TestTarget::Js => generate_js_source(&test_case.source, test_case.jsdoc),
+        TestTarget::Wat => generate_wat_source(&test_case.source),
+    }
+    .map(|(output, _)| output);
+
+    if let Some(expected_error) = &test_case.expected_error {
+        let actual_error = output.err().map(|diagnostic| diagnostic.message);
+        assert_eq!(actual_error.as_ref(), Some(expected_error));
+        return;
+    }
+
+    let Ok(output) = output else {
+        panic!(
+            "Failed to generate code for the source:\n{}\n",
+            test_case.source
+        )
+    };
+
+    // WebAssembly modules are checked by their structure
+    if let TestTarget::Wat = test_case.target {
+        let (imports, exports) = validate_wat(&output);
+        if let Some(expected_imports) = &test_case.expected_imports {
+            assert_eq!(imports, *expected_imports);
+        }
+        if let Some(expected_exports) = &test_case.expected_exports {
+            assert_eq!(exports, *expected_exports);
+        }
+        return;
+    }
+
+    // Emitted text is compared with the golden output when there is one
+    if let Some(expected_output) = &test_case.expected_output {
+        assert_eq!(output, *expected_output);
+        return;
+    }
+
+    let (stdout, stderr) = compile_and_run(&output);
+
+    // The compiled program prints what the interpreter prints
+    let Ok((program, _)) = check_source(&test_case.source) else {
+        panic!(
+            "Failed to check the test case source:\n{}\n",
+            test_case.source
+        )
+    };
+    let (expected_stdout, expected_stderr) = with_interpreter_stack(|| {
+        let mut expected_stdout: Vec<u8> = vec![];
+        let expected_stderr = Interpreter::new(&mut expected_stdout)
+            .execute(&program)
+            .err()
+            .map(|error| format!("error: {}\n", error))
+            .unwrap_or_default();
+        (expected_stdout, expected_stderr)
+    });
+
+    assert_eq!(stdout, String::from_utf8(expected_stdout).unwrap());
+    assert_eq!(stderr, expected_stderr);
+}
+
+///
+/// Assemble and validate the module, then list its imports as
+/// `module.name` and its exports
+///
+fn validate_wat(output: &str) -> (Vec<String>, Vec<String>) {
+    let bytes = match wat::parse_str(output) {
+        Ok(bytes) => bytes,
+        Err(error) => panic!(
+            "Failed to assemble the generated module:\n{}\n{}",
+            error, output
+        ),
+    };
+    if let Err(error) = wasmparser::Validator::new().validate_all(&bytes) {
+        panic!("Generated module is not valid:\n{}\n{}", error, output);
+    }
+
+    let mut imports = vec![];
+    let mut exports = vec![];
+    for payload in wasmparser::Parser::new(0).parse_all(&bytes) {
+        match payload.unwrap() {
+            wasmparser::Payload::ImportSection(reader) => {
+                for import in reader.into_imports() {
+                    let import = import.unwrap();
+                    imports.push(format!("{}.{}", import.module, import.name));
+                }
+            }
+            wasmparser::Payload::ExportSection(reader) => {
+                for export in reader {
+                    exports.push(String::from(export.unwrap().name));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    (imports, exports)
+}
+
+fn compile_and_run(output: &str) -> (String, String) {
+    let count = PROGRAM_COUNT.fetch_add(1, Ordering::SeqCst);
+    let base: PathBuf = std::env::temp_dir().join(format!(
+        "senbonzakura-codegen-{}-{}",
+        std::process::id(),
+        count
+    ));
+    let source_path = base.with_extension("c");
+    fs::write(&source_path, output).expect("Could not write the C source");
+
+    let compilation = Command::new("cc")
+        .args(["-std=c99", "-pedantic-errors", "-o"])
+        .arg(&base)
+        .arg(&source_path)
+        .output()
+        .expect("Could not run the C compiler");
+    assert!(
+        compilation.status.success(),
+        "Failed to compile the generated C:\n{}\n{}",
+        String::from_utf8_lossy(&compilation.stderr),
+        output
+    );
+
+    let execution = Command::new(&base)
+        .output()
+        .expect("Could not run the compiled program");
+    let _ = fs::remove_file(&source_path);
+    let _ = fs::remove_file(&base);
+
+    (
+        String::from_utf8(execution.stdout).unwrap(),
+        String::from_utf8(execution.stderr).unwrap(),
+    )
+}
+
+pub(super) fn load_yaml_test_cases(path: &str) -> Vec<YamlTestCase> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(error) => {
+            eprintln!("Failed to read the test yaml file: {}", error);
+            eprintln!("Path: {}", path);
+            panic!("Could not read test file: {}", error);
+        }
+    };
+
+    // Parse multiple documents
+    let mut test_cases = Vec::new();
+
+    for document in serde_yaml::Deserializer::from_str(&content) {
+        match YamlTestCase::deserialize(document) {
+            Ok(test_case) => test_cases.push(test_case),
+            Err(e) => {
+                eprintln!("Failed to parse a YAML document: {}", e);
+                eprintln!("Path: {}", path);
+                panic!("Could not parse YAML content: {}", e);
+            }
+        }
+    }
+
+    // Verify that we found some test cases
+    if test_cases.is_empty() {
+        eprintln!("No test cases found in file: {}", path);
+        panic!("File contained no valid test cases");
+    }
+
+    test_cases
+}
+
+pub(super) fn execute_yaml_test_cases(path: &str) {
+    let test_cases = load_yaml_test_cases(path);
+
+    for (index, test_case) in test_cases.iter().enumerate() {
+        println!("Testing case #{}:\n{}\n", index + 1, test_case.description);
+        execute_yaml_test(test_case);
+    }
+
+    println!("All {} test cases passed!", test_cases.len());
+}
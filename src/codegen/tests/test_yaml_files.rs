@@ -0,0 +1,21 @@
+use super::internal_util::execute_yaml_test_cases;
+
+#[test]
+fn test_codegen_c_programs() {
+    execute_yaml_test_cases("tests/codegen/test_codegen_c_programs.yaml");
+}
+
+#[test]
+fn test_codegen_c_classes() {
+    execute_yaml_test_cases("tests/codegen/test_codegen_c_classes.yaml");
+}
+
+#[test]
+fn test_codegen_js() {
+    execute_yaml_test_cases("tests/codegen/test_codegen_js.yaml");
+}
+
+#[test]
+fn test_codegen_wat() {
+    execute_yaml_test_cases("tests/codegen/test_codegen_wat.yaml");
+}
@@ -0,0 +1,183 @@
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::lexer::{Span, TokenType, TriviaKind};
+
+///
+/// Syntax node kinds, one per AST node
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub enum CstNodeKind {
+    // Statements
+    Program,
+    Block,
+    Empty,
+    ExpressionStatement,
+    VariableDeclaration,
+    If,
+    While,
+    DoWhile,
+    For,
+    FunctionDeclaration,
+    Return,
+    Break,
+    Continue,
+    Labeled,
+    ClassDeclaration,
+    InterfaceDeclaration,
+    EnumDeclaration,
+
+    // Class and interface members, enum variants
+    Field,
+    MethodSignature,
+    Variant,
+
+    // Expressions
+    Variable,
+    Assignment,
+    Binary,
+    Unary,
+    Logical,
+    BooleanLiteral,
+    NilLiteral,
+    StringLiteral,
+    NumericLiteral,
+    Identifier,
+    ArrayLiteral,
+    Member,
+    Call,
+    This,
+    Super,
+    New,
+    TypeTest,
+    EnumVariant,
+    Match,
+    Destructuring,
+
+    // Match arms and their patterns
+    MatchArm,
+    WildcardPattern,
+    BindingPattern,
+    LiteralPattern,
+    VariantPattern,
+    ArrayPattern,
+    ObjectPattern,
+    PatternProperty,
+
+    // Tokens of a statement that does not parse
+    Error,
+}
+
+impl fmt::Display for CstNodeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+///
+/// Whitespace or a comment together with its text
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CstTrivia {
+    pub kind: TriviaKind,
+    pub text: String,
+}
+
+///
+/// Token together with its text and the trivia in front of it
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CstToken {
+    pub token_type: TokenType,
+    pub text: String,
+    pub span: Span,
+    pub leading_trivia: Vec<CstTrivia>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CstElement {
+    Node(CstNode),
+    Token(CstToken),
+}
+
+///
+/// Lossless syntax tree node
+///
+/// Every byte of the source belongs to exactly one token or trivia of the
+/// tree, so printing the tree gives back the source it was built from
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CstNode {
+    pub kind: CstNodeKind,
+    pub span: Span,
+    pub children: Vec<CstElement>,
+}
+
+#[allow(dead_code)]
+impl CstNode {
+    ///
+    /// Tokens of the node and its descendants in source order
+    ///
+    pub fn tokens(&self) -> Vec<&CstToken> {
+        let mut tokens = vec![];
+        self.collect_tokens(&mut tokens);
+        tokens
+    }
+
+    fn collect_tokens<'a>(&'a self, tokens: &mut Vec<&'a CstToken>) {
+        for child in &self.children {
+            match child {
+                CstElement::Node(node) => node.collect_tokens(tokens),
+                CstElement::Token(token) => tokens.push(token),
+            }
+        }
+    }
+
+    ///
+    /// Indented outline of the tree, one node, token or trivia per line
+    ///
+    pub fn dump(&self) -> String {
+        let mut output = String::new();
+        self.dump_into(&mut output, 0);
+        output
+    }
+
+    fn dump_into(&self, output: &mut String, depth: usize) {
+        let indent = "  ".repeat(depth);
+        output.push_str(&format!("{}{}\n", indent, self.kind));
+
+        let indent = "  ".repeat(depth + 1);
+        for child in &self.children {
+            match child {
+                CstElement::Node(node) => node.dump_into(output, depth + 1),
+                CstElement::Token(token) => {
+                    for trivia in &token.leading_trivia {
+                        output
+                            .push_str(&format!("{}{:?} {:?}\n", indent, trivia.kind, trivia.text));
+                    }
+                    output.push_str(&format!(
+                        "{}{} {:?}\n",
+                        indent, token.token_type, token.text
+                    ));
+                }
+            }
+        }
+    }
+}
+
+///
+/// Prints the source text back, trivia included
+///
+impl fmt::Display for CstNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for token in self.tokens() {
+            for trivia in &token.leading_trivia {
+                write!(f, "{}", trivia.text)?;
+            }
+            write!(f, "{}", token.text)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,513 @@
+use std::iter::Peekable;
+
+use crate::{
+    ast::{
+        ClassMember, EnumVariant, Expression, InterfaceMember, MatchArm, Pattern, PatternProperty,
+        Statement,
+    },
+    lexer::{Lexer, LexerResult, Span, TokenType},
+    parser::{parse_root_leading_statement, Parser},
+};
+
+use super::{CstElement, CstNode, CstNodeKind, CstToken, CstTrivia};
+
+///
+/// Build the lossless syntax tree of the source from its AST
+///
+/// Tokens are assigned to the innermost AST node whose span contains them,
+/// the trivia in front of a token belongs to the token. The tree follows a
+/// parsed program, see build_partial_syntax_tree for source with syntax errors
+///
+pub fn build_concrete_syntax_tree(source: &str, program: &Statement) -> LexerResult<CstNode> {
+    let mut tokens = tokenize_with_trivia(source)?.into_iter().peekable();
+    let root = AstNode::Statement(program);
+
+    let mut node = build_node(&root, &mut tokens);
+    // Tokens past the end of the program, at least the End token with the trailing trivia
+    node.children.extend(tokens.map(CstElement::Token));
+
+    Ok(node)
+}
+
+///
+/// Build the lossless syntax tree of source that does not parse
+///
+/// Top level statements are parsed one at a time, the tokens of a statement
+/// that does not parse become an Error node up to the next statement end or
+/// closing brace outside of brackets, and parsing resumes after them
+///
+pub fn build_partial_syntax_tree(source: &str) -> LexerResult<CstNode> {
+    let mut tokens = tokenize_with_trivia(source)?.into_iter().peekable();
+    let mut statements = vec![];
+
+    while let Some(start) = tokens
+        .peek()
+        .filter(|token| token.token_type != TokenType::End)
+        .map(|token| token.span.start)
+    {
+        let statement = Parser::new_at(source, start)
+            .and_then(|mut parser| parse_root_leading_statement(&mut parser));
+        statements.push(match statement {
+            Ok(statement) => build_node(&AstNode::Statement(&statement), &mut tokens),
+            Err(_) => build_error_node(&mut tokens),
+        });
+    }
+
+    let span = match (statements.first(), statements.last()) {
+        (Some(first), Some(last)) => Span::new(first.span.start, last.span.end),
+        _ => Span::default(),
+    };
+    let mut children: Vec<CstElement> = statements.into_iter().map(CstElement::Node).collect();
+    children.extend(tokens.map(CstElement::Token));
+
+    Ok(CstNode {
+        kind: CstNodeKind::Program,
+        span,
+        children,
+    })
+}
+
+///
+/// Error node holding the tokens up to and including the next statement end
+/// or closing brace outside of brackets, at least one token is taken
+///
+fn build_error_node<I>(tokens: &mut Peekable<I>) -> CstNode
+where
+    I: Iterator<Item = CstToken>,
+{
+    let mut children = vec![];
+    let mut depth = 0usize;
+
+    while let Some(token) = tokens.next_if(|token| token.token_type != TokenType::End) {
+        let token_type = token.token_type;
+        children.push(CstElement::Token(token));
+
+        match token_type {
+            TokenType::OpeningBrace | TokenType::OpeningParenthesis | TokenType::OpeningBracket => {
+                depth += 1
+            }
+            TokenType::ClosingParenthesis | TokenType::ClosingBracket => {
+                depth = depth.saturating_sub(1)
+            }
+            TokenType::ClosingBrace => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    break;
+                }
+            }
+            TokenType::StatementEnd if depth == 0 => break,
+            _ => {}
+        }
+    }
+
+    let span = match (children.first(), children.last()) {
+        (Some(CstElement::Token(first)), Some(CstElement::Token(last))) => {
+            Span::new(first.span.start, last.span.end)
+        }
+        _ => Span::default(),
+    };
+
+    CstNode {
+        kind: CstNodeKind::Error,
+        span,
+        children,
+    }
+}
+
+fn tokenize_with_trivia(source: &str) -> LexerResult<Vec<CstToken>> {
+    let mut lexer = Lexer::new(source);
+    let mut tokens = vec![];
+
+    loop {
+        let (trivia, token) = lexer.next_token_with_trivia()?;
+        tokens.push(CstToken {
+            token_type: token.token_type,
+            text: String::from(token.text(source)),
+            span: token.span(),
+            leading_trivia: trivia
+                .iter()
+                .map(|trivia| CstTrivia {
+                    kind: trivia.kind,
+                    text: String::from(trivia.text(source)),
+                })
+                .collect(),
+        });
+
+        if token.token_type == TokenType::End {
+            return Ok(tokens);
+        }
+    }
+}
+
+fn build_node<I>(ast_node: &AstNode, tokens: &mut Peekable<I>) -> CstNode
+where
+    I: Iterator<Item = CstToken>,
+{
+    let span = ast_node.span();
+    let mut children = vec![];
+
+    let mut ast_children = ast_node.children();
+    ast_children.sort_by_key(|child| child.span().start.offset);
+
+    for ast_child in &ast_children {
+        let child_start = ast_child.span().start.offset;
+        take_tokens_before(child_start, tokens, &mut children);
+        children.push(CstElement::Node(build_node(ast_child, tokens)));
+    }
+    take_tokens_before(span.end.offset, tokens, &mut children);
+
+    CstNode {
+        kind: ast_node.kind(),
+        span,
+        children,
+    }
+}
+
+fn take_tokens_before<I>(offset: usize, tokens: &mut Peekable<I>, children: &mut Vec<CstElement>)
+where
+    I: Iterator<Item = CstToken>,
+{
+    while let Some(token) = tokens
+        .next_if(|token| token.token_type != TokenType::End && token.span.start.offset < offset)
+    {
+        children.push(CstElement::Token(token));
+    }
+}
+
+///
+/// AST node of either kind
+///
+enum AstNode<'a> {
+    Statement(&'a Statement),
+    Expression(&'a Expression),
+    ClassMember(&'a ClassMember),
+    InterfaceMember(&'a InterfaceMember),
+    EnumVariant(&'a EnumVariant),
+    MatchArm(&'a MatchArm),
+    Pattern(&'a Pattern),
+    PatternProperty(&'a PatternProperty),
+}
+
+impl AstNode<'_> {
+    fn span(&self) -> Span {
+        match self {
+            AstNode::Statement(statement) => statement.span(),
+            AstNode::Expression(expression) => expression.span(),
+            AstNode::ClassMember(member) => member.span(),
+            AstNode::InterfaceMember(member) => member.span(),
+            AstNode::EnumVariant(variant) => variant.span,
+            AstNode::MatchArm(arm) => arm.span,
+            AstNode::Pattern(pattern) => pattern.span(),
+            AstNode::PatternProperty(property) => property.span,
+        }
+    }
+
+    fn kind(&self) -> CstNodeKind {
+        match self {
+            AstNode::Statement(statement) => match statement {
+                Statement::Program { .. } => CstNodeKind::Program,
+                Statement::Block { .. } => CstNodeKind::Block,
+                Statement::Empty { .. } => CstNodeKind::Empty,
+                Statement::Expression { .. } => CstNodeKind::ExpressionStatement,
+                Statement::VariableDeclaration { .. } => CstNodeKind::VariableDeclaration,
+                Statement::If { .. } => CstNodeKind::If,
+                Statement::While { .. } => CstNodeKind::While,
+                Statement::DoWhile { .. } => CstNodeKind::DoWhile,
+                Statement::For { .. } => CstNodeKind::For,
+                Statement::FunctionDeclaration { .. } => CstNodeKind::FunctionDeclaration,
+                Statement::Return { .. } => CstNodeKind::Return,
+                Statement::Break { .. } => CstNodeKind::Break,
+                Statement::Continue { .. } => CstNodeKind::Continue,
+                Statement::Labeled { .. } => CstNodeKind::Labeled,
+                Statement::ClassDeclaration { .. } => CstNodeKind::ClassDeclaration,
+                Statement::InterfaceDeclaration { .. } => CstNodeKind::InterfaceDeclaration,
+                Statement::EnumDeclaration { .. } => CstNodeKind::EnumDeclaration,
+            },
+            AstNode::Expression(expression) => match expression {
+                Expression::Variable { .. } => CstNodeKind::Variable,
+                Expression::Assignment { .. } => CstNodeKind::Assignment,
+                Expression::Binary { .. } => CstNodeKind::Binary,
+                Expression::Unary { .. } => CstNodeKind::Unary,
+                Expression::Logical { .. } => CstNodeKind::Logical,
+                Expression::BooleanLiteral { .. } => CstNodeKind::BooleanLiteral,
+                Expression::NilLiteral { .. } => CstNodeKind::NilLiteral,
+                Expression::StringLiteral { .. } => CstNodeKind::StringLiteral,
+                Expression::NumericLiteral { .. } => CstNodeKind::NumericLiteral,
+                Expression::Identifier { .. } => CstNodeKind::Identifier,
+                Expression::ArrayLiteral { .. } => CstNodeKind::ArrayLiteral,
+                Expression::Member { .. } => CstNodeKind::Member,
+                Expression::Call { .. } => CstNodeKind::Call,
+                Expression::This { .. } => CstNodeKind::This,
+                Expression::Super { .. } => CstNodeKind::Super,
+                Expression::New { .. } => CstNodeKind::New,
+                Expression::TypeTest { .. } => CstNodeKind::TypeTest,
+                Expression::EnumVariant { .. } => CstNodeKind::EnumVariant,
+                Expression::Match { .. } => CstNodeKind::Match,
+                Expression::Destructuring { .. } => CstNodeKind::Destructuring,
+            },
+            AstNode::ClassMember(_) => CstNodeKind::Field,
+            AstNode::InterfaceMember(InterfaceMember::Field { .. }) => CstNodeKind::Field,
+            AstNode::InterfaceMember(InterfaceMember::Method { .. }) => {
+                CstNodeKind::MethodSignature
+            }
+            AstNode::EnumVariant(_) => CstNodeKind::Variant,
+            AstNode::MatchArm(_) => CstNodeKind::MatchArm,
+            AstNode::Pattern(pattern) => match pattern {
+                Pattern::Wildcard { .. } => CstNodeKind::WildcardPattern,
+                Pattern::Binding { .. } => CstNodeKind::BindingPattern,
+                Pattern::Literal { .. } => CstNodeKind::LiteralPattern,
+                Pattern::EnumVariant { .. } => CstNodeKind::VariantPattern,
+                Pattern::Array { .. } => CstNodeKind::ArrayPattern,
+                Pattern::Object { .. } => CstNodeKind::ObjectPattern,
+            },
+            AstNode::PatternProperty(_) => CstNodeKind::PatternProperty,
+        }
+    }
+
+    fn children(&self) -> Vec<AstNode<'_>> {
+        let mut children = vec![];
+
+        match self {
+            AstNode::Statement(statement) => match statement {
+                Statement::Program { body, .. } | Statement::Block { body, .. } => {
+                    children.extend(body.iter().map(AstNode::Statement));
+                }
+                Statement::Empty { .. } => {}
+                Statement::Expression { expression, .. } => {
+                    children.push(AstNode::Expression(expression));
+                }
+                Statement::VariableDeclaration { variables, .. } => {
+                    children.extend(variables.iter().map(AstNode::Expression));
+                }
+                Statement::If {
+                    condition,
+                    consequent,
+                    alternative,
+                    ..
+                } => {
+                    children.push(AstNode::Expression(condition));
+                    children.push(AstNode::Statement(consequent));
+                    if let Some(alternative) = alternative {
+                        children.push(AstNode::Statement(alternative));
+                    }
+                }
+                Statement::While {
+                    condition, body, ..
+                }
+                | Statement::DoWhile {
+                    body, condition, ..
+                } => {
+                    children.push(AstNode::Expression(condition));
+                    children.push(AstNode::Statement(body));
+                }
+                Statement::For {
+                    initializer,
+                    condition,
+                    increment,
+                    body,
+                    ..
+                } => {
+                    if let Some(initializer) = initializer {
+                        children.push(AstNode::Statement(initializer));
+                    }
+                    if let Some(condition) = condition {
+                        children.push(AstNode::Expression(condition));
+                    }
+                    if let Some(increment) = increment {
+                        children.push(AstNode::Expression(increment));
+                    }
+                    children.push(AstNode::Statement(body));
+                }
+                Statement::FunctionDeclaration {
+                    name,
+                    parameters,
+                    body,
+                    ..
+                } => {
+                    children.push(AstNode::Expression(name));
+                    children.extend(
+                        parameters
+                            .iter()
+                            .map(|(parameter, _)| AstNode::Expression(parameter)),
+                    );
+                    children.push(AstNode::Statement(body));
+                }
+                Statement::Return { argument, .. }
+                | Statement::Break {
+                    label: argument, ..
+                }
+                | Statement::Continue {
+                    label: argument, ..
+                } => {
+                    if let Some(argument) = argument {
+                        children.push(AstNode::Expression(argument));
+                    }
+                }
+                Statement::Labeled { label, body, .. } => {
+                    children.push(AstNode::Expression(label));
+                    children.push(AstNode::Statement(body));
+                }
+                Statement::ClassDeclaration {
+                    name,
+                    super_class,
+                    implements,
+                    members,
+                    ..
+                } => {
+                    children.push(AstNode::Expression(name));
+                    if let Some(super_class) = super_class {
+                        children.push(AstNode::Expression(super_class));
+                    }
+                    children.extend(implements.iter().map(AstNode::Expression));
+                    // Methods are their function declarations, only fields get a node
+                    for member in members {
+                        children.push(match member {
+                            ClassMember::Field { .. } => AstNode::ClassMember(member),
+                            ClassMember::Method { declaration }
+                            | ClassMember::Constructor { declaration } => {
+                                AstNode::Statement(declaration)
+                            }
+                        });
+                    }
+                }
+                Statement::InterfaceDeclaration { name, members, .. } => {
+                    children.push(AstNode::Expression(name));
+                    children.extend(members.iter().map(AstNode::InterfaceMember));
+                }
+                Statement::EnumDeclaration { name, variants, .. } => {
+                    children.push(AstNode::Expression(name));
+                    children.extend(variants.iter().map(AstNode::EnumVariant));
+                }
+            },
+            AstNode::Expression(expression) => match expression {
+                Expression::Variable {
+                    identifier,
+                    initializer,
+                    ..
+                } => {
+                    children.push(AstNode::Expression(identifier));
+                    if let Some(initializer) = initializer {
+                        children.push(AstNode::Expression(initializer));
+                    }
+                }
+                Expression::Assignment { left, right, .. }
+                | Expression::Binary { left, right, .. }
+                | Expression::Logical { left, right, .. } => {
+                    children.push(AstNode::Expression(left));
+                    children.push(AstNode::Expression(right));
+                }
+                Expression::Unary { right, .. }
+                | Expression::TypeTest {
+                    expression: right, ..
+                } => {
+                    children.push(AstNode::Expression(right));
+                }
+                Expression::Member {
+                    object, property, ..
+                } => {
+                    children.push(AstNode::Expression(object));
+                    children.push(AstNode::Expression(property));
+                }
+                Expression::Call {
+                    callee, arguments, ..
+                }
+                | Expression::New {
+                    callee, arguments, ..
+                } => {
+                    children.push(AstNode::Expression(callee));
+                    children.extend(arguments.iter().map(AstNode::Expression));
+                }
+                Expression::ArrayLiteral { elements, .. } => {
+                    children.extend(elements.iter().map(AstNode::Expression));
+                }
+                Expression::EnumVariant {
+                    enum_name,
+                    variant,
+                    arguments,
+                    ..
+                } => {
+                    children.push(AstNode::Expression(enum_name));
+                    children.push(AstNode::Expression(variant));
+                    children.extend(arguments.iter().map(AstNode::Expression));
+                }
+                Expression::Match { subject, arms, .. } => {
+                    children.push(AstNode::Expression(subject));
+                    children.extend(arms.iter().map(AstNode::MatchArm));
+                }
+                Expression::Destructuring { pattern, .. } => {
+                    children.push(AstNode::Pattern(pattern));
+                }
+                Expression::BooleanLiteral { .. }
+                | Expression::NilLiteral { .. }
+                | Expression::StringLiteral { .. }
+                | Expression::NumericLiteral { .. }
+                | Expression::Identifier { .. }
+                | Expression::This { .. }
+                | Expression::Super { .. } => {}
+            },
+            AstNode::ClassMember(member) => {
+                if let ClassMember::Field {
+                    name, initializer, ..
+                } = member
+                {
+                    children.push(AstNode::Expression(name));
+                    if let Some(initializer) = initializer {
+                        children.push(AstNode::Expression(initializer));
+                    }
+                }
+            }
+            AstNode::InterfaceMember(member) => match member {
+                InterfaceMember::Field { name, .. } => children.push(AstNode::Expression(name)),
+                InterfaceMember::Method {
+                    name, parameters, ..
+                } => {
+                    children.push(AstNode::Expression(name));
+                    children.extend(
+                        parameters
+                            .iter()
+                            .map(|(parameter, _)| AstNode::Expression(parameter)),
+                    );
+                }
+            },
+            AstNode::EnumVariant(variant) => children.push(AstNode::Expression(&variant.name)),
+            AstNode::MatchArm(arm) => {
+                children.push(AstNode::Pattern(&arm.pattern));
+                if let Some(guard) = &arm.guard {
+                    children.push(AstNode::Expression(guard));
+                }
+                children.push(AstNode::Expression(&arm.value));
+            }
+            AstNode::Pattern(pattern) => match pattern {
+                Pattern::Wildcard { .. } => {}
+                Pattern::Binding { name, .. } | Pattern::Literal { value: name, .. } => {
+                    children.push(AstNode::Expression(name));
+                }
+                Pattern::EnumVariant {
+                    enum_name,
+                    variant,
+                    arguments,
+                    ..
+                } => {
+                    children.push(AstNode::Expression(enum_name));
+                    children.push(AstNode::Expression(variant));
+                    children.extend(arguments.iter().map(AstNode::Pattern));
+                }
+                Pattern::Array { elements, .. } => {
+                    children.extend(elements.iter().map(AstNode::Pattern));
+                }
+                Pattern::Object { properties, .. } => {
+                    children.extend(properties.iter().map(AstNode::PatternProperty));
+                }
+            },
+            AstNode::PatternProperty(property) => {
+                // A shorthand property binds its own key, the key is the pattern
+                if property.value.span() != property.key.span() {
+                    children.push(AstNode::Expression(&property.key));
+                }
+                children.push(AstNode::Pattern(&property.value));
+            }
+        }
+
+        children
+    }
+}
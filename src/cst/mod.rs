@@ -0,0 +1,6 @@
+mod cst;
+mod cst_builder;
+mod tests;
+
+pub(crate) use self::cst::*;
+pub(crate) use self::cst_builder::*;
@@ -0,0 +1,16 @@
+use super::internal_util::execute_yaml_test_cases;
+
+#[test]
+fn test_cst_round_trip() {
+    execute_yaml_test_cases("tests/cst/test_cst_round_trip.yaml");
+}
+
+#[test]
+fn test_cst_structure() {
+    execute_yaml_test_cases("tests/cst/test_cst_structure.yaml");
+}
+
+#[test]
+fn test_cst_syntax_errors() {
+    execute_yaml_test_cases("tests/cst/test_cst_syntax_errors.yaml");
+}
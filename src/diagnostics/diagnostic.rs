@@ -0,0 +1,121 @@
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    checker::CheckerError,
+    codegen::CodegenError,
+    interpreter::InterpreterError,
+    ir::IrError,
+    lexer::{LexerError, Span, TokenPosition},
+    optimizer::OptimizerError,
+    parser::ParserError,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+///
+/// A message about the source reported by any of the compiler passes
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub span: Option<Span>,
+}
+
+#[allow(dead_code)]
+impl Diagnostic {
+    pub fn error(message: impl Into<String>, span: Option<Span>) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            message: message.into(),
+            span,
+        }
+    }
+
+    pub fn warning(message: impl Into<String>, span: Option<Span>) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            message: message.into(),
+            span,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    ///
+    /// Lexer errors only know the line and column they happened at
+    ///
+    pub fn from_lexer_error(error: &LexerError) -> Self {
+        let (line, column) = match error {
+            LexerError::UnexpectedCharacter { line, column, .. }
+            | LexerError::UnterminatedString { line, column }
+            | LexerError::UnterminatedComment { line, column }
+            | LexerError::InvalidNumber { line, column } => (*line, *column),
+        };
+        let position = TokenPosition {
+            line,
+            column,
+            offset: 0,
+        };
+
+        Diagnostic::error(error.to_string(), Some(Span::new(position, position)))
+    }
+
+    ///
+    /// Parser errors are reported at the token the parser stopped on
+    ///
+    pub fn from_parser_error(error: &ParserError, span: Span) -> Self {
+        Diagnostic::error(error.to_string(), Some(span))
+    }
+
+    pub fn from_checker_error(error: &CheckerError) -> Self {
+        Diagnostic::error(error.to_string(), Some(error.span()))
+    }
+
+    pub fn from_optimizer_error(error: &OptimizerError) -> Self {
+        Diagnostic::error(error.to_string(), Some(error.span()))
+    }
+
+    pub fn from_ir_error(error: &IrError) -> Self {
+        Diagnostic::error(error.to_string(), Some(error.span()))
+    }
+
+    pub fn from_codegen_error(error: &CodegenError) -> Self {
+        Diagnostic::error(error.to_string(), None)
+    }
+
+    pub fn from_interpreter_error(error: &InterpreterError) -> Self {
+        Diagnostic::error(error.to_string(), error.span())
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.span {
+            Some(span) => write!(
+                f,
+                "{}: {} at {}:{}",
+                self.severity, self.message, span.start.line, span.start.column
+            ),
+            None => write!(f, "{}: {}", self.severity, self.message),
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use super::Diagnostic;
+
+///
+/// Render a diagnostic with the offending source line underlined
+///
+/// error: Type error: Cannot assign 'string' to 'number'
+///  --> main.sbz:1:17
+///   |
+/// 1 | let x: number = "one";
+///   |                 ^^^^^
+///
+pub fn render_diagnostic(diagnostic: &Diagnostic, source: &str, path: &str) -> String {
+    let mut rendered = format!("{}: {}\n", diagnostic.severity, diagnostic.message);
+
+    let Some(span) = diagnostic.span else {
+        rendered.push_str(&format!(" --> {}\n", path));
+        return rendered;
+    };
+
+    let line_number = span.start.line;
+    let gutter_width = line_number.to_string().len();
+    let gutter = " ".repeat(gutter_width);
+
+    rendered.push_str(&format!(
+        "{}--> {}:{}:{}\n",
+        gutter, path, span.start.line, span.start.column
+    ));
+
+    let Some(line) = source.lines().nth(line_number.saturating_sub(1)) else {
+        return rendered;
+    };
+
+    // Multi-line spans are underlined up to the end of their first line
+    let line_length = line.chars().count();
+    let start_column = span.start.column.max(1);
+    let end_column = if span.end.line == span.start.line {
+        span.end.column
+    } else {
+        line_length + 1
+    };
+    let underline_length = end_column.saturating_sub(start_column).max(1);
+
+    rendered.push_str(&format!("{} |\n", gutter));
+    rendered.push_str(&format!("{} | {}\n", line_number, line));
+    rendered.push_str(&format!(
+        "{} | {}{}\n",
+        gutter,
+        " ".repeat(start_column - 1),
+        "^".repeat(underline_length)
+    ));
+
+    rendered
+}
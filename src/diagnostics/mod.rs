@@ -0,0 +1,5 @@
+mod diagnostic;
+mod diagnostic_renderer;
+
+pub(crate) use self::diagnostic::*;
+pub(crate) use self::diagnostic_renderer::*;
@@ -0,0 +1,94 @@
+use crate::{
+    ast::{Statement, StatementRef},
+    diagnostics::Diagnostic,
+    driver::parse_source,
+    lexer::{Lexer, Token, TokenType},
+};
+
+use super::document_reparse::reparse_statement;
+
+///
+/// Replacement of a source range, offsets are in bytes
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+}
+
+///
+/// Parsed source that follows text edits
+///
+/// An edit inside a single statement only reparses that statement, the
+/// spans and tokens after it are moved to their new positions
+///
+#[derive(Debug, Clone)]
+pub struct Document {
+    source: String,
+    tokens: Vec<Token>,
+    program: Result<StatementRef, Diagnostic>,
+}
+
+#[allow(dead_code)]
+impl Document {
+    pub fn new(source: String) -> Self {
+        let tokens = tokenize(&source);
+        let program = parse_source(&source);
+
+        Document {
+            source,
+            tokens,
+            program,
+        }
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    ///
+    /// Tokens up to the End token, or up to the first lexical error
+    ///
+    pub fn tokens(&self) -> &[Token] {
+        &self.tokens
+    }
+
+    pub fn program(&self) -> Result<&Statement, &Diagnostic> {
+        self.program.as_deref()
+    }
+
+    ///
+    /// Apply the edit, returns whether the document was reparsed incrementally
+    ///
+    pub fn apply_edit(&mut self, edit: &TextEdit) -> bool {
+        let mut source = String::with_capacity(self.source.len() + edit.text.len());
+        source.push_str(&self.source[..edit.start]);
+        source.push_str(&edit.text);
+        source.push_str(&self.source[edit.end..]);
+
+        if let Ok(program) = &mut self.program {
+            if reparse_statement(program, &mut self.tokens, &source, edit) {
+                self.source = source;
+                return true;
+            }
+        }
+
+        *self = Document::new(source);
+        false
+    }
+}
+
+fn tokenize(source: &str) -> Vec<Token> {
+    let mut lexer = Lexer::new(source);
+    let mut tokens = vec![];
+
+    while let Ok(token) = lexer.next_token() {
+        tokens.push(token);
+        if token.token_type == TokenType::End {
+            break;
+        }
+    }
+
+    tokens
+}
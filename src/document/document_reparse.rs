@@ -0,0 +1,187 @@
+use crate::{
+    ast::{Statement, StatementList},
+    lexer::{Lexer, Span, Token, TokenPosition, TokenType},
+    parser::{parse_root_single_statement, Parser},
+};
+
+use super::TextEdit;
+
+///
+/// Reparse the innermost statement holding the edit, the program and the
+/// tokens are updated in place and describe the new source afterwards
+///
+/// Returns false when no statement holds the edit or when the edited
+/// statement no longer parses on its own, nothing is changed then
+///
+pub(super) fn reparse_statement(
+    program: &mut Statement,
+    tokens: &mut Vec<Token>,
+    source: &str,
+    edit: &TextEdit,
+) -> bool {
+    let Some(old_span) = find_statement(program, edit) else {
+        return false;
+    };
+
+    // The edit is strictly inside the statement, so its last byte is kept
+    let delta = edit.text.len() as isize - (edit.end - edit.start) as isize;
+    let region_end = (old_span.end.offset as isize + delta) as usize;
+    let region = &source[..region_end];
+
+    let Ok(mut parser) = Parser::new_at(region, old_span.start) else {
+        return false;
+    };
+    let Ok(statement) = parse_root_single_statement(&mut parser) else {
+        return false;
+    };
+    let new_end = statement.span().end;
+    if new_end.offset != region_end {
+        return false;
+    }
+    let Some(region_tokens) = tokenize_region(region, old_span.start) else {
+        return false;
+    };
+
+    program.for_each_span_mut(&mut |span| {
+        shift_position(&mut span.start, old_span.end, new_end);
+        shift_position(&mut span.end, old_span.end, new_end);
+    });
+    let target = Span::new(old_span.start, new_end);
+    if !replace_statement(program, target, &mut Some(*statement)) {
+        return false;
+    }
+
+    let mut shifted_tokens: Vec<Token> = tokens
+        .iter()
+        .filter(|token| token.start.offset < old_span.start.offset)
+        .copied()
+        .collect();
+    shifted_tokens.extend(region_tokens);
+    shifted_tokens.extend(
+        tokens
+            .iter()
+            .filter(|token| token.start.offset >= old_span.end.offset)
+            .map(|token| {
+                let mut token = *token;
+                shift_position(&mut token.start, old_span.end, new_end);
+                shift_position(&mut token.end, old_span.end, new_end);
+                token
+            }),
+    );
+    *tokens = shifted_tokens;
+
+    true
+}
+
+///
+/// Span of the innermost statement of a program or block body that holds
+/// the edit strictly inside, class bodies are reparsed as a whole
+///
+fn find_statement(statement: &Statement, edit: &TextEdit) -> Option<Span> {
+    match statement {
+        Statement::Program { body, .. } | Statement::Block { body, .. } => {
+            let statement = body.iter().find(|statement| {
+                let span = statement.span();
+                span.start.offset < edit.start && edit.end < span.end.offset
+            })?;
+            find_statement(statement, edit).or(Some(statement.span()))
+        }
+        Statement::If {
+            consequent,
+            alternative,
+            ..
+        } => find_statement(consequent, edit).or_else(|| {
+            alternative
+                .as_ref()
+                .and_then(|alternative| find_statement(alternative, edit))
+        }),
+        Statement::While { body, .. }
+        | Statement::DoWhile { body, .. }
+        | Statement::For { body, .. }
+        | Statement::Labeled { body, .. }
+        | Statement::FunctionDeclaration { body, .. } => find_statement(body, edit),
+        _ => None,
+    }
+}
+
+///
+/// Replace the statement of a program or block body with the target span
+///
+fn replace_statement(
+    statement: &mut Statement,
+    target: Span,
+    replacement: &mut Option<Statement>,
+) -> bool {
+    match statement {
+        Statement::Program { body, .. } | Statement::Block { body, .. } => {
+            replace_in_list(body, target, replacement)
+        }
+        Statement::If {
+            consequent,
+            alternative,
+            ..
+        } => {
+            replace_statement(consequent, target, replacement)
+                || alternative
+                    .as_mut()
+                    .is_some_and(|alternative| replace_statement(alternative, target, replacement))
+        }
+        Statement::While { body, .. }
+        | Statement::DoWhile { body, .. }
+        | Statement::For { body, .. }
+        | Statement::Labeled { body, .. }
+        | Statement::FunctionDeclaration { body, .. } => {
+            replace_statement(body, target, replacement)
+        }
+        _ => false,
+    }
+}
+
+fn replace_in_list(
+    body: &mut StatementList,
+    target: Span,
+    replacement: &mut Option<Statement>,
+) -> bool {
+    for statement in body {
+        if statement.span() == target {
+            if let Some(replacement) = replacement.take() {
+                *statement = replacement;
+                return true;
+            }
+        }
+        if replace_statement(statement, target, replacement) {
+            return true;
+        }
+    }
+
+    false
+}
+
+///
+/// Move a position at or after the old end of the statement to the new end,
+/// positions before the statement are kept
+///
+fn shift_position(position: &mut TokenPosition, old_end: TokenPosition, new_end: TokenPosition) {
+    if position.offset < old_end.offset {
+        return;
+    }
+
+    if position.line == old_end.line {
+        position.column = position.column - old_end.column + new_end.column;
+    }
+    position.line = position.line - old_end.line + new_end.line;
+    position.offset = position.offset - old_end.offset + new_end.offset;
+}
+
+fn tokenize_region(region: &str, start: TokenPosition) -> Option<Vec<Token>> {
+    let mut lexer = Lexer::new_at(region, start);
+    let mut tokens = vec![];
+
+    loop {
+        let token = lexer.next_token().ok()?;
+        if token.token_type == TokenType::End {
+            return Some(tokens);
+        }
+        tokens.push(token);
+    }
+}
@@ -0,0 +1,5 @@
+mod document;
+mod document_reparse;
+mod tests;
+
+pub(crate) use self::document::*;
@@ -0,0 +1,91 @@
+use crate::document::{Document, TextEdit};
+use serde::Deserialize;
+use std::fs;
+
+#[derive(Debug, Deserialize)]
+pub(super) struct YamlTestCase {
+    description: String,
+    source: String,
+    edits: Vec<YamlTestEdit>,
+}
+
+#[derive(Debug, Deserialize)]
+struct YamlTestEdit {
+    find: String,
+    replace: String,
+    incremental: bool,
+}
+
+pub(super) fn execute_yaml_test(test_case: &YamlTestCase) {
+    let mut document = Document::new(test_case.source.clone());
+
+    for edit in &test_case.edits {
+        // Edits replace the first occurrence of the text
+        let Some(start) = document.source().find(&edit.find) else {
+            panic!(
+                "Text '{}' not found in the document:\n{}\n",
+                edit.find,
+                document.source()
+            )
+        };
+        let incremental = document.apply_edit(&TextEdit {
+            start,
+            end: start + edit.find.len(),
+            text: edit.replace.clone(),
+        });
+        assert_eq!(
+            incremental, edit.incremental,
+            "Unexpected reparse kind after replacing '{}' with '{}'",
+            edit.find, edit.replace
+        );
+
+        // The document must match a full reparse, spans and tokens included
+        let expected = Document::new(String::from(document.source()));
+        assert_eq!(document.program(), expected.program());
+        assert_eq!(document.tokens(), expected.tokens());
+    }
+}
+
+pub(super) fn load_yaml_test_cases(path: &str) -> Vec<YamlTestCase> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(error) => {
+            eprintln!("Failed to read the test yaml file: {}", error);
+            eprintln!("Path: {}", path);
+            panic!("Could not read test file: {}", error);
+        }
+    };
+
+    // Parse multiple documents
+    let mut test_cases = Vec::new();
+
+    for document in serde_yaml::Deserializer::from_str(&content) {
+        match YamlTestCase::deserialize(document) {
+            Ok(test_case) => test_cases.push(test_case),
+            Err(e) => {
+                eprintln!("Failed to parse a YAML document: {}", e);
+                eprintln!("Path: {}", path);
+                panic!("Could not parse YAML content: {}", e);
+            }
+        }
+    }
+
+    // Verify that we found some test cases
+    if test_cases.is_empty() {
+        eprintln!("No test cases found in file: {}", path);
+        panic!("File contained no valid test cases");
+    }
+
+    test_cases
+}
+
+pub(super) fn execute_yaml_test_cases(path: &str) {
+    let test_cases = load_yaml_test_cases(path);
+
+    for (index, test_case) in test_cases.iter().enumerate() {
+        println!("Testing case #{}:\n{}\n", index + 1, test_case.description);
+        execute_yaml_test(test_case);
+    }
+
+    println!("All {} test cases passed!", test_cases.len());
+}
@@ -0,0 +1,11 @@
+use super::internal_util::execute_yaml_test_cases;
+
+#[test]
+fn test_document_incremental() {
+    execute_yaml_test_cases("tests/document/test_document_incremental.yaml");
+}
+
+#[test]
+fn test_document_fallback() {
+    execute_yaml_test_cases("tests/document/test_document_fallback.yaml");
+}
@@ -0,0 +1,211 @@
+use crate::{
+    analysis::analyze_program,
+    ast::{ExpressionRef, StatementRef},
+    checker::TypeChecker,
+    codegen::{generate_c, generate_js, generate_wat},
+    cst::{build_concrete_syntax_tree, build_partial_syntax_tree, CstNode},
+    diagnostics::Diagnostic,
+    ir::{lower_program, IrModule},
+    lexer::{Lexer, LexerError, Token, TokenType},
+    optimizer::fold_constants,
+    parser::{parse_root_expression, parse_root_statement, Parser},
+};
+
+///
+/// Split the source into tokens, the End token is not included
+///
+pub fn tokenize_source(source: &str) -> Result<Vec<Token>, Diagnostic> {
+    let mut lexer = Lexer::new(source);
+    let mut tokens = vec![];
+
+    loop {
+        let token = lexer
+            .next_token()
+            .map_err(|error| Diagnostic::from_lexer_error(&error))?;
+        if token.token_type == TokenType::End {
+            return Ok(tokens);
+        }
+        tokens.push(token);
+    }
+}
+
+///
+/// Parse the source into a Program statement
+///
+pub fn parse_source(source: &str) -> Result<StatementRef, Diagnostic> {
+    // Lexing up front reports lexical errors at their exact position
+    tokenize_source(source)?;
+
+    let mut parser = Parser::new(source)
+        .map_err(|error| Diagnostic::from_parser_error(&error, Default::default()))?;
+    parse_root_statement(&mut parser)
+        .map_err(|error| Diagnostic::from_parser_error(&error, parser.lookahead().span()))
+}
+
+///
+/// Parse the source into its lossless syntax tree, source with a syntax
+/// error gets a partial tree together with the error
+///
+pub fn parse_source_with_cst(source: &str) -> Result<(CstNode, Option<Diagnostic>), Diagnostic> {
+    let (cst, error) = match parse_source(source) {
+        Ok(program) => (build_concrete_syntax_tree(source, &program), None),
+        Err(diagnostic) => (build_partial_syntax_tree(source), Some(diagnostic)),
+    };
+    let cst = cst.map_err(|error| Diagnostic::from_lexer_error(&error))?;
+
+    Ok((cst, error))
+}
+
+///
+/// Parse the source into a single expression, nothing may follow it
+///
+pub fn parse_expression_source(source: &str) -> Result<ExpressionRef, Diagnostic> {
+    tokenize_source(source)?;
+
+    let mut parser = Parser::new(source)
+        .map_err(|error| Diagnostic::from_parser_error(&error, Default::default()))?;
+    let expression = parse_root_expression(&mut parser)
+        .map_err(|error| Diagnostic::from_parser_error(&error, parser.lookahead().span()))?;
+
+    let lookahead = parser.lookahead();
+    if lookahead.token_type != TokenType::End {
+        return Err(Diagnostic::error(
+            format!(
+                "Unexpected token '{}' after the expression",
+                lookahead.text(source)
+            ),
+            Some(lookahead.span()),
+        ));
+    }
+
+    Ok(expression)
+}
+
+///
+/// Whether more lines are needed to complete the source, that is when a
+/// bracket or comment is left open or the parser ran out of tokens
+///
+pub fn is_incomplete_source(source: &str) -> bool {
+    let mut lexer = Lexer::new(source);
+    let mut depth: i32 = 0;
+
+    loop {
+        match lexer.next_token() {
+            Ok(token) => match token.token_type {
+                TokenType::OpeningBrace
+                | TokenType::OpeningParenthesis
+                | TokenType::OpeningBracket => depth += 1,
+                TokenType::ClosingBrace
+                | TokenType::ClosingParenthesis
+                | TokenType::ClosingBracket => depth -= 1,
+                TokenType::End => break,
+                _ => {}
+            },
+            Err(LexerError::UnterminatedComment { .. }) => return true,
+            Err(_) => return false,
+        }
+    }
+
+    if depth != 0 {
+        return depth > 0;
+    }
+
+    let Ok(mut parser) = Parser::new(source) else {
+        return false;
+    };
+    parse_root_statement(&mut parser).is_err() && parser.lookahead().token_type == TokenType::End
+}
+
+///
+/// Format a token the way the lex command prints it
+///
+pub fn format_token(token: &Token, source: &str) -> String {
+    format!(
+        "{}:{} {} {:?}",
+        token.start.line,
+        token.start.column,
+        token.token_type,
+        token.text(source)
+    )
+}
+
+///
+/// Parse, type check and analyze the source, the first error is returned
+/// and the warnings are kept with the program
+///
+pub fn check_source(source: &str) -> Result<(StatementRef, Vec<Diagnostic>), Diagnostic> {
+    let program = parse_source(source)?;
+
+    TypeChecker::new()
+        .check_program(&program)
+        .map_err(|error| Diagnostic::from_checker_error(&error))?;
+
+    let (errors, warnings): (Vec<_>, Vec<_>) = analyze_program(&program)
+        .into_iter()
+        .partition(Diagnostic::is_error);
+    if let Some(error) = errors.into_iter().next() {
+        return Err(error);
+    }
+
+    Ok((program, warnings))
+}
+
+///
+/// Check the source and fold its constants
+///
+pub fn optimize_source(source: &str) -> Result<(StatementRef, Vec<Diagnostic>), Diagnostic> {
+    let (program, warnings) = check_source(source)?;
+
+    let program =
+        fold_constants(&program).map_err(|error| Diagnostic::from_optimizer_error(&error))?;
+
+    Ok((program, warnings))
+}
+
+///
+/// Check the source and lower it into SSA form
+///
+pub fn lower_source(source: &str) -> Result<(IrModule, Vec<Diagnostic>), Diagnostic> {
+    let (program, warnings) = check_source(source)?;
+
+    let module = lower_program(&program).map_err(|error| Diagnostic::from_ir_error(&error))?;
+
+    Ok((module, warnings))
+}
+
+///
+/// Lower the source and emit it as a C program
+///
+pub fn generate_c_source(source: &str) -> Result<(String, Vec<Diagnostic>), Diagnostic> {
+    let (module, warnings) = lower_source(source)?;
+
+    let output = generate_c(&module).map_err(|error| Diagnostic::from_codegen_error(&error))?;
+
+    Ok((output, warnings))
+}
+
+///
+/// Check the source and translate it to JavaScript
+///
+pub fn generate_js_source(
+    source: &str,
+    jsdoc: bool,
+) -> Result<(String, Vec<Diagnostic>), Diagnostic> {
+    let (program, warnings) = check_source(source)?;
+
+    let output =
+        generate_js(&program, jsdoc).map_err(|error| Diagnostic::from_codegen_error(&error))?;
+
+    Ok((output, warnings))
+}
+
+///
+/// Lower the source and emit it as a WebAssembly text module
+///
+pub fn generate_wat_source(source: &str) -> Result<(String, Vec<Diagnostic>), Diagnostic> {
+    let (module, warnings) = lower_source(source)?;
+
+    let output = generate_wat(&module).map_err(|error| Diagnostic::from_codegen_error(&error))?;
+
+    Ok((output, warnings))
+}
@@ -0,0 +1,3 @@
+mod driver;
+
+pub(crate) use self::driver::*;
@@ -0,0 +1,1252 @@
+use std::{cell::RefCell, collections::HashMap, io::Write, panic, rc::Rc, thread};
+
+use anyhow::Result;
+
+use crate::{
+    ast::{
+        AssignmentOperator, AstVisitor, BinaryOperator, ClassMember, ClassMemberList, Expression,
+        ExpressionList, ExpressionRef, LogicalOperator, MatchArmList, Pattern, Statement,
+        StatementList, UnaryOperator, CONSTRUCTOR_NAME,
+    },
+    lexer::Span,
+};
+
+use super::{
+    interpreter_environment::{Environment, EnvironmentRef},
+    Builtin, ClassValue, EnumValue, FunctionValue, InstanceValue, InterpreterError,
+    InterpreterResult, Value,
+};
+
+///
+/// Deepest allowed nesting of function calls, programs run on a thread with
+/// a stack of INTERPRETER_STACK_SIZE that fits it in unoptimized builds
+///
+const MAX_CALL_DEPTH: usize = 5000;
+
+///
+/// Stack size of the thread running the interpreter
+///
+const INTERPRETER_STACK_SIZE: usize = 512 * 1024 * 1024;
+
+///
+/// Run the closure on a thread with a stack deep enough for MAX_CALL_DEPTH
+/// nested calls, the default stacks only fit a few hundred
+///
+pub fn with_interpreter_stack<T: Send>(run: impl FnOnce() -> T + Send) -> T {
+    thread::scope(|scope| {
+        let handle = thread::Builder::new()
+            .stack_size(INTERPRETER_STACK_SIZE)
+            .spawn_scoped(scope, run)
+            .expect("Could not start the interpreter thread");
+        match handle.join() {
+            Ok(result) => result,
+            Err(panic) => panic::resume_unwind(panic),
+        }
+    })
+}
+
+///
+/// Tree walking interpreter
+///
+/// Global bindings are kept between calls, so the same interpreter can run
+/// a program piece by piece
+///
+pub struct Interpreter<'a> {
+    environment: EnvironmentRef,
+    output: &'a mut dyn Write,
+    returning: Option<Value>,
+    jumping: Option<Jump>,
+    call_depth: usize,
+}
+
+///
+/// 'break' or 'continue' in progress, with the label of the targeted loop
+///
+enum Jump {
+    Break(Option<String>),
+    Continue(Option<String>),
+}
+
+#[allow(dead_code)]
+impl<'a> Interpreter<'a> {
+    pub fn new(output: &'a mut dyn Write) -> Self {
+        Interpreter {
+            environment: Environment::new_global(),
+            output,
+            returning: None,
+            jumping: None,
+            call_depth: 0,
+        }
+    }
+
+    ///
+    /// Execute a statement, returns the value of the last executed expression statement
+    ///
+    pub fn execute(&mut self, statement: &Statement) -> InterpreterResult<Value> {
+        self.visit_statement(statement)
+            .map_err(into_interpreter_error)
+    }
+
+    ///
+    /// Evaluate a standalone expression
+    ///
+    pub fn evaluate(&mut self, expression: &Expression) -> InterpreterResult<Value> {
+        self.visit_expression(expression)
+            .map_err(into_interpreter_error)
+    }
+
+    ///
+    /// Writer that receives the program output
+    ///
+    pub fn output(&mut self) -> &mut dyn Write {
+        self.output
+    }
+
+    fn execute_statement_list(&mut self, statements: &StatementList) -> Result<Value> {
+        // Functions and classes can be used before their declaration
+        for statement in statements {
+            if matches!(statement, Statement::FunctionDeclaration { .. }) {
+                self.visit_statement(statement)?;
+            }
+        }
+        for statement in statements {
+            if matches!(statement, Statement::ClassDeclaration { .. }) {
+                self.visit_statement(statement)?;
+            }
+        }
+
+        let mut last_value = Value::Nil;
+        for statement in statements {
+            if matches!(
+                statement,
+                Statement::FunctionDeclaration { .. } | Statement::ClassDeclaration { .. }
+            ) {
+                continue;
+            }
+
+            last_value = self.visit_statement(statement)?;
+            if self.returning.is_some() || self.jumping.is_some() {
+                break;
+            }
+        }
+
+        Ok(last_value)
+    }
+
+    fn execute_loop(&mut self, statement: &Statement, label: Option<&str>) -> Result<Value> {
+        match statement {
+            Statement::While {
+                condition, body, ..
+            } => {
+                while self.evaluate_condition(condition)? {
+                    self.visit_statement(body)?;
+                    if self.loop_stops(label) {
+                        break;
+                    }
+                }
+                Ok(Value::Nil)
+            }
+            Statement::DoWhile {
+                body, condition, ..
+            } => {
+                loop {
+                    self.visit_statement(body)?;
+                    if self.loop_stops(label) || !self.evaluate_condition(condition)? {
+                        break;
+                    }
+                }
+                Ok(Value::Nil)
+            }
+            Statement::For {
+                initializer,
+                condition,
+                increment,
+                body,
+                ..
+            } => {
+                let environment = Environment::new_child(&self.environment);
+                self.with_environment(environment, |this| {
+                    if let Some(initializer) = initializer {
+                        this.visit_statement(initializer)?;
+                    }
+
+                    loop {
+                        if let Some(condition) = condition {
+                            if !this.evaluate_condition(condition)? {
+                                break;
+                            }
+                        }
+
+                        this.visit_statement(body)?;
+                        if this.loop_stops(label) {
+                            break;
+                        }
+
+                        if let Some(increment) = increment {
+                            this.visit_expression(increment)?;
+                        }
+                    }
+
+                    Ok(Value::Nil)
+                })
+            }
+            _ => self.visit_statement(statement),
+        }
+    }
+
+    ///
+    /// Whether the loop with the label stops after its body ran, a jump to
+    /// the loop is consumed and a jump to an enclosing loop leaves it
+    ///
+    fn loop_stops(&mut self, label: Option<&str>) -> bool {
+        if self.returning.is_some() {
+            return true;
+        }
+
+        let targets = |target: &Option<String>| target.is_none() || target.as_deref() == label;
+        match self.jumping.take() {
+            None => false,
+            Some(Jump::Break(target)) if targets(&target) => true,
+            Some(Jump::Continue(target)) if targets(&target) => false,
+            jump => {
+                self.jumping = jump;
+                true
+            }
+        }
+    }
+
+    ///
+    /// Run the callback in the given environment, the current one is restored afterwards
+    ///
+    fn with_environment<T>(
+        &mut self,
+        environment: EnvironmentRef,
+        callback: impl FnOnce(&mut Self) -> Result<T>,
+    ) -> Result<T> {
+        let enclosing = std::mem::replace(&mut self.environment, environment);
+        let result = callback(self);
+        self.environment = enclosing;
+        result
+    }
+
+    fn define_function(&mut self, statement: &Statement) -> Result<Rc<FunctionValue>> {
+        let Statement::FunctionDeclaration {
+            name,
+            parameters,
+            body,
+            ..
+        } = statement
+        else {
+            return Err(runtime_error(
+                String::from("Expected a function declaration"),
+                statement.span(),
+            ));
+        };
+
+        Ok(Rc::new(FunctionValue {
+            name: identifier_name(name)?,
+            parameters: parameters
+                .iter()
+                .map(|(parameter, _)| parameter.clone())
+                .collect(),
+            body: Rc::new(body.as_ref().clone()),
+            closure: Rc::clone(&self.environment),
+        }))
+    }
+
+    fn define_class(
+        &mut self,
+        name: &ExpressionRef,
+        super_class: &Option<ExpressionRef>,
+        members: &ClassMemberList,
+    ) -> Result<Value> {
+        let class_name = identifier_name(name)?;
+
+        let super_class = match super_class {
+            Some(super_class) => match self.visit_expression(super_class)? {
+                Value::Class(class) => Some(class),
+                value => {
+                    return Err(runtime_error(
+                        format!("Superclass must be a class, found {}", value.type_name()),
+                        super_class.span(),
+                    ))
+                }
+            },
+            None => None,
+        };
+
+        let mut methods = HashMap::new();
+        let mut fields = vec![];
+        for member in members {
+            match member {
+                ClassMember::Field {
+                    name, initializer, ..
+                } => {
+                    fields.push((identifier_name(name)?, initializer.as_deref().cloned()));
+                }
+                ClassMember::Method { declaration } | ClassMember::Constructor { declaration } => {
+                    let function = self.define_function(declaration)?;
+                    methods.insert(function.name.clone(), function);
+                }
+            }
+        }
+
+        let class = Value::Class(Rc::new(ClassValue {
+            name: class_name.clone(),
+            super_class,
+            methods,
+            fields,
+        }));
+        self.environment
+            .borrow_mut()
+            .define(&class_name, class.clone());
+
+        Ok(class)
+    }
+
+    fn instantiate(
+        &mut self,
+        class: &Rc<ClassValue>,
+        arguments: Vec<Value>,
+        span: Span,
+    ) -> Result<Value> {
+        let instance = Rc::new(RefCell::new(InstanceValue {
+            class: Rc::clone(class),
+            fields: HashMap::new(),
+        }));
+
+        // Ancestors initialize their fields first
+        let mut chain = vec![];
+        let mut current = Some(Rc::clone(class));
+        while let Some(class) = current {
+            current = class.super_class.clone();
+            chain.push(class);
+        }
+
+        let environment = Environment::new_child(&self.environment);
+        environment
+            .borrow_mut()
+            .define("this", Value::Instance(Rc::clone(&instance)));
+        for class in chain.iter().rev() {
+            for (field_name, initializer) in &class.fields {
+                let value = match initializer {
+                    Some(initializer) => self
+                        .with_environment(Rc::clone(&environment), |this| {
+                            this.visit_expression(initializer)
+                        })?,
+                    None => Value::Nil,
+                };
+                instance
+                    .borrow_mut()
+                    .fields
+                    .insert(field_name.clone(), value);
+            }
+        }
+
+        match class.find_method(CONSTRUCTOR_NAME) {
+            Some((constructor, declaring_class)) => {
+                let constructor = bind_method(&constructor, &instance, &declaring_class);
+                self.call_function(&constructor, arguments, span)?;
+            }
+            None if !arguments.is_empty() => {
+                return Err(runtime_error(
+                    format!("Expected 0 arguments, found {}", arguments.len()),
+                    span,
+                ));
+            }
+            None => {}
+        }
+
+        Ok(Value::Instance(instance))
+    }
+
+    fn call_function(
+        &mut self,
+        function: &Rc<FunctionValue>,
+        arguments: Vec<Value>,
+        span: Span,
+    ) -> Result<Value> {
+        if function.parameters.len() != arguments.len() {
+            return Err(runtime_error(
+                format!(
+                    "Expected {} arguments, found {}",
+                    function.parameters.len(),
+                    arguments.len()
+                ),
+                span,
+            ));
+        }
+        if self.call_depth >= MAX_CALL_DEPTH {
+            return Err(runtime_error(
+                String::from("Maximum call depth exceeded"),
+                span,
+            ));
+        }
+
+        let environment = Environment::new_child(&function.closure);
+        for (parameter, argument) in function.parameters.iter().zip(arguments) {
+            self.define_declared(parameter, argument, &environment)?;
+        }
+
+        self.call_depth += 1;
+        let result =
+            self.with_environment(environment, |this| this.visit_statement(&function.body));
+        self.call_depth -= 1;
+
+        let return_value = self.returning.take().unwrap_or(Value::Nil);
+        result?;
+
+        Ok(return_value)
+    }
+
+    fn call_builtin(&mut self, builtin: Builtin, arguments: Vec<Value>) -> Result<Value> {
+        match builtin {
+            Builtin::Print => {
+                let line = arguments
+                    .iter()
+                    .map(|argument| argument.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                writeln!(self.output, "{}", line).map_err(|error| {
+                    anyhow::Error::from(InterpreterError::OutputError {
+                        message: error.to_string(),
+                    })
+                })?;
+                Ok(Value::Nil)
+            }
+        }
+    }
+
+    fn evaluate_call(
+        &mut self,
+        callee: &ExpressionRef,
+        arguments: &ExpressionList,
+        span: Span,
+    ) -> Result<Value> {
+        // super(...) runs the constructor of the parent class on the current instance
+        if let Expression::Super { span: super_span } = callee.as_ref() {
+            let arguments = self.evaluate_arguments(arguments)?;
+            let (super_class, instance) = self.super_and_this(*super_span)?;
+
+            return match super_class.find_method(CONSTRUCTOR_NAME) {
+                Some((constructor, declaring_class)) => {
+                    let constructor = bind_method(&constructor, &instance, &declaring_class);
+                    self.call_function(&constructor, arguments, span)?;
+                    Ok(Value::Nil)
+                }
+                None => Ok(Value::Nil),
+            };
+        }
+
+        let callee_value = self.visit_expression(callee)?;
+
+        // 'a?.m()' skips the call and its arguments when 'a' is nil
+        if matches!(callee_value, Value::Nil)
+            && matches!(callee.as_ref(), Expression::Member { optional: true, .. })
+        {
+            return Ok(Value::Nil);
+        }
+
+        let arguments = self.evaluate_arguments(arguments)?;
+
+        match callee_value {
+            Value::Function(function) => self.call_function(&function, arguments, span),
+            Value::Builtin(builtin) => self.call_builtin(builtin, arguments),
+            value => Err(runtime_error(
+                format!("Value of type {} is not callable", value.type_name()),
+                callee.span(),
+            )),
+        }
+    }
+
+    fn evaluate_arguments(&mut self, arguments: &ExpressionList) -> Result<Vec<Value>> {
+        arguments
+            .iter()
+            .map(|argument| self.visit_expression(argument))
+            .collect()
+    }
+
+    fn super_and_this(&self, span: Span) -> Result<(Rc<ClassValue>, Rc<RefCell<InstanceValue>>)> {
+        let environment = self.environment.borrow();
+        match (environment.get("super"), environment.get("this")) {
+            (Some(Value::Class(super_class)), Some(Value::Instance(instance))) => {
+                Ok((super_class, instance))
+            }
+            _ => Err(runtime_error(
+                String::from("'super' used outside of a subclass method"),
+                span,
+            )),
+        }
+    }
+
+    fn evaluate_member(
+        &mut self,
+        computed: bool,
+        optional: bool,
+        object: &ExpressionRef,
+        property: &ExpressionRef,
+        span: Span,
+    ) -> Result<Value> {
+        if let Expression::Super { span: super_span } = object.as_ref() {
+            let property_name = identifier_name(property)?;
+            let (super_class, instance) = self.super_and_this(*super_span)?;
+
+            return match super_class.find_method(&property_name) {
+                Some((method, declaring_class)) => Ok(Value::Function(bind_method(
+                    &method,
+                    &instance,
+                    &declaring_class,
+                ))),
+                None => Err(runtime_error(
+                    format!("Undefined property '{}'", property_name),
+                    property.span(),
+                )),
+            };
+        }
+
+        let object_value = self.visit_expression(object)?;
+        if optional && matches!(object_value, Value::Nil) {
+            return Ok(Value::Nil);
+        }
+
+        if computed {
+            let index = self.visit_expression(property)?;
+            return match (&object_value, index) {
+                (Value::Array(elements), Value::Number(index)) => elements
+                    .borrow()
+                    .get(index as usize)
+                    .filter(|_| index >= 0)
+                    .cloned()
+                    .ok_or_else(|| runtime_error(format!("Index {} out of bounds", index), span)),
+                (Value::String(value), Value::Number(index)) => value
+                    .chars()
+                    .nth(index as usize)
+                    .filter(|_| index >= 0)
+                    .map(|character| Value::String(character.to_string()))
+                    .ok_or_else(|| runtime_error(format!("Index {} out of bounds", index), span)),
+                (_, index) => Err(runtime_error(
+                    format!(
+                        "Cannot index {} with {}",
+                        object_value.type_name(),
+                        index.type_name()
+                    ),
+                    span,
+                )),
+            };
+        }
+
+        let property_name = identifier_name(property)?;
+        match &object_value {
+            Value::Instance(instance) => {
+                if let Some(value) = instance.borrow().fields.get(&property_name) {
+                    return Ok(value.clone());
+                }
+
+                let class = Rc::clone(&instance.borrow().class);
+                match class.find_method(&property_name) {
+                    Some((method, declaring_class)) => Ok(Value::Function(bind_method(
+                        &method,
+                        instance,
+                        &declaring_class,
+                    ))),
+                    None => Err(runtime_error(
+                        format!("Undefined property '{}'", property_name),
+                        property.span(),
+                    )),
+                }
+            }
+            Value::String(value) if property_name == "length" => {
+                Ok(Value::Number(value.chars().count() as i32))
+            }
+            Value::Array(elements) if property_name == "length" => {
+                Ok(Value::Number(elements.borrow().len() as i32))
+            }
+            _ => Err(runtime_error(
+                format!(
+                    "Property '{}' does not exist on {}",
+                    property_name,
+                    object_value.type_name()
+                ),
+                property.span(),
+            )),
+        }
+    }
+
+    fn evaluate_assignment(
+        &mut self,
+        operator: &AssignmentOperator,
+        left: &ExpressionRef,
+        right: &ExpressionRef,
+        span: Span,
+    ) -> Result<Value> {
+        let binary_operator = match operator {
+            AssignmentOperator::Assign => None,
+            AssignmentOperator::AssignAdd => Some(BinaryOperator::Add),
+            AssignmentOperator::AssignSubtract => Some(BinaryOperator::Subtract),
+            AssignmentOperator::AssignMultiply => Some(BinaryOperator::Multiply),
+            AssignmentOperator::AssignDivide => Some(BinaryOperator::Divide),
+        };
+
+        match left.as_ref() {
+            Expression::Identifier {
+                name,
+                span: name_span,
+            } => {
+                let right_value = self.visit_expression(right)?;
+                let value = match binary_operator {
+                    Some(binary_operator) => {
+                        let left_value = self.visit_expression(left)?;
+                        binary_operation(binary_operator, left_value, right_value, span)?
+                    }
+                    None => right_value,
+                };
+
+                if !self.environment.borrow_mut().assign(name, value.clone()) {
+                    return Err(runtime_error(
+                        format!("Undefined variable '{}'", name),
+                        *name_span,
+                    ));
+                }
+
+                Ok(value)
+            }
+            Expression::Member {
+                computed,
+                object,
+                property,
+                span: member_span,
+                ..
+            } => {
+                let object_value = self.visit_expression(object)?;
+                let key = if *computed {
+                    self.visit_expression(property)?
+                } else {
+                    Value::String(identifier_name(property)?)
+                };
+                let right_value = self.visit_expression(right)?;
+
+                let value = match binary_operator {
+                    Some(binary_operator) => {
+                        let left_value = self.visit_expression(left)?;
+                        binary_operation(binary_operator, left_value, right_value, span)?
+                    }
+                    None => right_value,
+                };
+
+                match (&object_value, key) {
+                    (Value::Instance(instance), Value::String(field_name)) if !computed => {
+                        instance
+                            .borrow_mut()
+                            .fields
+                            .insert(field_name, value.clone());
+                    }
+                    (Value::Array(elements), Value::Number(index)) if *computed => {
+                        let mut elements = elements.borrow_mut();
+                        match elements.get_mut(index as usize).filter(|_| index >= 0) {
+                            Some(element) => *element = value.clone(),
+                            None => {
+                                return Err(runtime_error(
+                                    format!("Index {} out of bounds", index),
+                                    *member_span,
+                                ))
+                            }
+                        }
+                    }
+                    _ => {
+                        return Err(runtime_error(
+                            format!("Cannot assign to a member of {}", object_value.type_name()),
+                            *member_span,
+                        ))
+                    }
+                }
+
+                Ok(value)
+            }
+            _ => Err(runtime_error(
+                String::from("Invalid assignment target"),
+                left.span(),
+            )),
+        }
+    }
+
+    ///
+    /// Value of the first arm whose pattern matches the subject and whose
+    /// guard holds, the bindings of an arm live in a scope of their own
+    ///
+    fn evaluate_match(
+        &mut self,
+        subject: &ExpressionRef,
+        arms: &MatchArmList,
+        span: Span,
+    ) -> Result<Value> {
+        let subject = self.visit_expression(subject)?;
+
+        for arm in arms {
+            let environment = Environment::new_child(&self.environment);
+            if !self.bind_pattern(&arm.pattern, &subject, &environment)? {
+                continue;
+            }
+
+            let value = self.with_environment(environment, |this| {
+                if let Some(guard) = &arm.guard {
+                    if !this.evaluate_condition(guard)? {
+                        return Ok(None);
+                    }
+                }
+                this.visit_expression(&arm.value).map(Some)
+            })?;
+            if let Some(value) = value {
+                return Ok(value);
+            }
+        }
+
+        Err(runtime_error(
+            format!("No match arm matches {}", subject.type_name()),
+            span,
+        ))
+    }
+
+    ///
+    /// Whether the pattern matches the value, the bindings are defined in the
+    /// given environment
+    ///
+    fn bind_pattern(
+        &mut self,
+        pattern: &Pattern,
+        value: &Value,
+        environment: &EnvironmentRef,
+    ) -> Result<bool> {
+        match pattern {
+            Pattern::Wildcard {
+                type_annotation, ..
+            } => Ok(type_annotation
+                .as_ref()
+                .is_none_or(|type_annotation| value.is_of_type(type_annotation))),
+            Pattern::Binding {
+                name,
+                type_annotation,
+                ..
+            } => {
+                if let Some(type_annotation) = type_annotation {
+                    if !value.is_of_type(type_annotation) {
+                        return Ok(false);
+                    }
+                }
+                environment
+                    .borrow_mut()
+                    .define(&identifier_name(name)?, value.clone());
+                Ok(true)
+            }
+            Pattern::Literal { value: literal, .. } => {
+                Ok(self.visit_expression(literal)?.equals(value))
+            }
+            Pattern::EnumVariant {
+                enum_name,
+                variant,
+                arguments,
+                ..
+            } => {
+                let Value::Enum(enum_value) = value else {
+                    return Ok(false);
+                };
+                if enum_value.enum_name != identifier_name(enum_name)?
+                    || enum_value.variant != identifier_name(variant)?
+                {
+                    return Ok(false);
+                }
+
+                for (argument, value) in arguments.iter().zip(&enum_value.values) {
+                    if !self.bind_pattern(argument, value, environment)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+            Pattern::Array { elements, .. } => {
+                let Value::Array(values) = value else {
+                    return Ok(false);
+                };
+                let values = values.borrow().clone();
+                if values.len() != elements.len() {
+                    return Ok(false);
+                }
+
+                for (element, value) in elements.iter().zip(&values) {
+                    if !self.bind_pattern(element, value, environment)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+            Pattern::Object { span, .. } => Err(runtime_error(
+                String::from("Object patterns are only allowed in declarations"),
+                *span,
+            )),
+        }
+    }
+
+    ///
+    /// Define a declared name, or the bindings of a destructuring pattern,
+    /// in the given environment
+    ///
+    fn define_declared(
+        &mut self,
+        declared: &Expression,
+        value: Value,
+        environment: &EnvironmentRef,
+    ) -> Result<()> {
+        match declared {
+            Expression::Destructuring { pattern, .. } => {
+                self.destructure(pattern, value, environment)
+            }
+            _ => {
+                environment
+                    .borrow_mut()
+                    .define(&identifier_name(declared)?, value);
+                Ok(())
+            }
+        }
+    }
+
+    fn destructure(
+        &mut self,
+        pattern: &Pattern,
+        value: Value,
+        environment: &EnvironmentRef,
+    ) -> Result<()> {
+        match (pattern, &value) {
+            (Pattern::Wildcard { .. }, _) => Ok(()),
+            (Pattern::Binding { name, .. }, _) => {
+                environment
+                    .borrow_mut()
+                    .define(&identifier_name(name)?, value);
+                Ok(())
+            }
+            (Pattern::Object { properties, .. }, Value::Instance(instance)) => {
+                for property in properties {
+                    let field_name = identifier_name(&property.key)?;
+                    let Some(field) = instance.borrow().fields.get(&field_name).cloned() else {
+                        return Err(runtime_error(
+                            format!("Undefined field '{}'", field_name),
+                            property.key.span(),
+                        ));
+                    };
+                    self.destructure(&property.value, field, environment)?;
+                }
+                Ok(())
+            }
+            (Pattern::Array { elements, .. }, Value::Array(values)) => {
+                let values = values.borrow().clone();
+                for (index, element) in elements.iter().enumerate() {
+                    // Like the compiled code, only the named elements are read
+                    if matches!(element, Pattern::Wildcard { .. }) {
+                        continue;
+                    }
+                    let Some(value) = values.get(index).cloned() else {
+                        return Err(runtime_error(
+                            format!("Index {} out of bounds", index),
+                            element.span(),
+                        ));
+                    };
+                    self.destructure(element, value, environment)?;
+                }
+                Ok(())
+            }
+            _ => Err(runtime_error(
+                format!("Cannot destructure {}", value.type_name()),
+                pattern.span(),
+            )),
+        }
+    }
+
+    fn evaluate_condition(&mut self, condition: &ExpressionRef) -> Result<bool> {
+        match self.visit_expression(condition)? {
+            Value::Boolean(value) => Ok(value),
+            value => Err(runtime_error(
+                format!("Condition must be a boolean, found {}", value.type_name()),
+                condition.span(),
+            )),
+        }
+    }
+}
+
+impl AstVisitor for Interpreter<'_> {
+    type Output = Value;
+
+    fn visit_statement(&mut self, statement: &Statement) -> Result<Self::Output> {
+        match statement {
+            Statement::Program { body, .. } => self.execute_statement_list(body),
+            Statement::Block { body, .. } => {
+                let environment = Environment::new_child(&self.environment);
+                self.with_environment(environment, |this| this.execute_statement_list(body))
+            }
+            Statement::Empty { .. }
+            | Statement::InterfaceDeclaration { .. }
+            | Statement::EnumDeclaration { .. } => Ok(Value::Nil),
+            Statement::Expression { expression, .. } => self.visit_expression(expression),
+            Statement::VariableDeclaration { variables, .. } => {
+                for variable in variables {
+                    self.visit_expression(variable)?;
+                }
+                Ok(Value::Nil)
+            }
+            Statement::If {
+                condition,
+                consequent,
+                alternative,
+                ..
+            } => {
+                if self.evaluate_condition(condition)? {
+                    self.visit_statement(consequent)?;
+                } else if let Some(alternative) = alternative {
+                    self.visit_statement(alternative)?;
+                }
+                Ok(Value::Nil)
+            }
+            Statement::While { .. } | Statement::DoWhile { .. } | Statement::For { .. } => {
+                self.execute_loop(statement, None)
+            }
+            Statement::Labeled { label, body, .. } => {
+                self.execute_loop(body, Some(&identifier_name(label)?))
+            }
+            Statement::FunctionDeclaration { name, .. } => {
+                let function = self.define_function(statement)?;
+                self.environment
+                    .borrow_mut()
+                    .define(&identifier_name(name)?, Value::Function(function));
+                Ok(Value::Nil)
+            }
+            Statement::Return { argument, .. } => {
+                let value = match argument {
+                    Some(argument) => self.visit_expression(argument)?,
+                    None => Value::Nil,
+                };
+                self.returning = Some(value);
+                Ok(Value::Nil)
+            }
+            Statement::Break { label, .. } => {
+                self.jumping = Some(Jump::Break(
+                    label.as_deref().map(identifier_name).transpose()?,
+                ));
+                Ok(Value::Nil)
+            }
+            Statement::Continue { label, .. } => {
+                self.jumping = Some(Jump::Continue(
+                    label.as_deref().map(identifier_name).transpose()?,
+                ));
+                Ok(Value::Nil)
+            }
+            Statement::ClassDeclaration {
+                name,
+                super_class,
+                members,
+                ..
+            } => {
+                self.define_class(name, super_class, members)?;
+                Ok(Value::Nil)
+            }
+        }
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) -> Result<Self::Output> {
+        match expression {
+            Expression::Variable {
+                identifier,
+                initializer,
+                ..
+            } => {
+                let value = match initializer {
+                    Some(initializer) => self.visit_expression(initializer)?,
+                    None => Value::Nil,
+                };
+                let environment = Rc::clone(&self.environment);
+                self.define_declared(identifier, value, &environment)?;
+                Ok(Value::Nil)
+            }
+            Expression::Assignment {
+                operator,
+                left,
+                right,
+                span,
+            } => self.evaluate_assignment(operator, left, right, *span),
+            Expression::Binary {
+                operator,
+                left,
+                right,
+                span,
+            } => {
+                let left_value = self.visit_expression(left)?;
+                let right_value = self.visit_expression(right)?;
+                binary_operation(*operator, left_value, right_value, *span)
+            }
+            Expression::Unary {
+                operator,
+                right,
+                span,
+            } => match (operator, self.visit_expression(right)?) {
+                (UnaryOperator::Plus, Value::Number(value)) => Ok(Value::Number(value)),
+                (UnaryOperator::Minus, Value::Number(value)) => value
+                    .checked_neg()
+                    .map(Value::Number)
+                    .ok_or_else(|| runtime_error(String::from("Numeric overflow"), *span)),
+                (UnaryOperator::Not, Value::Boolean(value)) => Ok(Value::Boolean(!value)),
+                (operator, value) => Err(runtime_error(
+                    format!(
+                        "Operator '{}' cannot be applied to {}",
+                        operator,
+                        value.type_name()
+                    ),
+                    *span,
+                )),
+            },
+            Expression::EnumVariant {
+                enum_name,
+                variant,
+                arguments,
+                ..
+            } => Ok(Value::Enum(Rc::new(EnumValue {
+                enum_name: identifier_name(enum_name)?,
+                variant: identifier_name(variant)?,
+                values: self.evaluate_arguments(arguments)?,
+            }))),
+            Expression::Match {
+                subject,
+                arms,
+                span,
+            } => self.evaluate_match(subject, arms, *span),
+            Expression::Destructuring { span, .. } => Err(runtime_error(
+                String::from("Destructuring is only allowed in declarations"),
+                *span,
+            )),
+            Expression::TypeTest {
+                expression,
+                type_annotation,
+                ..
+            } => Ok(Value::Boolean(
+                self.visit_expression(expression)?
+                    .is_of_type(type_annotation),
+            )),
+            Expression::Logical {
+                operator,
+                left,
+                right,
+                span,
+            } => {
+                // '??' keeps any value that is not nil
+                if *operator == LogicalOperator::NilCoalescing {
+                    return match self.visit_expression(left)? {
+                        Value::Nil => self.visit_expression(right),
+                        value => Ok(value),
+                    };
+                }
+
+                let left_value = match self.visit_expression(left)? {
+                    Value::Boolean(value) => value,
+                    value => {
+                        return Err(runtime_error(
+                            format!(
+                                "Operator '{}' cannot be applied to {}",
+                                operator,
+                                value.type_name()
+                            ),
+                            *span,
+                        ))
+                    }
+                };
+
+                match (operator, left_value) {
+                    (LogicalOperator::And, false) => Ok(Value::Boolean(false)),
+                    (LogicalOperator::Or, true) => Ok(Value::Boolean(true)),
+                    _ => match self.visit_expression(right)? {
+                        Value::Boolean(value) => Ok(Value::Boolean(value)),
+                        value => Err(runtime_error(
+                            format!(
+                                "Operator '{}' cannot be applied to {}",
+                                operator,
+                                value.type_name()
+                            ),
+                            *span,
+                        )),
+                    },
+                }
+            }
+            Expression::BooleanLiteral { value, .. } => Ok(Value::Boolean(*value)),
+            Expression::NilLiteral { .. } => Ok(Value::Nil),
+            Expression::StringLiteral { value, .. } => Ok(Value::String(value.clone())),
+            Expression::NumericLiteral { value, .. } => Ok(Value::Number(*value)),
+            Expression::ArrayLiteral { elements, .. } => {
+                let mut values = vec![];
+                for element in elements {
+                    values.push(self.visit_expression(element)?);
+                }
+                Ok(Value::Array(Rc::new(RefCell::new(values))))
+            }
+            Expression::Identifier { name, span } => {
+                if let Some(value) = self.environment.borrow().get(name) {
+                    return Ok(value);
+                }
+
+                Builtin::from_name(name)
+                    .map(Value::Builtin)
+                    .ok_or_else(|| runtime_error(format!("Undefined variable '{}'", name), *span))
+            }
+            Expression::Member {
+                computed,
+                optional,
+                object,
+                property,
+                span,
+            } => self.evaluate_member(*computed, *optional, object, property, *span),
+            Expression::Call {
+                callee,
+                arguments,
+                span,
+            } => self.evaluate_call(callee, arguments, *span),
+            Expression::This { span } => self.environment.borrow().get("this").ok_or_else(|| {
+                runtime_error(String::from("'this' used outside of a class"), *span)
+            }),
+            Expression::Super { span } => self.environment.borrow().get("super").ok_or_else(|| {
+                runtime_error(
+                    String::from("'super' used outside of a subclass method"),
+                    *span,
+                )
+            }),
+            Expression::New {
+                callee,
+                arguments,
+                span,
+            } => {
+                let class = match self.visit_expression(callee)? {
+                    Value::Class(class) => class,
+                    value => {
+                        return Err(runtime_error(
+                            format!("Cannot instantiate {}", value.type_name()),
+                            callee.span(),
+                        ))
+                    }
+                };
+                let arguments = self.evaluate_arguments(arguments)?;
+                self.instantiate(&class, arguments, *span)
+            }
+        }
+    }
+}
+
+///
+/// Bind 'this' and 'super' for a method found on the given declaring class
+///
+fn bind_method(
+    method: &Rc<FunctionValue>,
+    instance: &Rc<RefCell<InstanceValue>>,
+    declaring_class: &Rc<ClassValue>,
+) -> Rc<FunctionValue> {
+    let environment = Environment::new_child(&method.closure);
+    environment
+        .borrow_mut()
+        .define("this", Value::Instance(Rc::clone(instance)));
+    if let Some(super_class) = &declaring_class.super_class {
+        environment
+            .borrow_mut()
+            .define("super", Value::Class(Rc::clone(super_class)));
+    }
+
+    Rc::new(FunctionValue {
+        name: method.name.clone(),
+        parameters: method.parameters.clone(),
+        body: Rc::clone(&method.body),
+        closure: environment,
+    })
+}
+
+fn binary_operation(
+    operator: BinaryOperator,
+    left: Value,
+    right: Value,
+    span: Span,
+) -> Result<Value> {
+    let overflow = || runtime_error(String::from("Numeric overflow"), span);
+
+    match (operator, &left, &right) {
+        (BinaryOperator::Add, Value::Number(left), Value::Number(right)) => left
+            .checked_add(*right)
+            .map(Value::Number)
+            .ok_or_else(overflow),
+        (BinaryOperator::Add, Value::String(left), Value::String(right)) => {
+            Ok(Value::String(format!("{}{}", left, right)))
+        }
+        (BinaryOperator::Subtract, Value::Number(left), Value::Number(right)) => left
+            .checked_sub(*right)
+            .map(Value::Number)
+            .ok_or_else(overflow),
+        (BinaryOperator::Multiply, Value::Number(left), Value::Number(right)) => left
+            .checked_mul(*right)
+            .map(Value::Number)
+            .ok_or_else(overflow),
+        (BinaryOperator::Divide, Value::Number(_), Value::Number(0)) => {
+            Err(runtime_error(String::from("Division by zero"), span))
+        }
+        (BinaryOperator::Divide, Value::Number(left), Value::Number(right)) => left
+            .checked_div(*right)
+            .map(Value::Number)
+            .ok_or_else(overflow),
+        (BinaryOperator::Equal, _, _) => Ok(Value::Boolean(left.equals(&right))),
+        (BinaryOperator::NotEqual, _, _) => Ok(Value::Boolean(!left.equals(&right))),
+        (BinaryOperator::GreaterThan, Value::Number(left), Value::Number(right)) => {
+            Ok(Value::Boolean(left > right))
+        }
+        (BinaryOperator::GreaterThanOrEqualTo, Value::Number(left), Value::Number(right)) => {
+            Ok(Value::Boolean(left >= right))
+        }
+        (BinaryOperator::LessThan, Value::Number(left), Value::Number(right)) => {
+            Ok(Value::Boolean(left < right))
+        }
+        (BinaryOperator::LessThanOrEqualTo, Value::Number(left), Value::Number(right)) => {
+            Ok(Value::Boolean(left <= right))
+        }
+        (BinaryOperator::GreaterThan, Value::String(left), Value::String(right)) => {
+            Ok(Value::Boolean(left > right))
+        }
+        (BinaryOperator::GreaterThanOrEqualTo, Value::String(left), Value::String(right)) => {
+            Ok(Value::Boolean(left >= right))
+        }
+        (BinaryOperator::LessThan, Value::String(left), Value::String(right)) => {
+            Ok(Value::Boolean(left < right))
+        }
+        (BinaryOperator::LessThanOrEqualTo, Value::String(left), Value::String(right)) => {
+            Ok(Value::Boolean(left <= right))
+        }
+        _ => Err(runtime_error(
+            format!(
+                "Operator '{}' cannot be applied to {} and {}",
+                operator,
+                left.type_name(),
+                right.type_name()
+            ),
+            span,
+        )),
+    }
+}
+
+fn identifier_name(expression: &Expression) -> Result<String> {
+    match expression {
+        Expression::Identifier { name, .. } => Ok(name.clone()),
+        _ => Err(runtime_error(
+            String::from("Expected an identifier"),
+            expression.span(),
+        )),
+    }
+}
+
+fn runtime_error(message: String, span: Span) -> anyhow::Error {
+    InterpreterError::RuntimeError { message, span }.into()
+}
+
+fn into_interpreter_error(error: anyhow::Error) -> InterpreterError {
+    match error.downcast::<InterpreterError>() {
+        Ok(error) => error,
+        Err(error) => InterpreterError::RuntimeError {
+            message: error.to_string(),
+            span: Span::default(),
+        },
+    }
+}
@@ -0,0 +1,56 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use super::Value;
+
+pub type EnvironmentRef = Rc<RefCell<Environment>>;
+
+///
+/// Runtime bindings of a single scope
+///
+#[derive(Debug, Default)]
+pub struct Environment {
+    values: HashMap<String, Value>,
+    parent: Option<EnvironmentRef>,
+}
+
+impl Environment {
+    pub fn new_global() -> EnvironmentRef {
+        Rc::new(RefCell::new(Environment::default()))
+    }
+
+    pub fn new_child(parent: &EnvironmentRef) -> EnvironmentRef {
+        Rc::new(RefCell::new(Environment {
+            values: HashMap::new(),
+            parent: Some(Rc::clone(parent)),
+        }))
+    }
+
+    pub fn define(&mut self, name: &str, value: Value) {
+        self.values.insert(String::from(name), value);
+    }
+
+    pub fn get(&self, name: &str) -> Option<Value> {
+        match self.values.get(name) {
+            Some(value) => Some(value.clone()),
+            None => self
+                .parent
+                .as_ref()
+                .and_then(|parent| parent.borrow().get(name)),
+        }
+    }
+
+    ///
+    /// Update an existing binding, returns false if the name is not bound
+    ///
+    pub fn assign(&mut self, name: &str, value: Value) -> bool {
+        if let Some(slot) = self.values.get_mut(name) {
+            *slot = value;
+            return true;
+        }
+
+        match &self.parent {
+            Some(parent) => parent.borrow_mut().assign(name, value),
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use thiserror::Error;
+
+use crate::lexer::Span;
+
+#[derive(Error, Debug)]
+pub enum InterpreterError {
+    #[error("Runtime error: {message}")]
+    RuntimeError { message: String, span: Span },
+
+    #[error("Output error: {message}")]
+    OutputError { message: String },
+}
+
+impl InterpreterError {
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            InterpreterError::RuntimeError { span, .. } => Some(*span),
+            InterpreterError::OutputError { .. } => None,
+        }
+    }
+}
+
+pub type InterpreterResult<T> = Result<T, InterpreterError>;
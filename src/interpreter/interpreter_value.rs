@@ -0,0 +1,204 @@
+use core::fmt;
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use crate::ast::{Expression, PrimitiveType, Statement, Type};
+
+use super::interpreter_environment::EnvironmentRef;
+
+///
+/// Runtime value
+///
+#[derive(Debug, Clone)]
+pub enum Value {
+    Number(i32),
+    String(String),
+    Boolean(bool),
+    Nil,
+    Function(Rc<FunctionValue>),
+    Class(Rc<ClassValue>),
+    Instance(Rc<RefCell<InstanceValue>>),
+    Array(Rc<RefCell<Vec<Value>>>),
+    Enum(Rc<EnumValue>),
+    Builtin(Builtin),
+}
+
+///
+/// Functions implemented by the interpreter itself
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Builtin {
+    Print,
+}
+
+impl Builtin {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "print" => Some(Builtin::Print),
+            _ => None,
+        }
+    }
+}
+
+///
+/// User defined function or bound method together with its closure
+///
+pub struct FunctionValue {
+    pub name: String,
+
+    ///
+    /// Parameter names or destructuring patterns
+    ///
+    pub parameters: Vec<Expression>,
+    pub body: Rc<Statement>,
+    pub closure: EnvironmentRef,
+}
+
+impl fmt::Debug for FunctionValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FunctionValue({})", self.name)
+    }
+}
+
+///
+/// Class with its methods and field initializers
+///
+#[derive(Debug)]
+pub struct ClassValue {
+    pub name: String,
+    pub super_class: Option<Rc<ClassValue>>,
+    pub methods: HashMap<String, Rc<FunctionValue>>,
+    pub fields: Vec<(String, Option<Expression>)>,
+}
+
+impl ClassValue {
+    ///
+    /// Find a method in the class or its ancestors together with the class declaring it
+    ///
+    pub fn find_method(self: &Rc<Self>, name: &str) -> Option<(Rc<FunctionValue>, Rc<ClassValue>)> {
+        let mut current = Some(Rc::clone(self));
+
+        while let Some(class) = current {
+            if let Some(method) = class.methods.get(name) {
+                return Some((Rc::clone(method), class));
+            }
+            current = class.super_class.clone();
+        }
+
+        None
+    }
+}
+
+///
+/// Instance of a class
+///
+pub struct InstanceValue {
+    pub class: Rc<ClassValue>,
+    pub fields: HashMap<String, Value>,
+}
+
+impl fmt::Debug for InstanceValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "InstanceValue({})", self.class.name)
+    }
+}
+
+///
+/// Value of an enum built from one of its variants, the payload can not be
+/// changed once built
+///
+#[derive(Debug)]
+pub struct EnumValue {
+    pub enum_name: String,
+    pub variant: String,
+    pub values: Vec<Value>,
+}
+
+impl Value {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Boolean(_) => "boolean",
+            Value::Nil => "nil",
+            Value::Function(_) | Value::Builtin(_) => "function",
+            Value::Class(_) => "class",
+            Value::Instance(_) => "instance",
+            Value::Array(_) => "array",
+            Value::Enum(_) => "enum",
+        }
+    }
+
+    pub fn equals(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Number(left), Value::Number(right)) => left == right,
+            (Value::String(left), Value::String(right)) => left == right,
+            (Value::Boolean(left), Value::Boolean(right)) => left == right,
+            (Value::Nil, Value::Nil) => true,
+            (Value::Function(left), Value::Function(right)) => Rc::ptr_eq(left, right),
+            (Value::Class(left), Value::Class(right)) => Rc::ptr_eq(left, right),
+            (Value::Instance(left), Value::Instance(right)) => Rc::ptr_eq(left, right),
+            (Value::Array(left), Value::Array(right)) => Rc::ptr_eq(left, right),
+            (Value::Enum(left), Value::Enum(right)) => {
+                left.enum_name == right.enum_name
+                    && left.variant == right.variant
+                    && left
+                        .values
+                        .iter()
+                        .zip(&right.values)
+                        .all(|(left, right)| left.equals(right))
+            }
+            (Value::Builtin(left), Value::Builtin(right)) => left == right,
+            _ => false,
+        }
+    }
+
+    ///
+    /// Whether the value belongs to a type, instances belong to their class
+    /// and all of its ancestors
+    ///
+    pub fn is_of_type(&self, value_type: &Type) -> bool {
+        match (self, value_type) {
+            (_, Type::Union(members)) => members.iter().any(|member| self.is_of_type(member)),
+            (Value::Nil, Type::Nil | Type::Nullable(_)) => true,
+            (_, Type::Nullable(value_type)) => self.is_of_type(value_type),
+            (Value::Number(_), Type::Primitive(PrimitiveType::Number)) => true,
+            (Value::String(_), Type::Primitive(PrimitiveType::String)) => true,
+            (Value::Boolean(_), Type::Primitive(PrimitiveType::Boolean)) => true,
+            (Value::Instance(instance), Type::Class { name, .. }) => {
+                let mut current = Some(Rc::clone(&instance.borrow().class));
+                while let Some(class) = current {
+                    if &class.name == name {
+                        return true;
+                    }
+                    current = class.super_class.clone();
+                }
+                false
+            }
+            (Value::Array(elements), Type::Array(element_type)) => elements
+                .borrow()
+                .iter()
+                .all(|element| element.is_of_type(element_type)),
+            (Value::Enum(value), Type::Enum(name) | Type::Class { name, .. }) => {
+                &value.enum_name == name
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Number(value) => write!(f, "{}", value),
+            Value::String(value) => write!(f, "{}", value),
+            Value::Boolean(value) => write!(f, "{}", value),
+            Value::Nil => write!(f, "nil"),
+            Value::Function(function) => write!(f, "<def {}>", function.name),
+            Value::Class(class) => write!(f, "<class {}>", class.name),
+            Value::Instance(instance) => write!(f, "<{} instance>", instance.borrow().class.name),
+            Value::Array(_) => write!(f, "<array>"),
+            Value::Enum(value) => write!(f, "{}::{}", value.enum_name, value.variant),
+            Value::Builtin(_) => write!(f, "<builtin>"),
+        }
+    }
+}
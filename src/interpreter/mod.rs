@@ -0,0 +1,9 @@
+mod interpreter;
+mod interpreter_environment;
+mod interpreter_error;
+mod interpreter_value;
+mod tests;
+
+pub(crate) use self::interpreter::{with_interpreter_stack, Interpreter};
+pub(crate) use self::interpreter_error::*;
+pub(crate) use self::interpreter_value::*;
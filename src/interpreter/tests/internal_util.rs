@@ -0,0 +1,91 @@
+use crate::checker::TypeChecker;
+use crate::interpreter::{with_interpreter_stack, Interpreter};
+use crate::parser::{parse_root_statement, Parser};
+use serde::Deserialize;
+use std::fs;
+
+#[derive(Debug, Deserialize)]
+pub(super) struct YamlTestCase {
+    description: String,
+    source: String,
+    expected_output: String,
+    #[serde(default)]
+    expected_error: Option<String>,
+}
+
+pub(super) fn execute_yaml_test(test_case: &YamlTestCase) {
+    let Ok(mut parser) = Parser::new(&test_case.source) else {
+        panic!(
+            "Failed to parse the test case source:\n{}\n",
+            test_case.source
+        )
+    };
+    let Ok(ast) = parse_root_statement(&mut parser) else {
+        panic!(
+            "Failed to parse the test case source:\n{}\n",
+            test_case.source
+        )
+    };
+    if let Err(error) = TypeChecker::new().check_program(&ast) {
+        panic!("Failed to check the test case source: {}", error);
+    }
+
+    let (output, actual_error) = with_interpreter_stack(|| {
+        let mut output: Vec<u8> = vec![];
+        let error = Interpreter::new(&mut output)
+            .execute(&ast)
+            .err()
+            .map(|error| error.to_string());
+        (output, error)
+    });
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        test_case.expected_output
+    );
+    assert_eq!(actual_error, test_case.expected_error);
+}
+
+pub(super) fn load_yaml_test_cases(path: &str) -> Vec<YamlTestCase> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(error) => {
+            eprintln!("Failed to read the test yaml file: {}", error);
+            eprintln!("Path: {}", path);
+            panic!("Could not read test file: {}", error);
+        }
+    };
+
+    // Parse multiple documents
+    let mut test_cases = Vec::new();
+
+    for document in serde_yaml::Deserializer::from_str(&content) {
+        match YamlTestCase::deserialize(document) {
+            Ok(test_case) => test_cases.push(test_case),
+            Err(e) => {
+                eprintln!("Failed to parse a YAML document: {}", e);
+                eprintln!("Path: {}", path);
+                panic!("Could not parse YAML content: {}", e);
+            }
+        }
+    }
+
+    // Verify that we found some test cases
+    if test_cases.is_empty() {
+        eprintln!("No test cases found in file: {}", path);
+        panic!("File contained no valid test cases");
+    }
+
+    test_cases
+}
+
+pub(super) fn execute_yaml_test_cases(path: &str) {
+    let test_cases = load_yaml_test_cases(path);
+
+    for (index, test_case) in test_cases.iter().enumerate() {
+        println!("Testing case #{}:\n{}\n", index + 1, test_case.description);
+        execute_yaml_test(test_case);
+    }
+
+    println!("All {} test cases passed!", test_cases.len());
+}
@@ -0,0 +1,21 @@
+use super::internal_util::execute_yaml_test_cases;
+
+#[test]
+fn test_interpreter_classes() {
+    execute_yaml_test_cases("tests/interpreter/test_interpreter_classes.yaml");
+}
+
+#[test]
+fn test_interpreter_control_flow() {
+    execute_yaml_test_cases("tests/interpreter/test_interpreter_control_flow.yaml");
+}
+
+#[test]
+fn test_interpreter_expressions() {
+    execute_yaml_test_cases("tests/interpreter/test_interpreter_expressions.yaml");
+}
+
+#[test]
+fn test_interpreter_functions() {
+    execute_yaml_test_cases("tests/interpreter/test_interpreter_functions.yaml");
+}
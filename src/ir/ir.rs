@@ -0,0 +1,321 @@
+use crate::ast::{BinaryOperator, Type, UnaryOperator};
+
+pub type ValueId = usize;
+pub type BlockId = usize;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Constant {
+    Number(i32),
+    Boolean(bool),
+    String(String),
+    Nil,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InstructionKind {
+    Constant(Constant),
+
+    ///
+    /// Value of a variable declared without an initializer
+    ///
+    Undefined,
+
+    Unary {
+        operator: UnaryOperator,
+        operand: ValueId,
+    },
+
+    Binary {
+        operator: BinaryOperator,
+        left: ValueId,
+        right: ValueId,
+    },
+
+    ///
+    /// Value picked by the predecessor block control came from, always at
+    /// the start of its block
+    ///
+    Phi {
+        incoming: Vec<(BlockId, ValueId)>,
+    },
+
+    LoadGlobal {
+        name: String,
+    },
+
+    StoreGlobal {
+        name: String,
+        value: ValueId,
+    },
+
+    ///
+    /// Function used as a value
+    ///
+    Function {
+        name: String,
+    },
+
+    ///
+    /// Direct call of a function, a method or a builtin
+    ///
+    Call {
+        function: String,
+        arguments: Vec<ValueId>,
+    },
+
+    ///
+    /// Call of a function value
+    ///
+    CallIndirect {
+        callee: ValueId,
+        arguments: Vec<ValueId>,
+    },
+
+    ///
+    /// Call dispatched on the runtime class of the object, the class is
+    /// the one declaring the method in the static type
+    ///
+    CallMethod {
+        object: ValueId,
+        class_name: String,
+        method: String,
+        arguments: Vec<ValueId>,
+    },
+
+    ///
+    /// Allocation of a class instance with all its fields set to nil
+    ///
+    New {
+        class_name: String,
+    },
+
+    ///
+    /// Allocation of an array of the given length with all its elements set
+    /// to nil
+    ///
+    NewArray {
+        length: ValueId,
+    },
+
+    LoadField {
+        object: ValueId,
+        class_name: String,
+        field: String,
+    },
+
+    StoreField {
+        object: ValueId,
+        class_name: String,
+        field: String,
+        value: ValueId,
+    },
+
+    LoadIndex {
+        object: ValueId,
+        index: ValueId,
+    },
+
+    StoreIndex {
+        object: ValueId,
+        index: ValueId,
+        value: ValueId,
+    },
+
+    ///
+    /// Length of a string or an array
+    ///
+    Length {
+        object: ValueId,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    ///
+    /// Value defined by the instruction, none when it produces nothing
+    ///
+    pub result: Option<ValueId>,
+    pub kind: InstructionKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Terminator {
+    Jump(BlockId),
+
+    Branch {
+        condition: ValueId,
+        consequent: BlockId,
+        alternative: BlockId,
+    },
+
+    Return(Option<ValueId>),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IrBlock {
+    pub instructions: Vec<Instruction>,
+
+    ///
+    /// Last instruction of the block, none for a loop that never exits
+    ///
+    pub terminator: Option<Terminator>,
+}
+
+///
+/// Function in SSA form, every value is defined once and has a type
+///
+/// Parameters are the first values, methods take the instance as their
+/// first parameter. Control starts in the first block
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IrFunction {
+    pub name: String,
+    pub parameters: Vec<ValueId>,
+    pub return_type: Type,
+    pub value_types: Vec<Type>,
+    pub blocks: Vec<IrBlock>,
+}
+
+///
+/// Memory layout of a class, inherited fields are not repeated
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IrClass {
+    pub name: String,
+    pub super_class: Option<String>,
+    pub fields: Vec<(String, Type)>,
+    /// Printed names of the variants of an enum by their tag, empty for
+    /// classes
+    pub variants: Vec<String>,
+}
+
+///
+/// Lowered program, the top level statements become the program function
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IrModule {
+    pub globals: Vec<(String, Type)>,
+    pub classes: Vec<IrClass>,
+    pub functions: Vec<IrFunction>,
+}
+
+#[allow(dead_code)]
+impl IrModule {
+    pub fn function(&self, name: &str) -> Option<&IrFunction> {
+        self.functions.iter().find(|function| function.name == name)
+    }
+
+    pub fn class(&self, name: &str) -> Option<&IrClass> {
+        self.classes.iter().find(|class| class.name == name)
+    }
+}
+
+impl InstructionKind {
+    ///
+    /// Values read by the instruction
+    ///
+    pub fn operands(&self) -> Vec<ValueId> {
+        let mut operands = vec![];
+        self.clone()
+            .for_each_operand_mut(&mut |operand| operands.push(*operand));
+        operands
+    }
+
+    pub fn for_each_operand_mut(&mut self, callback: &mut dyn FnMut(&mut ValueId)) {
+        match self {
+            InstructionKind::Constant(_)
+            | InstructionKind::Undefined
+            | InstructionKind::LoadGlobal { .. }
+            | InstructionKind::Function { .. }
+            | InstructionKind::New { .. } => {}
+            InstructionKind::Unary { operand, .. } => callback(operand),
+            InstructionKind::Binary { left, right, .. } => {
+                callback(left);
+                callback(right);
+            }
+            InstructionKind::Phi { incoming } => {
+                for (_, value) in incoming {
+                    callback(value);
+                }
+            }
+            InstructionKind::StoreGlobal { value, .. } => callback(value),
+            InstructionKind::Call { arguments, .. } => {
+                for argument in arguments {
+                    callback(argument);
+                }
+            }
+            InstructionKind::CallIndirect { callee, arguments } => {
+                callback(callee);
+                for argument in arguments {
+                    callback(argument);
+                }
+            }
+            InstructionKind::CallMethod {
+                object, arguments, ..
+            } => {
+                callback(object);
+                for argument in arguments {
+                    callback(argument);
+                }
+            }
+            InstructionKind::LoadField { object, .. } | InstructionKind::Length { object } => {
+                callback(object)
+            }
+            InstructionKind::NewArray { length } => callback(length),
+            InstructionKind::StoreField { object, value, .. } => {
+                callback(object);
+                callback(value);
+            }
+            InstructionKind::LoadIndex { object, index } => {
+                callback(object);
+                callback(index);
+            }
+            InstructionKind::StoreIndex {
+                object,
+                index,
+                value,
+            } => {
+                callback(object);
+                callback(index);
+                callback(value);
+            }
+        }
+    }
+}
+
+impl Terminator {
+    pub fn successors(&self) -> Vec<BlockId> {
+        match self {
+            Terminator::Jump(target) => vec![*target],
+            Terminator::Branch {
+                consequent,
+                alternative,
+                ..
+            } => vec![*consequent, *alternative],
+            Terminator::Return(_) => vec![],
+        }
+    }
+
+    pub fn for_each_operand_mut(&mut self, callback: &mut dyn FnMut(&mut ValueId)) {
+        match self {
+            Terminator::Jump(_) | Terminator::Return(None) => {}
+            Terminator::Branch { condition, .. } => callback(condition),
+            Terminator::Return(Some(value)) => callback(value),
+        }
+    }
+
+    pub fn for_each_successor_mut(&mut self, callback: &mut dyn FnMut(&mut BlockId)) {
+        match self {
+            Terminator::Jump(target) => callback(target),
+            Terminator::Branch {
+                consequent,
+                alternative,
+                ..
+            } => {
+                callback(consequent);
+                callback(alternative);
+            }
+            Terminator::Return(_) => {}
+        }
+    }
+}
@@ -0,0 +1,3057 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::{
+    ast::{
+        AssignmentOperator, BinaryOperator, ClassMember, ClassMemberList, EnumVariantList,
+        Expression, ExpressionList, ExpressionRef, LogicalOperator, MatchArm, MatchArmList,
+        ParameterList, Pattern, PrimitiveType, Statement, StatementList, StatementRef, Type,
+        UnaryOperator, CONSTRUCTOR_NAME,
+    },
+    checker::BUILTIN_FUNCTIONS,
+    lexer::Span,
+};
+
+use super::{
+    BlockId, Constant, Instruction, InstructionKind, IrBlock, IrClass, IrError, IrFunction,
+    IrModule, IrResult, Terminator, ValueId,
+};
+
+///
+/// Name of the function holding the top level statements
+///
+pub const PROGRAM_FUNCTION_NAME: &str = "program";
+
+///
+/// Name of the method that runs the field initializers of a class, it is
+/// called on new instances before the constructor
+///
+pub const FIELD_INITIALIZER_NAME: &str = "<init>";
+
+///
+/// Field of an enum holding the index of its variant
+///
+const ENUM_TAG_FIELD: &str = "tag";
+
+type VariableId = usize;
+
+///
+/// Current SSA value of every local variable
+///
+type Definitions = BTreeMap<VariableId, ValueId>;
+
+///
+/// Lower a type checked program into SSA form
+///
+/// Variables declared directly in the program body are globals, every
+/// other variable lives in SSA values. Nested functions are lowered to
+/// functions of their own, reading a local variable of an enclosing
+/// function is reported since closures have no representation yet
+///
+pub fn lower_program(program: &Statement) -> IrResult<IrModule> {
+    let mut builder = IrBuilder {
+        module: IrModule::default(),
+        classes: HashMap::new(),
+        class_order: vec![],
+        interfaces: HashSet::new(),
+        enums: HashMap::new(),
+        scopes: vec![HashMap::new()],
+        functions: vec![],
+        function_names: HashSet::new(),
+        next_variable: 0,
+        variable_types: HashMap::new(),
+    };
+
+    let slot = builder.reserve_function(String::from(PROGRAM_FUNCTION_NAME));
+    builder.begin_function(PROGRAM_FUNCTION_NAME, &Type::Void, None);
+    builder.lower_statement(program)?;
+    builder.end_function(slot);
+
+    for class_name in &builder.class_order {
+        let layout = &builder.classes[class_name];
+        builder.module.classes.push(IrClass {
+            name: class_name.clone(),
+            super_class: layout.super_class.clone(),
+            fields: layout.fields.clone(),
+            variants: builder
+                .enums
+                .get(class_name)
+                .map(|variants| {
+                    variants
+                        .iter()
+                        .map(|variant| format!("{}::{}", class_name, variant))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        });
+    }
+
+    Ok(builder.module)
+}
+
+#[derive(Debug, Clone)]
+enum Binding {
+    Global {
+        variable_type: Type,
+    },
+    Local {
+        function: usize,
+        variable: VariableId,
+    },
+    Function {
+        name: String,
+        function_type: Type,
+    },
+}
+
+///
+/// Fields and methods declared by a class, inherited members are not included
+///
+#[derive(Debug, Clone)]
+struct ClassLayout {
+    super_class: Option<String>,
+    fields: Vec<(String, Type)>,
+    methods: HashMap<String, Type>,
+    has_initializers: bool,
+}
+
+///
+/// Function being lowered, nested function declarations push a new one
+///
+struct FunctionState {
+    function: IrFunction,
+
+    ///
+    /// Block the next instruction goes into, none after a return or a jump
+    ///
+    current: Option<BlockId>,
+
+    definitions: Definitions,
+    this: Option<ValueId>,
+    class_name: Option<String>,
+
+    ///
+    /// Loops enclosing the statement being lowered, innermost last
+    ///
+    loops: Vec<LoopJumps>,
+
+    ///
+    /// Span of the nil literal each untyped nil value comes from
+    ///
+    nil_spans: HashMap<ValueId, Span>,
+}
+
+///
+/// Loop being lowered, with the ends of the 'break' and 'continue'
+/// statements jumping out of its body and the variable values at them
+///
+struct LoopJumps {
+    label: Option<String>,
+    breaks: Vec<(BlockId, Definitions)>,
+    continues: Vec<(BlockId, Definitions)>,
+}
+
+///
+/// Blocks where the tests of a match arm fail, with the variable values at
+/// their end, they all continue with the next arm
+///
+struct Failures {
+    next: BlockId,
+    ends: Vec<(BlockId, Definitions)>,
+}
+
+struct IrBuilder {
+    module: IrModule,
+    classes: HashMap<String, ClassLayout>,
+    class_order: Vec<String>,
+
+    ///
+    /// Interfaces are only checked, values of their types are not lowered
+    ///
+    interfaces: HashSet<String>,
+
+    ///
+    /// Variant names of every enum in declaration order, the index is the tag
+    ///
+    enums: HashMap<String, Vec<String>>,
+    scopes: Vec<HashMap<String, Binding>>,
+    functions: Vec<FunctionState>,
+    function_names: HashSet<String>,
+    next_variable: VariableId,
+    variable_types: HashMap<VariableId, Type>,
+}
+
+impl IrBuilder {
+    fn state(&mut self) -> &mut FunctionState {
+        self.functions
+            .last_mut()
+            .expect("A function should always be lowered")
+    }
+
+    fn function_index(&self) -> usize {
+        self.functions.len() - 1
+    }
+
+    ///
+    /// Keep the place of a function in the module, so functions are listed
+    /// in source order whatever order they are lowered in
+    ///
+    fn reserve_function(&mut self, name: String) -> usize {
+        self.module.functions.push(IrFunction {
+            name,
+            parameters: vec![],
+            return_type: Type::Void,
+            value_types: vec![],
+            blocks: vec![],
+        });
+        self.module.functions.len() - 1
+    }
+
+    fn begin_function(&mut self, name: &str, return_type: &Type, class_name: Option<String>) {
+        self.functions.push(FunctionState {
+            function: IrFunction {
+                name: String::from(name),
+                parameters: vec![],
+                return_type: return_type.clone(),
+                value_types: vec![],
+                blocks: vec![IrBlock::default()],
+            },
+            current: Some(0),
+            definitions: Definitions::new(),
+            this: None,
+            class_name,
+            loops: vec![],
+            nil_spans: HashMap::new(),
+        });
+    }
+
+    fn end_function(&mut self, slot: usize) {
+        if let Some(block) = self.state().current {
+            self.terminate(block, Terminator::Return(None));
+        }
+
+        let mut state = self
+            .functions
+            .pop()
+            .expect("A function should always be lowered");
+        finalize_function(&mut state.function);
+        self.module.functions[slot] = state.function;
+    }
+
+    ///
+    /// Function names are unique in the module, nested functions are
+    /// prefixed with the name of their enclosing function
+    ///
+    fn function_name(&mut self, name: &str) -> String {
+        let mut function_name = match self.functions.last() {
+            Some(state) if self.functions.len() > 1 => {
+                format!("{}.{}", state.function.name, name)
+            }
+            _ => String::from(name),
+        };
+
+        let base_name = function_name.clone();
+        let mut suffix = 1;
+        while !self.function_names.insert(function_name.clone()) {
+            suffix += 1;
+            function_name = format!("{}#{}", base_name, suffix);
+        }
+
+        function_name
+    }
+
+    fn add_value(&mut self, value_type: Type) -> ValueId {
+        let value_types = &mut self.state().function.value_types;
+        value_types.push(value_type);
+        value_types.len() - 1
+    }
+
+    fn type_of(&self, value: ValueId) -> Type {
+        self.functions[self.function_index()].function.value_types[value].clone()
+    }
+
+    fn new_block(&mut self) -> BlockId {
+        let blocks = &mut self.state().function.blocks;
+        blocks.push(IrBlock::default());
+        blocks.len() - 1
+    }
+
+    ///
+    /// Block the next instruction goes into, code after a return starts
+    /// a block without predecessors
+    ///
+    fn current_block(&mut self) -> BlockId {
+        match self.state().current {
+            Some(block) => block,
+            None => {
+                let block = self.new_block();
+                self.state().current = Some(block);
+                block
+            }
+        }
+    }
+
+    fn push_instruction(&mut self, result: Option<ValueId>, kind: InstructionKind) {
+        let block = self.current_block();
+        self.state().function.blocks[block]
+            .instructions
+            .push(Instruction { result, kind });
+    }
+
+    fn emit(&mut self, kind: InstructionKind, value_type: Type) -> ValueId {
+        let value = self.add_value(value_type);
+        self.push_instruction(Some(value), kind);
+        value
+    }
+
+    fn emit_void(&mut self, kind: InstructionKind) {
+        self.push_instruction(None, kind);
+    }
+
+    ///
+    /// Emit a call, calls of void functions produce no value
+    ///
+    fn emit_call(&mut self, kind: InstructionKind, return_type: Type) -> Option<ValueId> {
+        if return_type == Type::Void {
+            self.emit_void(kind);
+            return None;
+        }
+        Some(self.emit(kind, return_type))
+    }
+
+    fn emit_constant(&mut self, constant: Constant) -> ValueId {
+        let value_type = match constant {
+            Constant::Number(_) => Type::number(),
+            Constant::Boolean(_) => Type::boolean(),
+            Constant::String(_) => Type::string(),
+            Constant::Nil => Type::Nil,
+        };
+        self.emit(InstructionKind::Constant(constant), value_type)
+    }
+
+    ///
+    /// Give nil the type of the place it is stored in, other values are
+    /// kept as they are
+    ///
+    /// Arrays built without an element type, empty or holding only nil,
+    /// take the element type of the place as well
+    ///
+    /// Nil can not be stored where a number or a boolean is expected, there
+    /// is no value left to represent it once nullable types are erased
+    ///
+    fn coerce(&mut self, value: ValueId, target_type: &Type) -> IrResult<ValueId> {
+        let value_type = self.type_of(value);
+        if matches!(value_type, Type::Array(_))
+            && has_no_element_type(&value_type)
+            && matches!(target_type, Type::Array(_))
+        {
+            self.state().function.value_types[value] = target_type.clone();
+            return Ok(value);
+        }
+        if value_type != Type::Nil || matches!(target_type, Type::Nil | Type::Void) {
+            return Ok(value);
+        }
+        if let Some(span) = self.state().nil_spans.get(&value) {
+            ensure_nil_representable(target_type, *span)?;
+        }
+        Ok(self.emit(
+            InstructionKind::Constant(Constant::Nil),
+            target_type.clone(),
+        ))
+    }
+
+    fn terminate(&mut self, block: BlockId, terminator: Terminator) {
+        let block = &mut self.state().function.blocks[block];
+        if block.terminator.is_none() {
+            block.terminator = Some(terminator);
+        }
+    }
+
+    ///
+    /// Continue in the join block of the given branch ends, variables with
+    /// a different value on each end get a phi node
+    ///
+    fn join_blocks(&mut self, join: BlockId, ends: Vec<(BlockId, Definitions)>) {
+        if ends.is_empty() {
+            self.state().current = None;
+            return;
+        }
+
+        for (end, _) in &ends {
+            self.terminate(*end, Terminator::Jump(join));
+        }
+        self.state().current = Some(join);
+
+        // Variables declared inside a branch are out of scope after the join
+        let mut definitions = Definitions::new();
+        for (variable, value) in &ends[0].1 {
+            let incoming: Option<Vec<(BlockId, ValueId)>> = ends
+                .iter()
+                .map(|(end, end_definitions)| {
+                    end_definitions.get(variable).map(|value| (*end, *value))
+                })
+                .collect();
+            let Some(incoming) = incoming else {
+                continue;
+            };
+
+            if incoming
+                .iter()
+                .all(|(_, incoming_value)| incoming_value == value)
+            {
+                definitions.insert(*variable, *value);
+                continue;
+            }
+
+            let phi = self.emit(
+                InstructionKind::Phi { incoming },
+                self.variable_types[variable].clone(),
+            );
+            definitions.insert(*variable, phi);
+        }
+
+        self.state().definitions = definitions;
+    }
+
+    ///
+    /// Start a loop header entered from the given block, variables the loop
+    /// assigns get a phi node completed by the back edge
+    ///
+    fn begin_loop(
+        &mut self,
+        header: BlockId,
+        entry: BlockId,
+        assigned_names: HashSet<String>,
+    ) -> Vec<(VariableId, ValueId)> {
+        self.state().current = Some(header);
+
+        let function = self.function_index();
+        let mut variables: Vec<VariableId> = assigned_names
+            .iter()
+            .filter_map(|name| match self.lookup(name) {
+                Some(Binding::Local {
+                    function: owner,
+                    variable,
+                }) if owner == function => Some(variable),
+                _ => None,
+            })
+            .collect();
+        variables.sort();
+
+        let mut phis = vec![];
+        for variable in variables {
+            let Some(value) = self.state().definitions.get(&variable).copied() else {
+                continue;
+            };
+            let phi = self.emit(
+                InstructionKind::Phi {
+                    incoming: vec![(entry, value)],
+                },
+                self.variable_types[&variable].clone(),
+            );
+            self.state().definitions.insert(variable, phi);
+            phis.push((variable, phi));
+        }
+
+        phis
+    }
+
+    ///
+    /// Add the values flowing back from the ends of the loop body
+    ///
+    fn close_loop(
+        &mut self,
+        header: BlockId,
+        phis: &[(VariableId, ValueId)],
+        back_edges: Vec<(BlockId, Definitions)>,
+    ) {
+        for (back_edge, definitions) in back_edges {
+            self.terminate(back_edge, Terminator::Jump(header));
+
+            for (variable, phi) in phis {
+                let value = definitions[variable];
+                let instructions = &mut self.state().function.blocks[header].instructions;
+                for instruction in instructions.iter_mut() {
+                    if let (Some(result), InstructionKind::Phi { incoming }) =
+                        (instruction.result, &mut instruction.kind)
+                    {
+                        if result == *phi {
+                            incoming.push((back_edge, value));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    ///
+    /// Continue from the given ends, several of them meet in a join block,
+    /// returns whether any end gets there
+    ///
+    fn continue_from(&mut self, mut ends: Vec<(BlockId, Definitions)>) -> bool {
+        if ends.len() > 1 {
+            let join = self.new_block();
+            self.join_blocks(join, ends);
+            return true;
+        }
+
+        let Some((end, definitions)) = ends.pop() else {
+            self.state().current = None;
+            return false;
+        };
+        self.state().current = Some(end);
+        self.state().definitions = definitions;
+        true
+    }
+
+    fn enter_loop(&mut self, label: Option<String>) {
+        self.state().loops.push(LoopJumps {
+            label,
+            breaks: vec![],
+            continues: vec![],
+        });
+    }
+
+    fn exit_loop(&mut self) -> LoopJumps {
+        self.state().loops.pop().expect("a loop was entered")
+    }
+
+    fn lookup(&self, name: &str) -> Option<Binding> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name).cloned())
+    }
+
+    fn declare_local(
+        &mut self,
+        name: String,
+        variable_type: &Type,
+        value: ValueId,
+    ) -> IrResult<()> {
+        let variable = self.next_variable;
+        self.next_variable += 1;
+
+        let value = self.coerce(value, variable_type)?;
+        self.variable_types.insert(variable, variable_type.clone());
+        self.state().definitions.insert(variable, value);
+        let function = self.function_index();
+        self.scopes
+            .last_mut()
+            .expect("Scope stack should never be empty")
+            .insert(name, Binding::Local { function, variable });
+        Ok(())
+    }
+
+    fn is_global_scope(&self) -> bool {
+        self.functions.len() == 1 && self.scopes.len() == 1
+    }
+
+    fn ensure_not_captured(&self, owner: usize, name: &str, span: Span) -> IrResult<()> {
+        if owner == self.function_index() {
+            return Ok(());
+        }
+
+        Err(lowering_error(
+            format!(
+                "Function '{}' captures the local variable '{}', closures are not supported",
+                self.functions[self.function_index()].function.name,
+                name
+            ),
+            span,
+        ))
+    }
+
+    fn this_value(&self, span: Span) -> IrResult<ValueId> {
+        let state = &self.functions[self.function_index()];
+        if let Some(this) = state.this {
+            return Ok(this);
+        }
+
+        if self.functions.iter().any(|state| state.this.is_some()) {
+            return Err(lowering_error(
+                format!(
+                    "Function '{}' captures 'this', closures are not supported",
+                    state.function.name
+                ),
+                span,
+            ));
+        }
+        Err(lowering_error(
+            String::from("'this' used outside of a class"),
+            span,
+        ))
+    }
+
+    fn super_class(&self, span: Span) -> IrResult<String> {
+        self.functions[self.function_index()]
+            .class_name
+            .as_ref()
+            .and_then(|class_name| self.classes.get(class_name))
+            .and_then(|layout| layout.super_class.clone())
+            .ok_or_else(|| lowering_error(String::from("Cannot resolve 'super'"), span))
+    }
+
+    ///
+    /// Walk the class and its ancestors, stops on inheritance cycles
+    ///
+    fn class_chain(&self, class_name: &str) -> Vec<String> {
+        let mut chain: Vec<String> = vec![];
+        let mut current = Some(String::from(class_name));
+
+        while let Some(name) = current {
+            if chain.contains(&name) {
+                break;
+            }
+            let Some(layout) = self.classes.get(&name) else {
+                break;
+            };
+            current = layout.super_class.clone();
+            chain.push(name);
+        }
+
+        chain
+    }
+
+    ///
+    /// Class declaring the field and the type of the field
+    ///
+    fn find_field(&self, class_name: &str, field_name: &str) -> Option<(String, Type)> {
+        self.class_chain(class_name).into_iter().find_map(|name| {
+            self.classes[&name]
+                .fields
+                .iter()
+                .find(|(field, _)| field == field_name)
+                .map(|(_, field_type)| (name.clone(), field_type.clone()))
+        })
+    }
+
+    ///
+    /// Class declaring the method and the type of the method
+    ///
+    fn find_method(&self, class_name: &str, method_name: &str) -> Option<(String, Type)> {
+        self.class_chain(class_name).into_iter().find_map(|name| {
+            self.classes[&name]
+                .methods
+                .get(method_name)
+                .map(|method_type| (name.clone(), method_type.clone()))
+        })
+    }
+
+    fn lower_statement_list(&mut self, statements: &StatementList) -> IrResult<()> {
+        self.hoist_declarations(statements)?;
+
+        for statement in statements {
+            // Code after a return never runs, declarations are still lowered
+            // since the reachable code may call them
+            let is_declaration = matches!(
+                statement,
+                Statement::FunctionDeclaration { .. } | Statement::ClassDeclaration { .. }
+            );
+            if self.state().current.is_none() && !is_declaration {
+                continue;
+            }
+
+            self.lower_statement(statement)?;
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Functions and classes are visible to the whole statement list
+    ///
+    fn hoist_declarations(&mut self, statements: &StatementList) -> IrResult<()> {
+        for statement in statements {
+            ensure_not_generic(statement)?;
+            if let Statement::InterfaceDeclaration { name, .. } = statement {
+                let (interface_name, _) = identifier_name(name)?;
+                self.interfaces.insert(interface_name);
+            }
+        }
+
+        for statement in statements {
+            match statement {
+                Statement::ClassDeclaration {
+                    name,
+                    super_class,
+                    members,
+                    ..
+                } => self.hoist_class(name, super_class, members)?,
+                Statement::EnumDeclaration { name, variants, .. } => {
+                    self.hoist_enum(name, variants)?
+                }
+                _ => {}
+            }
+        }
+
+        for statement in statements {
+            if let Statement::FunctionDeclaration {
+                name,
+                parameters,
+                return_type,
+                ..
+            } = statement
+            {
+                self.hoist_function(name, parameters, return_type)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn hoist_function(
+        &mut self,
+        name: &ExpressionRef,
+        parameters: &ParameterList,
+        return_type: &Type,
+    ) -> IrResult<()> {
+        let (name, span) = identifier_name(name)?;
+        self.ensure_supported_signature(parameters, return_type, span)?;
+        let binding = Binding::Function {
+            name: self.function_name(&name),
+            function_type: function_type(parameters, return_type),
+        };
+
+        self.scopes
+            .last_mut()
+            .expect("Scope stack should never be empty")
+            .insert(name, binding);
+        Ok(())
+    }
+
+    fn hoist_class(
+        &mut self,
+        name: &ExpressionRef,
+        super_class: &Option<ExpressionRef>,
+        members: &ClassMemberList,
+    ) -> IrResult<()> {
+        let (class_name, _) = identifier_name(name)?;
+        let super_class = match super_class {
+            Some(super_class) => Some(identifier_name(super_class)?.0),
+            None => None,
+        };
+
+        let mut layout = ClassLayout {
+            super_class,
+            fields: vec![],
+            methods: HashMap::new(),
+            has_initializers: false,
+        };
+
+        for member in members {
+            match member {
+                ClassMember::Field {
+                    name,
+                    type_annotation,
+                    initializer,
+                    ..
+                } => {
+                    let (field_name, span) = identifier_name(name)?;
+                    self.ensure_supported_type(type_annotation, span)?;
+                    layout.has_initializers |= initializer.is_some();
+
+                    // A redeclared field shares the storage of the inherited one
+                    let is_inherited = layout.super_class.as_ref().is_some_and(|super_class| {
+                        self.find_field(super_class, &field_name).is_some()
+                    });
+                    if !is_inherited {
+                        layout
+                            .fields
+                            .push((field_name, erase_nullable(type_annotation)));
+                    }
+                }
+                ClassMember::Method { declaration } | ClassMember::Constructor { declaration } => {
+                    if let Statement::FunctionDeclaration {
+                        name,
+                        parameters,
+                        return_type,
+                        ..
+                    } = declaration.as_ref()
+                    {
+                        let (method_name, span) = identifier_name(name)?;
+                        self.ensure_supported_signature(parameters, return_type, span)?;
+                        layout
+                            .methods
+                            .insert(method_name, function_type(parameters, return_type));
+                    }
+                }
+            }
+        }
+
+        self.class_order.push(class_name.clone());
+        self.classes.insert(class_name, layout);
+        Ok(())
+    }
+
+    ///
+    /// Enums are classes with the index of the variant in a tag field and a
+    /// field for every value of every variant
+    ///
+    fn hoist_enum(&mut self, name: &ExpressionRef, variants: &EnumVariantList) -> IrResult<()> {
+        let (enum_name, _) = identifier_name(name)?;
+
+        let mut layout = ClassLayout {
+            super_class: None,
+            fields: vec![(String::from(ENUM_TAG_FIELD), Type::number())],
+            methods: HashMap::new(),
+            has_initializers: false,
+        };
+        let mut variant_names = vec![];
+        for variant in variants {
+            let (variant_name, span) = identifier_name(&variant.name)?;
+            for (index, payload_type) in variant.payload.iter().enumerate() {
+                self.ensure_supported_type(payload_type, span)?;
+                layout.fields.push((
+                    payload_field(&variant_name, index),
+                    erase_nullable(payload_type),
+                ));
+            }
+            variant_names.push(variant_name);
+        }
+
+        self.class_order.push(enum_name.clone());
+        self.classes.insert(enum_name.clone(), layout);
+        self.enums.insert(enum_name, variant_names);
+        Ok(())
+    }
+
+    ///
+    /// Values of union types have no single representation and values of
+    /// interface types would need dispatch across unrelated classes, both
+    /// are kept only by the JavaScript backend
+    ///
+    fn ensure_supported_type(&self, value_type: &Type, span: Span) -> IrResult<()> {
+        match value_type {
+            Type::Union(_) => Err(lowering_error(
+                format!("Union type '{}' is not supported", value_type),
+                span,
+            )),
+            Type::Class { name, .. } if self.interfaces.contains(name) => Err(lowering_error(
+                format!("Interface type '{}' is not supported", name),
+                span,
+            )),
+            Type::Nullable(value_type) | Type::Array(value_type) => {
+                self.ensure_supported_type(value_type, span)
+            }
+            Type::Function {
+                params,
+                return_type,
+            } => {
+                for param in params {
+                    self.ensure_supported_type(param, span)?;
+                }
+                self.ensure_supported_type(return_type, span)
+            }
+            Type::Generic { type_args, .. } => {
+                for type_arg in type_args {
+                    self.ensure_supported_type(type_arg, span)?;
+                }
+                Ok(())
+            }
+            Type::Primitive(_)
+            | Type::Class { .. }
+            | Type::Enum(_)
+            | Type::Parameter(_)
+            | Type::Void
+            | Type::Nil => Ok(()),
+        }
+    }
+
+    fn ensure_supported_signature(
+        &self,
+        parameters: &ParameterList,
+        return_type: &Type,
+        span: Span,
+    ) -> IrResult<()> {
+        for (parameter, parameter_type) in parameters {
+            self.ensure_supported_type(parameter_type, parameter.span())?;
+        }
+        self.ensure_supported_type(return_type, span)
+    }
+
+    fn lower_statement(&mut self, statement: &Statement) -> IrResult<()> {
+        match statement {
+            Statement::Program { body, .. } => self.lower_statement_list(body),
+            Statement::Block { body, .. } => {
+                self.scopes.push(HashMap::new());
+                let result = self.lower_statement_list(body);
+                self.scopes.pop();
+                result
+            }
+            Statement::Empty { .. } => Ok(()),
+            Statement::Expression { expression, .. } => {
+                self.lower_expression(expression)?;
+                Ok(())
+            }
+            Statement::VariableDeclaration { variables, .. } => {
+                for variable in variables {
+                    self.lower_expression(variable)?;
+                }
+                Ok(())
+            }
+            Statement::If {
+                condition,
+                consequent,
+                alternative,
+                ..
+            } => self.lower_if(condition, consequent, alternative),
+            Statement::While { .. } | Statement::DoWhile { .. } | Statement::For { .. } => {
+                self.lower_loop(statement, None)
+            }
+            Statement::Labeled { label, body, .. } => {
+                let (label, _) = identifier_name(label)?;
+                self.lower_loop(body, Some(label))
+            }
+            Statement::FunctionDeclaration {
+                name,
+                parameters,
+                return_type,
+                body,
+                ..
+            } => {
+                ensure_not_generic(statement)?;
+                let (function_name, span) = identifier_name(name)?;
+
+                // Declarations outside of a statement list were not hoisted
+                let is_hoisted = self
+                    .scopes
+                    .last()
+                    .is_some_and(|scope| scope.contains_key(&function_name));
+                if !is_hoisted {
+                    self.hoist_function(name, parameters, return_type)?;
+                }
+
+                let Some(Binding::Function { name: ir_name, .. }) = self.lookup(&function_name)
+                else {
+                    return Err(lowering_error(
+                        format!("Undefined function '{}'", function_name),
+                        span,
+                    ));
+                };
+
+                let slot = self.reserve_function(ir_name.clone());
+                self.lower_function(&ir_name, None, parameters, return_type, body, slot)
+            }
+            Statement::Return { argument, .. } => {
+                let value = match argument {
+                    Some(argument) => {
+                        let value = self.lower_value(argument)?;
+                        let return_type = self.state().function.return_type.clone();
+                        Some(self.coerce(value, &return_type)?)
+                    }
+                    None => None,
+                };
+
+                let block = self.current_block();
+                self.terminate(block, Terminator::Return(value));
+                self.state().current = None;
+                Ok(())
+            }
+            Statement::Break { label, span } | Statement::Continue { label, span } => {
+                let label = match label {
+                    Some(label) => Some(identifier_name(label)?.0),
+                    None => None,
+                };
+                let end = (self.current_block(), self.state().definitions.clone());
+
+                let loops = &mut self.state().loops;
+                let Some(jumps) = loops
+                    .iter_mut()
+                    .rev()
+                    .find(|jumps| label.is_none() || jumps.label == label)
+                else {
+                    return Err(lowering_error(
+                        String::from("Jump outside of a loop"),
+                        *span,
+                    ));
+                };
+                match statement {
+                    Statement::Break { .. } => jumps.breaks.push(end),
+                    _ => jumps.continues.push(end),
+                }
+
+                // The jump is made when the loop joins its ends
+                self.state().current = None;
+                Ok(())
+            }
+            Statement::ClassDeclaration { name, members, .. } => self.lower_class(name, members),
+            Statement::InterfaceDeclaration { .. } | Statement::EnumDeclaration { .. } => Ok(()),
+        }
+    }
+
+    fn lower_if(
+        &mut self,
+        condition: &ExpressionRef,
+        consequent: &StatementRef,
+        alternative: &Option<StatementRef>,
+    ) -> IrResult<()> {
+        let condition = self.lower_value(condition)?;
+        let condition_block = self.current_block();
+        let definitions = self.state().definitions.clone();
+
+        let consequent_block = self.new_block();
+        self.state().current = Some(consequent_block);
+        self.lower_statement(consequent)?;
+        let consequent_end = self.branch_end();
+
+        let (alternative_block, alternative_end) = match alternative {
+            Some(alternative) => {
+                self.state().definitions = definitions;
+                let alternative_block = self.new_block();
+                self.state().current = Some(alternative_block);
+                self.lower_statement(alternative)?;
+                (Some(alternative_block), self.branch_end())
+            }
+            None => (None, Some((condition_block, definitions))),
+        };
+
+        let join = self.new_block();
+        self.terminate(
+            condition_block,
+            Terminator::Branch {
+                condition,
+                consequent: consequent_block,
+                alternative: alternative_block.unwrap_or(join),
+            },
+        );
+
+        let ends = [consequent_end, alternative_end].into_iter().flatten();
+        self.join_blocks(join, ends.collect());
+        Ok(())
+    }
+
+    ///
+    /// Block a branch ended in with the variable values at its end, none
+    /// when the branch returned
+    ///
+    fn branch_end(&mut self) -> Option<(BlockId, Definitions)> {
+        let state = self.state();
+        state
+            .current
+            .map(|block| (block, state.definitions.clone()))
+    }
+
+    fn lower_loop(&mut self, statement: &Statement, label: Option<String>) -> IrResult<()> {
+        match statement {
+            Statement::While {
+                condition, body, ..
+            } => self.lower_while(condition, body, label),
+            Statement::DoWhile {
+                body, condition, ..
+            } => self.lower_do_while(body, condition, label),
+            Statement::For {
+                initializer,
+                condition,
+                increment,
+                body,
+                ..
+            } => {
+                self.scopes.push(HashMap::new());
+                let result = self.lower_for(initializer, condition, increment, body, label);
+                self.scopes.pop();
+                result
+            }
+            _ => self.lower_statement(statement),
+        }
+    }
+
+    fn lower_while(
+        &mut self,
+        condition: &ExpressionRef,
+        body: &StatementRef,
+        label: Option<String>,
+    ) -> IrResult<()> {
+        let mut assigned_names = HashSet::new();
+        collect_assigned_names(condition, &mut assigned_names);
+        collect_statement_assigned_names(body, &mut assigned_names);
+
+        let entry = self.current_block();
+        let header = self.new_block();
+        self.terminate(entry, Terminator::Jump(header));
+        let phis = self.begin_loop(header, entry, assigned_names);
+
+        let condition = self.lower_value(condition)?;
+        let condition_end = self.current_block();
+        let exit_definitions = self.state().definitions.clone();
+
+        let body_block = self.new_block();
+        self.state().current = Some(body_block);
+        self.enter_loop(label);
+        self.lower_statement(body)?;
+        let jumps = self.exit_loop();
+
+        let back_edges = self.branch_end().into_iter().chain(jumps.continues);
+        self.close_loop(header, &phis, back_edges.collect());
+
+        let exit = self.new_block();
+        self.terminate(
+            condition_end,
+            Terminator::Branch {
+                condition,
+                consequent: body_block,
+                alternative: exit,
+            },
+        );
+        let mut exits = vec![(condition_end, exit_definitions)];
+        exits.extend(jumps.breaks);
+        self.join_blocks(exit, exits);
+        Ok(())
+    }
+
+    fn lower_do_while(
+        &mut self,
+        body: &StatementRef,
+        condition: &ExpressionRef,
+        label: Option<String>,
+    ) -> IrResult<()> {
+        let mut assigned_names = HashSet::new();
+        collect_statement_assigned_names(body, &mut assigned_names);
+        collect_assigned_names(condition, &mut assigned_names);
+
+        let entry = self.current_block();
+        let body_block = self.new_block();
+        self.terminate(entry, Terminator::Jump(body_block));
+        let phis = self.begin_loop(body_block, entry, assigned_names);
+        let loop_definitions = self.state().definitions.clone();
+
+        self.enter_loop(label);
+        self.lower_statement(body)?;
+        let jumps = self.exit_loop();
+
+        // A body that always returns or breaks never evaluates the condition
+        let ends = self.branch_end().into_iter().chain(jumps.continues);
+        let breaks = in_scope(jumps.breaks, &loop_definitions);
+        if !self.continue_from(in_scope(ends.collect(), &loop_definitions)) {
+            if !breaks.is_empty() {
+                let exit = self.new_block();
+                self.join_blocks(exit, breaks);
+            }
+            return Ok(());
+        }
+
+        let condition = self.lower_value(condition)?;
+        let condition_end = self.current_block();
+        let exit = self.new_block();
+        self.terminate(
+            condition_end,
+            Terminator::Branch {
+                condition,
+                consequent: body_block,
+                alternative: exit,
+            },
+        );
+        let condition_definitions = self.state().definitions.clone();
+        self.close_loop(
+            body_block,
+            &phis,
+            vec![(condition_end, condition_definitions.clone())],
+        );
+
+        let mut exits = vec![(condition_end, condition_definitions)];
+        exits.extend(breaks);
+        self.join_blocks(exit, exits);
+        Ok(())
+    }
+
+    fn lower_for(
+        &mut self,
+        initializer: &Option<StatementRef>,
+        condition: &Option<ExpressionRef>,
+        increment: &Option<ExpressionRef>,
+        body: &StatementRef,
+        label: Option<String>,
+    ) -> IrResult<()> {
+        if let Some(initializer) = initializer {
+            self.lower_statement(initializer)?;
+        }
+
+        let mut assigned_names = HashSet::new();
+        if let Some(condition) = condition {
+            collect_assigned_names(condition, &mut assigned_names);
+        }
+        if let Some(increment) = increment {
+            collect_assigned_names(increment, &mut assigned_names);
+        }
+        collect_statement_assigned_names(body, &mut assigned_names);
+
+        let entry = self.current_block();
+        let header = self.new_block();
+        self.terminate(entry, Terminator::Jump(header));
+        let phis = self.begin_loop(header, entry, assigned_names);
+
+        let condition = match condition {
+            Some(condition) => Some(self.lower_value(condition)?),
+            None => None,
+        };
+        let condition_end = self.current_block();
+        let exit_definitions = self.state().definitions.clone();
+
+        let body_block = self.new_block();
+        self.state().current = Some(body_block);
+        self.enter_loop(label);
+        self.lower_statement(body)?;
+        let jumps = self.exit_loop();
+
+        // A body that always returns or breaks never gets to the increment
+        let ends = self.branch_end().into_iter().chain(jumps.continues);
+        if self.continue_from(in_scope(ends.collect(), &exit_definitions)) {
+            if let Some(increment) = increment {
+                self.lower_expression(increment)?;
+            }
+            let back_edges = self.branch_end().into_iter().collect();
+            self.close_loop(header, &phis, back_edges);
+        }
+
+        let mut exits = in_scope(jumps.breaks, &exit_definitions);
+        match condition {
+            Some(condition) => {
+                let exit = self.new_block();
+                self.terminate(
+                    condition_end,
+                    Terminator::Branch {
+                        condition,
+                        consequent: body_block,
+                        alternative: exit,
+                    },
+                );
+                exits.insert(0, (condition_end, exit_definitions));
+                self.join_blocks(exit, exits);
+            }
+            None => {
+                // Without a condition the loop is only left by a return or a break
+                self.terminate(condition_end, Terminator::Jump(body_block));
+                match exits.is_empty() {
+                    true => self.state().current = None,
+                    false => {
+                        let exit = self.new_block();
+                        self.join_blocks(exit, exits);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn lower_function(
+        &mut self,
+        name: &str,
+        class_name: Option<&str>,
+        parameters: &ParameterList,
+        return_type: &Type,
+        body: &StatementRef,
+        slot: usize,
+    ) -> IrResult<()> {
+        self.begin_function(
+            name,
+            &erase_nullable(return_type),
+            class_name.map(String::from),
+        );
+        self.scopes.push(HashMap::new());
+
+        if let Some(class_name) = class_name {
+            let this = self.add_value(class_type(class_name));
+            self.state().function.parameters.push(this);
+            self.state().this = Some(this);
+        }
+
+        for (parameter, parameter_type) in parameters {
+            let parameter_type = erase_nullable(parameter_type);
+            let value = self.add_value(parameter_type.clone());
+            self.state().function.parameters.push(value);
+            if let Expression::Destructuring { pattern, .. } = parameter {
+                self.lower_destructuring(pattern, value)?;
+                continue;
+            }
+            let (parameter_name, _) = identifier_name(parameter)?;
+            self.declare_local(parameter_name, &parameter_type, value)?;
+        }
+
+        let result = self.lower_statement(body);
+        self.scopes.pop();
+        self.end_function(slot);
+        result
+    }
+
+    fn lower_class(&mut self, name: &ExpressionRef, members: &ClassMemberList) -> IrResult<()> {
+        let (class_name, _) = identifier_name(name)?;
+
+        let initializer_slot = self.classes[&class_name]
+            .has_initializers
+            .then(|| self.reserve_function(format!("{}.{}", class_name, FIELD_INITIALIZER_NAME)));
+
+        let mut methods = vec![];
+        for member in members {
+            let (ClassMember::Method { declaration } | ClassMember::Constructor { declaration }) =
+                member
+            else {
+                continue;
+            };
+            if let Statement::FunctionDeclaration { name, .. } = declaration.as_ref() {
+                let (method_name, _) = identifier_name(name)?;
+                let slot = self.reserve_function(format!("{}.{}", class_name, method_name));
+                methods.push((method_name, declaration, slot));
+            }
+        }
+
+        if let Some(slot) = initializer_slot {
+            self.lower_field_initializers(&class_name, members, slot)?;
+        }
+
+        for (method_name, method, slot) in methods {
+            let Statement::FunctionDeclaration {
+                parameters,
+                return_type,
+                body,
+                ..
+            } = method.as_ref()
+            else {
+                continue;
+            };
+
+            self.lower_function(
+                &format!("{}.{}", class_name, method_name),
+                Some(&class_name),
+                parameters,
+                return_type,
+                body,
+                slot,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn lower_field_initializers(
+        &mut self,
+        class_name: &str,
+        members: &ClassMemberList,
+        slot: usize,
+    ) -> IrResult<()> {
+        self.begin_function(
+            &format!("{}.{}", class_name, FIELD_INITIALIZER_NAME),
+            &Type::Void,
+            Some(String::from(class_name)),
+        );
+        let this = self.add_value(class_type(class_name));
+        self.state().function.parameters.push(this);
+        self.state().this = Some(this);
+
+        let mut result = Ok(());
+        for member in members {
+            let ClassMember::Field {
+                name,
+                initializer: Some(initializer),
+                ..
+            } = member
+            else {
+                continue;
+            };
+
+            result = identifier_name(name).and_then(|(field, _)| {
+                let value = self.lower_value(initializer)?;
+                let (declaring_class, value) = match self.find_field(class_name, &field) {
+                    Some((declaring_class, field_type)) => {
+                        (declaring_class, self.coerce(value, &field_type)?)
+                    }
+                    None => (String::from(class_name), value),
+                };
+                self.emit_void(InstructionKind::StoreField {
+                    object: this,
+                    class_name: declaring_class,
+                    field,
+                    value,
+                });
+                Ok(())
+            });
+            if result.is_err() {
+                break;
+            }
+        }
+
+        self.end_function(slot);
+        result
+    }
+
+    fn lower_value(&mut self, expression: &Expression) -> IrResult<ValueId> {
+        self.lower_expression(expression)?.ok_or_else(|| {
+            lowering_error(
+                String::from("Expression does not produce a value"),
+                expression.span(),
+            )
+        })
+    }
+
+    fn lower_expression(&mut self, expression: &Expression) -> IrResult<Option<ValueId>> {
+        match expression {
+            Expression::Variable {
+                identifier,
+                type_annotation,
+                initializer,
+                ..
+            } => {
+                self.lower_variable(identifier, type_annotation, initializer)?;
+                Ok(None)
+            }
+            Expression::Assignment {
+                operator,
+                left,
+                right,
+                ..
+            } => self.lower_assignment(*operator, left, right).map(Some),
+            Expression::Binary {
+                operator,
+                left,
+                right,
+                ..
+            } => {
+                let left = self.lower_value(left)?;
+                let right = self.lower_value(right)?;
+                let value_type = binary_type(*operator, self.type_of(left));
+                Ok(Some(self.emit(
+                    InstructionKind::Binary {
+                        operator: *operator,
+                        left,
+                        right,
+                    },
+                    value_type,
+                )))
+            }
+            Expression::TypeTest { span, .. } => Err(lowering_error(
+                String::from("Type tests are not supported"),
+                *span,
+            )),
+            Expression::Match { subject, arms, .. } => self.lower_match(subject, arms),
+            Expression::Destructuring { span, .. } => Err(lowering_error(
+                String::from("Destructuring is only allowed in declarations"),
+                *span,
+            )),
+            Expression::Unary {
+                operator, right, ..
+            } => {
+                let operand = self.lower_value(right)?;
+                let value_type = match operator {
+                    UnaryOperator::Plus => return Ok(Some(operand)),
+                    UnaryOperator::Minus => Type::number(),
+                    UnaryOperator::Not => Type::boolean(),
+                };
+                Ok(Some(self.emit(
+                    InstructionKind::Unary {
+                        operator: *operator,
+                        operand,
+                    },
+                    value_type,
+                )))
+            }
+            Expression::Logical {
+                operator,
+                left,
+                right,
+                ..
+            } => self.lower_logical(*operator, left, right).map(Some),
+            Expression::BooleanLiteral { value, .. } => {
+                Ok(Some(self.emit_constant(Constant::Boolean(*value))))
+            }
+            Expression::NilLiteral { span } => {
+                let nil = self.emit_constant(Constant::Nil);
+                self.state().nil_spans.insert(nil, *span);
+                Ok(Some(nil))
+            }
+            Expression::StringLiteral { value, .. } => {
+                Ok(Some(self.emit_constant(Constant::String(value.clone()))))
+            }
+            Expression::NumericLiteral { value, .. } => {
+                Ok(Some(self.emit_constant(Constant::Number(*value))))
+            }
+            Expression::Identifier { name, span } => self.lower_identifier(name, *span).map(Some),
+            Expression::ArrayLiteral { elements, .. } => {
+                self.lower_array_literal(elements).map(Some)
+            }
+            Expression::Member {
+                computed,
+                optional,
+                object,
+                property,
+                span,
+            } => {
+                let object = self.lower_object(object)?;
+                if *optional {
+                    return self.lower_unless_nil(object, *span, |builder| {
+                        builder
+                            .lower_member(object, *computed, property, *span)
+                            .map(Some)
+                    });
+                }
+                self.lower_member(object, *computed, property, *span)
+                    .map(Some)
+            }
+            Expression::Call {
+                callee,
+                arguments,
+                span,
+            } => self.lower_call(callee, arguments, *span),
+            Expression::This { span } | Expression::Super { span } => {
+                self.this_value(*span).map(Some)
+            }
+            Expression::New {
+                callee, arguments, ..
+            } => self.lower_new(callee, arguments).map(Some),
+            Expression::EnumVariant {
+                enum_name,
+                variant,
+                arguments,
+                ..
+            } => self
+                .lower_enum_variant(enum_name, variant, arguments)
+                .map(Some),
+        }
+    }
+
+    fn lower_variable(
+        &mut self,
+        identifier: &ExpressionRef,
+        type_annotation: &Option<Type>,
+        initializer: &Option<ExpressionRef>,
+    ) -> IrResult<()> {
+        if let Expression::Destructuring { pattern, span } = identifier.as_ref() {
+            let Some(initializer) = initializer else {
+                return Err(lowering_error(
+                    String::from("Destructured variables need an initializer"),
+                    *span,
+                ));
+            };
+            let mut value = self.lower_value(initializer)?;
+            if let Some(type_annotation) = type_annotation {
+                self.ensure_supported_type(type_annotation, *span)?;
+                value = self.coerce(value, &erase_nullable(type_annotation))?;
+            }
+            return self.lower_destructuring(pattern, value);
+        }
+
+        let (name, span) = identifier_name(identifier)?;
+        let value = match initializer {
+            Some(initializer) => Some(self.lower_value(initializer)?),
+            None => None,
+        };
+        let type_annotation = &match (type_annotation, value) {
+            (Some(type_annotation), _) => {
+                self.ensure_supported_type(type_annotation, span)?;
+                erase_nullable(type_annotation)
+            }
+            (None, Some(value)) => self.type_of(value),
+            (None, None) => {
+                return Err(lowering_error(
+                    format!("Cannot infer the type of '{}'", name),
+                    span,
+                ))
+            }
+        };
+
+        self.declare_variable(name, type_annotation, value)
+    }
+
+    ///
+    /// Declare a variable of the current scope, a global in the top level
+    /// scope and a local otherwise
+    ///
+    fn declare_variable(
+        &mut self,
+        name: String,
+        type_annotation: &Type,
+        value: Option<ValueId>,
+    ) -> IrResult<()> {
+        if self.is_global_scope() {
+            if let Some(value) = value {
+                let value = self.coerce(value, type_annotation)?;
+                self.emit_void(InstructionKind::StoreGlobal {
+                    name: name.clone(),
+                    value,
+                });
+            }
+            self.module
+                .globals
+                .push((name.clone(), type_annotation.clone()));
+            self.scopes[0].insert(
+                name,
+                Binding::Global {
+                    variable_type: type_annotation.clone(),
+                },
+            );
+            return Ok(());
+        }
+
+        let value = match value {
+            Some(value) => value,
+            None => self.emit(InstructionKind::Undefined, type_annotation.clone()),
+        };
+        self.declare_local(name, type_annotation, value)
+    }
+
+    fn lower_identifier(&mut self, name: &str, span: Span) -> IrResult<ValueId> {
+        match self.lookup(name) {
+            Some(Binding::Local { function, variable }) => {
+                self.ensure_not_captured(function, name, span)?;
+                Ok(self.state().definitions[&variable])
+            }
+            Some(Binding::Global { variable_type }) => Ok(self.emit(
+                InstructionKind::LoadGlobal {
+                    name: String::from(name),
+                },
+                variable_type,
+            )),
+            Some(Binding::Function {
+                name,
+                function_type,
+            }) => Ok(self.emit(InstructionKind::Function { name }, function_type)),
+            None => Err(lowering_error(
+                format!("Undefined variable '{}'", name),
+                span,
+            )),
+        }
+    }
+
+    fn lower_assignment(
+        &mut self,
+        operator: AssignmentOperator,
+        left: &ExpressionRef,
+        right: &ExpressionRef,
+    ) -> IrResult<ValueId> {
+        let operator = compound_operator(operator);
+
+        match left.as_ref() {
+            Expression::Identifier { name, span } => {
+                let right = self.lower_value(right)?;
+
+                match self.lookup(name) {
+                    Some(Binding::Local { function, variable }) => {
+                        self.ensure_not_captured(function, name, *span)?;
+                        let current = self.state().definitions[&variable];
+                        let value = self.combine(operator, current, right);
+                        let value = self.coerce(value, &self.variable_types[&variable].clone())?;
+                        self.state().definitions.insert(variable, value);
+                        Ok(value)
+                    }
+                    Some(Binding::Global { variable_type }) => {
+                        let value = match operator {
+                            Some(_) => {
+                                let current = self.emit(
+                                    InstructionKind::LoadGlobal { name: name.clone() },
+                                    variable_type.clone(),
+                                );
+                                self.combine(operator, current, right)
+                            }
+                            None => right,
+                        };
+                        let value = self.coerce(value, &variable_type)?;
+                        self.emit_void(InstructionKind::StoreGlobal {
+                            name: name.clone(),
+                            value,
+                        });
+                        Ok(value)
+                    }
+                    _ => Err(lowering_error(
+                        format!("Cannot assign to '{}'", name),
+                        *span,
+                    )),
+                }
+            }
+            Expression::Member {
+                computed: true,
+                object,
+                property,
+                ..
+            } => {
+                let object = self.lower_object(object)?;
+                let index = self.lower_value(property)?;
+                let right = self.lower_value(right)?;
+
+                let element_type = element_type(&self.type_of(object));
+                let value = match operator {
+                    Some(_) => {
+                        let current = self.emit(
+                            InstructionKind::LoadIndex { object, index },
+                            element_type.clone(),
+                        );
+                        self.combine(operator, current, right)
+                    }
+                    None => right,
+                };
+                let value = self.coerce(value, &element_type)?;
+                self.emit_void(InstructionKind::StoreIndex {
+                    object,
+                    index,
+                    value,
+                });
+                Ok(value)
+            }
+            Expression::Member {
+                computed: false,
+                object: object_expression,
+                property,
+                ..
+            } => {
+                let object = self.lower_object(object_expression)?;
+                let (field, span) = identifier_name(property)?;
+                let right = self.lower_value(right)?;
+
+                let class_name = object_class_name(&self.type_of(object), span)?;
+                let (declaring_class, field_type) = match self.find_field(&class_name, &field) {
+                    Some(field) => field,
+                    None => {
+                        return Err(lowering_error(
+                            format!(
+                                "Property '{}' does not exist on type '{}'",
+                                field, class_name
+                            ),
+                            span,
+                        ))
+                    }
+                };
+
+                let value = match operator {
+                    Some(_) => {
+                        let current = self.emit(
+                            InstructionKind::LoadField {
+                                object,
+                                class_name: declaring_class.clone(),
+                                field: field.clone(),
+                            },
+                            field_type.clone(),
+                        );
+                        self.combine(operator, current, right)
+                    }
+                    None => right,
+                };
+                let value = self.coerce(value, &field_type)?;
+                self.emit_void(InstructionKind::StoreField {
+                    object,
+                    class_name: declaring_class,
+                    field,
+                    value,
+                });
+                Ok(value)
+            }
+            _ => Err(lowering_error(
+                String::from("Invalid assignment target"),
+                left.span(),
+            )),
+        }
+    }
+
+    ///
+    /// Value of a compound assignment, the right value for a plain one
+    ///
+    fn combine(
+        &mut self,
+        operator: Option<BinaryOperator>,
+        current: ValueId,
+        right: ValueId,
+    ) -> ValueId {
+        let Some(operator) = operator else {
+            return right;
+        };
+
+        let value_type = binary_type(operator, self.type_of(current));
+        self.emit(
+            InstructionKind::Binary {
+                operator,
+                left: current,
+                right,
+            },
+            value_type,
+        )
+    }
+
+    fn lower_logical(
+        &mut self,
+        operator: LogicalOperator,
+        left: &ExpressionRef,
+        right: &ExpressionRef,
+    ) -> IrResult<ValueId> {
+        let left = self.lower_value(left)?;
+        let value_type = self.type_of(left);
+        // '??' keeps the left operand unless it is nil
+        let condition = match operator {
+            LogicalOperator::NilCoalescing => self.emit_not_nil(left),
+            LogicalOperator::And | LogicalOperator::Or => left,
+        };
+        let left_end = self.current_block();
+        let left_definitions = self.state().definitions.clone();
+
+        let right_block = self.new_block();
+        self.state().current = Some(right_block);
+        let right = self.lower_value(right)?;
+        let right = self.coerce(right, &value_type)?;
+        let right_end = self.current_block();
+        let right_definitions = self.state().definitions.clone();
+
+        // The right operand only runs when the left one does not decide the result
+        let join = self.new_block();
+        let (consequent, alternative) = match operator {
+            LogicalOperator::And => (right_block, join),
+            LogicalOperator::Or | LogicalOperator::NilCoalescing => (join, right_block),
+        };
+        self.terminate(
+            left_end,
+            Terminator::Branch {
+                condition,
+                consequent,
+                alternative,
+            },
+        );
+
+        self.join_blocks(
+            join,
+            vec![(left_end, left_definitions), (right_end, right_definitions)],
+        );
+        Ok(self.emit(
+            InstructionKind::Phi {
+                incoming: vec![(left_end, left), (right_end, right)],
+            },
+            value_type,
+        ))
+    }
+
+    ///
+    /// Boolean telling whether a value is not nil
+    ///
+    fn emit_not_nil(&mut self, value: ValueId) -> ValueId {
+        let nil = self.emit(
+            InstructionKind::Constant(Constant::Nil),
+            self.type_of(value),
+        );
+        self.emit(
+            InstructionKind::Binary {
+                operator: BinaryOperator::NotEqual,
+                left: value,
+                right: nil,
+            },
+            Type::boolean(),
+        )
+    }
+
+    ///
+    /// Lower the access of an optional member expression, it only runs when
+    /// the object is not nil and gives nil otherwise
+    ///
+    fn lower_unless_nil(
+        &mut self,
+        object: ValueId,
+        span: Span,
+        lower: impl FnOnce(&mut Self) -> IrResult<Option<ValueId>>,
+    ) -> IrResult<Option<ValueId>> {
+        let condition = self.emit_not_nil(object);
+        let object_end = self.current_block();
+        let object_definitions = self.state().definitions.clone();
+
+        let present_block = self.new_block();
+        self.state().current = Some(present_block);
+        let value = lower(self)?;
+        let present_end = self.current_block();
+        let present_definitions = self.state().definitions.clone();
+
+        // The nil the access gives is only known to have the type of the value
+        // once the value is lowered
+        let nil = match value {
+            Some(value) => {
+                let value_type = self.type_of(value);
+                ensure_nil_representable(&value_type, span)?;
+                self.state().current = Some(object_end);
+                Some(self.emit(InstructionKind::Constant(Constant::Nil), value_type))
+            }
+            None => None,
+        };
+
+        let join = self.new_block();
+        self.terminate(
+            object_end,
+            Terminator::Branch {
+                condition,
+                consequent: present_block,
+                alternative: join,
+            },
+        );
+        self.join_blocks(
+            join,
+            vec![
+                (object_end, object_definitions),
+                (present_end, present_definitions),
+            ],
+        );
+
+        let (Some(value), Some(nil)) = (value, nil) else {
+            return Ok(None);
+        };
+        let value_type = self.type_of(value);
+        Ok(Some(self.emit(
+            InstructionKind::Phi {
+                incoming: vec![(object_end, nil), (present_end, value)],
+            },
+            value_type,
+        )))
+    }
+
+    ///
+    /// Object of a member expression, 'super' refers to the current instance
+    ///
+    fn lower_object(&mut self, object: &Expression) -> IrResult<ValueId> {
+        match object {
+            Expression::Super { span } => self.this_value(*span),
+            _ => self.lower_value(object),
+        }
+    }
+
+    fn lower_member(
+        &mut self,
+        object: ValueId,
+        computed: bool,
+        property: &ExpressionRef,
+        span: Span,
+    ) -> IrResult<ValueId> {
+        let object_type = self.type_of(object);
+
+        if computed {
+            let index = self.lower_value(property)?;
+            return Ok(self.emit(
+                InstructionKind::LoadIndex { object, index },
+                element_type(&object_type),
+            ));
+        }
+
+        let (property_name, property_span) = identifier_name(property)?;
+        match &object_type {
+            Type::Array(_) | Type::Primitive(_) if property_name == "length" => {
+                Ok(self.emit(InstructionKind::Length { object }, Type::number()))
+            }
+            Type::Class { name, .. } => {
+                if let Some((declaring_class, field_type)) = self.find_field(name, &property_name) {
+                    return Ok(self.emit(
+                        InstructionKind::LoadField {
+                            object,
+                            class_name: declaring_class,
+                            field: property_name,
+                        },
+                        field_type,
+                    ));
+                }
+
+                let message = match self.find_method(name, &property_name) {
+                    Some(_) => format!(
+                        "Method '{}' can only be called, bound methods are not supported",
+                        property_name
+                    ),
+                    None => format!(
+                        "Property '{}' does not exist on type '{}'",
+                        property_name, name
+                    ),
+                };
+                Err(lowering_error(message, property_span))
+            }
+            _ => Err(lowering_error(
+                format!(
+                    "Property '{}' does not exist on type '{}'",
+                    property_name, object_type
+                ),
+                span,
+            )),
+        }
+    }
+
+    ///
+    /// Lower call arguments, nil arguments take the type of their parameter
+    ///
+    fn lower_arguments(
+        &mut self,
+        arguments: &ExpressionList,
+        callee_type: Option<&Type>,
+    ) -> IrResult<Vec<ValueId>> {
+        let parameter_types = match callee_type {
+            Some(Type::Function { params, .. }) => params.clone(),
+            _ => vec![],
+        };
+
+        let mut values = vec![];
+        for (index, argument) in arguments.iter().enumerate() {
+            let value = self.lower_value(argument)?;
+            values.push(match parameter_types.get(index) {
+                Some(parameter_type) => self.coerce(value, parameter_type)?,
+                None => value,
+            });
+        }
+
+        Ok(values)
+    }
+
+    fn lower_call(
+        &mut self,
+        callee: &ExpressionRef,
+        arguments: &ExpressionList,
+        span: Span,
+    ) -> IrResult<Option<ValueId>> {
+        match callee.as_ref() {
+            Expression::Identifier { name, .. } => match self.lookup(name) {
+                None if BUILTIN_FUNCTIONS.contains(&name.as_str()) => {
+                    let arguments = self.lower_arguments(arguments, None)?;
+                    self.emit_void(InstructionKind::Call {
+                        function: name.clone(),
+                        arguments,
+                    });
+                    return Ok(None);
+                }
+                Some(Binding::Function {
+                    name,
+                    function_type,
+                }) => {
+                    let arguments = self.lower_arguments(arguments, Some(&function_type))?;
+                    return Ok(self.emit_call(
+                        InstructionKind::Call {
+                            function: name,
+                            arguments,
+                        },
+                        return_type(&function_type),
+                    ));
+                }
+                _ => {}
+            },
+            Expression::Super { span: super_span } => {
+                // super(...) runs the constructor of the parent class on the current instance
+                let this = self.this_value(*super_span)?;
+                let super_class = self.super_class(*super_span)?;
+                let constructor = self.find_method(&super_class, CONSTRUCTOR_NAME);
+                let mut arguments =
+                    self.lower_arguments(arguments, constructor.as_ref().map(|(_, t)| t))?;
+
+                if let Some((declaring_class, _)) = constructor {
+                    arguments.insert(0, this);
+                    self.emit_void(InstructionKind::Call {
+                        function: format!("{}.{}", declaring_class, CONSTRUCTOR_NAME),
+                        arguments,
+                    });
+                }
+                return Ok(None);
+            }
+            Expression::Member {
+                computed: false,
+                optional,
+                object: object_expression,
+                property,
+                span: member_span,
+            } => {
+                let object = self.lower_object(object_expression)?;
+                if *optional {
+                    return self.lower_unless_nil(object, *member_span, |builder| {
+                        builder.lower_method_call(
+                            object,
+                            object_expression,
+                            property,
+                            arguments,
+                            *member_span,
+                        )
+                    });
+                }
+                return self.lower_method_call(
+                    object,
+                    object_expression,
+                    property,
+                    arguments,
+                    *member_span,
+                );
+            }
+            _ => {}
+        }
+
+        let callee_value = self.lower_value(callee)?;
+        if !matches!(self.type_of(callee_value), Type::Function { .. }) {
+            return Err(lowering_error(
+                String::from("Expression is not callable"),
+                span,
+            ));
+        }
+        self.lower_indirect_call(callee_value, arguments)
+    }
+
+    ///
+    /// Call a method, or a function stored in a field, of an object
+    ///
+    fn lower_method_call(
+        &mut self,
+        object: ValueId,
+        object_expression: &ExpressionRef,
+        property: &ExpressionRef,
+        arguments: &ExpressionList,
+        member_span: Span,
+    ) -> IrResult<Option<ValueId>> {
+        let (method_name, _) = identifier_name(property)?;
+
+        // Lookups through 'super' start at the parent class
+        let object_type = match object_expression.as_ref() {
+            Expression::Super { span } => class_type(&self.super_class(*span)?),
+            _ => self.type_of(object),
+        };
+        let method = match object_type {
+            Type::Class { name, .. } if self.find_field(&name, &method_name).is_none() => {
+                self.find_method(&name, &method_name)
+            }
+            _ => None,
+        };
+
+        let callee = match method {
+            Some((declaring_class, method_type)) => {
+                let arguments = self.lower_arguments(arguments, Some(&method_type))?;
+                let return_type = return_type(&method_type);
+
+                // Methods reached through 'super' are not dispatched dynamically
+                if matches!(object_expression.as_ref(), Expression::Super { .. }) {
+                    return Ok(self.emit_call(
+                        InstructionKind::Call {
+                            function: format!("{}.{}", declaring_class, method_name),
+                            arguments: [vec![object], arguments].concat(),
+                        },
+                        return_type,
+                    ));
+                }
+
+                return Ok(self.emit_call(
+                    InstructionKind::CallMethod {
+                        object,
+                        class_name: declaring_class,
+                        method: method_name,
+                        arguments,
+                    },
+                    return_type,
+                ));
+            }
+            None => self.lower_member(object, false, property, member_span)?,
+        };
+
+        self.lower_indirect_call(callee, arguments)
+    }
+
+    fn lower_indirect_call(
+        &mut self,
+        callee: ValueId,
+        arguments: &ExpressionList,
+    ) -> IrResult<Option<ValueId>> {
+        let callee_type = self.type_of(callee);
+        let arguments = self.lower_arguments(arguments, Some(&callee_type))?;
+        let return_type = return_type(&callee_type);
+        Ok(self.emit_call(
+            InstructionKind::CallIndirect { callee, arguments },
+            return_type,
+        ))
+    }
+
+    fn lower_new(
+        &mut self,
+        callee: &ExpressionRef,
+        arguments: &ExpressionList,
+    ) -> IrResult<ValueId> {
+        let (class_name, span) = identifier_name(callee)?;
+        if !self.classes.contains_key(&class_name) {
+            return Err(lowering_error(
+                format!("Undefined class '{}'", class_name),
+                span,
+            ));
+        }
+
+        let constructor = self.find_method(&class_name, CONSTRUCTOR_NAME);
+        let arguments = self.lower_arguments(arguments, constructor.as_ref().map(|(_, t)| t))?;
+        let object = self.emit(
+            InstructionKind::New {
+                class_name: class_name.clone(),
+            },
+            class_type(&class_name),
+        );
+
+        // Ancestors initialize their fields first
+        for ancestor in self.class_chain(&class_name).into_iter().rev() {
+            if self.classes[&ancestor].has_initializers {
+                self.emit_void(InstructionKind::Call {
+                    function: format!("{}.{}", ancestor, FIELD_INITIALIZER_NAME),
+                    arguments: vec![object],
+                });
+            }
+        }
+
+        if let Some((declaring_class, _)) = constructor {
+            self.emit_void(InstructionKind::Call {
+                function: format!("{}.{}", declaring_class, CONSTRUCTOR_NAME),
+                arguments: [vec![object], arguments].concat(),
+            });
+        }
+
+        Ok(object)
+    }
+
+    ///
+    /// Allocate the array and store the elements in order, the first element
+    /// that is not nil or an empty array gives the element type
+    ///
+    fn lower_array_literal(&mut self, elements: &ExpressionList) -> IrResult<ValueId> {
+        let mut values = vec![];
+        for element in elements {
+            values.push(self.lower_value(element)?);
+        }
+        let element_type = values
+            .iter()
+            .map(|value| self.type_of(*value))
+            .find(|value_type| !has_no_element_type(value_type))
+            .unwrap_or(Type::Nil);
+
+        let length = self.emit_constant(Constant::Number(values.len() as i32));
+        let array = self.emit(
+            InstructionKind::NewArray { length },
+            Type::Array(Box::new(element_type.clone())),
+        );
+        for (index, value) in values.into_iter().enumerate() {
+            let index = self.emit_constant(Constant::Number(index as i32));
+            let value = self.coerce(value, &element_type)?;
+            self.emit_void(InstructionKind::StoreIndex {
+                object: array,
+                index,
+                value,
+            });
+        }
+
+        Ok(array)
+    }
+
+    fn lower_enum_variant(
+        &mut self,
+        enum_name: &ExpressionRef,
+        variant: &ExpressionRef,
+        arguments: &ExpressionList,
+    ) -> IrResult<ValueId> {
+        let (enum_name, span) = identifier_name(enum_name)?;
+        let (variant_name, variant_span) = identifier_name(variant)?;
+        let Some(variants) = self.enums.get(&enum_name) else {
+            return Err(lowering_error(
+                format!("Undefined enum '{}'", enum_name),
+                span,
+            ));
+        };
+        let Some(tag) = variants.iter().position(|name| name == &variant_name) else {
+            return Err(lowering_error(
+                format!(
+                    "Variant '{}' does not exist on enum '{}'",
+                    variant_name, enum_name
+                ),
+                variant_span,
+            ));
+        };
+
+        let mut values = vec![];
+        for (index, argument) in arguments.iter().enumerate() {
+            let field = payload_field(&variant_name, index);
+            let Some((_, field_type)) = self.find_field(&enum_name, &field) else {
+                return Err(lowering_error(
+                    format!("Variant '{}' has no value {}", variant_name, index),
+                    argument.span(),
+                ));
+            };
+            let value = self.lower_value(argument)?;
+            values.push((field, self.coerce(value, &field_type)?));
+        }
+
+        let object = self.emit(
+            InstructionKind::New {
+                class_name: enum_name.clone(),
+            },
+            class_type(&enum_name),
+        );
+        let tag = self.emit_constant(Constant::Number(tag as i32));
+        values.insert(0, (String::from(ENUM_TAG_FIELD), tag));
+        for (field, value) in values {
+            self.emit_void(InstructionKind::StoreField {
+                object,
+                class_name: enum_name.clone(),
+                field,
+                value,
+            });
+        }
+
+        Ok(object)
+    }
+
+    ///
+    /// Lower a match to a chain of tests, a failed test continues with the
+    /// next arm and the arms that match join with the value they give
+    ///
+    /// The match is exhaustive, the block after the last arm never runs
+    ///
+    fn lower_match(
+        &mut self,
+        subject: &ExpressionRef,
+        arms: &MatchArmList,
+    ) -> IrResult<Option<ValueId>> {
+        let subject = self.lower_value(subject)?;
+
+        let mut ends = vec![];
+        for arm in arms {
+            let mut failures = Failures {
+                next: self.new_block(),
+                ends: vec![],
+            };
+            self.scopes.push(HashMap::new());
+            let value = self.lower_match_arm(arm, subject, &mut failures);
+            self.scopes.pop();
+            let value = value?;
+            if let Some((end, definitions)) = self.branch_end() {
+                ends.push((end, definitions, value));
+            }
+
+            self.join_blocks(failures.next, failures.ends);
+        }
+        self.state().current = None;
+
+        // Arms giving nil get the type of the other arms
+        let values: Option<Vec<ValueId>> = ends.iter().map(|(_, _, value)| *value).collect();
+        let value_type = values.as_ref().and_then(|values| {
+            values
+                .iter()
+                .map(|value| self.type_of(*value))
+                .find(|value_type| *value_type != Type::Nil)
+                .or(values.first().map(|_| Type::Nil))
+        });
+        let mut incoming = vec![];
+        let mut join_ends = vec![];
+        for (end, definitions, value) in ends {
+            if let (Some(value), Some(value_type)) = (value, &value_type) {
+                self.state().current = Some(end);
+                incoming.push((end, self.coerce(value, value_type)?));
+            }
+            join_ends.push((end, definitions));
+        }
+
+        let join = self.new_block();
+        self.join_blocks(join, join_ends);
+        match value_type {
+            Some(value_type) if self.state().current.is_some() => Ok(Some(
+                self.emit(InstructionKind::Phi { incoming }, value_type),
+            )),
+            _ => Ok(None),
+        }
+    }
+
+    ///
+    /// Lower the tests of an arm and its value
+    ///
+    fn lower_match_arm(
+        &mut self,
+        arm: &MatchArm,
+        subject: ValueId,
+        failures: &mut Failures,
+    ) -> IrResult<Option<ValueId>> {
+        self.lower_pattern(&arm.pattern, subject, failures)?;
+        if let Some(guard) = &arm.guard {
+            let guard = self.lower_value(guard)?;
+            self.branch_unless(guard, failures);
+        }
+        self.lower_expression(&arm.value)
+    }
+
+    fn lower_pattern(
+        &mut self,
+        pattern: &Pattern,
+        value: ValueId,
+        failures: &mut Failures,
+    ) -> IrResult<()> {
+        match pattern {
+            Pattern::Wildcard {
+                type_annotation: None,
+                ..
+            } => Ok(()),
+            Pattern::Binding {
+                name,
+                type_annotation: None,
+                ..
+            } => {
+                let (name, _) = identifier_name(name)?;
+                let value_type = self.type_of(value);
+                self.declare_local(name, &value_type, value)
+            }
+            Pattern::Wildcard { span, .. } | Pattern::Binding { span, .. } => Err(lowering_error(
+                String::from("Type patterns are not supported"),
+                *span,
+            )),
+            Pattern::Literal { value: literal, .. } => {
+                let literal = self.lower_value(literal)?;
+                let literal = self.coerce(literal, &self.type_of(value))?;
+                let equal = self.emit(
+                    InstructionKind::Binary {
+                        operator: BinaryOperator::Equal,
+                        left: value,
+                        right: literal,
+                    },
+                    Type::boolean(),
+                );
+                self.branch_unless(equal, failures);
+                Ok(())
+            }
+            Pattern::EnumVariant {
+                enum_name,
+                variant,
+                arguments,
+                ..
+            } => {
+                let (enum_name, span) = identifier_name(enum_name)?;
+                let (variant_name, variant_span) = identifier_name(variant)?;
+                let Some(variants) = self.enums.get(&enum_name) else {
+                    return Err(lowering_error(
+                        format!("Undefined enum '{}'", enum_name),
+                        span,
+                    ));
+                };
+                let Some(tag) = variants.iter().position(|name| name == &variant_name) else {
+                    return Err(lowering_error(
+                        format!(
+                            "Variant '{}' does not exist on enum '{}'",
+                            variant_name, enum_name
+                        ),
+                        variant_span,
+                    ));
+                };
+
+                // Nullable values are not told apart from the others
+                let not_nil = self.emit_not_nil(value);
+                self.branch_unless(not_nil, failures);
+
+                let value_tag = self.emit(
+                    InstructionKind::LoadField {
+                        object: value,
+                        class_name: enum_name.clone(),
+                        field: String::from(ENUM_TAG_FIELD),
+                    },
+                    Type::number(),
+                );
+                let tag = self.emit_constant(Constant::Number(tag as i32));
+                let equal = self.emit(
+                    InstructionKind::Binary {
+                        operator: BinaryOperator::Equal,
+                        left: value_tag,
+                        right: tag,
+                    },
+                    Type::boolean(),
+                );
+                self.branch_unless(equal, failures);
+
+                for (index, argument) in arguments.iter().enumerate() {
+                    // Values matched by anything are not loaded
+                    if matches!(
+                        argument,
+                        Pattern::Wildcard {
+                            type_annotation: None,
+                            ..
+                        }
+                    ) {
+                        continue;
+                    }
+
+                    let field = payload_field(&variant_name, index);
+                    let Some((_, field_type)) = self.find_field(&enum_name, &field) else {
+                        return Err(lowering_error(
+                            format!("Variant '{}' has no value {}", variant_name, index),
+                            argument.span(),
+                        ));
+                    };
+                    let payload = self.emit(
+                        InstructionKind::LoadField {
+                            object: value,
+                            class_name: enum_name.clone(),
+                            field,
+                        },
+                        field_type,
+                    );
+                    self.lower_pattern(argument, payload, failures)?;
+                }
+                Ok(())
+            }
+            Pattern::Array { span, .. } => Err(lowering_error(
+                String::from("Array patterns are not supported"),
+                *span,
+            )),
+            Pattern::Object { span, .. } => Err(lowering_error(
+                String::from("Object patterns are only allowed in declarations"),
+                *span,
+            )),
+        }
+    }
+
+    ///
+    /// Declare the bindings of a destructuring pattern, every part bound to
+    /// a name is loaded from the value
+    ///
+    fn lower_destructuring(&mut self, pattern: &Pattern, value: ValueId) -> IrResult<()> {
+        match pattern {
+            Pattern::Wildcard { .. } => Ok(()),
+            Pattern::Binding { name, .. } => {
+                let (name, _) = identifier_name(name)?;
+                let value_type = self.type_of(value);
+                self.declare_variable(name, &value_type, Some(value))
+            }
+            Pattern::Array { elements, .. } => {
+                let element_type = element_type(&self.type_of(value));
+                for (index, element) in elements.iter().enumerate() {
+                    if matches!(element, Pattern::Wildcard { .. }) {
+                        continue;
+                    }
+                    let index = self.emit_constant(Constant::Number(index as i32));
+                    let element_value = self.emit(
+                        InstructionKind::LoadIndex {
+                            object: value,
+                            index,
+                        },
+                        element_type.clone(),
+                    );
+                    self.lower_destructuring(element, element_value)?;
+                }
+                Ok(())
+            }
+            Pattern::Object { properties, .. } => {
+                for property in properties {
+                    if matches!(property.value, Pattern::Wildcard { .. }) {
+                        continue;
+                    }
+                    let field_value =
+                        self.lower_member(value, false, &property.key, property.span)?;
+                    self.lower_destructuring(&property.value, field_value)?;
+                }
+                Ok(())
+            }
+            Pattern::Literal { span, .. } | Pattern::EnumVariant { span, .. } => Err(
+                lowering_error(String::from("Only names can be destructured into"), *span),
+            ),
+        }
+    }
+
+    ///
+    /// Continue in a new block when the condition holds and with the next
+    /// arm otherwise
+    ///
+    fn branch_unless(&mut self, condition: ValueId, failures: &mut Failures) {
+        let block = self.current_block();
+        failures
+            .ends
+            .push((block, self.state().definitions.clone()));
+
+        let consequent = self.new_block();
+        self.terminate(
+            block,
+            Terminator::Branch {
+                condition,
+                consequent,
+                alternative: failures.next,
+            },
+        );
+        self.state().current = Some(consequent);
+    }
+}
+
+///
+/// Drop the blocks that cannot run and the phi nodes that pick a single
+/// value, then number blocks and values in order
+///
+fn finalize_function(function: &mut IrFunction) {
+    remove_unreachable_blocks(function);
+    remove_trivial_phis(function);
+    remove_unused_placeholder_values(function);
+    renumber_values(function);
+}
+
+fn remove_unreachable_blocks(function: &mut IrFunction) {
+    let mut reachable = vec![false; function.blocks.len()];
+    let mut pending = vec![0];
+    while let Some(block) = pending.pop() {
+        if reachable[block] {
+            continue;
+        }
+        reachable[block] = true;
+        if let Some(terminator) = &function.blocks[block].terminator {
+            pending.extend(terminator.successors());
+        }
+    }
+
+    let mut block_ids = vec![None; function.blocks.len()];
+    let mut next_id = 0;
+    for (block, is_reachable) in reachable.iter().enumerate() {
+        if *is_reachable {
+            block_ids[block] = Some(next_id);
+            next_id += 1;
+        }
+    }
+
+    let blocks = std::mem::take(&mut function.blocks);
+    for (block, mut ir_block) in blocks.into_iter().enumerate() {
+        if !reachable[block] {
+            continue;
+        }
+
+        for instruction in &mut ir_block.instructions {
+            if let InstructionKind::Phi { incoming } = &mut instruction.kind {
+                incoming.retain(|(predecessor, _)| reachable[*predecessor]);
+                for (predecessor, _) in incoming.iter_mut() {
+                    *predecessor = block_ids[*predecessor].unwrap_or_default();
+                }
+            }
+        }
+        if let Some(terminator) = &mut ir_block.terminator {
+            terminator.for_each_successor_mut(&mut |successor| {
+                *successor = block_ids[*successor].unwrap_or_default()
+            });
+        }
+
+        function.blocks.push(ir_block);
+    }
+}
+
+///
+/// Replace phi nodes whose operands are all the same value or the phi itself
+///
+fn remove_trivial_phis(function: &mut IrFunction) {
+    while let Some((phi, value)) = find_trivial_phi(function) {
+        for block in &mut function.blocks {
+            block
+                .instructions
+                .retain(|instruction| instruction.result != Some(phi));
+        }
+        replace_uses(function, phi, value);
+    }
+}
+
+fn find_trivial_phi(function: &IrFunction) -> Option<(ValueId, ValueId)> {
+    for instruction in function.blocks.iter().flat_map(|block| &block.instructions) {
+        let (Some(result), InstructionKind::Phi { incoming }) =
+            (instruction.result, &instruction.kind)
+        else {
+            continue;
+        };
+
+        let mut operands = incoming
+            .iter()
+            .map(|(_, value)| *value)
+            .filter(|value| *value != result);
+        let Some(first) = operands.next() else {
+            continue;
+        };
+        if operands.all(|value| value == first) {
+            return Some((result, first));
+        }
+    }
+
+    None
+}
+
+fn replace_uses(function: &mut IrFunction, old: ValueId, new: ValueId) {
+    let mut replace = |operand: &mut ValueId| {
+        if *operand == old {
+            *operand = new;
+        }
+    };
+
+    for block in &mut function.blocks {
+        for instruction in &mut block.instructions {
+            instruction.kind.for_each_operand_mut(&mut replace);
+        }
+        if let Some(terminator) = &mut block.terminator {
+            terminator.for_each_operand_mut(&mut replace);
+        }
+    }
+}
+
+///
+/// Undefined values and nil constants replaced by a typed nil are only
+/// kept where something still reads them
+///
+fn remove_unused_placeholder_values(function: &mut IrFunction) {
+    let mut used = HashSet::new();
+    for block in &function.blocks {
+        for instruction in &block.instructions {
+            used.extend(instruction.kind.operands());
+        }
+        if let Some(terminator) = &block.terminator {
+            terminator.clone().for_each_operand_mut(&mut |operand| {
+                used.insert(*operand);
+            });
+        }
+    }
+
+    for block in &mut function.blocks {
+        block.instructions.retain(|instruction| {
+            !matches!(
+                instruction.kind,
+                InstructionKind::Undefined | InstructionKind::Constant(Constant::Nil)
+            ) || instruction
+                .result
+                .is_some_and(|result| used.contains(&result))
+        });
+    }
+}
+
+///
+/// Number the parameters first, then the values in the order they are defined
+///
+fn renumber_values(function: &mut IrFunction) {
+    let mut value_ids = HashMap::new();
+    let mut value_types = vec![];
+
+    let mut number = |value: ValueId, value_ids: &mut HashMap<ValueId, ValueId>| {
+        value_ids.insert(value, value_types.len());
+        value_types.push(function.value_types[value].clone());
+    };
+
+    for parameter in &function.parameters {
+        number(*parameter, &mut value_ids);
+    }
+    for block in &function.blocks {
+        for instruction in &block.instructions {
+            if let Some(result) = instruction.result {
+                number(result, &mut value_ids);
+            }
+        }
+    }
+
+    let mut rename = |value: &mut ValueId| *value = value_ids[value];
+    for parameter in &mut function.parameters {
+        rename(parameter);
+    }
+    for block in &mut function.blocks {
+        for instruction in &mut block.instructions {
+            if let Some(result) = &mut instruction.result {
+                rename(result);
+            }
+            instruction.kind.for_each_operand_mut(&mut rename);
+        }
+        if let Some(terminator) = &mut block.terminator {
+            terminator.for_each_operand_mut(&mut rename);
+        }
+    }
+
+    function.value_types = value_types;
+}
+
+///
+/// Whether the type is nil or an array built from literals holding nothing
+/// but nil and such arrays
+///
+fn has_no_element_type(value_type: &Type) -> bool {
+    match value_type {
+        Type::Nil => true,
+        Type::Array(element_type) => has_no_element_type(element_type),
+        _ => false,
+    }
+}
+
+///
+/// Names of the variables assigned anywhere in the expression, nested
+/// functions and classes are left out
+///
+fn collect_assigned_names(expression: &Expression, names: &mut HashSet<String>) {
+    match expression {
+        Expression::Variable { initializer, .. } => {
+            if let Some(initializer) = initializer {
+                collect_assigned_names(initializer, names);
+            }
+        }
+        Expression::Assignment { left, right, .. } => {
+            if let Expression::Identifier { name, .. } = left.as_ref() {
+                names.insert(name.clone());
+            }
+            collect_assigned_names(left, names);
+            collect_assigned_names(right, names);
+        }
+        Expression::Binary { left, right, .. } | Expression::Logical { left, right, .. } => {
+            collect_assigned_names(left, names);
+            collect_assigned_names(right, names);
+        }
+        Expression::Unary { right, .. }
+        | Expression::TypeTest {
+            expression: right, ..
+        } => collect_assigned_names(right, names),
+        Expression::Member {
+            object, property, ..
+        } => {
+            collect_assigned_names(object, names);
+            collect_assigned_names(property, names);
+        }
+        Expression::Call {
+            callee, arguments, ..
+        }
+        | Expression::New {
+            callee, arguments, ..
+        } => {
+            collect_assigned_names(callee, names);
+            for argument in arguments {
+                collect_assigned_names(argument, names);
+            }
+        }
+        Expression::EnumVariant { arguments, .. }
+        | Expression::ArrayLiteral {
+            elements: arguments,
+            ..
+        } => {
+            for argument in arguments {
+                collect_assigned_names(argument, names);
+            }
+        }
+        Expression::Match { subject, arms, .. } => {
+            collect_assigned_names(subject, names);
+            for arm in arms {
+                if let Some(guard) = &arm.guard {
+                    collect_assigned_names(guard, names);
+                }
+                collect_assigned_names(&arm.value, names);
+            }
+        }
+        Expression::BooleanLiteral { .. }
+        | Expression::NilLiteral { .. }
+        | Expression::StringLiteral { .. }
+        | Expression::NumericLiteral { .. }
+        | Expression::Identifier { .. }
+        | Expression::This { .. }
+        | Expression::Super { .. }
+        | Expression::Destructuring { .. } => {}
+    }
+}
+
+///
+/// Ends of a loop body without the variables declared in the body, only the
+/// ones defined before it stay in scope
+///
+fn in_scope(ends: Vec<(BlockId, Definitions)>, scope: &Definitions) -> Vec<(BlockId, Definitions)> {
+    ends.into_iter()
+        .map(|(end, mut definitions)| {
+            definitions.retain(|variable, _| scope.contains_key(variable));
+            (end, definitions)
+        })
+        .collect()
+}
+
+fn collect_statement_assigned_names(statement: &Statement, names: &mut HashSet<String>) {
+    match statement {
+        Statement::Program { body, .. } | Statement::Block { body, .. } => {
+            for statement in body {
+                collect_statement_assigned_names(statement, names);
+            }
+        }
+        Statement::Expression { expression, .. } => collect_assigned_names(expression, names),
+        Statement::VariableDeclaration { variables, .. } => {
+            for variable in variables {
+                collect_assigned_names(variable, names);
+            }
+        }
+        Statement::If {
+            condition,
+            consequent,
+            alternative,
+            ..
+        } => {
+            collect_assigned_names(condition, names);
+            collect_statement_assigned_names(consequent, names);
+            if let Some(alternative) = alternative {
+                collect_statement_assigned_names(alternative, names);
+            }
+        }
+        Statement::While {
+            condition, body, ..
+        }
+        | Statement::DoWhile {
+            body, condition, ..
+        } => {
+            collect_assigned_names(condition, names);
+            collect_statement_assigned_names(body, names);
+        }
+        Statement::For {
+            initializer,
+            condition,
+            increment,
+            body,
+            ..
+        } => {
+            if let Some(initializer) = initializer {
+                collect_statement_assigned_names(initializer, names);
+            }
+            for expression in [condition, increment].into_iter().flatten() {
+                collect_assigned_names(expression, names);
+            }
+            collect_statement_assigned_names(body, names);
+        }
+        Statement::Labeled { body, .. } => collect_statement_assigned_names(body, names),
+        Statement::Return { argument, .. } => {
+            if let Some(argument) = argument {
+                collect_assigned_names(argument, names);
+            }
+        }
+        Statement::Empty { .. }
+        | Statement::Break { .. }
+        | Statement::Continue { .. }
+        | Statement::FunctionDeclaration { .. }
+        | Statement::ClassDeclaration { .. }
+        | Statement::InterfaceDeclaration { .. }
+        | Statement::EnumDeclaration { .. } => {}
+    }
+}
+
+fn compound_operator(operator: AssignmentOperator) -> Option<BinaryOperator> {
+    match operator {
+        AssignmentOperator::Assign => None,
+        AssignmentOperator::AssignAdd => Some(BinaryOperator::Add),
+        AssignmentOperator::AssignSubtract => Some(BinaryOperator::Subtract),
+        AssignmentOperator::AssignMultiply => Some(BinaryOperator::Multiply),
+        AssignmentOperator::AssignDivide => Some(BinaryOperator::Divide),
+    }
+}
+
+fn binary_type(operator: BinaryOperator, left_type: Type) -> Type {
+    match operator {
+        BinaryOperator::Add
+        | BinaryOperator::Subtract
+        | BinaryOperator::Multiply
+        | BinaryOperator::Divide => left_type,
+        BinaryOperator::Equal
+        | BinaryOperator::NotEqual
+        | BinaryOperator::GreaterThan
+        | BinaryOperator::GreaterThanOrEqualTo
+        | BinaryOperator::LessThan
+        | BinaryOperator::LessThanOrEqualTo => Type::boolean(),
+    }
+}
+
+///
+/// Type of the elements read by indexing, strings are indexed by character
+///
+fn element_type(object_type: &Type) -> Type {
+    match object_type {
+        Type::Array(element_type) => element_type.as_ref().clone(),
+        _ => Type::string(),
+    }
+}
+
+fn function_type(parameters: &ParameterList, return_type: &Type) -> Type {
+    Type::Function {
+        params: parameters
+            .iter()
+            .map(|(_, parameter_type)| erase_nullable(parameter_type))
+            .collect(),
+        return_type: Box::new(erase_nullable(return_type)),
+    }
+}
+
+///
+/// Nullable types are represented like the type they wrap, nil included
+///
+fn erase_nullable(value_type: &Type) -> Type {
+    match value_type {
+        Type::Nullable(value_type) => erase_nullable(value_type),
+        Type::Array(element_type) => Type::Array(Box::new(erase_nullable(element_type))),
+        Type::Function {
+            params,
+            return_type,
+        } => Type::Function {
+            params: params.iter().map(erase_nullable).collect(),
+            return_type: Box::new(erase_nullable(return_type)),
+        },
+        Type::Generic { base, type_args } => Type::Generic {
+            base: base.clone(),
+            type_args: type_args.iter().map(erase_nullable).collect(),
+        },
+        Type::Union(members) => Type::Union(members.iter().map(erase_nullable).collect()),
+        // Enums are lowered to classes of the same name
+        Type::Enum(name) => class_type(name),
+        Type::Primitive(_) | Type::Class { .. } | Type::Parameter(_) | Type::Void | Type::Nil => {
+            value_type.clone()
+        }
+    }
+}
+
+fn return_type(function_type: &Type) -> Type {
+    match function_type {
+        Type::Function { return_type, .. } => return_type.as_ref().clone(),
+        _ => Type::Void,
+    }
+}
+
+///
+/// Field of an enum holding a value of a variant
+///
+fn payload_field(variant_name: &str, index: usize) -> String {
+    format!("{}_{}", variant_name, index)
+}
+
+fn class_type(class_name: &str) -> Type {
+    Type::Class {
+        name: String::from(class_name),
+        super_class: None,
+    }
+}
+
+fn object_class_name(object_type: &Type, span: Span) -> IrResult<String> {
+    match object_type {
+        Type::Class { name, .. } => Ok(name.clone()),
+        _ => Err(lowering_error(
+            format!("Cannot assign to a member of type '{}'", object_type),
+            span,
+        )),
+    }
+}
+
+///
+/// Generic functions and classes have no single lowering, type parameters
+/// are erased only by the JavaScript backend
+///
+fn ensure_not_generic(statement: &Statement) -> IrResult<()> {
+    let (kind, name) = match statement {
+        Statement::FunctionDeclaration {
+            name,
+            type_parameters,
+            ..
+        } if !type_parameters.is_empty() => ("function", name),
+        Statement::ClassDeclaration {
+            name,
+            type_parameters,
+            ..
+        } if !type_parameters.is_empty() => ("class", name),
+        Statement::ClassDeclaration { members, .. } => {
+            for member in members {
+                if let ClassMember::Method { declaration } = member {
+                    ensure_not_generic(declaration)?;
+                }
+            }
+            return Ok(());
+        }
+        _ => return Ok(()),
+    };
+
+    let (name, span) = identifier_name(name)?;
+    Err(lowering_error(
+        format!("Generic {} '{}' is not supported", kind, name),
+        span,
+    ))
+}
+
+fn identifier_name(expression: &Expression) -> IrResult<(String, Span)> {
+    match expression {
+        Expression::Identifier { name, span } => Ok((name.clone(), *span)),
+        _ => Err(lowering_error(
+            String::from("Expected an identifier"),
+            expression.span(),
+        )),
+    }
+}
+
+fn lowering_error(message: String, span: Span) -> IrError {
+    IrError::LoweringError { message, span }
+}
+
+///
+/// Numbers and booleans use every value of their representation, so none
+/// is left for nil
+///
+fn ensure_nil_representable(value_type: &Type, span: Span) -> IrResult<()> {
+    match value_type {
+        Type::Primitive(PrimitiveType::Number | PrimitiveType::Boolean) => Err(lowering_error(
+            format!("Nil values of type '{}' are not supported", value_type),
+            span,
+        )),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,219 @@
+use std::fmt;
+
+use crate::ast::{BinaryOperator, UnaryOperator};
+
+use super::{
+    Constant, Instruction, InstructionKind, IrClass, IrFunction, IrModule, Terminator, ValueId,
+};
+
+///
+/// Textual form of the module, globals come first, then the class layouts
+/// and the functions, separated by empty lines
+///
+impl fmt::Display for IrModule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut sections = vec![];
+
+        if !self.globals.is_empty() {
+            let globals: String = self
+                .globals
+                .iter()
+                .map(|(name, global_type)| format!("global @{}: {}\n", name, global_type))
+                .collect();
+            sections.push(globals);
+        }
+        sections.extend(self.classes.iter().map(|class| class.to_string()));
+        sections.extend(self.functions.iter().map(|function| function.to_string()));
+
+        write!(f, "{}", sections.join("\n"))
+    }
+}
+
+impl fmt::Display for IrClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "class {}", self.name)?;
+        if let Some(super_class) = &self.super_class {
+            write!(f, " extends {}", super_class)?;
+        }
+
+        if self.fields.is_empty() {
+            return writeln!(f, " {{}}");
+        }
+
+        writeln!(f, " {{")?;
+        for (name, field_type) in &self.fields {
+            writeln!(f, "  {}: {}", name, field_type)?;
+        }
+        writeln!(f, "}}")
+    }
+}
+
+impl fmt::Display for IrFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parameters = self
+            .parameters
+            .iter()
+            .map(|parameter| format!("%{}: {}", parameter, self.value_types[*parameter]))
+            .collect::<Vec<_>>()
+            .join(", ");
+        writeln!(
+            f,
+            "def @{}({}): {} {{",
+            self.name, parameters, self.return_type
+        )?;
+
+        for (id, block) in self.blocks.iter().enumerate() {
+            writeln!(f, "b{}:", id)?;
+            for instruction in &block.instructions {
+                write!(f, "  ")?;
+                if let Some(result) = instruction.result {
+                    write!(f, "%{}: {} = ", result, self.value_types[result])?;
+                }
+                writeln!(f, "{}", instruction)?;
+            }
+            match &block.terminator {
+                Some(terminator) => writeln!(f, "  {}", terminator)?,
+                None => writeln!(f, "  unreachable")?,
+            }
+        }
+
+        writeln!(f, "}}")
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            InstructionKind::Constant(constant) => write!(f, "const {}", constant),
+            InstructionKind::Undefined => write!(f, "undef"),
+            InstructionKind::Unary { operator, operand } => {
+                write!(f, "{} %{}", unary_opcode(*operator), operand)
+            }
+            InstructionKind::Binary {
+                operator,
+                left,
+                right,
+            } => write!(f, "{} %{}, %{}", binary_opcode(*operator), left, right),
+            InstructionKind::Phi { incoming } => {
+                let incoming = incoming
+                    .iter()
+                    .map(|(block, value)| format!("[b{}: %{}]", block, value))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(f, "phi {}", incoming)
+            }
+            InstructionKind::LoadGlobal { name } => write!(f, "load_global @{}", name),
+            InstructionKind::StoreGlobal { name, value } => {
+                write!(f, "store_global @{}, %{}", name, value)
+            }
+            InstructionKind::Function { name } => write!(f, "function @{}", name),
+            InstructionKind::Call {
+                function,
+                arguments,
+            } => write!(f, "call @{}({})", function, format_values(arguments)),
+            InstructionKind::CallIndirect { callee, arguments } => {
+                write!(f, "call %{}({})", callee, format_values(arguments))
+            }
+            InstructionKind::CallMethod {
+                object,
+                class_name,
+                method,
+                arguments,
+            } => write!(
+                f,
+                "call_method %{}, {}.{}({})",
+                object,
+                class_name,
+                method,
+                format_values(arguments)
+            ),
+            InstructionKind::New { class_name } => write!(f, "new {}", class_name),
+            InstructionKind::NewArray { length } => write!(f, "new_array %{}", length),
+            InstructionKind::LoadField {
+                object,
+                class_name,
+                field,
+            } => write!(f, "load_field %{}, {}.{}", object, class_name, field),
+            InstructionKind::StoreField {
+                object,
+                class_name,
+                field,
+                value,
+            } => write!(
+                f,
+                "store_field %{}, {}.{}, %{}",
+                object, class_name, field, value
+            ),
+            InstructionKind::LoadIndex { object, index } => {
+                write!(f, "load_index %{}, %{}", object, index)
+            }
+            InstructionKind::StoreIndex {
+                object,
+                index,
+                value,
+            } => write!(f, "store_index %{}, %{}, %{}", object, index, value),
+            InstructionKind::Length { object } => write!(f, "length %{}", object),
+        }
+    }
+}
+
+impl fmt::Display for Terminator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Terminator::Jump(target) => write!(f, "jump b{}", target),
+            Terminator::Branch {
+                condition,
+                consequent,
+                alternative,
+            } => write!(
+                f,
+                "branch %{}, b{}, b{}",
+                condition, consequent, alternative
+            ),
+            Terminator::Return(Some(value)) => write!(f, "ret %{}", value),
+            Terminator::Return(None) => write!(f, "ret"),
+        }
+    }
+}
+
+impl fmt::Display for Constant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Constant::Number(value) => write!(f, "{}", value),
+            Constant::Boolean(value) => write!(f, "{}", value),
+            Constant::String(value) => write!(f, "{:?}", value),
+            Constant::Nil => write!(f, "nil"),
+        }
+    }
+}
+
+fn unary_opcode(operator: UnaryOperator) -> &'static str {
+    match operator {
+        UnaryOperator::Plus => "plus",
+        UnaryOperator::Minus => "neg",
+        UnaryOperator::Not => "not",
+    }
+}
+
+fn binary_opcode(operator: BinaryOperator) -> &'static str {
+    match operator {
+        BinaryOperator::Add => "add",
+        BinaryOperator::Subtract => "sub",
+        BinaryOperator::Multiply => "mul",
+        BinaryOperator::Divide => "div",
+        BinaryOperator::Equal => "eq",
+        BinaryOperator::NotEqual => "ne",
+        BinaryOperator::GreaterThan => "gt",
+        BinaryOperator::GreaterThanOrEqualTo => "ge",
+        BinaryOperator::LessThan => "lt",
+        BinaryOperator::LessThanOrEqualTo => "le",
+    }
+}
+
+fn format_values(values: &[ValueId]) -> String {
+    values
+        .iter()
+        .map(|value| format!("%{}", value))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
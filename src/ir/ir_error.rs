@@ -0,0 +1,19 @@
+use thiserror::Error;
+
+use crate::lexer::Span;
+
+#[derive(Error, Debug)]
+pub enum IrError {
+    #[error("Lowering error: {message}")]
+    LoweringError { message: String, span: Span },
+}
+
+impl IrError {
+    pub fn span(&self) -> Span {
+        match self {
+            IrError::LoweringError { span, .. } => *span,
+        }
+    }
+}
+
+pub type IrResult<T> = Result<T, IrError>;
@@ -0,0 +1,9 @@
+mod ir;
+mod ir_builder;
+mod ir_dump;
+mod ir_error;
+mod tests;
+
+pub(crate) use self::ir::*;
+pub(crate) use self::ir_builder::*;
+pub(crate) use self::ir_error::*;
@@ -0,0 +1,73 @@
+use crate::driver::lower_source;
+use serde::Deserialize;
+use std::fs;
+
+#[derive(Debug, Deserialize)]
+pub(super) struct YamlTestCase {
+    description: String,
+    source: String,
+    expected_ir: Option<String>,
+    expected_error: Option<String>,
+}
+
+pub(super) fn execute_yaml_test(test_case: &YamlTestCase) {
+    let module = lower_source(&test_case.source).map(|(module, _)| module);
+
+    match (&test_case.expected_ir, &test_case.expected_error) {
+        (Some(expected_ir), None) => {
+            let Ok(module) = module else {
+                panic!("Failed to lower the source:\n{}\n", test_case.source)
+            };
+            assert_eq!(module.to_string(), *expected_ir);
+        }
+        (None, Some(expected_error)) => {
+            let actual_error = module.err().map(|diagnostic| diagnostic.message);
+            assert_eq!(actual_error.as_ref(), Some(expected_error));
+        }
+        _ => panic!("Test cases need either an expected ir or an expected error"),
+    }
+}
+
+pub(super) fn load_yaml_test_cases(path: &str) -> Vec<YamlTestCase> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(error) => {
+            eprintln!("Failed to read the test yaml file: {}", error);
+            eprintln!("Path: {}", path);
+            panic!("Could not read test file: {}", error);
+        }
+    };
+
+    // Parse multiple documents
+    let mut test_cases = Vec::new();
+
+    for document in serde_yaml::Deserializer::from_str(&content) {
+        match YamlTestCase::deserialize(document) {
+            Ok(test_case) => test_cases.push(test_case),
+            Err(e) => {
+                eprintln!("Failed to parse a YAML document: {}", e);
+                eprintln!("Path: {}", path);
+                panic!("Could not parse YAML content: {}", e);
+            }
+        }
+    }
+
+    // Verify that we found some test cases
+    if test_cases.is_empty() {
+        eprintln!("No test cases found in file: {}", path);
+        panic!("File contained no valid test cases");
+    }
+
+    test_cases
+}
+
+pub(super) fn execute_yaml_test_cases(path: &str) {
+    let test_cases = load_yaml_test_cases(path);
+
+    for (index, test_case) in test_cases.iter().enumerate() {
+        println!("Testing case #{}:\n{}\n", index + 1, test_case.description);
+        execute_yaml_test(test_case);
+    }
+
+    println!("All {} test cases passed!", test_cases.len());
+}
@@ -0,0 +1,11 @@
+use super::internal_util::execute_yaml_test_cases;
+
+#[test]
+fn test_ir_lowering() {
+    execute_yaml_test_cases("tests/ir/test_ir_lowering.yaml");
+}
+
+#[test]
+fn test_ir_classes() {
+    execute_yaml_test_cases("tests/ir/test_ir_classes.yaml");
+}
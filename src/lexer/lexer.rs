@@ -1,5 +1,5 @@
 use super::{
-    token::{Token, TokenType},
+    token::{Token, TokenType, Trivia, TriviaKind},
     LexerError, LexerResult, TokenPosition,
 };
 
@@ -24,6 +24,21 @@ impl<'a> Lexer<'a> {
         }
     }
 
+    ///
+    /// Lexer that starts in the middle of the source, at a token boundary
+    ///
+    pub fn new_at(source: &'a str, start: TokenPosition) -> Self {
+        let mut chars = source[start.offset..].char_indices();
+        let current_char = chars.next();
+
+        Lexer {
+            source,
+            position: start,
+            chars,
+            current_char,
+        }
+    }
+
     pub fn next_token(&mut self) -> LexerResult<Token> {
         self.skip_whitespace_and_comments()?;
 
@@ -49,7 +64,7 @@ impl<'a> Lexer<'a> {
 
             // Single character tokens
             ';' => self.consume_single_char_token(TokenType::StatementEnd, start_pos),
-            ':' => self.consume_single_char_token(TokenType::Colon, start_pos),
+            ':' => self.read_colon(start_pos),
             '.' => self.consume_single_char_token(TokenType::Dot, start_pos),
             ',' => self.consume_single_char_token(TokenType::Comma, start_pos),
             '{' => self.consume_single_char_token(TokenType::OpeningBrace, start_pos),
@@ -70,6 +85,7 @@ impl<'a> Lexer<'a> {
             '&' => self.read_logical_and(start_pos),
             '|' => self.read_logical_or(start_pos),
             '!' => self.read_not_operator(start_pos),
+            '?' => self.read_question_mark(start_pos),
 
             _ => Err(LexerError::UnexpectedCharacter {
                 char: ch,
@@ -79,6 +95,19 @@ impl<'a> Lexer<'a> {
         }
     }
 
+    ///
+    /// Next token together with the whitespace and comments that precede it,
+    /// the End token carries the trivia at the end of the source
+    ///
+    pub fn next_token_with_trivia(&mut self) -> LexerResult<(Vec<Trivia>, Token)> {
+        let mut trivia = vec![];
+        while let Some(piece) = self.read_trivia()? {
+            trivia.push(piece);
+        }
+
+        Ok((trivia, self.next_token()?))
+    }
+
     #[allow(dead_code)]
     pub fn peek_token(&mut self) -> LexerResult<Token> {
         // Save current state
@@ -114,11 +143,16 @@ impl<'a> Lexer<'a> {
     fn read_equality_or_assignment(&mut self, start_pos: TokenPosition) -> LexerResult<Token> {
         self.advance(); // consume '='
 
-        let token_type = if self.peek_char() == Some('=') {
-            self.advance();
-            TokenType::EqualOperator
-        } else {
-            TokenType::SimpleAssignmentOperator
+        let token_type = match self.peek_char() {
+            Some('=') => {
+                self.advance();
+                TokenType::EqualOperator
+            }
+            Some('>') => {
+                self.advance();
+                TokenType::Arrow
+            }
+            _ => TokenType::SimpleAssignmentOperator,
         };
 
         Ok(Token {
@@ -260,10 +294,10 @@ impl<'a> Lexer<'a> {
                 end: self.position,
             })
         } else {
-            Err(LexerError::UnexpectedCharacter {
-                char: '|',
-                line: start_pos.line,
-                column: start_pos.column,
+            Ok(Token {
+                token_type: TokenType::BitwiseOrOperator,
+                start: start_pos,
+                end: self.position,
             })
         }
     }
@@ -285,6 +319,46 @@ impl<'a> Lexer<'a> {
         })
     }
 
+    fn read_colon(&mut self, start_pos: TokenPosition) -> LexerResult<Token> {
+        self.advance(); // consume ':'
+
+        let token_type = match self.peek_char() {
+            Some(':') => {
+                self.advance();
+                TokenType::DoubleColon
+            }
+            _ => TokenType::Colon,
+        };
+
+        Ok(Token {
+            token_type,
+            start: start_pos,
+            end: self.position,
+        })
+    }
+
+    fn read_question_mark(&mut self, start_pos: TokenPosition) -> LexerResult<Token> {
+        self.advance(); // consume '?'
+
+        let token_type = match self.peek_char() {
+            Some('?') => {
+                self.advance();
+                TokenType::NilCoalescingOperator
+            }
+            Some('.') => {
+                self.advance();
+                TokenType::OptionalChaining
+            }
+            _ => TokenType::QuestionMark,
+        };
+
+        Ok(Token {
+            token_type,
+            start: start_pos,
+            end: self.position,
+        })
+    }
+
     // Improved string reading with escape sequence support
     fn read_string(&mut self, quote: char) -> LexerResult<Token> {
         let start_pos = self.position;
@@ -311,9 +385,9 @@ impl<'a> Lexer<'a> {
                         'x' => {
                             // Hex escape sequence \xNN
                             self.advance();
-                            if self.peek_char().map_or(false, |c| c.is_ascii_hexdigit()) {
+                            if self.peek_char().is_some_and(|c| c.is_ascii_hexdigit()) {
                                 self.advance();
-                                if self.peek_char().map_or(false, |c| c.is_ascii_hexdigit()) {
+                                if self.peek_char().is_some_and(|c| c.is_ascii_hexdigit()) {
                                     self.advance();
                                 }
                             }
@@ -446,12 +520,19 @@ impl<'a> Lexer<'a> {
             "for" => TokenType::ForKeyword,
             "def" => TokenType::DefKeyword,
             "return" => TokenType::ReturnKeyword,
+            "match" => TokenType::MatchKeyword,
+            "break" => TokenType::BreakKeyword,
+            "continue" => TokenType::ContinueKeyword,
             "class" => TokenType::ClassKeyword,
             "extends" => TokenType::ExtendsKeyword,
+            "interface" => TokenType::InterfaceKeyword,
+            "implements" => TokenType::ImplementsKeyword,
+            "enum" => TokenType::EnumKeyword,
             "this" => TokenType::ThisKeyword,
             "super" => TokenType::SuperKeyword,
             "new" => TokenType::NewKeyword,
             "type" => TokenType::TypeKeyword,
+            "is" => TokenType::IsKeyword,
             "number" => TokenType::NumberTypeKeyword,
             "string" => TokenType::StringTypeKeyword,
             "boolean" => TokenType::BooleanTypeKeyword,
@@ -471,22 +552,30 @@ impl<'a> Lexer<'a> {
         self.current_char.map(|(_, ch)| ch)
     }
 
-    fn skip_single_line_comment(&mut self) {
+    fn read_whitespace(&mut self) {
+        while let Some(ch) = self.peek_char() {
+            if !ch.is_whitespace() {
+                break;
+            }
+            self.advance();
+        }
+    }
+
+    fn read_single_line_comment(&mut self) {
         // Skip the '//' characters
         self.advance(); // first '/'
         self.advance(); // second '/'
 
-        // Skip until end of line or end of file
+        // Skip until end of line or end of file, the newline is whitespace
         while let Some(ch) = self.peek_char() {
             if ch == '\n' {
-                self.advance(); // consume the newline
                 break;
             }
             self.advance();
         }
     }
 
-    fn skip_multi_line_comment(&mut self) -> Result<(), LexerError> {
+    fn read_multi_line_comment(&mut self) -> Result<(), LexerError> {
         let start_pos = self.position;
 
         // Skip the '/*' characters
@@ -512,47 +601,44 @@ impl<'a> Lexer<'a> {
         })
     }
 
-    fn skip_whitespace_and_comments(&mut self) -> Result<(), LexerError> {
-        loop {
-            // Skip whitespace
-            while let Some(ch) = self.peek_char() {
-                if ch.is_whitespace() {
-                    self.advance();
-                } else {
-                    break;
-                }
-            }
+    ///
+    /// Read a single run of whitespace or a comment, None when a token follows
+    ///
+    fn read_trivia(&mut self) -> LexerResult<Option<Trivia>> {
+        let start = self.position;
 
-            // Check for comments
-            match self.peek_char() {
-                Some('/') => {
-                    // Look ahead to see what kind of comment
-                    let mut chars_clone = self.chars.clone();
-                    if let Some((_, next_ch)) = chars_clone.next() {
-                        match next_ch {
-                            '/' => {
-                                self.skip_single_line_comment();
-                                // Continue the loop to check for more whitespace/comments
-                                continue;
-                            }
-                            '*' => {
-                                self.skip_multi_line_comment()?;
-                                // Continue the loop to check for more whitespace/comments
-                                continue;
-                            }
-                            _ => {
-                                // Not a comment, just a division operator
-                                break;
-                            }
-                        }
-                    } else {
-                        // End of file after '/', not a comment
-                        break;
+        let kind = match self.peek_char() {
+            Some(ch) if ch.is_whitespace() => {
+                self.read_whitespace();
+                TriviaKind::Whitespace
+            }
+            Some('/') => {
+                // Look ahead to see what kind of comment, if any
+                match self.chars.clone().next() {
+                    Some((_, '/')) => {
+                        self.read_single_line_comment();
+                        TriviaKind::LineComment
+                    }
+                    Some((_, '*')) => {
+                        self.read_multi_line_comment()?;
+                        TriviaKind::BlockComment
                     }
+                    // Not a comment, just a division operator
+                    _ => return Ok(None),
                 }
-                _ => break,
             }
-        }
+            _ => return Ok(None),
+        };
+
+        Ok(Some(Trivia {
+            kind,
+            start,
+            end: self.position,
+        }))
+    }
+
+    fn skip_whitespace_and_comments(&mut self) -> Result<(), LexerError> {
+        while self.read_trivia()?.is_some() {}
         Ok(())
     }
 }
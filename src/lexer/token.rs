@@ -22,18 +22,25 @@ pub enum TokenType {
     DoKeyword,
     ForKeyword,
     ReturnKeyword,
+    MatchKeyword,
+    BreakKeyword,
+    ContinueKeyword,
 
     // Declaration keywords
     LetKeyword,
     DefKeyword,
     ClassKeyword,
     ExtendsKeyword,
+    InterfaceKeyword,
+    ImplementsKeyword,
+    EnumKeyword,
 
     // Special keywords
     ThisKeyword,
     SuperKeyword,
     NewKeyword,
     TypeKeyword,
+    IsKeyword,
 
     // Type keywords
     NumberTypeKeyword,
@@ -70,10 +77,11 @@ pub enum TokenType {
     LogicalAndOperator,
     LogicalOrOperator,
     LogicalNotOperator,
+    NilCoalescingOperator,
 
     // Operators - Bitwise (new)
     BitwiseAndOperator, // & TODO
-    BitwiseOrOperator,  // | TODO
+    BitwiseOrOperator,  // | - separates the members of union types
     BitwiseXorOperator, // ^ TODO
     BitwiseNotOperator, // ~ TODO
     LeftShiftOperator,  // << TODO
@@ -94,9 +102,10 @@ pub enum TokenType {
     Comma,              // ,
     Dot,                // .
     Colon,              // :
-    QuestionMark,       // ? - new (for ternary operator) TODO
-    Arrow,              // => - new (for arrow functions) TODO
-    DoubleColon,        // :: - new (for namespacing) TODO
+    QuestionMark,       // ?
+    OptionalChaining,   // ?.
+    Arrow,              // => - separates a match arm from its value
+    DoubleColon,        // :: - separates an enum from its variant
 
     // Special
     End,
@@ -112,13 +121,19 @@ impl fmt::Display for TokenType {
 ///
 /// Token position in the source
 ///
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct TokenPosition {
     pub line: usize,
     pub column: usize,
     pub offset: usize,
 }
 
+impl Default for TokenPosition {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TokenPosition {
     pub fn new() -> Self {
         TokenPosition {
@@ -139,6 +154,62 @@ impl TokenPosition {
     }
 }
 
+///
+/// Source range covered by a token or a syntax node
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Span {
+    pub start: TokenPosition,
+    pub end: TokenPosition,
+}
+
+impl Span {
+    pub fn new(start: TokenPosition, end: TokenPosition) -> Self {
+        Span { start, end }
+    }
+
+    ///
+    /// Whether the position is inside the span, its end included
+    ///
+    pub fn contains(&self, position: TokenPosition) -> bool {
+        self.start.offset <= position.offset && position.offset <= self.end.offset
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}-{}:{}",
+            self.start.line, self.start.column, self.end.line, self.end.column
+        )
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub enum TriviaKind {
+    Whitespace,
+    LineComment,
+    BlockComment,
+}
+
+///
+/// Whitespace or a comment, the source text between tokens
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    pub start: TokenPosition,
+    pub end: TokenPosition,
+}
+
+impl Trivia {
+    pub fn text<'a>(&self, source: &'a str) -> &'a str {
+        &source[self.start.offset..self.end.offset]
+    }
+}
+
 ///
 /// Token structure
 ///
@@ -155,6 +226,10 @@ impl Token {
         &source[self.start.offset..self.end.offset]
     }
 
+    pub fn span(&self) -> Span {
+        Span::new(self.start, self.end)
+    }
+
     pub fn len(&self) -> usize {
         self.end.offset - self.start.offset
     }
@@ -184,14 +259,21 @@ impl Token {
                 | TokenType::DoKeyword
                 | TokenType::ForKeyword
                 | TokenType::ReturnKeyword
+                | TokenType::MatchKeyword
+                | TokenType::BreakKeyword
+                | TokenType::ContinueKeyword
                 | TokenType::LetKeyword
                 | TokenType::DefKeyword
                 | TokenType::ClassKeyword
                 | TokenType::ExtendsKeyword
+                | TokenType::InterfaceKeyword
+                | TokenType::ImplementsKeyword
+                | TokenType::EnumKeyword
                 | TokenType::ThisKeyword
                 | TokenType::SuperKeyword
                 | TokenType::NewKeyword
                 | TokenType::TypeKeyword
+                | TokenType::IsKeyword
         )
     }
 
@@ -223,6 +305,8 @@ impl Token {
                 | TokenType::LogicalAndOperator
                 | TokenType::LogicalOrOperator
                 | TokenType::LogicalNotOperator
+                | TokenType::NilCoalescingOperator
+                | TokenType::BitwiseOrOperator
         )
     }
 
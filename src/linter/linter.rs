@@ -0,0 +1,355 @@
+use anyhow::Result;
+
+use crate::{
+    ast::{AstVisitor, ClassMember, Expression, ParameterList, Statement},
+    diagnostics::Diagnostic,
+    lexer::Span,
+    resolver::{resolve_program, Resolution},
+};
+
+use super::{create_lint_rule, LintConfig, LintLevel, LintRule, LINT_RULE_NAMES};
+
+///
+/// Run the enabled rules over the program, the findings are sorted by their
+/// position in the source
+///
+pub fn lint_program(program: &Statement, config: &LintConfig) -> Vec<Diagnostic> {
+    let mut linter = Linter::new(resolve_program(program), config);
+
+    // The linter itself never fails
+    let _ = linter.visit_statement(program);
+    linter.run_rules(|rule, context| rule.finish(context));
+
+    let mut diagnostics = linter.diagnostics;
+    diagnostics.sort_by_key(|diagnostic| {
+        diagnostic
+            .span
+            .map(|span| span.start.offset)
+            .unwrap_or_default()
+    });
+    diagnostics
+}
+
+///
+/// What a rule can see while it checks a node
+///
+pub struct LintContext<'a> {
+    pub resolution: &'a Resolution,
+    scopes: &'a [Vec<(String, Span)>],
+    findings: Vec<(String, Span)>,
+}
+
+impl LintContext<'_> {
+    pub fn report(&mut self, message: String, span: Span) {
+        self.findings.push((message, span));
+    }
+
+    ///
+    /// Declaration of the name in a scope enclosing the innermost one
+    ///
+    pub fn lookup_outer(&self, name: &str) -> Option<Span> {
+        let (_, outer_scopes) = self.scopes.split_last()?;
+
+        outer_scopes.iter().rev().find_map(|scope| {
+            scope
+                .iter()
+                .find(|(declared, _)| declared == name)
+                .map(|(_, span)| *span)
+        })
+    }
+}
+
+///
+/// Visitor driving the lint rules, it keeps the scopes the rules look names up in
+///
+struct Linter {
+    rules: Vec<(Box<dyn LintRule>, LintLevel)>,
+    resolution: Resolution,
+    scopes: Vec<Vec<(String, Span)>>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Linter {
+    fn new(resolution: Resolution, config: &LintConfig) -> Self {
+        let rules = LINT_RULE_NAMES
+            .iter()
+            .map(|name| (name, config.level(name)))
+            .filter(|(_, level)| *level != LintLevel::Off)
+            .filter_map(|(name, level)| create_lint_rule(name).map(|rule| (rule, level)))
+            .collect();
+
+        Linter {
+            rules,
+            resolution,
+            scopes: vec![vec![]],
+            diagnostics: vec![],
+        }
+    }
+
+    fn run_rules(&mut self, mut hook: impl FnMut(&mut dyn LintRule, &mut LintContext)) {
+        for (rule, level) in &mut self.rules {
+            let mut context = LintContext {
+                resolution: &self.resolution,
+                scopes: &self.scopes,
+                findings: vec![],
+            };
+            hook(rule.as_mut(), &mut context);
+
+            for (message, span) in context.findings {
+                let message = format!("{} [{}]", message, rule.name());
+                self.diagnostics.push(match level {
+                    LintLevel::Error => Diagnostic::error(message, Some(span)),
+                    _ => Diagnostic::warning(message, Some(span)),
+                });
+            }
+        }
+    }
+
+    fn declare(&mut self, name: &Expression) {
+        if let Expression::Destructuring { pattern, .. } = name {
+            for binding in pattern.bindings() {
+                self.declare(binding);
+            }
+            return;
+        }
+        let Expression::Identifier { name, span } = name else {
+            return;
+        };
+
+        self.run_rules(|rule, context| rule.check_declaration(name, *span, context));
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.push((name.clone(), *span));
+        }
+    }
+
+    fn visit_scoped(&mut self, visit: impl FnOnce(&mut Self) -> Result<()>) -> Result<()> {
+        self.scopes.push(vec![]);
+        let result = visit(self);
+        self.scopes.pop();
+        result
+    }
+
+    fn visit_function(&mut self, parameters: &ParameterList, body: &Statement) -> Result<()> {
+        self.visit_scoped(|this| {
+            for (parameter, _) in parameters {
+                this.declare(parameter);
+            }
+            this.visit_statement(body)
+        })
+    }
+}
+
+impl AstVisitor for Linter {
+    type Output = ();
+
+    fn visit_statement(&mut self, statement: &Statement) -> Result<Self::Output> {
+        self.run_rules(|rule, context| rule.check_statement(statement, context));
+
+        match statement {
+            Statement::Program { body, .. } => {
+                for statement in body {
+                    self.visit_statement(statement)?;
+                }
+            }
+            Statement::Block { body, .. } => self.visit_scoped(|this| {
+                for statement in body {
+                    this.visit_statement(statement)?;
+                }
+                Ok(())
+            })?,
+            Statement::Empty { .. } => {}
+            Statement::Expression { expression, .. } => self.visit_expression(expression)?,
+            Statement::VariableDeclaration { variables, .. } => {
+                for variable in variables {
+                    self.visit_expression(variable)?;
+                }
+            }
+            Statement::If {
+                condition,
+                consequent,
+                alternative,
+                ..
+            } => {
+                self.visit_expression(condition)?;
+                self.visit_statement(consequent)?;
+                if let Some(alternative) = alternative {
+                    self.visit_statement(alternative)?;
+                }
+            }
+            Statement::While {
+                condition, body, ..
+            }
+            | Statement::DoWhile {
+                body, condition, ..
+            } => {
+                self.visit_expression(condition)?;
+                self.visit_statement(body)?;
+            }
+            Statement::For {
+                initializer,
+                condition,
+                increment,
+                body,
+                ..
+            } => self.visit_scoped(|this| {
+                if let Some(initializer) = initializer {
+                    this.visit_statement(initializer)?;
+                }
+                if let Some(condition) = condition {
+                    this.visit_expression(condition)?;
+                }
+                if let Some(increment) = increment {
+                    this.visit_expression(increment)?;
+                }
+                this.visit_statement(body)
+            })?,
+            Statement::FunctionDeclaration {
+                name,
+                parameters,
+                body,
+                ..
+            } => {
+                self.declare(name);
+                self.visit_function(parameters, body)?;
+            }
+            Statement::Return { argument, .. } => {
+                if let Some(argument) = argument {
+                    self.visit_expression(argument)?;
+                }
+            }
+            // Labels name loops, not variables
+            Statement::Break { .. } | Statement::Continue { .. } => {}
+            Statement::Labeled { body, .. } => self.visit_statement(body)?,
+            Statement::ClassDeclaration {
+                name,
+                super_class,
+                implements,
+                members,
+                ..
+            } => {
+                self.declare(name);
+                if let Some(super_class) = super_class {
+                    self.visit_expression(super_class)?;
+                }
+                for interface in implements {
+                    self.visit_expression(interface)?;
+                }
+
+                // Members are reached through 'this', they are not scoped names
+                for member in members {
+                    match member {
+                        ClassMember::Field { initializer, .. } => {
+                            if let Some(initializer) = initializer {
+                                self.visit_expression(initializer)?;
+                            }
+                        }
+                        ClassMember::Method { declaration }
+                        | ClassMember::Constructor { declaration } => {
+                            self.run_rules(|rule, context| {
+                                rule.check_statement(declaration, context)
+                            });
+                            if let Statement::FunctionDeclaration {
+                                parameters, body, ..
+                            } = declaration.as_ref()
+                            {
+                                self.visit_function(parameters, body)?;
+                            }
+                        }
+                    }
+                }
+            }
+            Statement::InterfaceDeclaration { name, .. }
+            | Statement::EnumDeclaration { name, .. } => self.declare(name),
+        }
+
+        Ok(())
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) -> Result<Self::Output> {
+        self.run_rules(|rule, context| rule.check_expression(expression, context));
+
+        match expression {
+            Expression::Variable {
+                identifier,
+                initializer,
+                ..
+            } => {
+                if let Some(initializer) = initializer {
+                    self.visit_expression(initializer)?;
+                }
+                self.declare(identifier);
+            }
+            Expression::Assignment { left, right, .. }
+            | Expression::Binary { left, right, .. }
+            | Expression::Logical { left, right, .. } => {
+                self.visit_expression(left)?;
+                self.visit_expression(right)?;
+            }
+            Expression::Unary { right, .. }
+            | Expression::TypeTest {
+                expression: right, ..
+            } => self.visit_expression(right)?,
+            Expression::Member {
+                computed,
+                object,
+                property,
+                ..
+            } => {
+                self.visit_expression(object)?;
+                if *computed {
+                    self.visit_expression(property)?;
+                }
+            }
+            Expression::Call {
+                callee, arguments, ..
+            }
+            | Expression::New {
+                callee, arguments, ..
+            } => {
+                self.visit_expression(callee)?;
+                for argument in arguments {
+                    self.visit_expression(argument)?;
+                }
+            }
+            Expression::ArrayLiteral { elements, .. } => {
+                for element in elements {
+                    self.visit_expression(element)?;
+                }
+            }
+            Expression::EnumVariant {
+                enum_name,
+                arguments,
+                ..
+            } => {
+                self.visit_expression(enum_name)?;
+                for argument in arguments {
+                    self.visit_expression(argument)?;
+                }
+            }
+            Expression::Match { subject, arms, .. } => {
+                self.visit_expression(subject)?;
+                for arm in arms {
+                    self.visit_scoped(|this| {
+                        for binding in arm.pattern.bindings() {
+                            this.declare(binding);
+                        }
+                        if let Some(guard) = &arm.guard {
+                            this.visit_expression(guard)?;
+                        }
+                        this.visit_expression(&arm.value)
+                    })?;
+                }
+            }
+            Expression::BooleanLiteral { .. }
+            | Expression::NilLiteral { .. }
+            | Expression::StringLiteral { .. }
+            | Expression::NumericLiteral { .. }
+            | Expression::Identifier { .. }
+            | Expression::This { .. }
+            | Expression::Super { .. }
+            | Expression::Destructuring { .. } => {}
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+use std::{collections::HashMap, fmt};
+
+use super::{LintError, LintResult, LINT_RULE_NAMES};
+
+///
+/// What a rule does with its findings
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintLevel {
+    Off,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for LintLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LintLevel::Off => write!(f, "off"),
+            LintLevel::Warning => write!(f, "warning"),
+            LintLevel::Error => write!(f, "error"),
+        }
+    }
+}
+
+///
+/// Level of each rule, rules that are not configured report warnings
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LintConfig {
+    levels: HashMap<String, LintLevel>,
+}
+
+#[allow(dead_code)]
+impl LintConfig {
+    pub fn new() -> Self {
+        LintConfig::default()
+    }
+
+    pub fn level(&self, rule: &str) -> LintLevel {
+        self.levels.get(rule).copied().unwrap_or(LintLevel::Warning)
+    }
+
+    pub fn set_level(&mut self, rule: &str, level: LintLevel) -> LintResult<()> {
+        if !LINT_RULE_NAMES.contains(&rule) {
+            return Err(LintError::ConfigError {
+                message: format!("Unknown lint rule '{}'", rule),
+            });
+        }
+
+        self.levels.insert(String::from(rule), level);
+        Ok(())
+    }
+
+    ///
+    /// Apply a setting written as 'rule=level'
+    ///
+    pub fn apply_setting(&mut self, setting: &str) -> LintResult<()> {
+        let Some((rule, level)) = setting.split_once('=') else {
+            return Err(LintError::ConfigError {
+                message: format!("Expected 'rule=level', found '{}'", setting),
+            });
+        };
+
+        let level = match level {
+            "off" => LintLevel::Off,
+            "warning" => LintLevel::Warning,
+            "error" => LintLevel::Error,
+            level => {
+                return Err(LintError::ConfigError {
+                    message: format!("Unknown lint level '{}'", level),
+                })
+            }
+        };
+
+        self.set_level(rule, level)
+    }
+}
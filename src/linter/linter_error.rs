@@ -0,0 +1,9 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum LintError {
+    #[error("Lint configuration error: {message}")]
+    ConfigError { message: String },
+}
+
+pub type LintResult<T> = Result<T, LintError>;
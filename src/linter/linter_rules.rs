@@ -0,0 +1,246 @@
+use crate::{
+    ast::{BinaryOperator, Expression, Statement},
+    lexer::Span,
+    resolver::SymbolKind,
+};
+
+use super::LintContext;
+
+///
+/// Names of the built in rules, in the order they run
+///
+pub const LINT_RULE_NAMES: &[&str] = &[
+    "unused-variable",
+    "shadowed-binding",
+    "empty-block",
+    "assignment-in-condition",
+    "constant-condition",
+    "nil-comparison",
+];
+
+///
+/// A lint rule, the linter calls the hooks for every node in source order
+///
+pub trait LintRule {
+    ///
+    /// Name used to configure the rule and shown with its findings
+    ///
+    fn name(&self) -> &'static str;
+
+    fn check_statement(&mut self, _statement: &Statement, _context: &mut LintContext) {}
+
+    fn check_expression(&mut self, _expression: &Expression, _context: &mut LintContext) {}
+
+    ///
+    /// Called before a name is added to the innermost scope
+    ///
+    fn check_declaration(&mut self, _name: &str, _span: Span, _context: &mut LintContext) {}
+
+    ///
+    /// Called once after the whole program was visited
+    ///
+    fn finish(&mut self, _context: &mut LintContext) {}
+}
+
+///
+/// Rule with the given name
+///
+pub fn create_lint_rule(name: &str) -> Option<Box<dyn LintRule>> {
+    let rule: Box<dyn LintRule> = match name {
+        "unused-variable" => Box::new(UnusedVariable),
+        "shadowed-binding" => Box::new(ShadowedBinding),
+        "empty-block" => Box::new(EmptyBlock::default()),
+        "assignment-in-condition" => Box::new(AssignmentInCondition),
+        "constant-condition" => Box::new(ConstantCondition),
+        "nil-comparison" => Box::new(NilComparison),
+        _ => return None,
+    };
+    Some(rule)
+}
+
+///
+/// Variables and parameters that are never used, names starting with an
+/// underscore are skipped
+///
+struct UnusedVariable;
+
+impl LintRule for UnusedVariable {
+    fn name(&self) -> &'static str {
+        "unused-variable"
+    }
+
+    fn finish(&mut self, context: &mut LintContext) {
+        let resolution = context.resolution;
+
+        for (index, symbol) in resolution.symbols.iter().enumerate() {
+            let kind = match symbol.kind {
+                SymbolKind::Variable => "Variable",
+                SymbolKind::Parameter => "Parameter",
+                _ => continue,
+            };
+            if symbol.name.starts_with('_')
+                || resolution
+                    .references
+                    .iter()
+                    .any(|reference| reference.symbol == index)
+            {
+                continue;
+            }
+
+            context.report(
+                format!("{} '{}' is never used", kind, symbol.name),
+                symbol.span,
+            );
+        }
+    }
+}
+
+///
+/// Declarations hiding a declaration of an enclosing scope
+///
+struct ShadowedBinding;
+
+impl LintRule for ShadowedBinding {
+    fn name(&self) -> &'static str {
+        "shadowed-binding"
+    }
+
+    fn check_declaration(&mut self, name: &str, span: Span, context: &mut LintContext) {
+        if let Some(outer) = context.lookup_outer(name) {
+            context.report(
+                format!(
+                    "'{}' shadows the declaration at {}:{}",
+                    name, outer.start.line, outer.start.column
+                ),
+                span,
+            );
+        }
+    }
+}
+
+///
+/// Blocks without statements, function bodies may be empty
+///
+#[derive(Default)]
+struct EmptyBlock {
+    allowed: Vec<Span>,
+}
+
+impl LintRule for EmptyBlock {
+    fn name(&self) -> &'static str {
+        "empty-block"
+    }
+
+    fn check_statement(&mut self, statement: &Statement, context: &mut LintContext) {
+        match statement {
+            Statement::FunctionDeclaration { body, .. } => self.allowed.push(body.span()),
+            Statement::Block { body, span } if body.is_empty() && !self.allowed.contains(span) => {
+                context.report(String::from("Empty block"), *span)
+            }
+            _ => {}
+        }
+    }
+}
+
+///
+/// Conditions that assign instead of compare
+///
+struct AssignmentInCondition;
+
+impl LintRule for AssignmentInCondition {
+    fn name(&self) -> &'static str {
+        "assignment-in-condition"
+    }
+
+    fn check_statement(&mut self, statement: &Statement, context: &mut LintContext) {
+        if let Some(condition @ Expression::Assignment { .. }) = condition_of(statement) {
+            context.report(
+                String::from("Assignment used as a condition"),
+                condition.span(),
+            );
+        }
+    }
+}
+
+///
+/// Conditions made of literals only, 'while (true)' loops are allowed
+///
+struct ConstantCondition;
+
+impl LintRule for ConstantCondition {
+    fn name(&self) -> &'static str {
+        "constant-condition"
+    }
+
+    fn check_statement(&mut self, statement: &Statement, context: &mut LintContext) {
+        let Some(condition) = condition_of(statement) else {
+            return;
+        };
+
+        let is_loop = !matches!(statement, Statement::If { .. });
+        if is_loop && matches!(condition, Expression::BooleanLiteral { value: true, .. }) {
+            return;
+        }
+
+        if is_constant(condition) {
+            context.report(
+                String::from("Condition is always the same"),
+                condition.span(),
+            );
+        }
+    }
+}
+
+///
+/// Equality comparisons with the nil literal
+///
+struct NilComparison;
+
+impl LintRule for NilComparison {
+    fn name(&self) -> &'static str {
+        "nil-comparison"
+    }
+
+    fn check_expression(&mut self, expression: &Expression, context: &mut LintContext) {
+        if let Expression::Binary {
+            operator: operator @ (BinaryOperator::Equal | BinaryOperator::NotEqual),
+            left,
+            right,
+            span,
+        } = expression
+        {
+            if matches!(left.as_ref(), Expression::NilLiteral { .. })
+                || matches!(right.as_ref(), Expression::NilLiteral { .. })
+            {
+                context.report(
+                    format!("Comparison against 'nil' with '{}'", operator),
+                    *span,
+                );
+            }
+        }
+    }
+}
+
+fn condition_of(statement: &Statement) -> Option<&Expression> {
+    match statement {
+        Statement::If { condition, .. }
+        | Statement::While { condition, .. }
+        | Statement::DoWhile { condition, .. } => Some(condition),
+        Statement::For { condition, .. } => condition.as_deref(),
+        _ => None,
+    }
+}
+
+fn is_constant(expression: &Expression) -> bool {
+    match expression {
+        Expression::BooleanLiteral { .. }
+        | Expression::NilLiteral { .. }
+        | Expression::StringLiteral { .. }
+        | Expression::NumericLiteral { .. } => true,
+        Expression::Unary { right, .. } => is_constant(right),
+        Expression::Binary { left, right, .. } | Expression::Logical { left, right, .. } => {
+            is_constant(left) && is_constant(right)
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,10 @@
+mod linter;
+mod linter_config;
+mod linter_error;
+mod linter_rules;
+mod tests;
+
+pub(crate) use self::linter::*;
+pub(crate) use self::linter_config::*;
+pub(crate) use self::linter_error::*;
+pub(crate) use self::linter_rules::*;
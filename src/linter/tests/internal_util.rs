@@ -0,0 +1,93 @@
+use crate::linter::{lint_program, LintConfig};
+use crate::parser::{parse_root_statement, Parser};
+use serde::Deserialize;
+use std::fs;
+
+#[derive(Debug, Deserialize)]
+pub(super) struct YamlTestCase {
+    description: String,
+    source: String,
+    #[serde(default)]
+    rules: Vec<String>,
+    expected_diagnostics: Vec<String>,
+}
+
+pub(super) fn execute_yaml_test(test_case: &YamlTestCase) {
+    let Ok(mut parser) = Parser::new(&test_case.source) else {
+        panic!(
+            "Failed to parse the test case source:\n{}\n",
+            test_case.source
+        )
+    };
+    let Ok(ast) = parse_root_statement(&mut parser) else {
+        panic!(
+            "Failed to parse the test case source:\n{}\n",
+            test_case.source
+        )
+    };
+
+    let mut config = LintConfig::new();
+    for setting in &test_case.rules {
+        if let Err(error) = config.apply_setting(setting) {
+            panic!("Invalid rule setting '{}': {}", setting, error)
+        }
+    }
+
+    // Diagnostics are written as 'line:column severity: message'
+    let actual_diagnostics: Vec<String> = lint_program(&ast, &config)
+        .iter()
+        .map(|diagnostic| match diagnostic.span {
+            Some(span) => format!(
+                "{}:{} {}: {}",
+                span.start.line, span.start.column, diagnostic.severity, diagnostic.message
+            ),
+            None => diagnostic.message.clone(),
+        })
+        .collect();
+
+    assert_eq!(actual_diagnostics, test_case.expected_diagnostics);
+}
+
+pub(super) fn load_yaml_test_cases(path: &str) -> Vec<YamlTestCase> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(error) => {
+            eprintln!("Failed to read the test yaml file: {}", error);
+            eprintln!("Path: {}", path);
+            panic!("Could not read test file: {}", error);
+        }
+    };
+
+    // Parse multiple documents
+    let mut test_cases = Vec::new();
+
+    for document in serde_yaml::Deserializer::from_str(&content) {
+        match YamlTestCase::deserialize(document) {
+            Ok(test_case) => test_cases.push(test_case),
+            Err(e) => {
+                eprintln!("Failed to parse a YAML document: {}", e);
+                eprintln!("Path: {}", path);
+                panic!("Could not parse YAML content: {}", e);
+            }
+        }
+    }
+
+    // Verify that we found some test cases
+    if test_cases.is_empty() {
+        eprintln!("No test cases found in file: {}", path);
+        panic!("File contained no valid test cases");
+    }
+
+    test_cases
+}
+
+pub(super) fn execute_yaml_test_cases(path: &str) {
+    let test_cases = load_yaml_test_cases(path);
+
+    for (index, test_case) in test_cases.iter().enumerate() {
+        println!("Testing case #{}:\n{}\n", index + 1, test_case.description);
+        execute_yaml_test(test_case);
+    }
+
+    println!("All {} test cases passed!", test_cases.len());
+}
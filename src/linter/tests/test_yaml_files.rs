@@ -0,0 +1,11 @@
+use super::internal_util::execute_yaml_test_cases;
+
+#[test]
+fn test_linter_rules() {
+    execute_yaml_test_cases("tests/linter/test_linter_rules.yaml");
+}
+
+#[test]
+fn test_linter_config() {
+    execute_yaml_test_cases("tests/linter/test_linter_config.yaml");
+}
@@ -0,0 +1,29 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum LspError {
+    #[error("Protocol error: {message}")]
+    ProtocolError { message: String },
+
+    #[error("IO error: {message}")]
+    IoError { message: String },
+}
+
+impl From<std::io::Error> for LspError {
+    fn from(error: std::io::Error) -> Self {
+        LspError::IoError {
+            message: error.to_string(),
+        }
+    }
+}
+
+pub type LspResult<T> = Result<T, LspError>;
+
+///
+/// JSON-RPC error codes used in responses
+///
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const SERVER_NOT_INITIALIZED: i64 = -32002;
@@ -0,0 +1,440 @@
+use serde_json::{json, Value};
+
+use crate::{
+    analysis::analyze_program,
+    ast::{ClassMember, ClassMemberList, Expression, InterfaceMember, Statement, StatementList},
+    checker::TypeChecker,
+    diagnostics::{Diagnostic, Severity},
+    document::Document,
+    lexer::{Lexer, Span, TokenPosition, TokenType, TriviaKind},
+    resolver::{resolve_program, Resolution, SymbolKind},
+};
+
+use super::lsp_position::{to_lsp_position, to_lsp_range};
+
+///
+/// Semantic token types, the index in the legend is the encoded type
+///
+pub(super) const SEMANTIC_TOKEN_TYPES: &[&str] = &[
+    "keyword",
+    "type",
+    "number",
+    "string",
+    "operator",
+    "variable",
+    "parameter",
+    "function",
+    "class",
+    "method",
+    "property",
+    "comment",
+    "interface",
+    "enum",
+    "enumMember",
+];
+
+// Symbol kinds of the document symbols
+const SYMBOL_KIND_CLASS: u32 = 5;
+const SYMBOL_KIND_METHOD: u32 = 6;
+const SYMBOL_KIND_FIELD: u32 = 8;
+const SYMBOL_KIND_CONSTRUCTOR: u32 = 9;
+const SYMBOL_KIND_ENUM: u32 = 10;
+const SYMBOL_KIND_INTERFACE: u32 = 11;
+const SYMBOL_KIND_FUNCTION: u32 = 12;
+const SYMBOL_KIND_ENUM_MEMBER: u32 = 22;
+
+const DIAGNOSTIC_SEVERITY_ERROR: u32 = 1;
+const DIAGNOSTIC_SEVERITY_WARNING: u32 = 2;
+
+///
+/// Diagnostics of the document, the checker stops at the first error and the
+/// analyses only run on programs without type errors
+///
+pub(super) fn diagnostics(document: &Document) -> Vec<Value> {
+    let diagnostics = match document.program() {
+        Ok(program) => match TypeChecker::new().check_program(program) {
+            Ok(_) => analyze_program(program),
+            Err(error) => vec![Diagnostic::from_checker_error(&error)],
+        },
+        Err(diagnostic) => vec![diagnostic.clone()],
+    };
+
+    diagnostics
+        .iter()
+        .map(|diagnostic| lsp_diagnostic(document.source(), diagnostic))
+        .collect()
+}
+
+fn lsp_diagnostic(source: &str, diagnostic: &Diagnostic) -> Value {
+    let range = match diagnostic.span {
+        Some(span) => to_lsp_range(source, span),
+        None => json!({
+            "start": { "line": 0, "character": 0 },
+            "end": { "line": 0, "character": 0 },
+        }),
+    };
+    let severity = match diagnostic.severity {
+        Severity::Error => DIAGNOSTIC_SEVERITY_ERROR,
+        Severity::Warning => DIAGNOSTIC_SEVERITY_WARNING,
+    };
+
+    json!({
+        "range": range,
+        "severity": severity,
+        "source": "senbonzakura",
+        "message": diagnostic.message,
+    })
+}
+
+///
+/// Declaration of the name under the position
+///
+pub(super) fn hover(document: &Document, position: TokenPosition) -> Value {
+    let Some(resolution) = resolve_document(document) else {
+        return Value::Null;
+    };
+    let Some(symbol) = resolution.symbol_at(position) else {
+        return Value::Null;
+    };
+
+    // Variables declared without an annotation show the type the checker inferred
+    let mut detail = symbol.detail.clone();
+    if symbol.kind == SymbolKind::Variable && detail == format!("let {}", symbol.name) {
+        if let Ok(program) = document.program() {
+            let mut checker = TypeChecker::new();
+            // Names declared before a type error keep their types
+            let _ = checker.check_program(program);
+            if let Some(declared_type) = checker.declared_type_at(symbol.span) {
+                detail = format!("let {}: {}", symbol.name, declared_type);
+            }
+        }
+    }
+
+    json!({
+        "contents": {
+            "kind": "markdown",
+            "value": format!("```senbonzakura\n{}\n```", detail),
+        },
+    })
+}
+
+///
+/// Location of the declaration of the name under the position
+///
+pub(super) fn definition(uri: &str, document: &Document, position: TokenPosition) -> Value {
+    let Some(resolution) = resolve_document(document) else {
+        return Value::Null;
+    };
+    let Some(symbol) = resolution.symbol_at(position) else {
+        return Value::Null;
+    };
+
+    json!({
+        "uri": uri,
+        "range": to_lsp_range(document.source(), symbol.span),
+    })
+}
+
+///
+/// Functions and classes of the document, class members are nested in their class
+///
+pub(super) fn document_symbols(document: &Document) -> Value {
+    let Ok(Statement::Program { body, .. }) = document.program() else {
+        return json!([]);
+    };
+
+    Value::Array(collect_symbols(document.source(), body))
+}
+
+fn collect_symbols(source: &str, statements: &StatementList) -> Vec<Value> {
+    let mut symbols = vec![];
+    for statement in statements {
+        collect_statement_symbols(source, statement, &mut symbols);
+    }
+    symbols
+}
+
+fn collect_statement_symbols(source: &str, statement: &Statement, symbols: &mut Vec<Value>) {
+    match statement {
+        Statement::FunctionDeclaration {
+            name, body, span, ..
+        } => {
+            let mut children = vec![];
+            collect_statement_symbols(source, body, &mut children);
+            symbols.push(document_symbol(
+                source,
+                name,
+                SYMBOL_KIND_FUNCTION,
+                *span,
+                children,
+            ));
+        }
+        Statement::ClassDeclaration {
+            name,
+            members,
+            span,
+            ..
+        } => {
+            let children = collect_class_members(source, members);
+            symbols.push(document_symbol(
+                source,
+                name,
+                SYMBOL_KIND_CLASS,
+                *span,
+                children,
+            ));
+        }
+        Statement::InterfaceDeclaration {
+            name,
+            members,
+            span,
+        } => {
+            let children = members
+                .iter()
+                .map(|member| match member {
+                    InterfaceMember::Field { name, span, .. } => {
+                        document_symbol(source, name, SYMBOL_KIND_FIELD, *span, vec![])
+                    }
+                    InterfaceMember::Method { name, span, .. } => {
+                        document_symbol(source, name, SYMBOL_KIND_METHOD, *span, vec![])
+                    }
+                })
+                .collect();
+            symbols.push(document_symbol(
+                source,
+                name,
+                SYMBOL_KIND_INTERFACE,
+                *span,
+                children,
+            ));
+        }
+        Statement::EnumDeclaration {
+            name,
+            variants,
+            span,
+        } => {
+            let children = variants
+                .iter()
+                .map(|variant| {
+                    document_symbol(
+                        source,
+                        &variant.name,
+                        SYMBOL_KIND_ENUM_MEMBER,
+                        variant.span,
+                        vec![],
+                    )
+                })
+                .collect();
+            symbols.push(document_symbol(
+                source,
+                name,
+                SYMBOL_KIND_ENUM,
+                *span,
+                children,
+            ));
+        }
+        // Declarations nested in blocks are listed with the enclosing declaration
+        Statement::Block { body, .. } => symbols.extend(collect_symbols(source, body)),
+        Statement::If {
+            consequent,
+            alternative,
+            ..
+        } => {
+            collect_statement_symbols(source, consequent, symbols);
+            if let Some(alternative) = alternative {
+                collect_statement_symbols(source, alternative, symbols);
+            }
+        }
+        Statement::While { body, .. }
+        | Statement::DoWhile { body, .. }
+        | Statement::For { body, .. }
+        | Statement::Labeled { body, .. } => collect_statement_symbols(source, body, symbols),
+        _ => {}
+    }
+}
+
+fn collect_class_members(source: &str, members: &ClassMemberList) -> Vec<Value> {
+    let mut symbols = vec![];
+
+    for member in members {
+        let (declaration, kind) = match member {
+            ClassMember::Field { name, span, .. } => {
+                symbols.push(document_symbol(
+                    source,
+                    name,
+                    SYMBOL_KIND_FIELD,
+                    *span,
+                    vec![],
+                ));
+                continue;
+            }
+            ClassMember::Method { declaration } => (declaration, SYMBOL_KIND_METHOD),
+            ClassMember::Constructor { declaration } => (declaration, SYMBOL_KIND_CONSTRUCTOR),
+        };
+        if let Statement::FunctionDeclaration { name, span, .. } = declaration.as_ref() {
+            symbols.push(document_symbol(source, name, kind, *span, vec![]));
+        }
+    }
+
+    symbols
+}
+
+fn document_symbol(
+    source: &str,
+    name: &Expression,
+    kind: u32,
+    span: Span,
+    children: Vec<Value>,
+) -> Value {
+    let symbol_name = match name {
+        Expression::Identifier { name, .. } => name.clone(),
+        _ => String::new(),
+    };
+
+    json!({
+        "name": symbol_name,
+        "kind": kind,
+        "range": to_lsp_range(source, span),
+        "selectionRange": to_lsp_range(source, name.span()),
+        "children": children,
+    })
+}
+
+///
+/// Semantic tokens of the document, relative encoded as the protocol requires
+///
+pub(super) fn semantic_tokens(document: &Document) -> Value {
+    let source = document.source();
+    let resolution = resolve_document(document);
+    let mut lexer = Lexer::new(source);
+
+    // Absolute tokens as (line, character, length, type)
+    let mut tokens: Vec<(usize, usize, usize, usize)> = vec![];
+
+    let mut previous_token_type = TokenType::End;
+
+    // Tokens up to a lexical error are still highlighted
+    while let Ok((trivia, token)) = lexer.next_token_with_trivia() {
+        for trivia in trivia {
+            if trivia.kind != TriviaKind::Whitespace {
+                push_semantic_token(
+                    &mut tokens,
+                    source,
+                    trivia.start,
+                    trivia.text(source),
+                    token_type_index("comment"),
+                );
+            }
+        }
+
+        if token.token_type == TokenType::End {
+            break;
+        }
+
+        let token_type = if token.token_type == TokenType::Identifier {
+            let symbol_kind = resolution
+                .as_ref()
+                .and_then(|resolution| resolution.symbol_of(token.span()))
+                .map(|symbol| symbol.kind);
+            Some(match symbol_kind {
+                Some(SymbolKind::Parameter) => "parameter",
+                Some(SymbolKind::Function) => "function",
+                Some(SymbolKind::Class) => "class",
+                Some(SymbolKind::Interface) => "interface",
+                Some(SymbolKind::Enum) => "enum",
+                Some(SymbolKind::Variant) => "enumMember",
+                Some(SymbolKind::Method) => "method",
+                Some(SymbolKind::Field) => "property",
+                Some(SymbolKind::Variable) => "variable",
+                // Members of objects other than this are not resolved
+                None if matches!(
+                    previous_token_type,
+                    TokenType::Dot | TokenType::OptionalChaining
+                ) =>
+                {
+                    "property"
+                }
+                None => "variable",
+            })
+        } else if token.is_type_keyword() {
+            Some("type")
+        } else if token.is_keyword()
+            || matches!(
+                token.token_type,
+                TokenType::BooleanTrue | TokenType::BooleanFalse | TokenType::Nil
+            )
+        {
+            Some("keyword")
+        } else if token.token_type == TokenType::Number {
+            Some("number")
+        } else if token.token_type == TokenType::String {
+            Some("string")
+        } else if token.is_operator() || token.is_assignment_operator() {
+            Some("operator")
+        } else {
+            None
+        };
+
+        if let Some(token_type) = token_type {
+            push_semantic_token(
+                &mut tokens,
+                source,
+                token.start,
+                token.text(source),
+                token_type_index(token_type),
+            );
+        }
+        previous_token_type = token.token_type;
+    }
+
+    let mut data = vec![];
+    let (mut previous_line, mut previous_character) = (0, 0);
+    for (line, character, length, token_type) in tokens {
+        let delta_character = if line == previous_line {
+            character - previous_character
+        } else {
+            character
+        };
+        data.extend([line - previous_line, delta_character, length, token_type, 0]);
+        (previous_line, previous_character) = (line, character);
+    }
+
+    json!({ "data": data })
+}
+
+///
+/// Multi-line tokens are split into one token per line
+///
+fn push_semantic_token(
+    tokens: &mut Vec<(usize, usize, usize, usize)>,
+    source: &str,
+    start: TokenPosition,
+    text: &str,
+    token_type: usize,
+) {
+    let start = to_lsp_position(source, start);
+    let start_line = start["line"].as_u64().unwrap_or_default() as usize;
+    let start_character = start["character"].as_u64().unwrap_or_default() as usize;
+
+    for (index, line_text) in text.split('\n').enumerate() {
+        let line_text = line_text.trim_end_matches('\r');
+        let length: usize = line_text.chars().map(char::len_utf16).sum();
+        if length == 0 {
+            continue;
+        }
+
+        let character = if index == 0 { start_character } else { 0 };
+        tokens.push((start_line + index, character, length, token_type));
+    }
+}
+
+fn token_type_index(token_type: &str) -> usize {
+    SEMANTIC_TOKEN_TYPES
+        .iter()
+        .position(|known| *known == token_type)
+        .unwrap_or_default()
+}
+
+fn resolve_document(document: &Document) -> Option<Resolution> {
+    document.program().ok().map(resolve_program)
+}
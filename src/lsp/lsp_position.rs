@@ -0,0 +1,53 @@
+use serde_json::{json, Value};
+
+use crate::lexer::{Span, TokenPosition};
+
+///
+/// LSP position of a source position, lines count from zero and
+/// characters are UTF-16 code units
+///
+pub(super) fn to_lsp_position(source: &str, position: TokenPosition) -> Value {
+    let line_text = source
+        .split('\n')
+        .nth(position.line.saturating_sub(1))
+        .unwrap_or_default();
+    let character: usize = line_text
+        .chars()
+        .take(position.column.saturating_sub(1))
+        .map(char::len_utf16)
+        .sum();
+
+    json!({
+        "line": position.line.saturating_sub(1),
+        "character": character,
+    })
+}
+
+pub(super) fn to_lsp_range(source: &str, span: Span) -> Value {
+    json!({
+        "start": to_lsp_position(source, span.start),
+        "end": to_lsp_position(source, span.end),
+    })
+}
+
+///
+/// Source position of an LSP position, positions past the end of a line
+/// are moved to its end
+///
+pub(super) fn from_lsp_position(source: &str, line: usize, character: usize) -> TokenPosition {
+    let mut position = TokenPosition::new();
+    let mut characters = 0;
+
+    for ch in source.chars() {
+        let at_line = position.line == line + 1;
+        if position.line > line + 1 || (at_line && (ch == '\n' || characters >= character)) {
+            break;
+        }
+        if at_line {
+            characters += ch.len_utf16();
+        }
+        position.advance(ch);
+    }
+
+    position
+}
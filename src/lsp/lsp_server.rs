@@ -0,0 +1,270 @@
+use std::{
+    collections::HashMap,
+    io::{BufRead, Write},
+};
+
+use serde_json::{json, Value};
+
+use crate::{
+    document::{Document, TextEdit},
+    lexer::TokenPosition,
+};
+
+use super::{
+    lsp_features::{
+        definition, diagnostics, document_symbols, hover, semantic_tokens, SEMANTIC_TOKEN_TYPES,
+    },
+    lsp_position::from_lsp_position,
+    read_message, write_message, LspResult, INVALID_PARAMS, INVALID_REQUEST, METHOD_NOT_FOUND,
+    PARSE_ERROR, SERVER_NOT_INITIALIZED,
+};
+
+const TEXT_DOCUMENT_SYNC_INCREMENTAL: u32 = 2;
+
+///
+/// Language server speaking JSON-RPC over a pair of streams
+///
+/// Documents are synchronized incrementally, an edit inside a statement
+/// only reparses that statement
+///
+pub struct LspServer<'a> {
+    output: &'a mut dyn Write,
+    documents: HashMap<String, Document>,
+    initialized: bool,
+    shutdown_requested: bool,
+}
+
+///
+/// Error answered to a request
+///
+struct ResponseError {
+    code: i64,
+    message: String,
+}
+
+impl<'a> LspServer<'a> {
+    pub fn new(output: &'a mut dyn Write) -> Self {
+        LspServer {
+            output,
+            documents: HashMap::new(),
+            initialized: false,
+            shutdown_requested: false,
+        }
+    }
+
+    ///
+    /// Serve messages until the exit notification or the end of the input,
+    /// returns whether the client asked for a shutdown first
+    ///
+    pub fn run(&mut self, input: &mut dyn BufRead) -> LspResult<bool> {
+        while let Some(body) = read_message(input)? {
+            let message = match serde_json::from_str::<Value>(&body) {
+                Ok(message) => message,
+                Err(error) => {
+                    self.send_error(Value::Null, PARSE_ERROR, &error.to_string())?;
+                    continue;
+                }
+            };
+
+            let Some(method) = message.get("method").and_then(Value::as_str) else {
+                // Responses to server requests, the server sends none
+                continue;
+            };
+            if method == "exit" {
+                break;
+            }
+
+            let params = message.get("params").cloned().unwrap_or(Value::Null);
+            match message.get("id").cloned() {
+                Some(id) => match self.handle_request(method, &params) {
+                    Ok(result) => {
+                        self.send(json!({ "jsonrpc": "2.0", "id": id, "result": result }))?
+                    }
+                    Err(error) => self.send_error(id, error.code, &error.message)?,
+                },
+                None => self.handle_notification(method, &params)?,
+            }
+        }
+
+        Ok(self.shutdown_requested)
+    }
+
+    fn handle_request(&mut self, method: &str, params: &Value) -> Result<Value, ResponseError> {
+        if method == "initialize" {
+            self.initialized = true;
+            return Ok(json!({
+                "capabilities": {
+                    "textDocumentSync": TEXT_DOCUMENT_SYNC_INCREMENTAL,
+                    "hoverProvider": true,
+                    "definitionProvider": true,
+                    "documentSymbolProvider": true,
+                    "semanticTokensProvider": {
+                        "legend": {
+                            "tokenTypes": SEMANTIC_TOKEN_TYPES,
+                            "tokenModifiers": [],
+                        },
+                        "full": true,
+                    },
+                },
+                "serverInfo": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            }));
+        }
+
+        if !self.initialized {
+            return Err(response_error(
+                SERVER_NOT_INITIALIZED,
+                "The server is not initialized",
+            ));
+        }
+        if self.shutdown_requested {
+            return Err(response_error(
+                INVALID_REQUEST,
+                "The server is shutting down",
+            ));
+        }
+
+        match method {
+            "shutdown" => {
+                self.shutdown_requested = true;
+                Ok(Value::Null)
+            }
+            "textDocument/hover" => {
+                let (document, position) = self.document_position(params)?;
+                Ok(hover(document, position))
+            }
+            "textDocument/definition" => {
+                let (document, position) = self.document_position(params)?;
+                Ok(definition(document_uri(params)?, document, position))
+            }
+            "textDocument/documentSymbol" => Ok(document_symbols(self.document(params)?)),
+            "textDocument/semanticTokens/full" => Ok(semantic_tokens(self.document(params)?)),
+            _ => Err(response_error(
+                METHOD_NOT_FOUND,
+                &format!("Unknown method '{}'", method),
+            )),
+        }
+    }
+
+    fn handle_notification(&mut self, method: &str, params: &Value) -> LspResult<()> {
+        let Ok(uri) = document_uri(params) else {
+            return Ok(());
+        };
+        let uri = String::from(uri);
+
+        match method {
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                self.documents
+                    .insert(uri.clone(), Document::new(String::from(text)));
+                self.publish_diagnostics(&uri)
+            }
+            "textDocument/didChange" => {
+                let changes = params["contentChanges"].as_array().cloned();
+                if let Some(document) = self.documents.get_mut(&uri) {
+                    for change in changes.unwrap_or_default() {
+                        apply_change(document, &change);
+                    }
+                }
+                self.publish_diagnostics(&uri)
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                self.send(json!({
+                    "jsonrpc": "2.0",
+                    "method": "textDocument/publishDiagnostics",
+                    "params": { "uri": uri, "diagnostics": [] },
+                }))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn publish_diagnostics(&mut self, uri: &str) -> LspResult<()> {
+        let diagnostics = self.documents.get(uri).map(diagnostics).unwrap_or_default();
+
+        self.send(json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": { "uri": uri, "diagnostics": diagnostics },
+        }))
+    }
+
+    fn document(&self, params: &Value) -> Result<&Document, ResponseError> {
+        let uri = document_uri(params)?;
+        self.documents
+            .get(uri)
+            .ok_or_else(|| response_error(INVALID_PARAMS, &format!("Unknown document '{}'", uri)))
+    }
+
+    fn document_position(
+        &self,
+        params: &Value,
+    ) -> Result<(&Document, TokenPosition), ResponseError> {
+        let document = self.document(params)?;
+        let (Some(line), Some(character)) = (
+            params["position"]["line"].as_u64(),
+            params["position"]["character"].as_u64(),
+        ) else {
+            return Err(response_error(INVALID_PARAMS, "Missing position"));
+        };
+
+        Ok((
+            document,
+            from_lsp_position(document.source(), line as usize, character as usize),
+        ))
+    }
+
+    fn send(&mut self, message: Value) -> LspResult<()> {
+        write_message(self.output, &message)
+    }
+
+    fn send_error(&mut self, id: Value, code: i64, message: &str) -> LspResult<()> {
+        self.send(json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": code, "message": message },
+        }))
+    }
+}
+
+///
+/// Apply a content change, changes without a range replace the whole text
+///
+fn apply_change(document: &mut Document, change: &Value) {
+    let text = String::from(change["text"].as_str().unwrap_or_default());
+
+    let range = &change["range"];
+    if range.is_null() {
+        *document = Document::new(text);
+        return;
+    }
+
+    let offset = |position: &Value| {
+        let line = position["line"].as_u64().unwrap_or_default() as usize;
+        let character = position["character"].as_u64().unwrap_or_default() as usize;
+        from_lsp_position(document.source(), line, character).offset
+    };
+    let (start, end) = (offset(&range["start"]), offset(&range["end"]));
+
+    document.apply_edit(&TextEdit {
+        start: start.min(end),
+        end,
+        text,
+    });
+}
+
+fn document_uri(params: &Value) -> Result<&str, ResponseError> {
+    params["textDocument"]["uri"]
+        .as_str()
+        .ok_or_else(|| response_error(INVALID_PARAMS, "Missing text document uri"))
+}
+
+fn response_error(code: i64, message: &str) -> ResponseError {
+    ResponseError {
+        code,
+        message: String::from(message),
+    }
+}
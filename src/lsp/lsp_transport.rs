@@ -0,0 +1,66 @@
+use std::io::{BufRead, Write};
+
+use serde_json::Value;
+
+use super::{LspError, LspResult};
+
+///
+/// Read a message framed with a Content-Length header, None at the end of the input
+///
+/// The body is returned as is, so the caller can answer malformed JSON with an error
+///
+pub fn read_message(input: &mut dyn BufRead) -> LspResult<Option<String>> {
+    let mut content_length = None;
+
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return match content_length {
+                None => Ok(None),
+                Some(_) => Err(protocol_error("Unexpected end of input in the headers")),
+            };
+        }
+
+        let header = header.trim_end();
+        if header.is_empty() {
+            if content_length.is_some() {
+                break;
+            }
+            // Blank lines between messages are tolerated
+            continue;
+        }
+
+        let Some((name, value)) = header.split_once(':') else {
+            return Err(protocol_error(&format!("Malformed header '{}'", header)));
+        };
+        if name.trim().eq_ignore_ascii_case("Content-Length") {
+            let length = value.trim().parse::<usize>().map_err(|_| {
+                protocol_error(&format!("Invalid Content-Length '{}'", value.trim()))
+            })?;
+            content_length = Some(length);
+        }
+    }
+
+    let mut body = vec![0; content_length.unwrap_or_default()];
+    input.read_exact(&mut body)?;
+
+    String::from_utf8(body)
+        .map(Some)
+        .map_err(|_| protocol_error("Message body is not valid UTF-8"))
+}
+
+///
+/// Write a message framed with a Content-Length header
+///
+pub fn write_message(output: &mut dyn Write, message: &Value) -> LspResult<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    output.flush()?;
+    Ok(())
+}
+
+fn protocol_error(message: &str) -> LspError {
+    LspError::ProtocolError {
+        message: String::from(message),
+    }
+}
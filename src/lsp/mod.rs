@@ -0,0 +1,10 @@
+mod lsp_error;
+mod lsp_features;
+mod lsp_position;
+mod lsp_server;
+mod lsp_transport;
+mod tests;
+
+pub(crate) use self::lsp_error::*;
+pub(crate) use self::lsp_server::LspServer;
+pub(crate) use self::lsp_transport::*;
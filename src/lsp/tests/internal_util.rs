@@ -0,0 +1,92 @@
+use crate::lsp::{read_message, write_message, LspServer};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::fs;
+
+#[derive(Debug, Deserialize)]
+pub(super) struct YamlTestCase {
+    description: String,
+    #[serde(default)]
+    initialize: bool,
+    messages: Vec<Value>,
+    expected_messages: Vec<Value>,
+    expected_shutdown: Option<bool>,
+}
+
+pub(super) fn execute_yaml_test(test_case: &YamlTestCase) {
+    // The scripted client sends every message up front and reads all the answers
+    let mut input: Vec<u8> = vec![];
+    if test_case.initialize {
+        let initialize = json!({ "jsonrpc": "2.0", "id": 0, "method": "initialize", "params": {} });
+        let initialized = json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} });
+        write_message(&mut input, &initialize).unwrap();
+        write_message(&mut input, &initialized).unwrap();
+    }
+    for message in &test_case.messages {
+        write_message(&mut input, message).unwrap();
+    }
+
+    let mut output: Vec<u8> = vec![];
+    let shutdown = LspServer::new(&mut output)
+        .run(&mut input.as_slice())
+        .unwrap();
+
+    let mut output = output.as_slice();
+    let mut actual_messages = vec![];
+    while let Some(body) = read_message(&mut output).unwrap() {
+        actual_messages.push(serde_json::from_str::<Value>(&body).unwrap());
+    }
+    if test_case.initialize {
+        // The capabilities are covered by the lifecycle tests
+        actual_messages.remove(0);
+    }
+
+    assert_eq!(actual_messages, test_case.expected_messages);
+    if let Some(expected_shutdown) = test_case.expected_shutdown {
+        assert_eq!(shutdown, expected_shutdown);
+    }
+}
+
+pub(super) fn load_yaml_test_cases(path: &str) -> Vec<YamlTestCase> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(error) => {
+            eprintln!("Failed to read the test yaml file: {}", error);
+            eprintln!("Path: {}", path);
+            panic!("Could not read test file: {}", error);
+        }
+    };
+
+    // Parse multiple documents
+    let mut test_cases = Vec::new();
+
+    for document in serde_yaml::Deserializer::from_str(&content) {
+        match YamlTestCase::deserialize(document) {
+            Ok(test_case) => test_cases.push(test_case),
+            Err(e) => {
+                eprintln!("Failed to parse a YAML document: {}", e);
+                eprintln!("Path: {}", path);
+                panic!("Could not parse YAML content: {}", e);
+            }
+        }
+    }
+
+    // Verify that we found some test cases
+    if test_cases.is_empty() {
+        eprintln!("No test cases found in file: {}", path);
+        panic!("File contained no valid test cases");
+    }
+
+    test_cases
+}
+
+pub(super) fn execute_yaml_test_cases(path: &str) {
+    let test_cases = load_yaml_test_cases(path);
+
+    for (index, test_case) in test_cases.iter().enumerate() {
+        println!("Testing case #{}:\n{}\n", index + 1, test_case.description);
+        execute_yaml_test(test_case);
+    }
+
+    println!("All {} test cases passed!", test_cases.len());
+}
@@ -0,0 +1,21 @@
+use super::internal_util::execute_yaml_test_cases;
+
+#[test]
+fn test_lsp_lifecycle() {
+    execute_yaml_test_cases("tests/lsp/test_lsp_lifecycle.yaml");
+}
+
+#[test]
+fn test_lsp_diagnostics() {
+    execute_yaml_test_cases("tests/lsp/test_lsp_diagnostics.yaml");
+}
+
+#[test]
+fn test_lsp_navigation() {
+    execute_yaml_test_cases("tests/lsp/test_lsp_navigation.yaml");
+}
+
+#[test]
+fn test_lsp_semantic_tokens() {
+    execute_yaml_test_cases("tests/lsp/test_lsp_semantic_tokens.yaml");
+}
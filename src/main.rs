@@ -1,34 +1,43 @@
-// use crate::parser::parse_root_statement;
+// Modules are named after their directory, e.g. lexer/lexer.rs
+#![allow(clippy::module_inception)]
+
+use std::{io, process::ExitCode};
+
 use lexer::{Lexer, Token};
-use parser::{parse_root_statement, Parser};
+use parser::Parser;
 
+mod analysis;
 mod ast;
+mod cfg;
+mod checker;
+mod cli;
+mod codegen;
+mod cst;
+mod diagnostics;
+mod document;
+mod driver;
+mod interpreter;
+mod ir;
 mod lexer;
+mod linter;
+mod lsp;
+mod optimizer;
 mod parser;
+mod repl;
+mod resolver;
 
-fn main() {
-    let Ok(mut parser) = Parser::new(
-        r#"
-        class Point {
-            def constructor(x: number, y: number) {
-                this.x = x;
-                this.y = y;
-            }
-        }
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
 
-        let p: Point = new Point(10, 20);
-        "#,
-    ) else {
-        panic!("Failed to parse the source");
-    };
-    let ast_result = parse_root_statement(&mut parser);
+    // Programs run by the run and repl commands need a deep stack
+    let exit_code = interpreter::with_interpreter_stack(|| {
+        cli::run_cli(
+            &args,
+            &mut io::stdin(),
+            &mut io::stdout(),
+            &mut io::stderr(),
+        )
+    });
 
-    match ast_result {
-        Ok(ast) => {
-            println!("Yaml:\n{}", serde_yaml::to_string(&ast).unwrap());
-        }
-        Err(error) => {
-            panic!("Failed to parse the source: {}", error);
-        }
-    }
+    ExitCode::from(exit_code)
 }
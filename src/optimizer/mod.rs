@@ -0,0 +1,6 @@
+mod optimizer;
+mod optimizer_error;
+mod tests;
+
+pub(crate) use self::optimizer::*;
+pub(crate) use self::optimizer_error::*;
@@ -0,0 +1,529 @@
+use crate::{
+    ast::{
+        BinaryOperator, ClassMember, Expression, ExpressionRef, LogicalOperator, MatchArm,
+        Statement, StatementRef, UnaryOperator,
+    },
+    lexer::Span,
+};
+
+use super::{OptimizerError, OptimizerResult};
+
+///
+/// Fold constant subexpressions, simplify algebraic identities and prune
+/// if statements with a constant condition
+///
+/// The program must be type checked, identities such as 'x * 1' rely on the
+/// operand types. Expressions that could have a side effect are never
+/// dropped, overflowing arithmetic is left for the runtime to report
+///
+pub fn fold_constants(program: &Statement) -> OptimizerResult<StatementRef> {
+    fold_statement(program).map(Box::new)
+}
+
+fn fold_statement(statement: &Statement) -> OptimizerResult<Statement> {
+    Ok(match statement {
+        Statement::Program { body, span } => Statement::Program {
+            body: body.iter().map(fold_statement).collect::<Result<_, _>>()?,
+            span: *span,
+        },
+        Statement::Block { body, span } => Statement::Block {
+            body: body.iter().map(fold_statement).collect::<Result<_, _>>()?,
+            span: *span,
+        },
+        Statement::Empty { span } => Statement::Empty { span: *span },
+        Statement::InterfaceDeclaration { .. } | Statement::EnumDeclaration { .. } => {
+            statement.clone()
+        }
+        Statement::Expression { expression, span } => Statement::Expression {
+            expression: fold_expression_ref(expression)?,
+            span: *span,
+        },
+        Statement::VariableDeclaration { variables, span } => Statement::VariableDeclaration {
+            variables: variables
+                .iter()
+                .map(fold_expression)
+                .collect::<Result<_, _>>()?,
+            span: *span,
+        },
+        Statement::If {
+            condition,
+            consequent,
+            alternative,
+            span,
+        } => {
+            let condition = fold_expression_ref(condition)?;
+            let consequent = fold_statement(consequent)?;
+            let alternative = alternative.as_deref().map(fold_statement).transpose()?;
+
+            // The branch that is never taken is dropped
+            match (condition.as_ref(), alternative) {
+                (Expression::BooleanLiteral { value: true, .. }, _) => consequent,
+                (Expression::BooleanLiteral { value: false, .. }, Some(alternative)) => alternative,
+                (Expression::BooleanLiteral { value: false, .. }, None) => {
+                    Statement::Empty { span: *span }
+                }
+                (_, alternative) => Statement::If {
+                    condition,
+                    consequent: Box::new(consequent),
+                    alternative: alternative.map(Box::new),
+                    span: *span,
+                },
+            }
+        }
+        Statement::While {
+            condition,
+            body,
+            span,
+        } => Statement::While {
+            condition: fold_expression_ref(condition)?,
+            body: fold_statement_ref(body)?,
+            span: *span,
+        },
+        Statement::DoWhile {
+            body,
+            condition,
+            span,
+        } => Statement::DoWhile {
+            body: fold_statement_ref(body)?,
+            condition: fold_expression_ref(condition)?,
+            span: *span,
+        },
+        Statement::For {
+            initializer,
+            condition,
+            increment,
+            body,
+            span,
+        } => Statement::For {
+            initializer: initializer.as_ref().map(fold_statement_ref).transpose()?,
+            condition: condition.as_ref().map(fold_expression_ref).transpose()?,
+            increment: increment.as_ref().map(fold_expression_ref).transpose()?,
+            body: fold_statement_ref(body)?,
+            span: *span,
+        },
+        Statement::FunctionDeclaration {
+            name,
+            type_parameters,
+            parameters,
+            return_type,
+            body,
+            span,
+        } => Statement::FunctionDeclaration {
+            name: name.clone(),
+            type_parameters: type_parameters.clone(),
+            parameters: parameters.clone(),
+            return_type: return_type.clone(),
+            body: fold_statement_ref(body)?,
+            span: *span,
+        },
+        Statement::Return { argument, span } => Statement::Return {
+            argument: argument.as_ref().map(fold_expression_ref).transpose()?,
+            span: *span,
+        },
+        Statement::Break { .. } | Statement::Continue { .. } => statement.clone(),
+        Statement::Labeled { label, body, span } => Statement::Labeled {
+            label: label.clone(),
+            body: fold_statement_ref(body)?,
+            span: *span,
+        },
+        Statement::ClassDeclaration {
+            name,
+            type_parameters,
+            super_class,
+            implements,
+            members,
+            span,
+        } => Statement::ClassDeclaration {
+            name: name.clone(),
+            type_parameters: type_parameters.clone(),
+            super_class: super_class.clone(),
+            implements: implements.clone(),
+            members: members
+                .iter()
+                .map(fold_class_member)
+                .collect::<OptimizerResult<_>>()?,
+            span: *span,
+        },
+    })
+}
+
+fn fold_class_member(member: &ClassMember) -> OptimizerResult<ClassMember> {
+    Ok(match member {
+        ClassMember::Field {
+            name,
+            type_annotation,
+            initializer,
+            span,
+        } => ClassMember::Field {
+            name: name.clone(),
+            type_annotation: type_annotation.clone(),
+            initializer: initializer.as_ref().map(fold_expression_ref).transpose()?,
+            span: *span,
+        },
+        ClassMember::Method { declaration } => ClassMember::Method {
+            declaration: fold_statement_ref(declaration)?,
+        },
+        ClassMember::Constructor { declaration } => ClassMember::Constructor {
+            declaration: fold_statement_ref(declaration)?,
+        },
+    })
+}
+
+fn fold_statement_ref(statement: &StatementRef) -> OptimizerResult<StatementRef> {
+    fold_statement(statement).map(Box::new)
+}
+
+fn fold_expression_ref(expression: &ExpressionRef) -> OptimizerResult<ExpressionRef> {
+    fold_expression(expression).map(Box::new)
+}
+
+fn fold_expression(expression: &Expression) -> OptimizerResult<Expression> {
+    Ok(match expression {
+        Expression::Variable {
+            identifier,
+            type_annotation,
+            initializer,
+            span,
+        } => Expression::Variable {
+            identifier: identifier.clone(),
+            type_annotation: type_annotation.clone(),
+            initializer: initializer.as_ref().map(fold_expression_ref).transpose()?,
+            span: *span,
+        },
+        Expression::Assignment {
+            operator,
+            left,
+            right,
+            span,
+        } => Expression::Assignment {
+            operator: *operator,
+            left: fold_expression_ref(left)?,
+            right: fold_expression_ref(right)?,
+            span: *span,
+        },
+        Expression::Binary {
+            operator,
+            left,
+            right,
+            span,
+        } => fold_binary(
+            *operator,
+            fold_expression_ref(left)?,
+            fold_expression_ref(right)?,
+            *span,
+        )?,
+        Expression::Unary {
+            operator,
+            right,
+            span,
+        } => fold_unary(*operator, fold_expression(right)?, *span),
+        Expression::Logical {
+            operator,
+            left,
+            right,
+            span,
+        } => fold_logical(
+            *operator,
+            fold_expression_ref(left)?,
+            fold_expression_ref(right)?,
+            *span,
+        ),
+        Expression::Member {
+            computed,
+            optional,
+            object,
+            property,
+            span,
+        } => Expression::Member {
+            computed: *computed,
+            optional: *optional,
+            object: fold_expression_ref(object)?,
+            property: fold_expression_ref(property)?,
+            span: *span,
+        },
+        Expression::Call {
+            callee,
+            arguments,
+            span,
+        } => Expression::Call {
+            callee: fold_expression_ref(callee)?,
+            arguments: arguments
+                .iter()
+                .map(fold_expression)
+                .collect::<Result<_, _>>()?,
+            span: *span,
+        },
+        Expression::New {
+            callee,
+            arguments,
+            span,
+        } => Expression::New {
+            callee: fold_expression_ref(callee)?,
+            arguments: arguments
+                .iter()
+                .map(fold_expression)
+                .collect::<Result<_, _>>()?,
+            span: *span,
+        },
+        Expression::ArrayLiteral { elements, span } => Expression::ArrayLiteral {
+            elements: elements
+                .iter()
+                .map(fold_expression)
+                .collect::<Result<_, _>>()?,
+            span: *span,
+        },
+        Expression::EnumVariant {
+            enum_name,
+            variant,
+            arguments,
+            span,
+        } => Expression::EnumVariant {
+            enum_name: enum_name.clone(),
+            variant: variant.clone(),
+            arguments: arguments
+                .iter()
+                .map(fold_expression)
+                .collect::<Result<_, _>>()?,
+            span: *span,
+        },
+        Expression::Match {
+            subject,
+            arms,
+            span,
+        } => Expression::Match {
+            subject: fold_expression_ref(subject)?,
+            arms: arms
+                .iter()
+                .map(|arm| {
+                    Ok(MatchArm {
+                        pattern: arm.pattern.clone(),
+                        guard: arm.guard.as_ref().map(fold_expression_ref).transpose()?,
+                        value: fold_expression_ref(&arm.value)?,
+                        span: arm.span,
+                    })
+                })
+                .collect::<OptimizerResult<_>>()?,
+            span: *span,
+        },
+        Expression::TypeTest {
+            expression,
+            type_annotation,
+            span,
+        } => Expression::TypeTest {
+            expression: fold_expression_ref(expression)?,
+            type_annotation: type_annotation.clone(),
+            span: *span,
+        },
+        Expression::BooleanLiteral { .. }
+        | Expression::NilLiteral { .. }
+        | Expression::StringLiteral { .. }
+        | Expression::NumericLiteral { .. }
+        | Expression::Identifier { .. }
+        | Expression::This { .. }
+        | Expression::Super { .. }
+        | Expression::Destructuring { .. } => expression.clone(),
+    })
+}
+
+fn fold_binary(
+    operator: BinaryOperator,
+    left: ExpressionRef,
+    right: ExpressionRef,
+    span: Span,
+) -> OptimizerResult<Expression> {
+    use Expression::{BooleanLiteral, NilLiteral, NumericLiteral, StringLiteral};
+
+    if operator == BinaryOperator::Divide {
+        if let NumericLiteral { value: 0, .. } = right.as_ref() {
+            return Err(OptimizerError::ConstantError {
+                message: String::from("Division by zero"),
+                span,
+            });
+        }
+    }
+
+    let folded = match (operator, left.as_ref(), right.as_ref()) {
+        (_, NumericLiteral { value: left, .. }, NumericLiteral { value: right, .. }) => {
+            let (left, right) = (*left, *right);
+            match operator {
+                BinaryOperator::Add => left.checked_add(right).map(number),
+                BinaryOperator::Subtract => left.checked_sub(right).map(number),
+                BinaryOperator::Multiply => left.checked_mul(right).map(number),
+                BinaryOperator::Divide => left.checked_div(right).map(number),
+                BinaryOperator::Equal => Some(boolean(left == right)),
+                BinaryOperator::NotEqual => Some(boolean(left != right)),
+                BinaryOperator::GreaterThan => Some(boolean(left > right)),
+                BinaryOperator::GreaterThanOrEqualTo => Some(boolean(left >= right)),
+                BinaryOperator::LessThan => Some(boolean(left < right)),
+                BinaryOperator::LessThanOrEqualTo => Some(boolean(left <= right)),
+            }
+        }
+        (_, StringLiteral { value: left, .. }, StringLiteral { value: right, .. }) => {
+            match operator {
+                BinaryOperator::Add => Some(string(format!("{}{}", left, right))),
+                BinaryOperator::Equal => Some(boolean(left == right)),
+                BinaryOperator::NotEqual => Some(boolean(left != right)),
+                BinaryOperator::GreaterThan => Some(boolean(left > right)),
+                BinaryOperator::GreaterThanOrEqualTo => Some(boolean(left >= right)),
+                BinaryOperator::LessThan => Some(boolean(left < right)),
+                BinaryOperator::LessThanOrEqualTo => Some(boolean(left <= right)),
+                _ => None,
+            }
+        }
+        (
+            BinaryOperator::Equal | BinaryOperator::NotEqual,
+            BooleanLiteral { value: left, .. },
+            BooleanLiteral { value: right, .. },
+        ) => Some(boolean(
+            (left == right) == (operator == BinaryOperator::Equal),
+        )),
+        (
+            BinaryOperator::Equal | BinaryOperator::NotEqual,
+            NilLiteral { .. },
+            NilLiteral { .. },
+        ) => Some(boolean(operator == BinaryOperator::Equal)),
+        _ => None,
+    };
+    if let Some(folded) = folded {
+        return Ok(with_span(folded, span));
+    }
+
+    // Identities keep the other operand, it is a number after type checking
+    Ok(match (operator, left.as_ref(), right.as_ref()) {
+        (BinaryOperator::Add, _, NumericLiteral { value: 0, .. })
+        | (BinaryOperator::Subtract, _, NumericLiteral { value: 0, .. })
+        | (BinaryOperator::Multiply, _, NumericLiteral { value: 1, .. })
+        | (BinaryOperator::Divide, _, NumericLiteral { value: 1, .. }) => *left,
+        (BinaryOperator::Add, NumericLiteral { value: 0, .. }, _)
+        | (BinaryOperator::Multiply, NumericLiteral { value: 1, .. }, _) => *right,
+        _ => Expression::Binary {
+            operator,
+            left,
+            right,
+            span,
+        },
+    })
+}
+
+fn fold_unary(operator: UnaryOperator, right: Expression, span: Span) -> Expression {
+    match (operator, right) {
+        (UnaryOperator::Plus, Expression::NumericLiteral { value, .. }) => {
+            with_span(number(value), span)
+        }
+        (UnaryOperator::Minus, Expression::NumericLiteral { value, .. })
+            if value.checked_neg().is_some() =>
+        {
+            with_span(number(-value), span)
+        }
+        (UnaryOperator::Not, Expression::BooleanLiteral { value, .. }) => {
+            with_span(boolean(!value), span)
+        }
+        // Double negation of a boolean is the boolean itself
+        (
+            UnaryOperator::Not,
+            Expression::Unary {
+                operator: UnaryOperator::Not,
+                right,
+                ..
+            },
+        ) => *right,
+        (operator, right) => Expression::Unary {
+            operator,
+            right: Box::new(right),
+            span,
+        },
+    }
+}
+
+fn fold_logical(
+    operator: LogicalOperator,
+    left: ExpressionRef,
+    right: ExpressionRef,
+    span: Span,
+) -> Expression {
+    use Expression::{BooleanLiteral, NilLiteral, NumericLiteral, StringLiteral};
+
+    match (operator, left.as_ref(), right.as_ref()) {
+        // Literals are either always or never nil
+        (LogicalOperator::NilCoalescing, NilLiteral { .. }, _) => *right,
+        (
+            LogicalOperator::NilCoalescing,
+            BooleanLiteral { .. } | NumericLiteral { .. } | StringLiteral { .. },
+            _,
+        ) => *left,
+        // The right operand is never evaluated
+        (LogicalOperator::And, BooleanLiteral { value: false, .. }, _)
+        | (LogicalOperator::Or, BooleanLiteral { value: true, .. }, _) => *left,
+        (LogicalOperator::And, BooleanLiteral { value: true, .. }, _)
+        | (LogicalOperator::Or, BooleanLiteral { value: false, .. }, _) => *right,
+        (LogicalOperator::And, _, BooleanLiteral { value: true, .. })
+        | (LogicalOperator::Or, _, BooleanLiteral { value: false, .. }) => *left,
+        // The left operand is dropped, it must not have side effects
+        (LogicalOperator::And, _, BooleanLiteral { value: false, .. })
+        | (LogicalOperator::Or, _, BooleanLiteral { value: true, .. })
+            if is_pure(&left) =>
+        {
+            with_span(*right, span)
+        }
+        _ => Expression::Logical {
+            operator,
+            left,
+            right,
+            span,
+        },
+    }
+}
+
+///
+/// Whether evaluating the expression can neither fail nor change any state
+///
+fn is_pure(expression: &Expression) -> bool {
+    match expression {
+        Expression::BooleanLiteral { .. }
+        | Expression::NilLiteral { .. }
+        | Expression::StringLiteral { .. }
+        | Expression::NumericLiteral { .. }
+        | Expression::Identifier { .. }
+        | Expression::This { .. } => true,
+        Expression::Unary {
+            operator: UnaryOperator::Not | UnaryOperator::Plus,
+            right,
+            ..
+        } => is_pure(right),
+        Expression::Logical { left, right, .. } => is_pure(left) && is_pure(right),
+        _ => false,
+    }
+}
+
+fn number(value: i32) -> Expression {
+    Expression::NumericLiteral {
+        value,
+        span: Span::default(),
+    }
+}
+
+fn boolean(value: bool) -> Expression {
+    Expression::BooleanLiteral {
+        value,
+        span: Span::default(),
+    }
+}
+
+fn string(value: String) -> Expression {
+    Expression::StringLiteral {
+        value,
+        span: Span::default(),
+    }
+}
+
+///
+/// Folded literals cover the source of the expression they replace
+///
+fn with_span(expression: Expression, span: Span) -> Expression {
+    match expression {
+        Expression::NumericLiteral { value, .. } => Expression::NumericLiteral { value, span },
+        Expression::BooleanLiteral { value, .. } => Expression::BooleanLiteral { value, span },
+        Expression::StringLiteral { value, .. } => Expression::StringLiteral { value, span },
+        expression => expression,
+    }
+}
@@ -0,0 +1,19 @@
+use thiserror::Error;
+
+use crate::lexer::Span;
+
+#[derive(Error, Debug)]
+pub enum OptimizerError {
+    #[error("Constant error: {message}")]
+    ConstantError { message: String, span: Span },
+}
+
+impl OptimizerError {
+    pub fn span(&self) -> Span {
+        match self {
+            OptimizerError::ConstantError { span, .. } => *span,
+        }
+    }
+}
+
+pub type OptimizerResult<T> = Result<T, OptimizerError>;
@@ -0,0 +1,11 @@
+use super::internal_util::execute_yaml_test_cases;
+
+#[test]
+fn test_optimizer_folding() {
+    execute_yaml_test_cases("tests/optimizer/test_optimizer_folding.yaml");
+}
+
+#[test]
+fn test_optimizer_simplification() {
+    execute_yaml_test_cases("tests/optimizer/test_optimizer_simplification.yaml");
+}
@@ -8,4 +8,7 @@ pub(crate) use self::parser::Parser;
 #[allow(unused_imports)]
 pub(crate) use self::parser_error::*;
 #[allow(unused_imports)]
-pub(crate) use self::parsers::parse_root_statement;
+pub(crate) use self::parsers::{
+    parse_root_expression, parse_root_leading_statement, parse_root_single_statement,
+    parse_root_statement,
+};
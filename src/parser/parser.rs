@@ -1,6 +1,6 @@
 use crate::{
     ast::{Expression, ExpressionRef},
-    lexer::TokenType,
+    lexer::{Span, TokenPosition, TokenType},
     Lexer, Token,
 };
 
@@ -14,17 +14,25 @@ pub struct Parser<'a> {
     pub(super) source: &'a str,
     pub(super) lexer: Lexer<'a>,
     pub(super) lookahead: Token,
+    pub(super) previous_end: TokenPosition,
 }
 
 #[allow(dead_code)]
 impl<'a> Parser<'a> {
     pub fn new(source: &'a str) -> ParserResult<Self> {
-        let mut lexer = Lexer::new(&source);
+        Self::new_at(source, TokenPosition::new())
+    }
+
+    ///
+    /// Parser that starts in the middle of the source, at a token boundary
+    ///
+    pub fn new_at(source: &'a str, start: TokenPosition) -> ParserResult<Self> {
+        let mut lexer = Lexer::new_at(source, start);
         let lookahead = match lexer.next_token() {
             Ok(token) => token,
             Err(error) => {
                 return Err(ParserError::LexicalError {
-                    message: format!("Could not parse token: {:?}", error),
+                    message: format!("Could not parse token: {}", error),
                 })
             }
         };
@@ -33,9 +41,24 @@ impl<'a> Parser<'a> {
             source,
             lexer,
             lookahead,
+            previous_end: start,
         })
     }
 
+    ///
+    /// The token the parser is currently looking at
+    ///
+    pub fn lookahead(&self) -> Token {
+        self.lookahead
+    }
+
+    ///
+    /// Span from the given start position to the end of the last consumed token
+    ///
+    pub(super) fn span_from(&self, start: TokenPosition) -> Span {
+        Span::new(start, self.previous_end)
+    }
+
     ///
     /// Expects a token of a given type
     ///
@@ -54,10 +77,11 @@ impl<'a> Parser<'a> {
             Ok(token) => token,
             Err(error) => {
                 return Err(ParserError::LexicalError {
-                    message: format!("Could not parse token: {:?}", error),
+                    message: format!("Could not parse token: {}", error),
                 });
             }
         };
+        self.previous_end = pre_token.end;
         Ok(pre_token)
     }
 
@@ -72,20 +96,21 @@ impl<'a> Parser<'a> {
                     Ok(token) => token,
                     Err(error) => {
                         return Err(ParserError::LexicalError {
-                            message: format!("Could not parse token: {:?}", error),
+                            message: format!("Could not parse token: {}", error),
                         });
                     }
                 };
+                self.previous_end = pre_token.end;
                 return Ok(pre_token);
             }
         }
 
-        return Err(ParserError::LexicalError {
+        Err(ParserError::LexicalError {
             message: format!(
                 "Unexpected token: {}, expected tokens: '{:?}'",
                 self.lookahead.token_type, token_types
             ),
-        });
+        })
     }
 
     ///
@@ -114,7 +139,11 @@ impl<'a> Parser<'a> {
     pub(super) fn is_expression_valid_assignment_target(&self, expression: &ExpressionRef) -> bool {
         matches!(
             expression.as_ref(),
-            Expression::Identifier { .. } | Expression::Member { .. }
+            Expression::Identifier { .. }
+                | Expression::Member {
+                    optional: false,
+                    ..
+                }
         )
     }
 
@@ -1,6 +1,6 @@
 use crate::ast::{AssignmentOperator, Expression, ExpressionRef};
 use crate::lexer::TokenType;
-use crate::parser::parsers::expression_parse_relational_and_logical::parse_logical_or_expression;
+use crate::parser::parsers::expression_parse_relational_and_logical::parse_nil_coalescing_expression;
 use crate::parser::{Parser, ParserError, ParserResult};
 
 ///
@@ -10,7 +10,8 @@ use crate::parser::{Parser, ParserError, ParserResult};
 ///  ;
 ///
 pub(super) fn parse_assignment_expression(parser: &mut Parser) -> ParserResult<ExpressionRef> {
-    let left = parse_logical_or_expression(parser)?;
+    let start = parser.lookahead.start;
+    let left = parse_nil_coalescing_expression(parser)?;
 
     if !parser.is_next_token_assignment_operator() {
         return Ok(left);
@@ -44,9 +45,11 @@ pub(super) fn parse_assignment_expression(parser: &mut Parser) -> ParserResult<E
     }
 
     let right = parse_assignment_expression(parser)?;
+    let span = parser.span_from(start);
     Ok(Box::new(Expression::Assignment {
         operator: assignment_operator,
         left,
-        right: right,
+        right,
+        span,
     }))
 }
@@ -17,7 +17,7 @@ pub(super) fn parse_additive_expression(parser: &mut Parser) -> ParserResult<Exp
             TokenType::AdditivePlusOperator,
             TokenType::AdditiveMinusOperator,
         ],
-        |parser| parse_factor_expression(parser),
+        parse_factor_expression,
         |op| match op {
             TokenType::AdditivePlusOperator => Ok(BinaryOperator::Add),
             TokenType::AdditiveMinusOperator => Ok(BinaryOperator::Subtract),
@@ -41,7 +41,7 @@ pub(super) fn parse_factor_expression(parser: &mut Parser) -> ParserResult<Expre
             TokenType::FactorMultiplicationOperator,
             TokenType::FactorDivisionOperator,
         ],
-        |parser| parse_unary_expression(parser),
+        parse_unary_expression,
         |op| match op {
             TokenType::FactorMultiplicationOperator => Ok(BinaryOperator::Multiply),
             TokenType::FactorDivisionOperator => Ok(BinaryOperator::Divide),
@@ -1,6 +1,6 @@
 use crate::{
     ast::{Expression, ExpressionList, ExpressionRef},
-    lexer::TokenType,
+    lexer::{TokenPosition, TokenType},
     parser::ParserResult,
     Parser,
 };
@@ -28,11 +28,12 @@ pub(super) fn parse_left_hand_side_expression(parser: &mut Parser) -> ParserResu
 ///
 pub(super) fn parse_call_member_expression(parser: &mut Parser) -> ParserResult<ExpressionRef> {
     // Member part might be part of a call
+    let start = parser.lookahead.start;
     let member = parse_member_expression(parser)?;
 
     // See if we have a call expression
     if parser.is_next_token_of_type(TokenType::OpeningParenthesis) {
-        return parse_call_expression(parser, member);
+        return parse_call_expression(parser, member, start);
     }
 
     // Simple member expression
@@ -54,14 +55,17 @@ pub(super) fn parse_call_member_expression(parser: &mut Parser) -> ParserResult<
 pub(super) fn parse_call_expression(
     parser: &mut Parser,
     callee: ExpressionRef,
+    start: TokenPosition,
 ) -> ParserResult<ExpressionRef> {
+    let arguments = parse_arguments(parser)?;
     let mut call_expression = Box::new(Expression::Call {
-        callee: callee.clone(),
-        arguments: parse_arguments(parser)?,
+        callee,
+        arguments,
+        span: parser.span_from(start),
     });
 
     if parser.is_next_token_of_type(TokenType::OpeningParenthesis) {
-        call_expression = parse_call_expression(parser, call_expression)?;
+        call_expression = parse_call_expression(parser, call_expression, start)?;
     }
 
     Ok(call_expression)
@@ -110,23 +114,22 @@ pub(super) fn parse_arguments_list(parser: &mut Parser) -> ParserResult<Expressi
 ///
 /// MemberExpression
 ///  : PrimaryExpression
-///  | MemberExpression '.' Identifier
-///  | MemberExpression '[' Expression ']'
+///  | MemberExpression ['.' | '?.'] Identifier
+///  | MemberExpression ['?.'] '[' Expression ']'
 ///  ;
 ///
 pub(super) fn parse_member_expression(parser: &mut Parser) -> ParserResult<ExpressionRef> {
+    let start = parser.lookahead.start;
     let mut object = parse_primary_expression(parser)?;
 
-    while parser.is_next_token_any_of_type(&[TokenType::Dot, TokenType::OpeningBracket]) {
-        if parser.is_next_token_of_type(TokenType::Dot) {
-            parser.eat_token(TokenType::Dot)?;
-            let property = parse_identifier_expression(parser)?;
-
-            object = Box::new(Expression::Member {
-                computed: false,
-                object,
-                property,
-            });
+    while parser.is_next_token_any_of_type(&[
+        TokenType::Dot,
+        TokenType::OptionalChaining,
+        TokenType::OpeningBracket,
+    ]) {
+        let optional = parser.is_next_token_of_type(TokenType::OptionalChaining);
+        if optional {
+            parser.eat_token(TokenType::OptionalChaining)?;
         }
 
         if parser.is_next_token_of_type(TokenType::OpeningBracket) {
@@ -134,12 +137,30 @@ pub(super) fn parse_member_expression(parser: &mut Parser) -> ParserResult<Expre
             let property = parse_root_expression(parser)?;
             parser.eat_token(TokenType::ClosingBracket)?;
 
+            let span = parser.span_from(start);
             object = Box::new(Expression::Member {
                 computed: true,
+                optional,
                 object,
                 property,
+                span,
             });
+            continue;
+        }
+
+        if !optional {
+            parser.eat_token(TokenType::Dot)?;
         }
+        let property = parse_identifier_expression(parser)?;
+
+        let span = parser.span_from(start);
+        object = Box::new(Expression::Member {
+            computed: false,
+            optional,
+            object,
+            property,
+            span,
+        });
     }
 
     Ok(object)
@@ -32,7 +32,10 @@ pub(super) fn parse_boolean_literal_expression(parser: &mut Parser) -> ParserRes
     let token = parser.eat_any_of_token(&[TokenType::BooleanTrue, TokenType::BooleanFalse])?;
     let bool_value = token.token_type == TokenType::BooleanTrue;
 
-    Ok(Box::new(Expression::BooleanLiteral { value: bool_value }))
+    Ok(Box::new(Expression::BooleanLiteral {
+        value: bool_value,
+        span: token.span(),
+    }))
 }
 
 ///
@@ -41,9 +44,9 @@ pub(super) fn parse_boolean_literal_expression(parser: &mut Parser) -> ParserRes
 ///  ;
 ///
 pub(super) fn parse_nil_literal_expression(parser: &mut Parser) -> ParserResult<ExpressionRef> {
-    parser.eat_token(TokenType::Nil)?;
+    let token = parser.eat_token(TokenType::Nil)?;
 
-    Ok(Box::new(Expression::NilLiteral))
+    Ok(Box::new(Expression::NilLiteral { span: token.span() }))
 }
 
 ///
@@ -54,9 +57,17 @@ pub(super) fn parse_nil_literal_expression(parser: &mut Parser) -> ParserResult<
 pub(super) fn parse_numeric_literal_expression(parser: &mut Parser) -> ParserResult<ExpressionRef> {
     let token = parser.eat_token(TokenType::Number)?;
     let token_value = token.text(parser.source);
-    let token_value = token_value.trim().parse().unwrap();
+    let token_value = token_value
+        .trim()
+        .parse()
+        .map_err(|_| ParserError::SemanticError {
+            message: format!("Invalid numeric literal '{}'", token_value),
+        })?;
 
-    Ok(Box::new(Expression::NumericLiteral { value: token_value }))
+    Ok(Box::new(Expression::NumericLiteral {
+        value: token_value,
+        span: token.span(),
+    }))
 }
 
 ///
@@ -69,6 +80,48 @@ pub(super) fn parse_string_literal_expression(parser: &mut Parser) -> ParserResu
     let token_value = &parser.source[token.start.offset + 1..token.end.offset - 1];
 
     Ok(Box::new(Expression::StringLiteral {
-        value: String::from(token_value),
+        value: decode_escapes(token_value),
+        span: token.span(),
     }))
 }
+
+///
+/// Replace the escape sequences the lexer accepts with the characters they
+/// stand for, unknown escapes keep the escaped character
+///
+fn decode_escapes(text: &str) -> String {
+    let mut value = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            value.push(ch);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => value.push('\n'),
+            Some('t') => value.push('\t'),
+            Some('r') => value.push('\r'),
+            Some('0') => value.push('\0'),
+            Some('x') => {
+                let mut code = None;
+                for _ in 0..2 {
+                    let Some(digit) = chars.peek().and_then(|c| c.to_digit(16)) else {
+                        break;
+                    };
+                    chars.next();
+                    code = Some(code.unwrap_or(0) * 16 + digit);
+                }
+                match code {
+                    Some(code) => value.push(char::from(code as u8)),
+                    None => value.push('x'),
+                }
+            }
+            Some(escaped) => value.push(escaped),
+            None => value.push('\\'),
+        }
+    }
+
+    value
+}
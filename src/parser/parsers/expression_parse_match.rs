@@ -0,0 +1,75 @@
+use crate::{
+    ast::{Expression, ExpressionRef, MatchArm},
+    lexer::TokenType,
+    parser::{
+        parsers::{parse_root_expression, pattern_parse_patterns::parse_pattern},
+        ParserResult,
+    },
+    Parser,
+};
+
+///
+/// MatchExpression
+///  : match '(' Expression ')' '{' [MatchArmList] '}'
+///  ;
+///
+/// MatchArmList
+///  : MatchArm
+///  | MatchArmList ',' MatchArm
+///  ;
+///
+/// A trailing comma after the last arm is allowed
+///
+pub(super) fn parse_match_expression(parser: &mut Parser) -> ParserResult<ExpressionRef> {
+    let start = parser.lookahead.start;
+    parser.eat_token(TokenType::MatchKeyword)?;
+
+    parser.eat_token(TokenType::OpeningParenthesis)?;
+    let subject = parse_root_expression(parser)?;
+    parser.eat_token(TokenType::ClosingParenthesis)?;
+
+    parser.eat_token(TokenType::OpeningBrace)?;
+    let mut arms = vec![];
+    while !parser.is_next_token_any_of_type(&[TokenType::ClosingBrace, TokenType::End]) {
+        arms.push(parse_match_arm(parser)?);
+
+        if !parser.is_next_token_of_type(TokenType::Comma) {
+            break;
+        }
+        parser.eat_token(TokenType::Comma)?;
+    }
+    parser.eat_token(TokenType::ClosingBrace)?;
+
+    Ok(Box::new(Expression::Match {
+        subject,
+        arms,
+        span: parser.span_from(start),
+    }))
+}
+
+///
+/// MatchArm
+///  : Pattern [if Expression] '=>' Expression
+///  ;
+///
+fn parse_match_arm(parser: &mut Parser) -> ParserResult<MatchArm> {
+    let start = parser.lookahead.start;
+    let pattern = parse_pattern(parser)?;
+
+    let guard = if parser.is_next_token_of_type(TokenType::IfKeyword) {
+        parser.eat_token(TokenType::IfKeyword)?;
+        Some(parse_root_expression(parser)?)
+    } else {
+        None
+    };
+
+    parser.eat_token(TokenType::Arrow)?;
+    let value = parse_root_expression(parser)?;
+
+    Ok(MatchArm {
+        pattern,
+        guard,
+        value,
+        span: parser.span_from(start),
+    })
+}
@@ -1,17 +1,21 @@
 use super::expression_parse_left_hand_side::{
-    parse_arguments, parse_left_hand_side_expression, parse_member_expression,
+    parse_arguments, parse_arguments_list, parse_member_expression,
 };
+use super::expression_parse_match::parse_match_expression;
 use crate::ast::{Expression, ExpressionRef};
 use crate::lexer::TokenType;
 use crate::parser::parsers::expression_parse_literals::parse_literal_expression;
 use crate::parser::parsers::root::parse_root_expression;
-use crate::parser::{Parser, ParserResult};
+use crate::parser::{Parser, ParserError, ParserResult};
 
 ///
 /// PrimaryExpression
 ///  : LiteralExpression
+///  | ArrayLiteralExpression
 ///  | GroupExpression
 ///  | IdentifierExpression
+///  | EnumVariantExpression
+///  | MatchExpression
 ///  | ThisExpression
 ///  ;
 ///
@@ -22,11 +26,18 @@ pub(super) fn parse_primary_expression(parser: &mut Parser) -> ParserResult<Expr
 
     match parser.lookahead.token_type {
         TokenType::OpeningParenthesis => parse_group_expression(parser),
-        TokenType::Identifier => parse_identifier_expression(parser),
+        TokenType::OpeningBracket => parse_array_literal_expression(parser),
+        TokenType::Identifier => parse_enum_variant_expression(parser),
+        TokenType::MatchKeyword => parse_match_expression(parser),
         TokenType::ThisKeyword => parse_this_expression(parser),
         TokenType::SuperKeyword => parse_super_expression(parser),
         TokenType::NewKeyword => parse_new_expression(parser),
-        _ => parse_left_hand_side_expression(parser),
+        _ => Err(ParserError::ParserError {
+            message: format!(
+                "Unexpected token: {}, expected an expression",
+                parser.lookahead.token_type
+            ),
+        }),
     }
 }
 
@@ -43,6 +54,27 @@ pub(super) fn parse_group_expression(parser: &mut Parser) -> ParserResult<Expres
     Ok(expression_ref)
 }
 
+///
+/// ArrayLiteralExpression
+///  : '[' [ArgumentList] ']'
+///  ;
+///
+fn parse_array_literal_expression(parser: &mut Parser) -> ParserResult<ExpressionRef> {
+    let start = parser.lookahead.start;
+    parser.eat_token(TokenType::OpeningBracket)?;
+    let elements = if parser.is_next_token_of_type(TokenType::ClosingBracket) {
+        vec![]
+    } else {
+        parse_arguments_list(parser)?
+    };
+    parser.eat_token(TokenType::ClosingBracket)?;
+
+    Ok(Box::new(Expression::ArrayLiteral {
+        elements,
+        span: parser.span_from(start),
+    }))
+}
+
 ///
 /// IdentifierExpression
 ///  : IDENTIFIER
@@ -54,6 +86,37 @@ pub(super) fn parse_identifier_expression(parser: &mut Parser) -> ParserResult<E
 
     Ok(Box::new(Expression::Identifier {
         name: String::from(identifier_value),
+        span: identifier_token.span(),
+    }))
+}
+
+///
+/// EnumVariantExpression
+///  : IdentifierExpression '::' IdentifierExpression [Arguments]
+///  ;
+///
+/// An identifier without '::' after it stays a plain identifier
+///
+fn parse_enum_variant_expression(parser: &mut Parser) -> ParserResult<ExpressionRef> {
+    let start = parser.lookahead.start;
+    let enum_name = parse_identifier_expression(parser)?;
+    if !parser.is_next_token_of_type(TokenType::DoubleColon) {
+        return Ok(enum_name);
+    }
+
+    parser.eat_token(TokenType::DoubleColon)?;
+    let variant = parse_identifier_expression(parser)?;
+    let arguments = if parser.is_next_token_of_type(TokenType::OpeningParenthesis) {
+        parse_arguments(parser)?
+    } else {
+        vec![]
+    };
+
+    Ok(Box::new(Expression::EnumVariant {
+        enum_name,
+        variant,
+        arguments,
+        span: parser.span_from(start),
     }))
 }
 
@@ -63,8 +126,8 @@ pub(super) fn parse_identifier_expression(parser: &mut Parser) -> ParserResult<E
 ///  ;
 ///
 pub(super) fn parse_this_expression(parser: &mut Parser) -> ParserResult<ExpressionRef> {
-    parser.eat_token(TokenType::ThisKeyword)?;
-    Ok(Box::new(Expression::This {}))
+    let token = parser.eat_token(TokenType::ThisKeyword)?;
+    Ok(Box::new(Expression::This { span: token.span() }))
 }
 
 ///
@@ -73,8 +136,8 @@ pub(super) fn parse_this_expression(parser: &mut Parser) -> ParserResult<Express
 ///  ;
 ///
 pub(super) fn parse_super_expression(parser: &mut Parser) -> ParserResult<ExpressionRef> {
-    parser.eat_token(TokenType::SuperKeyword)?;
-    Ok(Box::new(Expression::Super {}))
+    let token = parser.eat_token(TokenType::SuperKeyword)?;
+    Ok(Box::new(Expression::Super { span: token.span() }))
 }
 
 ///
@@ -83,11 +146,16 @@ pub(super) fn parse_super_expression(parser: &mut Parser) -> ParserResult<Expres
 ///  ;
 ///
 pub(super) fn parse_new_expression(parser: &mut Parser) -> ParserResult<ExpressionRef> {
+    let start = parser.lookahead.start;
     parser.eat_token(TokenType::NewKeyword)?;
 
     let callee = parse_member_expression(parser)?;
 
     let arguments = parse_arguments(parser)?;
 
-    Ok(Box::new(Expression::New { callee, arguments }))
+    Ok(Box::new(Expression::New {
+        callee,
+        arguments,
+        span: parser.span_from(start),
+    }))
 }
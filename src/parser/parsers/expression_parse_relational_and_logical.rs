@@ -1,20 +1,41 @@
-use crate::ast::{BinaryOperator, ExpressionRef, LogicalOperator};
+use crate::ast::{BinaryOperator, Expression, ExpressionRef, LogicalOperator};
 use crate::lexer::TokenType;
 use crate::parser::parsers::expression_parse_binary::parse_additive_expression;
 use crate::parser::parsers::internal_util::{parse_binary_expression, parse_logical_expression};
+use crate::parser::parsers::type_parse_annotations::parse_type;
 use crate::parser::{Parser, ParserError, ParserResult};
 
+///
+/// NilCoalescingExpression
+///  : LogicalOrExpression NIL_COALESCING_OPERATOR NilCoalescingExpression
+///  | LogicalOrExpression
+///  ;
+///
+pub(super) fn parse_nil_coalescing_expression(parser: &mut Parser) -> ParserResult<ExpressionRef> {
+    parse_logical_expression(
+        parser,
+        &[TokenType::NilCoalescingOperator],
+        parse_logical_or_expression,
+        |op| match op {
+            TokenType::NilCoalescingOperator => Ok(LogicalOperator::NilCoalescing),
+            _ => Err(ParserError::ParserError {
+                message: format!("Unknown logical operator {}", op),
+            }),
+        },
+    )
+}
+
 ///
 /// LogicalOrExpression
 ///  : LogicalAndExpression LOGICAL_OR_OPERATOR LogicalOrExpression
 ///  | LogicalAndExpression
 ///  ;
 ///
-pub(super) fn parse_logical_or_expression(parser: &mut Parser) -> ParserResult<ExpressionRef> {
+fn parse_logical_or_expression(parser: &mut Parser) -> ParserResult<ExpressionRef> {
     parse_logical_expression(
         parser,
         &[TokenType::LogicalOrOperator],
-        |parser| parse_logical_and_expression(parser),
+        parse_logical_and_expression,
         |op| match op {
             TokenType::LogicalOrOperator => Ok(LogicalOperator::Or),
             _ => Err(ParserError::ParserError {
@@ -34,7 +55,7 @@ pub(super) fn parse_logical_and_expression(parser: &mut Parser) -> ParserResult<
     parse_logical_expression(
         parser,
         &[TokenType::LogicalAndOperator],
-        |parser| parse_equality_expression(parser),
+        parse_equality_expression,
         |op| match op {
             TokenType::LogicalAndOperator => Ok(LogicalOperator::And),
             _ => Err(ParserError::ParserError {
@@ -54,7 +75,7 @@ pub(super) fn parse_equality_expression(parser: &mut Parser) -> ParserResult<Exp
     parse_binary_expression(
         parser,
         &[TokenType::EqualOperator, TokenType::NotEqualOperator],
-        |parser| parse_relational_expression(parser),
+        parse_relational_expression,
         |op| match op {
             TokenType::EqualOperator => Ok(BinaryOperator::Equal),
             TokenType::NotEqualOperator => Ok(BinaryOperator::NotEqual),
@@ -67,8 +88,8 @@ pub(super) fn parse_equality_expression(parser: &mut Parser) -> ParserResult<Exp
 
 ///
 /// RelationalExpression
-///  : AdditiveExpression
-///  | AdditiveExpression RELATIONAL_OPERATOR AdditiveExpression
+///  : TypeTestExpression
+///  | TypeTestExpression RELATIONAL_OPERATOR TypeTestExpression
 ///  ;
 ///
 pub(super) fn parse_relational_expression(parser: &mut Parser) -> ParserResult<ExpressionRef> {
@@ -80,7 +101,7 @@ pub(super) fn parse_relational_expression(parser: &mut Parser) -> ParserResult<E
             TokenType::RelationalLessThanOperator,
             TokenType::RelationalLessThanOrEqualToOperator,
         ],
-        |parser| parse_additive_expression(parser),
+        parse_type_test_expression,
         |op| match op {
             TokenType::RelationalGreaterThanOperator => Ok(BinaryOperator::GreaterThan),
             TokenType::RelationalGreaterThanOrEqualToOperator => {
@@ -94,3 +115,27 @@ pub(super) fn parse_relational_expression(parser: &mut Parser) -> ParserResult<E
         },
     )
 }
+
+///
+/// TypeTestExpression
+///  : AdditiveExpression
+///  | AdditiveExpression 'is' Type
+///  ;
+///
+fn parse_type_test_expression(parser: &mut Parser) -> ParserResult<ExpressionRef> {
+    let start = parser.lookahead.start;
+    let expression = parse_additive_expression(parser)?;
+
+    if !parser.is_next_token_of_type(TokenType::IsKeyword) {
+        return Ok(expression);
+    }
+
+    parser.eat_token(TokenType::IsKeyword)?;
+    let type_annotation = parse_type(parser)?;
+
+    Ok(Box::new(Expression::TypeTest {
+        expression,
+        type_annotation,
+        span: parser.span_from(start),
+    }))
+}
@@ -12,6 +12,7 @@ use super::expression_parse_left_hand_side::parse_left_hand_side_expression;
 ///  ;
 ///
 pub(super) fn parse_unary_expression(parser: &mut Parser) -> ParserResult<ExpressionRef> {
+    let start = parser.lookahead.start;
     if parser.is_next_token_any_of_type(&[
         TokenType::AdditivePlusOperator,
         TokenType::AdditiveMinusOperator,
@@ -35,7 +36,11 @@ pub(super) fn parse_unary_expression(parser: &mut Parser) -> ParserResult<Expres
         };
 
         let right = parse_unary_expression(parser)?;
-        return Ok(Box::new(Expression::Unary { operator, right }));
+        return Ok(Box::new(Expression::Unary {
+            operator,
+            right,
+            span: parser.span_from(start),
+        }));
     }
 
     parse_left_hand_side_expression(parser)
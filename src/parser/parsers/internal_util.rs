@@ -15,6 +15,7 @@ where
     OperandParserFnType: Fn(&mut Parser) -> ParserResult<ExpressionRef>,
     OperatorMapperFnType: Fn(TokenType) -> ParserResult<BinaryOperator>,
 {
+    let start = parser.lookahead.start;
     let mut left = operand_parser(parser)?;
 
     while parser.is_next_token_any_of_type(token_types) {
@@ -22,11 +23,13 @@ where
         let operator = operator_mapper(operator_token.token_type)?;
 
         let right = operand_parser(parser)?;
+        let span = parser.span_from(start);
 
         left = Box::new(Expression::Binary {
             operator,
             left,
             right,
+            span,
         });
     }
 
@@ -46,6 +49,7 @@ where
     OperandParserFnType: Fn(&mut Parser) -> ParserResult<ExpressionRef>,
     OperatorMapperFnType: Fn(TokenType) -> ParserResult<LogicalOperator>,
 {
+    let start = parser.lookahead.start;
     let mut left = operand_parser(parser)?;
 
     while parser.is_next_token_any_of_type(token_types) {
@@ -53,11 +57,13 @@ where
         let operator = operator_mapper(operator_token.token_type)?;
 
         let right = operand_parser(parser)?;
+        let span = parser.span_from(start);
 
         left = Box::new(Expression::Logical {
             operator,
             left,
             right,
+            span,
         });
     }
 
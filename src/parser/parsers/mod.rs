@@ -2,19 +2,26 @@ mod expression_parse_assignment;
 mod expression_parse_binary;
 mod expression_parse_left_hand_side;
 mod expression_parse_literals;
+mod expression_parse_match;
 mod expression_parse_primary;
 mod expression_parse_relational_and_logical;
 mod expression_parse_unary;
 mod internal_util;
+mod pattern_parse_patterns;
 mod root;
 mod statement_parse_block;
 mod statement_parse_class_declaration;
 mod statement_parse_conditional;
 mod statement_parse_empty_and_expression;
+mod statement_parse_enum_declaration;
 mod statement_parse_function_declaration;
+mod statement_parse_interface_declaration;
 mod statement_parse_loop;
 mod statement_parse_variable_declaration;
 mod type_parse_annotations;
 
 #[allow(dead_code)]
-pub(crate) use root::{parse_root_expression, parse_root_statement};
+pub(crate) use root::{
+    parse_root_expression, parse_root_leading_statement, parse_root_single_statement,
+    parse_root_statement,
+};
@@ -0,0 +1,275 @@
+use crate::{
+    ast::{Expression, ExpressionRef, Pattern, PatternList, PatternProperty, Type},
+    lexer::{Span, TokenType},
+    parser::{
+        parsers::{
+            expression_parse_literals::parse_literal_expression,
+            expression_parse_primary::parse_identifier_expression,
+            type_parse_annotations::parse_type,
+        },
+        ParserError, ParserResult,
+    },
+    Parser,
+};
+
+///
+/// Name of the pattern matching any value without binding it
+///
+const WILDCARD_NAME: &str = "_";
+
+///
+/// Parser of the nested patterns of an array or object pattern
+///
+type PatternParser = fn(&mut Parser) -> ParserResult<Pattern>;
+
+///
+/// Pattern
+///  : LiteralPattern
+///  | ArrayPattern
+///  | EnumVariantPattern
+///  | BindingPattern
+///  ;
+///
+pub(super) fn parse_pattern(parser: &mut Parser) -> ParserResult<Pattern> {
+    if parser.is_next_token_literal()
+        || parser.is_next_token_of_type(TokenType::AdditiveMinusOperator)
+    {
+        return parse_literal_pattern(parser);
+    }
+
+    match parser.lookahead.token_type {
+        TokenType::OpeningBracket => parse_array_pattern(parser, parse_pattern),
+        TokenType::Identifier => parse_enum_variant_or_binding_pattern(parser),
+        _ => Err(ParserError::ParserError {
+            message: format!(
+                "Unexpected token: {}, expected a pattern",
+                parser.lookahead.token_type
+            ),
+        }),
+    }
+}
+
+///
+/// LiteralPattern
+///  : LiteralExpression
+///  | '-' NUMBER
+///  ;
+///
+fn parse_literal_pattern(parser: &mut Parser) -> ParserResult<Pattern> {
+    let start = parser.lookahead.start;
+    let is_negative = parser.is_next_token_of_type(TokenType::AdditiveMinusOperator);
+    if is_negative {
+        parser.eat_token(TokenType::AdditiveMinusOperator)?;
+        if !parser.is_next_token_of_type(TokenType::Number) {
+            return Err(ParserError::ParserError {
+                message: format!(
+                    "Unexpected token: {}, expected a number after '-' in a pattern",
+                    parser.lookahead.token_type
+                ),
+            });
+        }
+    }
+
+    let mut value = parse_literal_expression(parser)?;
+    let span = parser.span_from(start);
+    if let Expression::NumericLiteral {
+        value: number,
+        span: number_span,
+    } = value.as_mut()
+    {
+        if is_negative {
+            *number = -*number;
+            *number_span = span;
+        }
+    }
+
+    Ok(Pattern::Literal { value, span })
+}
+
+///
+/// DestructuringExpression
+///  : ArrayPattern
+///  | ObjectPattern
+///  ;
+///
+/// Destructuring patterns match every value of their type, their nested
+/// patterns are names, '_' or destructuring patterns again
+///
+pub(super) fn parse_destructuring_expression(parser: &mut Parser) -> ParserResult<ExpressionRef> {
+    let start = parser.lookahead.start;
+    let pattern = match parser.lookahead.token_type {
+        TokenType::OpeningBracket => parse_array_pattern(parser, parse_destructuring_element)?,
+        _ => parse_object_pattern(parser, parse_destructuring_element)?,
+    };
+
+    Ok(Box::new(Expression::Destructuring {
+        pattern,
+        span: parser.span_from(start),
+    }))
+}
+
+///
+/// DestructuringElement
+///  : ArrayPattern
+///  | ObjectPattern
+///  | IdentifierExpression
+///  ;
+///
+fn parse_destructuring_element(parser: &mut Parser) -> ParserResult<Pattern> {
+    match parser.lookahead.token_type {
+        TokenType::OpeningBracket => parse_array_pattern(parser, parse_destructuring_element),
+        TokenType::OpeningBrace => parse_object_pattern(parser, parse_destructuring_element),
+        TokenType::Identifier => {
+            let name = parse_identifier_expression(parser)?;
+            let span = name.span();
+            Ok(binding_or_wildcard(name, None, span))
+        }
+        _ => Err(ParserError::ParserError {
+            message: format!(
+                "Unexpected token: {}, expected a name or a destructuring pattern",
+                parser.lookahead.token_type
+            ),
+        }),
+    }
+}
+
+///
+/// ArrayPattern
+///  : '[' [Pattern ( ',' Pattern )*] ']'
+///  ;
+///
+fn parse_array_pattern(parser: &mut Parser, parse_element: PatternParser) -> ParserResult<Pattern> {
+    let start = parser.lookahead.start;
+    parser.eat_token(TokenType::OpeningBracket)?;
+    let elements = parse_pattern_list(parser, TokenType::ClosingBracket, parse_element)?;
+    parser.eat_token(TokenType::ClosingBracket)?;
+
+    Ok(Pattern::Array {
+        elements,
+        span: parser.span_from(start),
+    })
+}
+
+///
+/// ObjectPattern
+///  : '{' [PatternProperty ( ',' PatternProperty )*] '}'
+///  ;
+///
+/// PatternProperty
+///  : IdentifierExpression [':' DestructuringElement]
+///  ;
+///
+/// A property without a pattern binds the field to its own name
+///
+fn parse_object_pattern(parser: &mut Parser, parse_value: PatternParser) -> ParserResult<Pattern> {
+    let start = parser.lookahead.start;
+    parser.eat_token(TokenType::OpeningBrace)?;
+
+    let mut properties = vec![];
+    while !parser.is_next_token_any_of_type(&[TokenType::ClosingBrace, TokenType::End]) {
+        let property_start = parser.lookahead.start;
+        let key = parse_identifier_expression(parser)?;
+        let value = if parser.is_next_token_of_type(TokenType::Colon) {
+            parser.eat_token(TokenType::Colon)?;
+            parse_value(parser)?
+        } else {
+            let span = key.span();
+            binding_or_wildcard(key.clone(), None, span)
+        };
+        properties.push(PatternProperty {
+            key,
+            value,
+            span: parser.span_from(property_start),
+        });
+
+        if !parser.is_next_token_of_type(TokenType::Comma) {
+            break;
+        }
+        parser.eat_token(TokenType::Comma)?;
+    }
+    parser.eat_token(TokenType::ClosingBrace)?;
+
+    Ok(Pattern::Object {
+        properties,
+        span: parser.span_from(start),
+    })
+}
+
+///
+/// EnumVariantPattern
+///  : IdentifierExpression '::' IdentifierExpression ['(' Pattern ( ',' Pattern )* ')']
+///  ;
+///
+/// BindingPattern
+///  : IdentifierExpression [':' Type]
+///  ;
+///
+/// A binding named '_' is a wildcard, it matches without binding the value
+///
+fn parse_enum_variant_or_binding_pattern(parser: &mut Parser) -> ParserResult<Pattern> {
+    let start = parser.lookahead.start;
+    let name = parse_identifier_expression(parser)?;
+
+    if parser.is_next_token_of_type(TokenType::DoubleColon) {
+        parser.eat_token(TokenType::DoubleColon)?;
+        let variant = parse_identifier_expression(parser)?;
+        let arguments = if parser.is_next_token_of_type(TokenType::OpeningParenthesis) {
+            parser.eat_token(TokenType::OpeningParenthesis)?;
+            let arguments =
+                parse_pattern_list(parser, TokenType::ClosingParenthesis, parse_pattern)?;
+            parser.eat_token(TokenType::ClosingParenthesis)?;
+            arguments
+        } else {
+            vec![]
+        };
+
+        return Ok(Pattern::EnumVariant {
+            enum_name: name,
+            variant,
+            arguments,
+            span: parser.span_from(start),
+        });
+    }
+
+    let type_annotation = if parser.is_next_token_of_type(TokenType::Colon) {
+        parser.eat_token(TokenType::Colon)?;
+        Some(parse_type(parser)?)
+    } else {
+        None
+    };
+    let span = parser.span_from(start);
+
+    Ok(binding_or_wildcard(name, type_annotation, span))
+}
+
+fn binding_or_wildcard(name: ExpressionRef, type_annotation: Option<Type>, span: Span) -> Pattern {
+    match name.as_ref() {
+        Expression::Identifier { name, .. } if name == WILDCARD_NAME => Pattern::Wildcard {
+            type_annotation,
+            span,
+        },
+        _ => Pattern::Binding {
+            name,
+            type_annotation,
+            span,
+        },
+    }
+}
+
+fn parse_pattern_list(
+    parser: &mut Parser,
+    closing: TokenType,
+    parse_element: PatternParser,
+) -> ParserResult<PatternList> {
+    let mut patterns = vec![];
+    while !parser.is_next_token_any_of_type(&[closing, TokenType::End]) {
+        patterns.push(parse_element(parser)?);
+
+        if !parser.is_next_token_of_type(TokenType::Comma) {
+            break;
+        }
+        parser.eat_token(TokenType::Comma)?;
+    }
+
+    Ok(patterns)
+}
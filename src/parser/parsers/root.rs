@@ -1,7 +1,8 @@
 use crate::ast::{ExpressionRef, StatementRef};
+use crate::lexer::TokenType;
 use crate::parser::parsers::expression_parse_assignment::parse_assignment_expression;
-use crate::parser::parsers::statement_parse_block::parse_program_statement;
-use crate::parser::{Parser, ParserResult};
+use crate::parser::parsers::statement_parse_block::{parse_program_statement, parse_statement};
+use crate::parser::{Parser, ParserError, ParserResult};
 
 ///
 /// Parses a string into an AST
@@ -10,6 +11,32 @@ pub fn parse_root_statement(parser: &mut Parser) -> ParserResult<StatementRef> {
     parse_program_statement(parser)
 }
 
+///
+/// Parses a string holding exactly one statement
+///
+pub fn parse_root_single_statement(parser: &mut Parser) -> ParserResult<StatementRef> {
+    let statement = parse_statement(parser)?;
+
+    if !parser.is_next_token_of_type(TokenType::End) {
+        return Err(ParserError::ParserError {
+            message: format!(
+                "Unexpected token: {}, expected a single statement",
+                parser.lookahead.token_type
+            ),
+        });
+    }
+
+    Ok(statement)
+}
+
+///
+/// Parses the statement at the start of the string, the rest is left to
+/// the caller
+///
+pub fn parse_root_leading_statement(parser: &mut Parser) -> ParserResult<StatementRef> {
+    parse_statement(parser)
+}
+
 ///
 /// Expression
 ///  : AssignmentExpression
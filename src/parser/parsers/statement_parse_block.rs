@@ -5,11 +5,14 @@ use crate::parser::parsers::statement_parse_conditional::parse_if_statement;
 use crate::parser::parsers::statement_parse_empty_and_expression::{
     parse_empty_statement, parse_expression_statement,
 };
+use crate::parser::parsers::statement_parse_enum_declaration::parse_enum_declaration;
 use crate::parser::parsers::statement_parse_function_declaration::{
     parse_function_declaration_statement, parse_return_statement,
 };
+use crate::parser::parsers::statement_parse_interface_declaration::parse_interface_declaration;
 use crate::parser::parsers::statement_parse_loop::{
-    parse_do_while_statement, parse_for_statement, parse_while_statement,
+    parse_break_statement, parse_continue_statement, parse_do_while_statement, parse_for_statement,
+    parse_while_statement,
 };
 use crate::parser::parsers::statement_parse_variable_declaration::parse_variable_declaration_statement;
 use crate::parser::{Parser, ParserResult};
@@ -21,9 +24,11 @@ use crate::parser::{Parser, ParserResult};
 ///  ;
 ///
 pub(super) fn parse_program_statement(parser: &mut Parser) -> ParserResult<StatementRef> {
+    let start = parser.lookahead.start;
     let statement_list = parse_statement_list(parser, None)?;
     Ok(Box::new(Statement::Program {
         body: statement_list,
+        span: parser.span_from(start),
     }))
 }
 
@@ -33,6 +38,7 @@ pub(super) fn parse_program_statement(parser: &mut Parser) -> ParserResult<State
 ///  ;
 ///
 pub(super) fn parse_block_statement(parser: &mut Parser) -> ParserResult<StatementRef> {
+    let start = parser.lookahead.start;
     parser.eat_token(TokenType::OpeningBrace)?;
 
     let block = if !parser.is_next_token_of_type(TokenType::ClosingBrace) {
@@ -43,7 +49,10 @@ pub(super) fn parse_block_statement(parser: &mut Parser) -> ParserResult<Stateme
 
     parser.eat_token(TokenType::ClosingBrace)?;
 
-    Ok(Box::new(Statement::Block { body: block }))
+    Ok(Box::new(Statement::Block {
+        body: block,
+        span: parser.span_from(start),
+    }))
 }
 
 ///
@@ -78,7 +87,12 @@ pub(super) fn parse_statement_list(
 ///  | IterationStatement
 ///  | FunctionDeclarationStatement
 ///  | ReturnStatement
+///  | BreakStatement
+///  | ContinueStatement
+///  | LabeledStatement
 ///  | ClassDeclaration
+///  | InterfaceDeclaration
+///  | EnumDeclaration
 ///  ;
 ///
 pub(super) fn parse_statement(parser: &mut Parser) -> ParserResult<StatementRef> {
@@ -92,7 +106,11 @@ pub(super) fn parse_statement(parser: &mut Parser) -> ParserResult<StatementRef>
         TokenType::ForKeyword => parse_for_statement(parser),
         TokenType::DefKeyword => parse_function_declaration_statement(parser),
         TokenType::ReturnKeyword => parse_return_statement(parser),
+        TokenType::BreakKeyword => parse_break_statement(parser),
+        TokenType::ContinueKeyword => parse_continue_statement(parser),
         TokenType::ClassKeyword => parse_class_declaration(parser),
+        TokenType::InterfaceKeyword => parse_interface_declaration(parser),
+        TokenType::EnumKeyword => parse_enum_declaration(parser),
         _ => parse_expression_statement(parser, true),
     }
 }
@@ -1,25 +1,37 @@
 use crate::{
-    ast::{ExpressionRef, Statement, StatementRef},
+    ast::{
+        ClassMember, ClassMemberList, Expression, ExpressionList, ExpressionRef, Statement,
+        StatementRef, Type, CONSTRUCTOR_NAME,
+    },
     lexer::TokenType,
     parser::{
         parsers::{
             expression_parse_primary::parse_identifier_expression,
-            statement_parse_block::parse_block_statement,
+            statement_parse_function_declaration::parse_function_declaration_statement,
+            statement_parse_variable_declaration::parse_variable_expression,
+            type_parse_annotations::parse_type_parameters,
         },
-        ParserResult,
+        ParserError, ParserResult,
     },
     Parser,
 };
 
 ///
 /// ClassDeclaration
-///  : class IdentifierExpression [ClassExtendsExpression] BlockStatement
+///  : class IdentifierExpression [TypeParameters] [ClassExtendsExpression] [ClassImplementsList] '{' [ClassMemberList] '}'
+///  ;
+///
+/// ClassMemberList
+///  : ClassMember
+///  | ClassMemberList ClassMember
 ///  ;
 ///
 pub(super) fn parse_class_declaration(parser: &mut Parser) -> ParserResult<StatementRef> {
+    let start = parser.lookahead.start;
     parser.eat_token(TokenType::ClassKeyword)?;
 
     let name = parse_identifier_expression(parser)?;
+    let type_parameters = parse_type_parameters(parser)?;
 
     let super_class = if parser.is_next_token_of_type(TokenType::ExtendsKeyword) {
         let expression = parse_class_extends_expression(parser)?;
@@ -27,13 +39,26 @@ pub(super) fn parse_class_declaration(parser: &mut Parser) -> ParserResult<State
     } else {
         None
     };
+    let implements = if parser.is_next_token_of_type(TokenType::ImplementsKeyword) {
+        parse_class_implements_list(parser)?
+    } else {
+        vec![]
+    };
 
-    let body = parse_block_statement(parser)?;
+    parser.eat_token(TokenType::OpeningBrace)?;
+    let mut members = vec![];
+    while !parser.is_next_token_any_of_type(&[TokenType::ClosingBrace, TokenType::End]) {
+        members.extend(parse_class_member(parser)?);
+    }
+    parser.eat_token(TokenType::ClosingBrace)?;
 
     Ok(Box::new(Statement::ClassDeclaration {
         name,
+        type_parameters,
         super_class,
-        body,
+        implements,
+        members,
+        span: parser.span_from(start),
     }))
 }
 
@@ -46,3 +71,106 @@ fn parse_class_extends_expression(parser: &mut Parser) -> ParserResult<Expressio
     parser.eat_token(TokenType::ExtendsKeyword)?;
     parse_identifier_expression(parser)
 }
+
+///
+/// ClassImplementsList
+///  : implements IdentifierExpression ( ',' IdentifierExpression )*
+///  ;
+///
+fn parse_class_implements_list(parser: &mut Parser) -> ParserResult<ExpressionList> {
+    parser.eat_token(TokenType::ImplementsKeyword)?;
+    let mut interfaces = vec![*parse_identifier_expression(parser)?];
+    while parser.is_next_token_of_type(TokenType::Comma) {
+        parser.eat_token(TokenType::Comma)?;
+        interfaces.push(*parse_identifier_expression(parser)?);
+    }
+
+    Ok(interfaces)
+}
+
+///
+/// ClassMember
+///  : FieldDeclaration
+///  | FunctionDeclaration
+///  ;
+///
+/// A function declaration named 'constructor' is the constructor of the class
+///
+fn parse_class_member(parser: &mut Parser) -> ParserResult<ClassMemberList> {
+    match parser.lookahead.token_type {
+        TokenType::LetKeyword => parse_field_declaration(parser),
+        TokenType::DefKeyword => {
+            let declaration = parse_function_declaration_statement(parser)?;
+            let Statement::FunctionDeclaration {
+                name, return_type, ..
+            } = declaration.as_ref()
+            else {
+                unreachable!("a function declaration was parsed");
+            };
+
+            let is_constructor = matches!(name.as_ref(), Expression::Identifier { name, .. } if name == CONSTRUCTOR_NAME);
+            if !is_constructor {
+                return Ok(vec![ClassMember::Method { declaration }]);
+            }
+            if *return_type != Type::Void {
+                return Err(ParserError::TypeError {
+                    message: String::from("A constructor can not declare a return type"),
+                });
+            }
+            Ok(vec![ClassMember::Constructor { declaration }])
+        }
+        token_type => Err(ParserError::ParserError {
+            message: format!(
+                "Unexpected token: {}, expected a field or a method declaration",
+                token_type
+            ),
+        }),
+    }
+}
+
+///
+/// FieldDeclaration
+///  : 'let' VariableList ';'
+///  ;
+///
+fn parse_field_declaration(parser: &mut Parser) -> ParserResult<ClassMemberList> {
+    let mut fields = vec![];
+
+    parser.eat_token(TokenType::LetKeyword)?;
+    loop {
+        let Expression::Variable {
+            identifier,
+            type_annotation,
+            initializer,
+            span,
+        } = *parse_variable_expression(parser)?
+        else {
+            unreachable!("a variable expression was parsed");
+        };
+        if matches!(identifier.as_ref(), Expression::Destructuring { .. }) {
+            return Err(ParserError::ParserError {
+                message: String::from("Expected a field name, fields cannot be destructured"),
+            });
+        }
+        // Field types are needed before the initializers are checked
+        let Some(type_annotation) = type_annotation else {
+            return Err(ParserError::ParserError {
+                message: String::from("Expected a type annotation for the field"),
+            });
+        };
+        fields.push(ClassMember::Field {
+            name: identifier,
+            type_annotation,
+            initializer,
+            span,
+        });
+
+        if !parser.is_next_token_of_type(TokenType::Comma) {
+            break;
+        }
+        parser.eat_token(TokenType::Comma)?;
+    }
+    parser.eat_token(TokenType::StatementEnd)?;
+
+    Ok(fields)
+}
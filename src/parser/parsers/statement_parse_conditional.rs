@@ -9,6 +9,7 @@ use crate::parser::{Parser, ParserResult};
 ///  : if '(' Expression ')' Statement [else Statement]
 ///
 pub(super) fn parse_if_statement(parser: &mut Parser) -> ParserResult<StatementRef> {
+    let start = parser.lookahead.start;
     parser.eat_token(TokenType::IfKeyword)?;
 
     parser.eat_token(TokenType::OpeningParenthesis)?;
@@ -29,5 +30,6 @@ pub(super) fn parse_if_statement(parser: &mut Parser) -> ParserResult<StatementR
         condition,
         consequent,
         alternative,
+        span: parser.span_from(start),
     }))
 }
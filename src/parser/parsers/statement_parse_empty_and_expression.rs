@@ -1,6 +1,7 @@
-use crate::ast::{Statement, StatementRef};
+use crate::ast::{Expression, Statement, StatementRef};
 use crate::lexer::TokenType;
 use crate::parser::parsers::parse_root_expression;
+use crate::parser::parsers::statement_parse_loop::parse_labeled_statement;
 use crate::parser::{Parser, ParserResult};
 
 ///
@@ -9,9 +10,9 @@ use crate::parser::{Parser, ParserResult};
 ///  ;
 ///
 pub(super) fn parse_empty_statement(parser: &mut Parser) -> ParserResult<StatementRef> {
-    parser.eat_token(TokenType::StatementEnd)?;
+    let token = parser.eat_token(TokenType::StatementEnd)?;
 
-    Ok(Box::new(Statement::Empty))
+    Ok(Box::new(Statement::Empty { span: token.span() }))
 }
 
 ///
@@ -19,15 +20,32 @@ pub(super) fn parse_empty_statement(parser: &mut Parser) -> ParserResult<Stateme
 ///  : Expression ';'
 ///  ;
 ///
+/// A match ends with a closing brace like a block, the ';' after it is optional,
+/// an identifier followed by ':' is the label of a loop
+///
 pub(super) fn parse_expression_statement(
     parser: &mut Parser,
     consume_statement_end: bool,
 ) -> ParserResult<StatementRef> {
+    let start = parser.lookahead.start;
     let expression = parse_root_expression(parser)?;
 
-    if consume_statement_end {
+    if consume_statement_end
+        && matches!(expression.as_ref(), Expression::Identifier { .. })
+        && parser.is_next_token_of_type(TokenType::Colon)
+    {
+        return parse_labeled_statement(parser, expression);
+    }
+
+    let ends_with_brace = matches!(expression.as_ref(), Expression::Match { .. });
+    if consume_statement_end
+        && (!ends_with_brace || parser.is_next_token_of_type(TokenType::StatementEnd))
+    {
         parser.eat_token(TokenType::StatementEnd)?;
     }
 
-    Ok(Box::new(Statement::Expression { expression }))
+    Ok(Box::new(Statement::Expression {
+        expression,
+        span: parser.span_from(start),
+    }))
 }
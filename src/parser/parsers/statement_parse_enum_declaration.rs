@@ -0,0 +1,79 @@
+use crate::{
+    ast::{EnumVariant, Statement, StatementRef},
+    lexer::TokenType,
+    parser::{
+        parsers::{
+            expression_parse_primary::parse_identifier_expression,
+            type_parse_annotations::parse_type,
+        },
+        ParserResult,
+    },
+    Parser,
+};
+
+///
+/// EnumDeclaration
+///  : enum IdentifierExpression '{' [EnumVariantList] '}'
+///  ;
+///
+/// EnumVariantList
+///  : EnumVariant
+///  | EnumVariantList ',' EnumVariant
+///  ;
+///
+/// A trailing comma after the last variant is allowed
+///
+pub(super) fn parse_enum_declaration(parser: &mut Parser) -> ParserResult<StatementRef> {
+    let start = parser.lookahead.start;
+    parser.eat_token(TokenType::EnumKeyword)?;
+
+    let name = parse_identifier_expression(parser)?;
+
+    parser.eat_token(TokenType::OpeningBrace)?;
+    let mut variants = vec![];
+    while !parser.is_next_token_any_of_type(&[TokenType::ClosingBrace, TokenType::End]) {
+        variants.push(parse_enum_variant(parser)?);
+
+        if !parser.is_next_token_of_type(TokenType::Comma) {
+            break;
+        }
+        parser.eat_token(TokenType::Comma)?;
+    }
+    parser.eat_token(TokenType::ClosingBrace)?;
+
+    Ok(Box::new(Statement::EnumDeclaration {
+        name,
+        variants,
+        span: parser.span_from(start),
+    }))
+}
+
+///
+/// EnumVariant
+///  : IdentifierExpression ['(' Type ( ',' Type )* ')']
+///  ;
+///
+fn parse_enum_variant(parser: &mut Parser) -> ParserResult<EnumVariant> {
+    let start = parser.lookahead.start;
+    let name = parse_identifier_expression(parser)?;
+
+    let mut payload = vec![];
+    if parser.is_next_token_of_type(TokenType::OpeningParenthesis) {
+        parser.eat_token(TokenType::OpeningParenthesis)?;
+        loop {
+            payload.push(parse_type(parser)?);
+
+            if !parser.is_next_token_of_type(TokenType::Comma) {
+                break;
+            }
+            parser.eat_token(TokenType::Comma)?;
+        }
+        parser.eat_token(TokenType::ClosingParenthesis)?;
+    }
+
+    Ok(EnumVariant {
+        name,
+        payload,
+        span: parser.span_from(start),
+    })
+}
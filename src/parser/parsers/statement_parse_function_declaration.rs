@@ -3,19 +3,22 @@ use crate::lexer::TokenType;
 use crate::parser::parsers::expression_parse_primary::parse_identifier_expression;
 use crate::parser::parsers::parse_root_expression;
 use crate::parser::parsers::statement_parse_block::parse_block_statement;
+use crate::parser::parsers::statement_parse_variable_declaration::parse_declared_name;
 use crate::parser::{Parser, ParserResult};
 
-use super::type_parse_annotations::parse_type;
+use super::type_parse_annotations::{parse_type, parse_type_parameters};
 
 ///
 /// FunctionDeclaration
-///  : def '(' [FormalParameterList] ')' [':' Type] BlockStatement
+///  : def IdentifierExpression [TypeParameters] '(' [FormalParameterList] ')' [':' Type] BlockStatement
 ///
 pub(super) fn parse_function_declaration_statement(
     parser: &mut Parser,
 ) -> ParserResult<StatementRef> {
+    let start = parser.lookahead.start;
     parser.eat_token(TokenType::DefKeyword)?;
     let name = parse_identifier_expression(parser)?;
+    let type_parameters = parse_type_parameters(parser)?;
 
     parser.eat_token(TokenType::OpeningParenthesis)?;
     let parameters = if parser.is_next_token_of_type(TokenType::ClosingParenthesis) {
@@ -37,16 +40,18 @@ pub(super) fn parse_function_declaration_statement(
 
     Ok(Box::new(Statement::FunctionDeclaration {
         name,
+        type_parameters,
         parameters,
         return_type,
         body,
+        span: parser.span_from(start),
     }))
 }
 
 ///
 /// FormalParameterList
-///  : IdentifierExpression ':' Type
-///  | FormalParameterList ',' IdentifierExpression ':' Type
+///  : DeclaredName ':' Type
+///  | FormalParameterList ',' DeclaredName ':' Type
 ///  ;
 ///
 pub(super) fn parse_formal_parameter_list_expression(
@@ -55,7 +60,7 @@ pub(super) fn parse_formal_parameter_list_expression(
     let mut parameters = vec![];
 
     // Parse first parameter
-    let param_name = parse_identifier_expression(parser)?;
+    let param_name = parse_declared_name(parser)?;
     parser.eat_token(TokenType::Colon)?;
     let param_type = parse_type(parser)?;
     parameters.push((*param_name, param_type));
@@ -63,7 +68,7 @@ pub(super) fn parse_formal_parameter_list_expression(
     // Parse additional parameters if any
     while parser.is_next_token_of_type(TokenType::Comma) {
         parser.eat_token(TokenType::Comma)?;
-        let param_name = parse_identifier_expression(parser)?;
+        let param_name = parse_declared_name(parser)?;
         parser.eat_token(TokenType::Colon)?;
         let param_type = parse_type(parser)?;
         parameters.push((*param_name, param_type));
@@ -77,6 +82,7 @@ pub(super) fn parse_formal_parameter_list_expression(
 ///  : return [Expression] 'l'
 ///
 pub(super) fn parse_return_statement(parser: &mut Parser) -> ParserResult<StatementRef> {
+    let start = parser.lookahead.start;
     parser.eat_token(TokenType::ReturnKeyword)?;
     let argument = if parser.is_next_token_of_type(TokenType::StatementEnd) {
         None
@@ -86,5 +92,8 @@ pub(super) fn parse_return_statement(parser: &mut Parser) -> ParserResult<Statem
     };
     parser.eat_token(TokenType::StatementEnd)?;
 
-    Ok(Box::new(Statement::Return { argument }))
+    Ok(Box::new(Statement::Return {
+        argument,
+        span: parser.span_from(start),
+    }))
 }
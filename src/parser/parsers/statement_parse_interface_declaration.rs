@@ -0,0 +1,101 @@
+use crate::{
+    ast::{InterfaceMember, Statement, StatementRef, Type},
+    lexer::TokenType,
+    parser::{
+        parsers::{
+            expression_parse_primary::parse_identifier_expression,
+            statement_parse_function_declaration::parse_formal_parameter_list_expression,
+            type_parse_annotations::parse_type,
+        },
+        ParserError, ParserResult,
+    },
+    Parser,
+};
+
+///
+/// InterfaceDeclaration
+///  : interface IdentifierExpression '{' [InterfaceMemberList] '}'
+///  ;
+///
+/// InterfaceMemberList
+///  : InterfaceMember
+///  | InterfaceMemberList InterfaceMember
+///  ;
+///
+pub(super) fn parse_interface_declaration(parser: &mut Parser) -> ParserResult<StatementRef> {
+    let start = parser.lookahead.start;
+    parser.eat_token(TokenType::InterfaceKeyword)?;
+
+    let name = parse_identifier_expression(parser)?;
+
+    parser.eat_token(TokenType::OpeningBrace)?;
+    let mut members = vec![];
+    while !parser.is_next_token_any_of_type(&[TokenType::ClosingBrace, TokenType::End]) {
+        members.push(parse_interface_member(parser)?);
+    }
+    parser.eat_token(TokenType::ClosingBrace)?;
+
+    Ok(Box::new(Statement::InterfaceDeclaration {
+        name,
+        members,
+        span: parser.span_from(start),
+    }))
+}
+
+///
+/// InterfaceMember
+///  : 'let' IdentifierExpression ':' Type ';'
+///  | def IdentifierExpression '(' [FormalParameterList] ')' [':' Type] ';'
+///  ;
+///
+fn parse_interface_member(parser: &mut Parser) -> ParserResult<InterfaceMember> {
+    let start = parser.lookahead.start;
+    match parser.lookahead.token_type {
+        TokenType::LetKeyword => {
+            parser.eat_token(TokenType::LetKeyword)?;
+            let name = parse_identifier_expression(parser)?;
+            parser.eat_token(TokenType::Colon)?;
+            let type_annotation = parse_type(parser)?;
+            parser.eat_token(TokenType::StatementEnd)?;
+
+            Ok(InterfaceMember::Field {
+                name,
+                type_annotation,
+                span: parser.span_from(start),
+            })
+        }
+        TokenType::DefKeyword => {
+            parser.eat_token(TokenType::DefKeyword)?;
+            let name = parse_identifier_expression(parser)?;
+
+            parser.eat_token(TokenType::OpeningParenthesis)?;
+            let parameters = if parser.is_next_token_of_type(TokenType::ClosingParenthesis) {
+                vec![]
+            } else {
+                parse_formal_parameter_list_expression(parser)?
+            };
+            parser.eat_token(TokenType::ClosingParenthesis)?;
+
+            let return_type = if parser.is_next_token_of_type(TokenType::Colon) {
+                parser.eat_token(TokenType::Colon)?;
+                parse_type(parser)?
+            } else {
+                Type::Void
+            };
+            parser.eat_token(TokenType::StatementEnd)?;
+
+            Ok(InterfaceMember::Method {
+                name,
+                parameters,
+                return_type,
+                span: parser.span_from(start),
+            })
+        }
+        token_type => Err(ParserError::ParserError {
+            message: format!(
+                "Unexpected token: {}, expected a field or a method signature",
+                token_type
+            ),
+        }),
+    }
+}
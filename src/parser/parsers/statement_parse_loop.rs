@@ -1,10 +1,11 @@
-use crate::ast::{Statement, StatementRef};
+use crate::ast::{Expression, ExpressionRef, Statement, StatementRef};
 use crate::lexer::TokenType;
+use crate::parser::parsers::expression_parse_primary::parse_identifier_expression;
 use crate::parser::parsers::parse_root_expression;
 use crate::parser::parsers::statement_parse_block::parse_statement;
 use crate::parser::parsers::statement_parse_empty_and_expression::parse_expression_statement;
 use crate::parser::parsers::statement_parse_variable_declaration::parse_variable_declaration_statement;
-use crate::parser::{Parser, ParserResult};
+use crate::parser::{Parser, ParserError, ParserResult};
 
 ///
 /// WhileStatement
@@ -12,6 +13,7 @@ use crate::parser::{Parser, ParserResult};
 ///  ;
 ///
 pub(super) fn parse_while_statement(parser: &mut Parser) -> ParserResult<StatementRef> {
+    let start = parser.lookahead.start;
     parser.eat_token(TokenType::WhileKeyword)?;
 
     parser.eat_token(TokenType::OpeningParenthesis)?;
@@ -20,7 +22,11 @@ pub(super) fn parse_while_statement(parser: &mut Parser) -> ParserResult<Stateme
 
     let body = parse_statement(parser)?;
 
-    Ok(Box::new(Statement::While { condition, body }))
+    Ok(Box::new(Statement::While {
+        condition,
+        body,
+        span: parser.span_from(start),
+    }))
 }
 
 ///
@@ -29,6 +35,7 @@ pub(super) fn parse_while_statement(parser: &mut Parser) -> ParserResult<Stateme
 ///  ;
 ///
 pub(super) fn parse_do_while_statement(parser: &mut Parser) -> ParserResult<StatementRef> {
+    let start = parser.lookahead.start;
     parser.eat_token(TokenType::DoKeyword)?;
 
     let body = parse_statement(parser)?;
@@ -41,7 +48,11 @@ pub(super) fn parse_do_while_statement(parser: &mut Parser) -> ParserResult<Stat
 
     parser.eat_token(TokenType::StatementEnd)?;
 
-    Ok(Box::new(Statement::DoWhile { body, condition }))
+    Ok(Box::new(Statement::DoWhile {
+        body,
+        condition,
+        span: parser.span_from(start),
+    }))
 }
 
 ///
@@ -50,6 +61,7 @@ pub(super) fn parse_do_while_statement(parser: &mut Parser) -> ParserResult<Stat
 ///  ;
 ///
 pub(super) fn parse_for_statement(parser: &mut Parser) -> ParserResult<StatementRef> {
+    let start = parser.lookahead.start;
     parser.eat_token(TokenType::ForKeyword)?;
     parser.eat_token(TokenType::OpeningParenthesis)?;
 
@@ -84,6 +96,7 @@ pub(super) fn parse_for_statement(parser: &mut Parser) -> ParserResult<Statement
         condition,
         increment,
         body,
+        span: parser.span_from(start),
     }))
 }
 
@@ -102,3 +115,80 @@ pub(super) fn parse_for_statement_init_statement(
 
     parse_expression_statement(parser, false)
 }
+
+///
+/// LabeledStatement
+///  : Identifier ':' IterationStatement
+///  ;
+///
+/// The label is parsed as an expression before the ':' tells it apart
+///
+pub(super) fn parse_labeled_statement(
+    parser: &mut Parser,
+    label: ExpressionRef,
+) -> ParserResult<StatementRef> {
+    let start = label.span().start;
+    parser.eat_token(TokenType::Colon)?;
+
+    let body = match parser.lookahead.token_type {
+        TokenType::WhileKeyword => parse_while_statement(parser)?,
+        TokenType::DoKeyword => parse_do_while_statement(parser)?,
+        TokenType::ForKeyword => parse_for_statement(parser)?,
+        _ => {
+            let Expression::Identifier { name, .. } = label.as_ref() else {
+                unreachable!("labels are identifiers");
+            };
+            return Err(ParserError::ParserError {
+                message: format!("Expected a loop after the label '{}'", name),
+            });
+        }
+    };
+
+    Ok(Box::new(Statement::Labeled {
+        label,
+        body,
+        span: parser.span_from(start),
+    }))
+}
+
+///
+/// BreakStatement
+///  : break [Identifier] ';'
+///  ;
+///
+pub(super) fn parse_break_statement(parser: &mut Parser) -> ParserResult<StatementRef> {
+    let start = parser.lookahead.start;
+    parser.eat_token(TokenType::BreakKeyword)?;
+    let label = parse_jump_label(parser)?;
+    parser.eat_token(TokenType::StatementEnd)?;
+
+    Ok(Box::new(Statement::Break {
+        label,
+        span: parser.span_from(start),
+    }))
+}
+
+///
+/// ContinueStatement
+///  : continue [Identifier] ';'
+///  ;
+///
+pub(super) fn parse_continue_statement(parser: &mut Parser) -> ParserResult<StatementRef> {
+    let start = parser.lookahead.start;
+    parser.eat_token(TokenType::ContinueKeyword)?;
+    let label = parse_jump_label(parser)?;
+    parser.eat_token(TokenType::StatementEnd)?;
+
+    Ok(Box::new(Statement::Continue {
+        label,
+        span: parser.span_from(start),
+    }))
+}
+
+fn parse_jump_label(parser: &mut Parser) -> ParserResult<Option<ExpressionRef>> {
+    if !parser.is_next_token_of_type(TokenType::Identifier) {
+        return Ok(None);
+    }
+
+    Ok(Some(parse_identifier_expression(parser)?))
+}
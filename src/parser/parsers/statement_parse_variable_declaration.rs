@@ -1,9 +1,10 @@
 use super::expression_parse_assignment::parse_assignment_expression;
 use super::expression_parse_primary::parse_identifier_expression;
+use super::pattern_parse_patterns::parse_destructuring_expression;
 use super::type_parse_annotations::parse_type;
 use crate::ast::{Expression, ExpressionRef, Statement, StatementRef};
 use crate::lexer::TokenType;
-use crate::parser::{Parser, ParserResult};
+use crate::parser::{Parser, ParserError, ParserResult};
 
 ///
 /// VariableDeclarationStatement
@@ -20,6 +21,7 @@ pub(super) fn parse_variable_declaration_statement(
 ) -> ParserResult<StatementRef> {
     let mut variables: Vec<Expression> = vec![];
 
+    let start = parser.lookahead.start;
     parser.eat_token(TokenType::LetKeyword)?;
     loop {
         let variable_expression = parse_variable_expression(parser)?;
@@ -35,20 +37,30 @@ pub(super) fn parse_variable_declaration_statement(
         parser.eat_token(TokenType::StatementEnd)?;
     }
 
-    Ok(Box::new(Statement::VariableDeclaration { variables }))
+    Ok(Box::new(Statement::VariableDeclaration {
+        variables,
+        span: parser.span_from(start),
+    }))
 }
 
 ///
 /// VariableInitializationExpression
-///  : Identifier ':' Type ['=' Initializer]
+///  : Identifier [':' Type] ['=' Initializer]
+///  | DestructuringExpression [':' Type] '=' Initializer
 ///  ;
 ///
+/// Variables without a type annotation take the type of their initializer
+///
 pub(super) fn parse_variable_expression(parser: &mut Parser) -> ParserResult<ExpressionRef> {
-    let identifier = parse_identifier_expression(parser)?;
+    let start = parser.lookahead.start;
+    let identifier = parse_declared_name(parser)?;
 
-    // Require type annotation
-    parser.eat_token(TokenType::Colon)?;
-    let type_annotation = parse_type(parser)?;
+    let type_annotation = if parser.is_next_token_of_type(TokenType::Colon) {
+        parser.eat_token(TokenType::Colon)?;
+        Some(parse_type(parser)?)
+    } else {
+        None
+    };
 
     let initializer: Option<ExpressionRef> =
         if parser.is_next_token_any_of_type(&[TokenType::StatementEnd, TokenType::Comma]) {
@@ -58,10 +70,31 @@ pub(super) fn parse_variable_expression(parser: &mut Parser) -> ParserResult<Exp
             let initializer = parse_assignment_expression(parser)?;
             Some(initializer)
         };
+    if initializer.is_none() && matches!(identifier.as_ref(), Expression::Destructuring { .. }) {
+        return Err(ParserError::ParserError {
+            message: String::from("Expected an initializer for the destructured variables"),
+        });
+    }
 
     Ok(Box::new(Expression::Variable {
         identifier,
         type_annotation,
         initializer,
+        span: parser.span_from(start),
     }))
 }
+
+///
+/// DeclaredName
+///  : IdentifierExpression
+///  | DestructuringExpression
+///  ;
+///
+pub(super) fn parse_declared_name(parser: &mut Parser) -> ParserResult<ExpressionRef> {
+    match parser.lookahead.token_type {
+        TokenType::OpeningBracket | TokenType::OpeningBrace => {
+            parse_destructuring_expression(parser)
+        }
+        _ => parse_identifier_expression(parser),
+    }
+}
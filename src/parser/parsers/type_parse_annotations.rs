@@ -1,11 +1,49 @@
 use crate::{
-    ast::{PrimitiveType, Type},
+    ast::{PrimitiveType, Type, TypeParameter, TypeParameterList},
     lexer::TokenType,
-    parser::{ParserError, ParserResult},
+    parser::{
+        parsers::expression_parse_primary::parse_identifier_expression, ParserError, ParserResult,
+    },
     Parser,
 };
 
+///
+/// Type
+///  : NullableType ( '|' NullableType )*
+///  ;
+///
 pub(super) fn parse_type(parser: &mut Parser) -> ParserResult<Type> {
+    let first_type = parse_nullable_type(parser)?;
+    if !parser.is_next_token_of_type(TokenType::BitwiseOrOperator) {
+        return Ok(first_type);
+    }
+
+    let mut members = vec![first_type];
+    while parser.is_next_token_of_type(TokenType::BitwiseOrOperator) {
+        parser.eat_token(TokenType::BitwiseOrOperator)?;
+        members.push(parse_nullable_type(parser)?);
+    }
+
+    Ok(Type::union(members))
+}
+
+///
+/// NullableType
+///  : BaseType ['?']
+///  ;
+///
+fn parse_nullable_type(parser: &mut Parser) -> ParserResult<Type> {
+    let base_type = parse_base_type(parser)?;
+
+    if parser.is_next_token_of_type(TokenType::QuestionMark) {
+        parser.eat_token(TokenType::QuestionMark)?;
+        return Ok(Type::Nullable(Box::new(base_type)));
+    }
+
+    Ok(base_type)
+}
+
+fn parse_base_type(parser: &mut Parser) -> ParserResult<Type> {
     match parser.lookahead.token_type {
         TokenType::NumberTypeKeyword => {
             parser.eat_token(TokenType::NumberTypeKeyword)?;
@@ -23,32 +61,20 @@ pub(super) fn parse_type(parser: &mut Parser) -> ParserResult<Type> {
             parser.eat_token(TokenType::VoidTypeKeyword)?;
             Ok(Type::Void)
         }
+        TokenType::Nil => {
+            parser.eat_token(TokenType::Nil)?;
+            Ok(Type::Nil)
+        }
         TokenType::Identifier => {
             // Handle class types or custom types
             let identifier_token = parser.eat_token(TokenType::Identifier)?;
             let type_name = identifier_token.text(parser.source);
 
-            // Check for generic type parameters
+            // Check for generic type arguments
             if parser.is_next_token_of_type(TokenType::OpeningBracket) {
-                parser.eat_token(TokenType::OpeningBracket)?;
-                let mut type_args = vec![];
-
-                loop {
-                    let type_arg = parse_type(parser)?;
-                    type_args.push(type_arg);
-
-                    if !parser.is_next_token_of_type(TokenType::Comma) {
-                        break;
-                    }
-
-                    parser.eat_token(TokenType::Comma)?;
-                }
-
-                parser.eat_token(TokenType::ClosingBracket)?;
-
                 Ok(Type::Generic {
                     base: String::from(type_name),
-                    type_args,
+                    type_args: parse_type_arguments(parser)?,
                 })
             } else {
                 Ok(Type::Class {
@@ -73,3 +99,66 @@ pub(super) fn parse_type(parser: &mut Parser) -> ParserResult<Type> {
         }),
     }
 }
+
+///
+/// TypeArguments
+///  : '[' Type ( ',' Type )* ']'
+///  ;
+///
+fn parse_type_arguments(parser: &mut Parser) -> ParserResult<Vec<Type>> {
+    parser.eat_token(TokenType::OpeningBracket)?;
+    let mut type_args = vec![];
+
+    loop {
+        let type_arg = parse_type(parser)?;
+        type_args.push(type_arg);
+
+        if !parser.is_next_token_of_type(TokenType::Comma) {
+            break;
+        }
+
+        parser.eat_token(TokenType::Comma)?;
+    }
+
+    parser.eat_token(TokenType::ClosingBracket)?;
+    Ok(type_args)
+}
+
+///
+/// TypeParameters
+///  : '[' TypeParameter ( ',' TypeParameter )* ']'
+///  ;
+///
+/// TypeParameter
+///  : IdentifierExpression [extends Type]
+///  ;
+///
+/// Declarations without type parameters get an empty list
+///
+pub(super) fn parse_type_parameters(parser: &mut Parser) -> ParserResult<TypeParameterList> {
+    let mut type_parameters = vec![];
+    if !parser.is_next_token_of_type(TokenType::OpeningBracket) {
+        return Ok(type_parameters);
+    }
+
+    parser.eat_token(TokenType::OpeningBracket)?;
+    loop {
+        let name = parse_identifier_expression(parser)?;
+        let bound = if parser.is_next_token_of_type(TokenType::ExtendsKeyword) {
+            parser.eat_token(TokenType::ExtendsKeyword)?;
+            Some(parse_type(parser)?)
+        } else {
+            None
+        };
+        type_parameters.push(TypeParameter { name, bound });
+
+        if !parser.is_next_token_of_type(TokenType::Comma) {
+            break;
+        }
+
+        parser.eat_token(TokenType::Comma)?;
+    }
+    parser.eat_token(TokenType::ClosingBracket)?;
+
+    Ok(type_parameters)
+}
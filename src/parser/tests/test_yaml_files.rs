@@ -20,6 +20,11 @@ fn test_expression_parse_literals() {
     execute_yaml_test_cases("tests/parser/test_expression_parse_literals.yaml");
 }
 
+#[test]
+fn test_expression_parse_match() {
+    execute_yaml_test_cases("tests/parser/test_expression_parse_match.yaml");
+}
+
 #[test]
 fn test_expression_parse_primary() {
     execute_yaml_test_cases("tests/parser/test_expression_parse_primary.yaml");
@@ -50,6 +55,11 @@ fn test_statement_parse_conditional_if() {
     execute_yaml_test_cases("tests/parser/test_statement_parse_conditional_if.yaml");
 }
 
+#[test]
+fn test_statement_parse_enum_declaration() {
+    execute_yaml_test_cases("tests/parser/test_statement_parse_enum_declaration.yaml");
+}
+
 #[test]
 fn test_statement_parse_function_declaration() {
     execute_yaml_test_cases("tests/parser/test_statement_parse_function_declaration.yaml");
@@ -65,6 +75,11 @@ fn test_statement_parse_loop_for() {
     execute_yaml_test_cases("tests/parser/test_statement_parse_loop_for.yaml");
 }
 
+#[test]
+fn test_statement_parse_loop_jumps() {
+    execute_yaml_test_cases("tests/parser/test_statement_parse_loop_jumps.yaml");
+}
+
 #[test]
 fn test_statement_parse_loop_while() {
     execute_yaml_test_cases("tests/parser/test_statement_parse_loop_while.yaml");
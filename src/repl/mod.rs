@@ -0,0 +1,3 @@
+mod repl;
+
+pub(crate) use self::repl::*;
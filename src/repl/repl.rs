@@ -0,0 +1,286 @@
+use std::io::{self, BufRead, Write};
+
+use crate::{
+    analysis::analyze_program,
+    ast::{Statement, StatementRef},
+    checker::TypeChecker,
+    diagnostics::{render_diagnostic, Diagnostic},
+    driver::{
+        format_token, is_incomplete_source, parse_expression_source, parse_source, tokenize_source,
+    },
+    interpreter::{Interpreter, Value},
+    optimizer::fold_constants,
+};
+
+pub const PROMPT: &str = "> ";
+pub const CONTINUATION_PROMPT: &str = "... ";
+
+///
+/// Name shown in diagnostics for REPL input
+///
+const REPL_PATH: &str = "<repl>";
+
+const REPL_HELP: &str = "\
+Commands:
+  :ast <source>       Print the syntax tree of the source
+  :tokens <source>    Print the tokens of the source
+  :type <expression>  Print the type of the expression
+  :help               Print this help
+  :quit               Leave the REPL
+
+Input with open brackets continues on the next line, an empty line submits it as is.";
+
+///
+/// Read-eval-print loop, declarations are kept between inputs
+///
+pub struct Repl<'a> {
+    checker: TypeChecker,
+    interpreter: Interpreter<'a>,
+    ///
+    /// Source of the accepted inputs, the analyses see them as one program
+    ///
+    history: String,
+}
+
+///
+/// What the REPL should do after an input
+///
+enum ReplAction {
+    Continue,
+    Quit,
+}
+
+impl<'a> Repl<'a> {
+    pub fn new(output: &'a mut dyn Write) -> Self {
+        Repl {
+            checker: TypeChecker::new(),
+            interpreter: Interpreter::new(output),
+            history: String::new(),
+        }
+    }
+
+    ///
+    /// Evaluate inputs until the end of the input or :quit, errors are
+    /// reported and the REPL keeps going
+    ///
+    pub fn run(&mut self, input: &mut dyn BufRead, errors: &mut dyn Write) -> io::Result<()> {
+        while let Some(entry) = self.read_entry(input)? {
+            let (result, source) = match entry.trim().strip_prefix(':') {
+                Some(command) => {
+                    let (name, source) = command
+                        .split_once(char::is_whitespace)
+                        .unwrap_or((command, ""));
+                    let source = source.trim();
+                    (self.run_meta_command(name, source), source)
+                }
+                None => (self.evaluate(&entry), entry.as_str()),
+            };
+
+            match result {
+                Ok(ReplAction::Continue) => {}
+                Ok(ReplAction::Quit) => break,
+                Err(diagnostic) => {
+                    write!(
+                        errors,
+                        "{}",
+                        render_diagnostic(&diagnostic, source, REPL_PATH)
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Read lines until they form a complete input, None at the end of the input
+    ///
+    fn read_entry(&mut self, input: &mut dyn BufRead) -> io::Result<Option<String>> {
+        let mut entry = String::new();
+
+        loop {
+            let prompt = if entry.is_empty() {
+                PROMPT
+            } else {
+                CONTINUATION_PROMPT
+            };
+            write!(self.interpreter.output(), "{}", prompt)?;
+            self.interpreter.output().flush()?;
+
+            let mut line = String::new();
+            if input.read_line(&mut line)? == 0 {
+                writeln!(self.interpreter.output())?;
+                return Ok((!entry.trim().is_empty()).then_some(entry));
+            }
+
+            if entry.is_empty() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                if line.trim_start().starts_with(':') {
+                    return Ok(Some(line));
+                }
+            } else if line.trim().is_empty() {
+                return Ok(Some(entry));
+            }
+
+            entry.push_str(&line);
+            if !needs_more_input(&entry) {
+                return Ok(Some(entry));
+            }
+        }
+    }
+
+    ///
+    /// Check, analyze and fold the input like the run command, then run it
+    ///
+    fn evaluate(&mut self, entry: &str) -> Result<ReplAction, Diagnostic> {
+        let (program, source) = parse_entry(entry)?;
+
+        // Declarations only become visible once the whole input checks, the
+        // interpreter keeps what runs before a runtime error so the checker
+        // keeps them too
+        let mut checker = self.checker.clone();
+        checker
+            .check_program(&program)
+            .map_err(|error| Diagnostic::from_checker_error(&error))?;
+        self.analyze(&source)?;
+        let program =
+            fold_constants(&program).map_err(|error| Diagnostic::from_optimizer_error(&error))?;
+        self.checker = checker;
+        self.history.push_str(&source);
+        self.history.push('\n');
+
+        let value = self
+            .interpreter
+            .execute(&program)
+            .map_err(|error| Diagnostic::from_interpreter_error(&error))?;
+
+        let ends_with_expression = matches!(
+            &*program,
+            Statement::Program { body, .. } if matches!(body.last(), Some(Statement::Expression { .. }))
+        );
+        if ends_with_expression && !matches!(value, Value::Nil) {
+            self.print(&value.to_string())?;
+        }
+
+        Ok(ReplAction::Continue)
+    }
+
+    ///
+    /// Run the data flow analyses over the earlier inputs followed by this
+    /// one, the first error of this input is reported
+    ///
+    fn analyze(&self, source: &str) -> Result<(), Diagnostic> {
+        let Ok(program) = parse_source(&format!("{}{}", self.history, source)) else {
+            return Ok(());
+        };
+        let offset = self.history.len();
+        let lines = self.history.matches('\n').count();
+
+        let error = analyze_program(&program).into_iter().find(|diagnostic| {
+            diagnostic.is_error()
+                && diagnostic
+                    .span
+                    .is_some_and(|span| span.start.offset >= offset)
+        });
+        match error {
+            Some(mut error) => {
+                // Positions become relative to the input again
+                if let Some(span) = &mut error.span {
+                    for position in [&mut span.start, &mut span.end] {
+                        position.offset -= offset;
+                        position.line -= lines;
+                    }
+                }
+                Err(error)
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn run_meta_command(&mut self, name: &str, source: &str) -> Result<ReplAction, Diagnostic> {
+        match name {
+            "ast" => {
+                let (program, _) = parse_entry(required_source(name, source)?)?;
+                let output = serde_yaml::to_string(&program).map_err(output_error)?;
+                self.print(output.trim_end())?;
+            }
+            "tokens" => {
+                let source = required_source(name, source)?;
+                for token in tokenize_source(source)? {
+                    self.print(&format_token(&token, source))?;
+                }
+            }
+            "type" => {
+                let expression = parse_expression_source(required_source(name, source)?)?;
+                let expression_type = self
+                    .checker
+                    .clone()
+                    .infer_expression(&expression)
+                    .map_err(|error| Diagnostic::from_checker_error(&error))?;
+                self.print(&expression_type.to_string())?;
+            }
+            "help" => self.print(REPL_HELP)?,
+            "quit" => return Ok(ReplAction::Quit),
+            _ => {
+                return Err(Diagnostic::error(
+                    format!("Unknown command ':{}', try :help", name),
+                    None,
+                ))
+            }
+        }
+
+        Ok(ReplAction::Continue)
+    }
+
+    fn print(&mut self, text: &str) -> Result<(), Diagnostic> {
+        writeln!(self.interpreter.output(), "{}", text).map_err(output_error)
+    }
+}
+
+///
+/// Input is complete once the brackets are closed and it parses, possibly
+/// after adding the missing statement end
+///
+fn needs_more_input(source: &str) -> bool {
+    is_incomplete_source(source) && parse_source(&with_statement_end(source)).is_err()
+}
+
+///
+/// Parse an input, the statement end of the last statement is optional and
+/// the parsed source is returned with the program
+///
+fn parse_entry(entry: &str) -> Result<(StatementRef, String), Diagnostic> {
+    match parse_source(entry) {
+        Ok(program) => Ok((program, String::from(entry))),
+        Err(diagnostic) => {
+            let source = with_statement_end(entry);
+            match parse_source(&source) {
+                Ok(program) => Ok((program, source)),
+                Err(_) => Err(diagnostic),
+            }
+        }
+    }
+}
+
+fn with_statement_end(source: &str) -> String {
+    format!("{};", source.trim_end())
+}
+
+fn required_source<'s>(command: &str, source: &'s str) -> Result<&'s str, Diagnostic> {
+    if source.is_empty() {
+        return Err(Diagnostic::error(
+            format!("Missing source after ':{}'", command),
+            None,
+        ));
+    }
+    Ok(source)
+}
+
+fn output_error(error: impl ToString) -> Diagnostic {
+    Diagnostic::error(
+        format!("Could not write the output: {}", error.to_string()),
+        None,
+    )
+}
@@ -0,0 +1,6 @@
+mod resolver;
+mod resolver_symbol;
+mod tests;
+
+pub(crate) use self::resolver::*;
+pub(crate) use self::resolver_symbol::*;
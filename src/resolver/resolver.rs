@@ -0,0 +1,721 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+
+use crate::{
+    ast::{
+        AstVisitor, ClassMember, ClassMemberList, EnumVariantList, Expression, ExpressionRef,
+        ParameterList, Pattern, Statement, StatementList, StatementRef, Type, TypeParameterList,
+    },
+    lexer::Span,
+};
+
+use super::{Reference, Resolution, Symbol, SymbolKind};
+
+///
+/// Name resolution, links every identifier to its declaration
+///
+/// Names that cannot be resolved are skipped, reporting them is left to the checker
+///
+pub struct Resolver {
+    scopes: Vec<HashMap<String, usize>>,
+    classes: HashMap<String, ClassMembers>,
+    enums: HashMap<String, HashMap<String, usize>>,
+    current_class: Option<String>,
+    resolution: Resolution,
+}
+
+///
+/// Member symbols of a class declaration
+///
+struct ClassMembers {
+    super_class: Option<String>,
+    members: HashMap<String, usize>,
+}
+
+///
+/// Resolve the names of a program
+///
+pub fn resolve_program(program: &Statement) -> Resolution {
+    let mut resolver = Resolver::new();
+    // The resolver never fails, a partial resolution is still useful
+    let _ = resolver.visit_statement(program);
+    resolver.resolution
+}
+
+impl Resolver {
+    pub fn new() -> Self {
+        Resolver {
+            scopes: vec![HashMap::new()],
+            classes: HashMap::new(),
+            enums: HashMap::new(),
+            current_class: None,
+            resolution: Resolution::default(),
+        }
+    }
+
+    fn add_symbol(&mut self, name: &str, kind: SymbolKind, span: Span, detail: String) -> usize {
+        self.resolution.symbols.push(Symbol {
+            name: String::from(name),
+            kind,
+            span,
+            detail,
+        });
+        self.resolution.symbols.len() - 1
+    }
+
+    fn declare(&mut self, name: &str, kind: SymbolKind, span: Span, detail: String) {
+        let symbol = self.add_symbol(name, kind, span, detail);
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(String::from(name), symbol);
+        }
+    }
+
+    ///
+    /// Whether the declaration at the span was already hoisted into the current scope
+    ///
+    fn is_hoisted(&self, name: &str, span: Span) -> bool {
+        self.scopes
+            .last()
+            .and_then(|scope| scope.get(name))
+            .is_some_and(|symbol| self.resolution.symbols[*symbol].span == span)
+    }
+
+    fn add_reference(&mut self, span: Span, symbol: usize) {
+        self.resolution.references.push(Reference { span, symbol });
+    }
+
+    fn lookup(&self, name: &str) -> Option<usize> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name).copied())
+    }
+
+    fn find_member(&self, class_name: &str, member_name: &str) -> Option<usize> {
+        let mut visited = HashSet::new();
+        let mut current = Some(class_name.to_string());
+
+        while let Some(name) = current {
+            if !visited.insert(name.clone()) {
+                return None;
+            }
+            let class_members = self.classes.get(&name)?;
+            if let Some(symbol) = class_members.members.get(member_name) {
+                return Some(*symbol);
+            }
+            current = class_members.super_class.clone();
+        }
+
+        None
+    }
+
+    fn resolve_statement_list(&mut self, statements: &StatementList) -> Result<()> {
+        self.hoist_declarations(statements);
+
+        for statement in statements {
+            self.visit_statement(statement)?;
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Functions, classes, interfaces and enums are visible to the whole
+    /// statement list
+    ///
+    fn hoist_declarations(&mut self, statements: &StatementList) {
+        for statement in statements {
+            match statement {
+                Statement::FunctionDeclaration {
+                    name,
+                    type_parameters,
+                    parameters,
+                    return_type,
+                    ..
+                } => {
+                    let Some((function_name, span)) = identifier_name(name) else {
+                        continue;
+                    };
+                    let detail =
+                        function_detail(function_name, type_parameters, parameters, return_type);
+                    self.declare(function_name, SymbolKind::Function, span, detail);
+                }
+                Statement::ClassDeclaration {
+                    name,
+                    type_parameters,
+                    super_class,
+                    implements,
+                    members,
+                    ..
+                } => {
+                    let Some((class_name, span)) = identifier_name(name) else {
+                        continue;
+                    };
+                    let super_class = super_class
+                        .as_ref()
+                        .and_then(|super_class| identifier_name(super_class));
+                    let type_parameters = type_parameters_detail(type_parameters);
+                    let mut detail = match super_class {
+                        Some((super_class, _)) => format!(
+                            "class {}{} extends {}",
+                            class_name, type_parameters, super_class
+                        ),
+                        None => format!("class {}{}", class_name, type_parameters),
+                    };
+                    let interfaces: Vec<_> = implements
+                        .iter()
+                        .filter_map(identifier_name)
+                        .map(|(interface_name, _)| interface_name)
+                        .collect();
+                    if !interfaces.is_empty() {
+                        detail += &format!(" implements {}", interfaces.join(", "));
+                    }
+                    self.declare(class_name, SymbolKind::Class, span, detail);
+                    self.hoist_class_members(
+                        class_name,
+                        super_class.map(|(super_class, _)| String::from(super_class)),
+                        members,
+                    );
+                }
+                Statement::InterfaceDeclaration { name, .. } => {
+                    let Some((interface_name, span)) = identifier_name(name) else {
+                        continue;
+                    };
+                    let detail = format!("interface {}", interface_name);
+                    self.declare(interface_name, SymbolKind::Interface, span, detail);
+                }
+                Statement::EnumDeclaration { name, variants, .. } => {
+                    let Some((enum_name, span)) = identifier_name(name) else {
+                        continue;
+                    };
+                    let detail = format!("enum {}", enum_name);
+                    self.declare(enum_name, SymbolKind::Enum, span, detail);
+                    self.hoist_enum_variants(enum_name, variants);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn hoist_class_members(
+        &mut self,
+        class_name: &str,
+        super_class: Option<String>,
+        class_members: &ClassMemberList,
+    ) {
+        let mut members = HashMap::new();
+
+        for member in class_members {
+            match member {
+                ClassMember::Field {
+                    name,
+                    type_annotation,
+                    ..
+                } => {
+                    let Some((field_name, span)) = identifier_name(name) else {
+                        continue;
+                    };
+                    let detail = format!("(field) {}: {}", field_name, type_annotation);
+                    let symbol = self.add_symbol(field_name, SymbolKind::Field, span, detail);
+                    members.insert(String::from(field_name), symbol);
+                }
+                ClassMember::Method { declaration } | ClassMember::Constructor { declaration } => {
+                    let Statement::FunctionDeclaration {
+                        name,
+                        type_parameters,
+                        parameters,
+                        return_type,
+                        ..
+                    } = declaration.as_ref()
+                    else {
+                        continue;
+                    };
+                    let Some((method_name, span)) = identifier_name(name) else {
+                        continue;
+                    };
+                    let detail = format!(
+                        "(method) {}",
+                        function_detail(method_name, type_parameters, parameters, return_type)
+                    );
+                    let symbol = self.add_symbol(method_name, SymbolKind::Method, span, detail);
+                    members.insert(String::from(method_name), symbol);
+                }
+            }
+        }
+
+        self.classes.insert(
+            String::from(class_name),
+            ClassMembers {
+                super_class,
+                members,
+            },
+        );
+    }
+
+    fn hoist_enum_variants(&mut self, enum_name: &str, variants: &EnumVariantList) {
+        let mut variant_symbols = HashMap::new();
+
+        for variant in variants {
+            let Some((variant_name, span)) = identifier_name(&variant.name) else {
+                continue;
+            };
+            let mut detail = format!("(variant) {}::{}", enum_name, variant_name);
+            if !variant.payload.is_empty() {
+                let payload = variant
+                    .payload
+                    .iter()
+                    .map(|value_type| value_type.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                detail += &format!("({})", payload);
+            }
+            let symbol = self.add_symbol(variant_name, SymbolKind::Variant, span, detail);
+            variant_symbols.insert(String::from(variant_name), symbol);
+        }
+
+        self.enums.insert(String::from(enum_name), variant_symbols);
+    }
+
+    fn resolve_variant(&mut self, enum_name: &Expression, variant: &Expression) {
+        let variant_symbol = identifier_name(enum_name)
+            .zip(identifier_name(variant))
+            .and_then(|((enum_name, _), (variant_name, span))| {
+                let symbol = self.enums.get(enum_name)?.get(variant_name)?;
+                Some((*symbol, span))
+            });
+        if let Some((symbol, span)) = variant_symbol {
+            self.add_reference(span, symbol);
+        }
+    }
+
+    ///
+    /// Declare the names bound by a pattern in the current scope
+    ///
+    fn resolve_pattern(&mut self, pattern: &Pattern) -> Result<()> {
+        match pattern {
+            Pattern::Binding {
+                name,
+                type_annotation,
+                ..
+            } => {
+                if let Some((binding_name, span)) = identifier_name(name) {
+                    let detail = match type_annotation {
+                        Some(type_annotation) => {
+                            format!("let {}: {}", binding_name, type_annotation)
+                        }
+                        None => format!("let {}", binding_name),
+                    };
+                    self.declare(binding_name, SymbolKind::Variable, span, detail);
+                }
+            }
+            Pattern::EnumVariant {
+                enum_name,
+                variant,
+                arguments,
+                ..
+            } => {
+                self.visit_expression(enum_name)?;
+                self.resolve_variant(enum_name, variant);
+                for argument in arguments {
+                    self.resolve_pattern(argument)?;
+                }
+            }
+            Pattern::Array { elements, .. } => {
+                for element in elements {
+                    self.resolve_pattern(element)?;
+                }
+            }
+            // Keys name fields of the destructured value, not symbols
+            Pattern::Object { properties, .. } => {
+                for property in properties {
+                    self.resolve_pattern(&property.value)?;
+                }
+            }
+            Pattern::Wildcard { .. } | Pattern::Literal { .. } => {}
+        }
+
+        Ok(())
+    }
+
+    fn resolve_function(&mut self, parameters: &ParameterList, body: &StatementRef) -> Result<()> {
+        self.scopes.push(HashMap::new());
+        for (parameter, parameter_type) in parameters {
+            if let Expression::Destructuring { pattern, .. } = parameter {
+                for binding in pattern.bindings() {
+                    if let Some((binding_name, span)) = identifier_name(binding) {
+                        let detail = format!("(parameter) {}", binding_name);
+                        self.declare(binding_name, SymbolKind::Parameter, span, detail);
+                    }
+                }
+            }
+            if let Some((parameter_name, span)) = identifier_name(parameter) {
+                let detail = format!("(parameter) {}: {}", parameter_name, parameter_type);
+                self.declare(parameter_name, SymbolKind::Parameter, span, detail);
+            }
+        }
+        let result = self.visit_statement(body);
+        self.scopes.pop();
+
+        result
+    }
+
+    fn resolve_class_body(&mut self, class_name: &str, members: &ClassMemberList) -> Result<()> {
+        let enclosing_class = self.current_class.replace(String::from(class_name));
+        for member in members {
+            match member {
+                // Fields were declared while hoisting, only initializers refer to names
+                ClassMember::Field {
+                    initializer: Some(initializer),
+                    ..
+                } => self.visit_expression(initializer)?,
+                ClassMember::Field { .. } => {}
+                ClassMember::Method { declaration } | ClassMember::Constructor { declaration } => {
+                    if let Statement::FunctionDeclaration {
+                        parameters, body, ..
+                    } = declaration.as_ref()
+                    {
+                        self.resolve_function(parameters, body)?;
+                    }
+                }
+            }
+        }
+        self.current_class = enclosing_class;
+
+        Ok(())
+    }
+
+    fn resolve_member(&mut self, object: &ExpressionRef, property: &ExpressionRef) {
+        let Some(current_class) = self.current_class.clone() else {
+            return;
+        };
+        let class_name = match object.as_ref() {
+            Expression::This { .. } => Some(current_class),
+            Expression::Super { .. } => self
+                .classes
+                .get(&current_class)
+                .and_then(|class_members| class_members.super_class.clone()),
+            _ => None,
+        };
+
+        let Some((property_name, span)) = identifier_name(property) else {
+            return;
+        };
+        if let Some(symbol) =
+            class_name.and_then(|class_name| self.find_member(&class_name, property_name))
+        {
+            self.add_reference(span, symbol);
+        }
+    }
+}
+
+impl AstVisitor for Resolver {
+    type Output = ();
+
+    fn visit_statement(&mut self, statement: &Statement) -> Result<Self::Output> {
+        match statement {
+            Statement::Program { body, .. } => self.resolve_statement_list(body)?,
+            Statement::Block { body, .. } => {
+                self.scopes.push(HashMap::new());
+                let result = self.resolve_statement_list(body);
+                self.scopes.pop();
+                result?;
+            }
+            Statement::Empty { .. } => {}
+            Statement::Expression { expression, .. } => self.visit_expression(expression)?,
+            Statement::VariableDeclaration { variables, .. } => {
+                for variable in variables {
+                    self.visit_expression(variable)?;
+                }
+            }
+            Statement::If {
+                condition,
+                consequent,
+                alternative,
+                ..
+            } => {
+                self.visit_expression(condition)?;
+                self.visit_statement(consequent)?;
+                if let Some(alternative) = alternative {
+                    self.visit_statement(alternative)?;
+                }
+            }
+            Statement::While {
+                condition, body, ..
+            }
+            | Statement::DoWhile {
+                body, condition, ..
+            } => {
+                self.visit_expression(condition)?;
+                self.visit_statement(body)?;
+            }
+            Statement::For {
+                initializer,
+                condition,
+                increment,
+                body,
+                ..
+            } => {
+                self.scopes.push(HashMap::new());
+                if let Some(initializer) = initializer {
+                    self.visit_statement(initializer)?;
+                }
+                if let Some(condition) = condition {
+                    self.visit_expression(condition)?;
+                }
+                if let Some(increment) = increment {
+                    self.visit_expression(increment)?;
+                }
+                self.visit_statement(body)?;
+                self.scopes.pop();
+            }
+            Statement::FunctionDeclaration {
+                name,
+                type_parameters,
+                parameters,
+                return_type,
+                body,
+                ..
+            } => {
+                if let Some((function_name, span)) = identifier_name(name) {
+                    // Declarations outside of a statement list were not hoisted
+                    if !self.is_hoisted(function_name, span) {
+                        let detail = function_detail(
+                            function_name,
+                            type_parameters,
+                            parameters,
+                            return_type,
+                        );
+                        self.declare(function_name, SymbolKind::Function, span, detail);
+                    }
+                }
+                self.resolve_function(parameters, body)?;
+            }
+            Statement::Return { argument, .. } => {
+                if let Some(argument) = argument {
+                    self.visit_expression(argument)?;
+                }
+            }
+            // Labels name loops, they are not symbols
+            Statement::Break { .. } | Statement::Continue { .. } => {}
+            Statement::Labeled { body, .. } => self.visit_statement(body)?,
+            Statement::ClassDeclaration {
+                name,
+                super_class,
+                implements,
+                members,
+                ..
+            } => {
+                if let Some(super_class) = super_class {
+                    self.visit_expression(super_class)?;
+                }
+                for interface in implements {
+                    self.visit_expression(interface)?;
+                }
+                if let Some((class_name, _)) = identifier_name(name) {
+                    self.resolve_class_body(class_name, members)?;
+                }
+            }
+            Statement::InterfaceDeclaration { .. } | Statement::EnumDeclaration { .. } => {}
+        }
+
+        Ok(())
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) -> Result<Self::Output> {
+        match expression {
+            Expression::Variable {
+                identifier,
+                type_annotation,
+                initializer,
+                ..
+            } => {
+                if let Some(initializer) = initializer {
+                    self.visit_expression(initializer)?;
+                }
+                if let Expression::Destructuring { pattern, .. } = identifier.as_ref() {
+                    self.resolve_pattern(pattern)?;
+                }
+                if let Some((variable_name, span)) = identifier_name(identifier) {
+                    let detail = match type_annotation {
+                        Some(type_annotation) => {
+                            format!("let {}: {}", variable_name, type_annotation)
+                        }
+                        None => format!("let {}", variable_name),
+                    };
+                    self.declare(variable_name, SymbolKind::Variable, span, detail);
+                }
+            }
+            Expression::Assignment { left, right, .. }
+            | Expression::Binary { left, right, .. }
+            | Expression::Logical { left, right, .. } => {
+                self.visit_expression(left)?;
+                self.visit_expression(right)?;
+            }
+            Expression::Unary { right, .. }
+            | Expression::TypeTest {
+                expression: right, ..
+            } => self.visit_expression(right)?,
+            Expression::Identifier { name, span } => {
+                if let Some(symbol) = self.lookup(name) {
+                    self.add_reference(*span, symbol);
+                }
+            }
+            Expression::Member {
+                computed,
+                object,
+                property,
+                ..
+            } => {
+                self.visit_expression(object)?;
+                if *computed {
+                    self.visit_expression(property)?;
+                } else {
+                    self.resolve_member(object, property);
+                }
+            }
+            Expression::Call {
+                callee, arguments, ..
+            }
+            | Expression::New {
+                callee, arguments, ..
+            } => {
+                self.visit_expression(callee)?;
+                for argument in arguments {
+                    self.visit_expression(argument)?;
+                }
+            }
+            Expression::ArrayLiteral { elements, .. } => {
+                for element in elements {
+                    self.visit_expression(element)?;
+                }
+            }
+            Expression::EnumVariant {
+                enum_name,
+                variant,
+                arguments,
+                ..
+            } => {
+                self.visit_expression(enum_name)?;
+                self.resolve_variant(enum_name, variant);
+                for argument in arguments {
+                    self.visit_expression(argument)?;
+                }
+            }
+            Expression::Match { subject, arms, .. } => {
+                self.visit_expression(subject)?;
+                for arm in arms {
+                    // Names bound by the pattern are visible to the guard and the value
+                    self.scopes.push(HashMap::new());
+                    self.resolve_pattern(&arm.pattern)?;
+                    if let Some(guard) = &arm.guard {
+                        self.visit_expression(guard)?;
+                    }
+                    let result = self.visit_expression(&arm.value);
+                    self.scopes.pop();
+                    result?;
+                }
+            }
+            Expression::BooleanLiteral { .. }
+            | Expression::NilLiteral { .. }
+            | Expression::StringLiteral { .. }
+            | Expression::NumericLiteral { .. }
+            | Expression::This { .. }
+            | Expression::Super { .. }
+            | Expression::Destructuring { .. } => {}
+        }
+
+        Ok(())
+    }
+}
+
+fn identifier_name(expression: &Expression) -> Option<(&str, Span)> {
+    match expression {
+        Expression::Identifier { name, span } => Some((name, *span)),
+        _ => None,
+    }
+}
+
+fn function_detail(
+    name: &str,
+    type_parameters: &TypeParameterList,
+    parameters: &ParameterList,
+    return_type: &Type,
+) -> String {
+    let parameters = parameters
+        .iter()
+        .filter_map(|(parameter, parameter_type)| match parameter {
+            Expression::Destructuring { pattern, .. } => {
+                Some(format!("{}: {}", pattern_detail(pattern), parameter_type))
+            }
+            _ => {
+                identifier_name(parameter).map(|(name, _)| format!("{}: {}", name, parameter_type))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!(
+        "def {}{}({}): {}",
+        name,
+        type_parameters_detail(type_parameters),
+        parameters,
+        return_type
+    )
+}
+
+///
+/// Destructuring pattern of a parameter as written in the source
+///
+fn pattern_detail(pattern: &Pattern) -> String {
+    match pattern {
+        Pattern::Binding { name, .. } => identifier_name(name)
+            .map(|(name, _)| String::from(name))
+            .unwrap_or_default(),
+        Pattern::Array { elements, .. } => format!(
+            "[{}]",
+            elements
+                .iter()
+                .map(pattern_detail)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Pattern::Object { properties, .. } => {
+            let properties = properties
+                .iter()
+                .filter_map(|property| {
+                    let (key, _) = identifier_name(&property.key)?;
+                    let value = pattern_detail(&property.value);
+                    Some(match value == key {
+                        true => value,
+                        false => format!("{}: {}", key, value),
+                    })
+                })
+                .collect::<Vec<_>>();
+            match properties.is_empty() {
+                true => String::from("{}"),
+                false => format!("{{ {} }}", properties.join(", ")),
+            }
+        }
+        _ => String::from("_"),
+    }
+}
+
+fn type_parameters_detail(type_parameters: &TypeParameterList) -> String {
+    if type_parameters.is_empty() {
+        return String::new();
+    }
+
+    let type_parameters = type_parameters
+        .iter()
+        .filter_map(|type_parameter| {
+            let (name, _) = identifier_name(&type_parameter.name)?;
+            Some(match &type_parameter.bound {
+                Some(bound) => format!("{} extends {}", name, bound),
+                None => String::from(name),
+            })
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("[{}]", type_parameters)
+}
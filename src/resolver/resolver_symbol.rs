@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+
+use crate::lexer::{Span, TokenPosition};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub enum SymbolKind {
+    Variable,
+    Parameter,
+    Function,
+    Class,
+    Interface,
+    Enum,
+    Variant,
+    Method,
+    Field,
+}
+
+///
+/// A declared name
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    ///
+    /// Span of the declared identifier
+    ///
+    pub span: Span,
+    ///
+    /// Declaration in source syntax, e.g. 'let x: number'
+    ///
+    pub detail: String,
+}
+
+///
+/// Use of a name, points into the symbol list
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reference {
+    pub span: Span,
+    pub symbol: usize,
+}
+
+///
+/// Declarations of a program and the uses resolved to them
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Resolution {
+    pub symbols: Vec<Symbol>,
+    pub references: Vec<Reference>,
+}
+
+impl Resolution {
+    ///
+    /// Symbol declared or used at the position
+    ///
+    pub fn symbol_at(&self, position: TokenPosition) -> Option<&Symbol> {
+        self.references
+            .iter()
+            .find(|reference| reference.span.contains(position))
+            .map(|reference| &self.symbols[reference.symbol])
+            .or_else(|| {
+                self.symbols
+                    .iter()
+                    .find(|symbol| symbol.span.contains(position))
+            })
+    }
+
+    ///
+    /// Symbol a use or declaration at the given span belongs to
+    ///
+    pub fn symbol_of(&self, span: Span) -> Option<&Symbol> {
+        self.references
+            .iter()
+            .find(|reference| reference.span == span)
+            .map(|reference| &self.symbols[reference.symbol])
+            .or_else(|| self.symbols.iter().find(|symbol| symbol.span == span))
+    }
+}
@@ -0,0 +1,4 @@
+#[cfg(test)]
+mod internal_util;
+#[cfg(test)]
+mod test_yaml_files;
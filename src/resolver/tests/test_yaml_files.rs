@@ -0,0 +1,11 @@
+use super::internal_util::execute_yaml_test_cases;
+
+#[test]
+fn test_resolver_scopes() {
+    execute_yaml_test_cases("tests/resolver/test_resolver_scopes.yaml");
+}
+
+#[test]
+fn test_resolver_classes() {
+    execute_yaml_test_cases("tests/resolver/test_resolver_classes.yaml");
+}